@@ -38,6 +38,24 @@ fn bench_filter_check_event(c: &mut Criterion) {
         })
     });
 
+    // All-pass scenario: a second press 20ms after the first. With a zero window this
+    // takes the fast path; with a 10ms window the general comparison path.
+    let event_later = key_ev(20_000, 30, 1);
+    let mut group = c.benchmark_group("filter::check_event_all_pass");
+    for (name, window) in [
+        ("zero_window", Duration::ZERO),
+        ("general_path", debounce_time),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let mut filter = BounceFilter::new(0);
+                black_box(filter.check_event(&event_pass, window, false));
+                black_box(filter.check_event(&event_later, window, false));
+            })
+        });
+    }
+    group.finish();
+
     // Benchmark a non-key event scenario
     c.bench_function("filter::check_event_non_key", |b| {
         b.iter(|| {
@@ -263,7 +281,7 @@ fn bench_logger_process_message(c: &mut Criterion) {
                 Some(0),
             );
             let msg = LogMessage::Event(dummy_event_info_inner);
-            logger.process_message(msg, &None); // Add &None for near_miss_counter
+            logger.process_message(msg, &None); // Add &None for near_miss_histogram
         })
     });
 
@@ -284,7 +302,7 @@ fn bench_logger_process_message(c: &mut Criterion) {
             let dummy_event_info_inner =
                 bounced_event_info(key_ev(15_000, 30, 1), 15_000, 5_000, Some(10_000));
             let msg = LogMessage::Event(dummy_event_info_inner);
-            logger.process_message(msg, &None); // Add &None for near_miss_counter
+            logger.process_message(msg, &None); // Add &None for near_miss_histogram
         })
     });
 }
@@ -358,7 +376,9 @@ fn bench_stats_collector_print(c: &mut Criterion) {
     c.bench_function("stats::print_json", |b| {
         b.iter(|| {
             let mut writer = Vec::new(); // Write to buffer
-            stats.print_stats_json(&config, runtime, "Benchmark", &mut writer);
+            stats
+                .print_stats_json(&config, runtime, "Benchmark", &mut writer)
+                .expect("Writing JSON stats failed");
             criterion::black_box(writer); // Prevent optimization
         })
     });
@@ -44,7 +44,7 @@ case "${1:-help}" in
     ;;
   test)
     echo "Running tests..."
-    cargo test --all
+    cargo test --all --features intercept-bounce/test-hooks
     ;;
   docs)
     echo "Generating documentation..."
@@ -23,7 +23,7 @@ _intercept-bounce() {
 
     case "${cmd}" in
         intercept__bounce)
-            opts="-t -h -V --debounce-time --near-miss-threshold-time --log-interval --log-all-events --log-bounces --list-devices --stats-json --verbose --ring-buffer-size --debounce-key --ignore-key --otel-endpoint --help --version"
+            opts="-t -o -h -V --debounce-time --near-miss-threshold --near-miss-threshold-time --log-interval --periodic-compact --poll-interval --shutdown-timeout --max-runtime --expect-events --write-error --gap-threshold --log-all-events --log-bounces --warn-near-miss --list-devices --json --self-test --merge --output --input --grab --auto-device --stats-json --stats-json-file --no-stats --no-stats-on-broken-pipe --restart-logger --fail-on-logger-panic --stats-format --timeline-csv --dropped-events-file --export-trailer --heatmap-json --stats-stream-fd --stats-stream-interval --measure-latency --tolerate-partial --strict-values --passthrough-types --key-events-only --synthesize-syn --drop-orphan-scancodes --analyze --stats-to-stdout --sweep --collapse-press-release --drop-rule --mode --count-threshold --burst-gap --lead-in --debounce-states --max-repeat-rate --suppress-initial --initial-window --drop-exact-duplicates --collapse-zero-duration --event-json --event-json-raw --verbose --debounce-rel --dead-switch-threshold --color --stats-granularity --hide-repeat-stats --per-key-histograms --focus-key --inspect --top-keys --histogram-cumulative --histogram-width --histogram-char --histogram-legend --interval-histogram --time-precision --sample-seed --persist-state --key-aliases --anonymize --config --ring-buffer-size --debounce-key --ignore-key --tap --tap-fd --otel-endpoint --otel-key-attributes --otel-flush-interval --otel-required --help --version"
             if [[ ${cur} == -* || ${COMP_CWORD} -eq 1 ]] ; then
                 COMPREPLY=( $(compgen -W "${opts}" -- "${cur}") )
                 return 0
@@ -41,10 +41,170 @@ _intercept-bounce() {
                     COMPREPLY=($(compgen -f "${cur}"))
                     return 0
                     ;;
+                --near-miss-threshold)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
                 --log-interval)
                     COMPREPLY=($(compgen -f "${cur}"))
                     return 0
                     ;;
+                --poll-interval)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --shutdown-timeout)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --max-runtime)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --write-error)
+                    COMPREPLY=($(compgen -W "abort skip" -- "${cur}"))
+                    return 0
+                    ;;
+                --gap-threshold)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --merge)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --output)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                -o)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --input)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --stats-json-file)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --stats-format)
+                    COMPREPLY=($(compgen -W "json msgpack" -- "${cur}"))
+                    return 0
+                    ;;
+                --timeline-csv)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --dropped-events-file)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --heatmap-json)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --stats-stream-fd)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --stats-stream-interval)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --passthrough-types)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --sweep)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --drop-rule)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --mode)
+                    COMPREPLY=($(compgen -W "time count burst" -- "${cur}"))
+                    return 0
+                    ;;
+                --count-threshold)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --burst-gap)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --lead-in)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --debounce-states)
+                    COMPREPLY=($(compgen -W "press release" -- "${cur}"))
+                    return 0
+                    ;;
+                --max-repeat-rate)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --initial-window)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --dead-switch-threshold)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --color)
+                    COMPREPLY=($(compgen -W "auto always never" -- "${cur}"))
+                    return 0
+                    ;;
+                --stats-granularity)
+                    COMPREPLY=($(compgen -W "key state" -- "${cur}"))
+                    return 0
+                    ;;
+                --focus-key)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --inspect)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --top-keys)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --histogram-width)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --histogram-char)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --time-precision)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --sample-seed)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --persist-state)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --key-aliases)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --config)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
                 --ring-buffer-size)
                     COMPREPLY=($(compgen -f "${cur}"))
                     return 0
@@ -57,10 +217,22 @@ _intercept-bounce() {
                     COMPREPLY=($(compgen -f "${cur}"))
                     return 0
                     ;;
+                --tap)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
+                --tap-fd)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
                 --otel-endpoint)
                     COMPREPLY=($(compgen -f "${cur}"))
                     return 0
                     ;;
+                --otel-flush-interval)
+                    COMPREPLY=($(compgen -f "${cur}"))
+                    return 0
+                    ;;
                 *)
                     COMPREPLY=()
                     ;;
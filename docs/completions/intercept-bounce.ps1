@@ -21,19 +21,97 @@ Register-ArgumentCompleter -Native -CommandName 'intercept-bounce' -ScriptBlock
 
     $completions = @(switch ($command) {
         'intercept-bounce' {
-            [CompletionResult]::new('-t', '-t', [CompletionResultType]::ParameterName, 'Debounce time threshold (milliseconds). Duplicate key events (same keycode and value) occurring faster than this threshold are discarded. (Default: 25ms). The "value" refers to the state of the key: `1` for press, `0` for release, `2` for repeat. Only press and release events are debounced. Accepts values like "10ms", "0.5s"')
-            [CompletionResult]::new('--debounce-time', '--debounce-time', [CompletionResultType]::ParameterName, 'Debounce time threshold (milliseconds). Duplicate key events (same keycode and value) occurring faster than this threshold are discarded. (Default: 25ms). The "value" refers to the state of the key: `1` for press, `0` for release, `2` for repeat. Only press and release events are debounced. Accepts values like "10ms", "0.5s"')
-            [CompletionResult]::new('--near-miss-threshold-time', '--near-miss-threshold-time', [CompletionResultType]::ParameterName, 'Threshold for logging "near-miss" events. Passed key events occurring within this time of the previous passed event are logged/counted. (Default: 100ms) Accepts values like "100ms", "0.1s"')
+            [CompletionResult]::new('-t', '-t', [CompletionResultType]::ParameterName, 'Debounce time threshold. Duplicate key events (same keycode and value) occurring faster than this threshold are discarded. (Default: 25ms). The "value" refers to the state of the key: `1` for press, `0` for release, `2` for repeat. Only press and release events are debounced. Accepts values like "10ms", "0.5s", "750us" (kept to the exact microsecond, for very fast switches), or a frequency such as "50hz" (minimum interval of 1/50 s = 20ms)')
+            [CompletionResult]::new('--debounce-time', '--debounce-time', [CompletionResultType]::ParameterName, 'Debounce time threshold. Duplicate key events (same keycode and value) occurring faster than this threshold are discarded. (Default: 25ms). The "value" refers to the state of the key: `1` for press, `0` for release, `2` for repeat. Only press and release events are debounced. Accepts values like "10ms", "0.5s", "750us" (kept to the exact microsecond, for very fast switches), or a frequency such as "50hz" (minimum interval of 1/50 s = 20ms)')
+            [CompletionResult]::new('--near-miss-threshold-time', '--near-miss-threshold-time', [CompletionResultType]::ParameterName, 'Threshold for logging "near-miss" events. Passed key events occurring within this time of the previous passed event are logged/counted. (Default: 100ms) Accepts values like "100ms", "0.1s". A comma-separated list ("30ms,60ms,120ms") also counts near misses per band; the largest value is the overall threshold')
+            [CompletionResult]::new('--near-miss-threshold', '--near-miss-threshold', [CompletionResultType]::ParameterName, 'Threshold for logging "near-miss" events. Passed key events occurring within this time of the previous passed event are logged/counted. (Default: 100ms) Accepts values like "100ms", "0.1s". A comma-separated list ("30ms,60ms,120ms") also counts near misses per band; the largest value is the overall threshold')
             [CompletionResult]::new('--log-interval', '--log-interval', [CompletionResultType]::ParameterName, 'Periodically dump statistics to stderr. (Default: 15m). Set to "0" to disable periodic dumps. Accepts values like "60s", "15m", "1h"')
+            [CompletionResult]::new('--poll-interval', '--poll-interval', [CompletionResultType]::ParameterName, 'How often the main loop, while waiting for input, and the logger thread re-check for shutdown and periodic dumps. Smaller values make shutdown snappier at a small CPU cost. Between 1ms and 1s. (Default: 100ms)')
+            [CompletionResult]::new('--shutdown-timeout', '--shutdown-timeout', [CompletionResultType]::ParameterName, 'How long shutdown waits for the logger thread to finish (e.g. a slow OTLP flush) before exiting without its statistics, with a warning but the usual exit status, so a wedged logger can''t hang the pipeline. "0" waits as long as it takes, so the final statistics and `--persist-state` are never lost. (Default: 5s)')
+            [CompletionResult]::new('--max-runtime', '--max-runtime', [CompletionResultType]::ParameterName, 'Shut down on its own, printing the final statistics as usual, once this long has passed since startup (monotonic clock), e.g. `60s` for a fixed-window measurement. Default: run until EOF or a signal')
+            [CompletionResult]::new('--write-error', '--write-error', [CompletionResultType]::ParameterName, 'What to do when writing an event to stdout fails: `abort` ends the run, `skip` logs the error, drops that event and keeps going, so a transient output hiccup doesn''t leave the keyboard dead. A dropped release leaves its key stuck down until it is pressed again. A closed pipe, or an event written only in part, always ends the run')
+            [CompletionResult]::new('--gap-threshold', '--gap-threshold', [CompletionResultType]::ParameterName, 'Log and count gaps of at least this long between consecutive input events (e.g. "5s"). Gaps while a key is held are flagged as possible dropped events (USB hiccups), as opposed to an idle keyboard. Disabled by default')
+            [CompletionResult]::new('--merge', '--merge', [CompletionResultType]::ParameterName, 'Offline: read two reports written with `--stats-json` (e.g. from separate sessions), add up their counts, histograms and timing samples, and write the combined report as JSON to stdout or `--output`. Needs no input')
+            [CompletionResult]::new('-o', '-o', [CompletionResultType]::ParameterName, 'With `--merge`, write the combined report to PATH instead of stdout')
+            [CompletionResult]::new('--output', '--output', [CompletionResultType]::ParameterName, 'With `--merge`, write the combined report to PATH instead of stdout')
+            [CompletionResult]::new('--input', '--input', [CompletionResultType]::ParameterName, 'Read input_event structs from PATH (an evdev node such as /dev/input/eventX, or a file of recorded events) instead of standard input. The device is not grabbed, so its events still reach other readers too')
+            [CompletionResult]::new('--stats-json-file', '--stats-json-file', [CompletionResultType]::ParameterName, 'At exit, also write the final statistics as JSON to PATH, whatever the format on stderr, so operators keep the human-readable report while a pipeline picks up the machine-readable one. Overwrites PATH')
+            [CompletionResult]::new('--stats-format', '--stats-format', [CompletionResultType]::ParameterName, 'Encoding of the machine-readable statistics. `msgpack` writes each report as a MessagePack map with the same fields as the JSON, prefixed by its length (4 bytes, big-endian), and implies `--stats-json`. Best combined with `--analyze --stats-to-stdout`, away from the log lines on stderr')
+            [CompletionResult]::new('--timeline-csv', '--timeline-csv', [CompletionResultType]::ParameterName, 'Stream a CSV timeline of every processed key event to PATH, one row per event: `event_us,key_code,key_name,value,decision,diff_us`. `decision` is `pass`, `drop` or `near_miss`; `diff_us` is the time since the last passed event of the same key and value. Write errors are logged and never stop filtering')
+            [CompletionResult]::new('--dropped-events-file', '--dropped-events-file', [CompletionResultType]::ParameterName, 'Record every dropped event to PATH as it happens, one JSON object per line (timestamp, event type, key, value, time since the last passed event and the debounce time in effect), as evidence of chatter. Write errors are logged and never stop filtering')
+            [CompletionResult]::new('--heatmap-json', '--heatmap-json', [CompletionResultType]::ParameterName, 'At exit, write a per-key chatter heatmap to PATH as JSON, for coloring a keyboard layout: each key''s drops weighted by its drop rate, normalized to 0-1 so the hottest key is 1. Covers the persisted totals with `--persist-state`')
+            [CompletionResult]::new('--stats-stream-fd', '--stats-stream-fd', [CompletionResultType]::ParameterName, 'Push live statistics to the already open file descriptor FD (e.g. a pipe set up by a GUI) every `--stats-stream-interval`, plus once at exit. Each frame is a compact JSON object prefixed by its length (u32, big-endian)')
+            [CompletionResult]::new('--stats-stream-interval', '--stats-stream-interval', [CompletionResultType]::ParameterName, 'Interval between `--stats-stream-fd` frames. Zero sends only the final frame')
+            [CompletionResult]::new('--passthrough-types', '--passthrough-types', [CompletionResultType]::ParameterName, 'Only write events of these types (names like `EV_SYN` or numbers, comma-separated) to stdout; all other non-key events are dropped from the stream, e.g. `EV_KEY,EV_SYN` for consumers that only want keys. Key events are always debounced and written as usual. Default: all types pass')
+            [CompletionResult]::new('--sweep', '--sweep', [CompletionResultType]::ParameterName, 'Offline tuning: read the whole capture, replay it once per debounce time in the comma-separated list (e.g. "5ms,10ms,15ms,20ms") and print a table of drop counts and rates per setting to stdout (JSON with `--stats-json`). No events are written')
+            [CompletionResult]::new('--drop-rule', '--drop-rule', [CompletionResultType]::ParameterName, 'Custom drop decision evaluated for each debounce candidate instead of the plain `diff_us < debounce_us` check. Variables: diff_us, debounce_us, key_code, value, prev_bounces. Example: `--drop-rule "diff_us < debounce_us && prev_bounces >= 2"`')
+            [CompletionResult]::new('--mode', '--mode', [CompletionResultType]::ParameterName, 'Debounce algorithm. `time` drops an event arriving within the debounce time of the last passed one. `count` lets the first `--count-threshold` identical events within a debounce-time window through and drops the rest, so fast intentional double-taps survive. `burst` passes the first event of a burst and drops the rest until the key and value have been quiet for `--burst-gap`, however long the burst lasts. `--drop-rule` only applies to `time`')
+            [CompletionResult]::new('--count-threshold', '--count-threshold', [CompletionResultType]::ParameterName, 'Number of identical events (same key and value) allowed per debounce-time window with `--mode count`')
+            [CompletionResult]::new('--burst-gap', '--burst-gap', [CompletionResultType]::ParameterName, 'Silence (no event of the same key and value) that ends a burst with `--mode burst`, e.g. "30ms". Default: the debounce time')
+            [CompletionResult]::new('--lead-in', '--lead-in', [CompletionResultType]::ParameterName, 'Let KEY pass its first N identical events (same value) per debounce-time window before debouncing kicks in, e.g. `--lead-in 183=2` for a macro key (KEY_F13) that is double-tapped on purpose. Count mode for just that key: the window is `--debounce-time`, and it overrides `--count-threshold` and `--drop-rule` for the key. Repeat the flag for more keys')
+            [CompletionResult]::new('--debounce-states', '--debounce-states', [CompletionResultType]::ParameterName, 'Key states to debounce, comma-separated: `press`, `release` or both (the default). States left out pass unfiltered, e.g. `--debounce-states press` for a keyboard that only chatters on presses, so fast releases survive')
+            [CompletionResult]::new('--max-repeat-rate', '--max-repeat-rate', [CompletionResultType]::ParameterName, 'Pass at most N key repeats per key per second and drop the rest, e.g. to contain a failing controller''s repeat storm. Dropped repeats count as drops in the statistics. Default: unlimited')
+            [CompletionResult]::new('--initial-window', '--initial-window', [CompletionResultType]::ParameterName, 'Startup window for `--suppress-initial`, measured from process start (event clock)')
+            [CompletionResult]::new('--dead-switch-threshold', '--dead-switch-threshold', [CompletionResultType]::ParameterName, 'Flag keys whose drop rate reaches this percentage (over at least 20 events) as likely dead or stuck switches rather than chatter, in the statistics')
+            [CompletionResult]::new('--color', '--color', [CompletionResultType]::ParameterName, 'Colorize log output and the human-readable statistics (drop rates by severity, histogram bars). `auto` colors only when stderr is a terminal and `NO_COLOR` is unset')
+            [CompletionResult]::new('--stats-granularity', '--stats-granularity', [CompletionResultType]::ParameterName, 'Level of detail of the per-key human-readable statistics: `state` shows Press/Release/Repeat lines under each key''s total, `key` only the total line')
+            [CompletionResult]::new('--focus-key', '--focus-key', [CompletionResultType]::ParameterName, 'Print per-key bounce histograms for just this key (code or name, e.g. `KEY_A`). Repeat the flag for several keys')
+            [CompletionResult]::new('--inspect', '--inspect', [CompletionResultType]::ParameterName, 'Replace the human-readable statistics with a deep-dive on this one key (code or name): per-state counts, bounce and near-miss histograms, percentiles, first/last event and its recommended debounce time')
+            [CompletionResult]::new('--top-keys', '--top-keys', [CompletionResultType]::ParameterName, 'Number of keys listed, most drops first, in the "Chattiest Keys" summary that opens the statistics (and in the JSON `top_keys` array). "0" disables it')
+            [CompletionResult]::new('--histogram-width', '--histogram-width', [CompletionResultType]::ParameterName, 'Longest histogram bar in characters. Default: fitted to the terminal (at most 50), or 50 if statistics don''t go to a terminal')
+            [CompletionResult]::new('--histogram-char', '--histogram-char', [CompletionResultType]::ParameterName, 'Character histogram bars are drawn with, e.g. `█`')
+            [CompletionResult]::new('--time-precision', '--time-precision', [CompletionResultType]::ParameterName, 'Number of decimal places in human-readable timings (e.g. `2` prints `1.38 ms`). With 1 or more, times under 1 ms are printed in ms as well (`3` prints `0.300 ms`); with 0 they stay whole µs. Default: whole µs, one decimal for ms, three for seconds')
+            [CompletionResult]::new('--sample-seed', '--sample-seed', [CompletionResultType]::ParameterName, 'Seed for random sampling in the statistics, reported as `sample_seed` in JSON output. Timing samples keep the newest timings per key and state, so they don''t draw on it yet. Default: 0')
+            [CompletionResult]::new('--persist-state', '--persist-state', [CompletionResultType]::ParameterName, 'Keep cumulative statistics in FILE across runs: prior counts, histograms and timing samples are loaded at startup and the merged totals saved on exit. The file is created if it doesn''t exist')
+            [CompletionResult]::new('--key-aliases', '--key-aliases', [CompletionResultType]::ParameterName, 'Show custom names for keys in human-readable statistics and log lines, read from a TOML FILE of `KEY_NAME = "Label"` (or `code = "Label"`) entries. Keys without an alias keep their usual name; JSON output is unchanged')
+            [CompletionResult]::new('--config', '--config', [CompletionResultType]::ParameterName, 'Read settings from a TOML file. Keys are long option names (e.g. `debounce_time = "15ms"`); flags given on the command line take precedence. The file is re-read on SIGHUP')
             [CompletionResult]::new('--ring-buffer-size', '--ring-buffer-size', [CompletionResultType]::ParameterName, 'Size of the ring buffer for storing recently passed events (for debugging). Set to 0 to disable. (Default: 0)')
             [CompletionResult]::new('--debounce-key', '--debounce-key', [CompletionResultType]::ParameterName, 'Key codes or names to debounce. When present, only these keys are debounced (all others pass through). Takes precedence over `--ignore-key`. Example: `--debounce-key KEY_ENTER` (repeat flag for multiple keys)')
             [CompletionResult]::new('--ignore-key', '--ignore-key', [CompletionResultType]::ParameterName, 'Key codes or names to ignore (never debounce) unless they also appear in `--debounce-key`. Example: `--ignore-key 114` or `--ignore-key KEY_VOLUMEDOWN`')
+            [CompletionResult]::new('--tap', '--tap', [CompletionResultType]::ParameterName, 'Mirror the raw events of this key (code or name), passed and dropped alike, to `--tap-fd`; stdout is unaffected. Narrower than a full capture when only one key is suspect. Repeat the flag for several keys')
+            [CompletionResult]::new('--tap-fd', '--tap-fd', [CompletionResultType]::ParameterName, 'Already open file descriptor (e.g. `3>tap.bin` in the shell) receiving the `--tap` keys'' events as binary `input_event` structs, like stdout')
             [CompletionResult]::new('--otel-endpoint', '--otel-endpoint', [CompletionResultType]::ParameterName, 'OTLP endpoint URL for exporting traces and metrics (e.g., "http://localhost:4317")')
+            [CompletionResult]::new('--otel-flush-interval', '--otel-flush-interval', [CompletionResultType]::ParameterName, 'How often OTLP metrics are exported and batched spans sent (e.g. "5s"). The exporter defaults (60s for metrics) can swallow a short diagnostic run; everything is flushed at shutdown regardless')
+            [CompletionResult]::new('--periodic-compact', '--periodic-compact', [CompletionResultType]::ParameterName, 'Print each periodic dump as a single line (`PERIODIC processed=.. dropped=.. drop_pct=.. top_key=..`) instead of the full statistics block. The final cumulative report is unaffected')
+            [CompletionResult]::new('--expect-events', '--expect-events', [CompletionResultType]::ParameterName, 'Exit with status 5 if the run ends without a single key event, e.g. when a misconfigured `intercept` stage hits EOF at once. Lets health checks (udevmon, systemd) tell a dead pipeline from a clean exit')
             [CompletionResult]::new('--log-all-events', '--log-all-events', [CompletionResultType]::ParameterName, 'Log details of *every* incoming event to stderr ([PASS] or [DROP])')
             [CompletionResult]::new('--log-bounces', '--log-bounces', [CompletionResultType]::ParameterName, 'Log details of *only dropped* (bounced) key events to stderr')
+            [CompletionResult]::new('--warn-near-miss', '--warn-near-miss', [CompletionResultType]::ParameterName, 'Log a warning for every passed key press or release that was a near miss (within `--near-miss-threshold-time` of the previous pass), without needing `--log-all-events`: a live view of keys flirting with the debounce time')
             [CompletionResult]::new('--list-devices', '--list-devices', [CompletionResultType]::ParameterName, 'List available input devices and their capabilities (requires root)')
+            [CompletionResult]::new('--json', '--json', [CompletionResultType]::ParameterName, 'With `--list-devices`, print the listing as a JSON array on stdout. Devices that could not be opened or queried appear with an `error` field')
+            [CompletionResult]::new('--self-test', '--self-test', [CompletionResultType]::ParameterName, 'Run a known event sequence through the filter and statistics, print PASS/FAIL per check and exit (status 6 on failure). Needs no input')
+            [CompletionResult]::new('--grab', '--grab', [CompletionResultType]::ParameterName, 'Grab the `--input` device exclusively (EVIOCGRAB), as `intercept -g` does: other readers, including your desktop session, stop receiving its events until intercept-bounce exits. Only filtered events written to stdout remain')
+            [CompletionResult]::new('--auto-device', '--auto-device', [CompletionResultType]::ParameterName, 'Instead of standard input, read the first keyboard found in /dev/input (a device with at least 40 of the regular keyboard keys, so mice and media-key devices are skipped) and grab it, as with `--input` and `--grab`. Lists the other candidates if there are several. Requires root')
             [CompletionResult]::new('--stats-json', '--stats-json', [CompletionResultType]::ParameterName, 'Output statistics as JSON format to stderr on exit and periodic dump')
+            [CompletionResult]::new('--no-stats', '--no-stats', [CompletionResultType]::ParameterName, 'Only filter: don''t start the logger thread, so no statistics, periodic dumps or event logging, and no per-event hand-off to another thread. For the lowest overhead when the filter is already tuned. Can''t be combined with statistics or event logging options')
+            [CompletionResult]::new('--no-stats-on-broken-pipe', '--no-stats-on-broken-pipe', [CompletionResultType]::ParameterName, 'Skip the final statistics if the run ended because the consumer of stdout went away (broken pipe), e.g. to keep a udevmon restart loop from flooding the logs. Statistics are still printed after EOF or a signal')
+            [CompletionResult]::new('--restart-logger', '--restart-logger', [CompletionResultType]::ParameterName, 'If the logger thread panics mid-run, start a new one (once) and keep filtering, instead of stopping with status 1. Statistics collected before the panic are lost. For long-running daemons that should heal themselves')
+            [CompletionResult]::new('--fail-on-logger-panic', '--fail-on-logger-panic', [CompletionResultType]::ParameterName, 'Exit with status 1 at the end of a run whose logger thread panicked. A panic already ends the run that way on its own; with `--restart-logger`, this keeps the status at 1 after the recovery, so monitoring still notices')
+            [CompletionResult]::new('--export-trailer', '--export-trailer', [CompletionResultType]::ParameterName, 'End `--timeline-csv` and `--dropped-events-file` with a trailer line giving the number of records and an FNV-1a checksum of them, written at shutdown, so a file cut short (e.g. by a killed process) can be told from a complete one')
+            [CompletionResult]::new('--measure-latency', '--measure-latency', [CompletionResultType]::ParameterName, 'Time how long the filter decision (locking the filter and checking the event) takes for each event, on a monotonic clock, and report the min/avg/max in the statistics. Off by default to save the clock reads')
+            [CompletionResult]::new('--tolerate-partial', '--tolerate-partial', [CompletionResultType]::ParameterName, 'Treat input that ends in the middle of an event (a truncated capture, a flaky source) as a clean end of input: the partial event is discarded with a warning. Without it, a truncated final event is a read error (exit 1)')
+            [CompletionResult]::new('--strict-values', '--strict-values', [CompletionResultType]::ParameterName, 'Drop key events whose value isn''t 0 (release), 1 (press) or 2 (repeat), with a warning, instead of passing them on and counting them as repeats. Such values usually mean a corrupted stream')
+            [CompletionResult]::new('--key-events-only', '--key-events-only', [CompletionResultType]::ParameterName, 'Write only key events to stdout; SYN, MSC, LED and all other events are dropped from the stream. Same as `--passthrough-types EV_KEY`')
+            [CompletionResult]::new('--synthesize-syn', '--synthesize-syn', [CompletionResultType]::ParameterName, 'With `--key-events-only`, write a SYN_REPORT (stamped with the key event''s time) after each key event, for consumers that only act on a report once it is terminated by a SYN')
+            [CompletionResult]::new('--drop-orphan-scancodes', '--drop-orphan-scancodes', [CompletionResultType]::ParameterName, 'Drop the MSC_SCAN event the kernel sends right before a key event along with that key event when it is dropped, instead of passing on an orphaned scancode. Each MSC_SCAN is held back until the decision on the key event with the same timestamp is known')
+            [CompletionResult]::new('--analyze', '--analyze', [CompletionResultType]::ParameterName, 'Analysis only: filter and collect statistics as usual, but don''t write any events to stdout. Useful for evaluating a capture or settings offline')
+            [CompletionResult]::new('--stats-to-stdout', '--stats-to-stdout', [CompletionResultType]::ParameterName, 'Write statistics (final and periodic) to stdout instead of stderr. Requires `--analyze`, since stdout otherwise carries the event stream')
+            [CompletionResult]::new('--collapse-press-release', '--collapse-press-release', [CompletionResultType]::ParameterName, 'Treat a key release followed within the debounce time by a press of the same key as chatter and drop both. Releases are held back for up to the debounce time to decide this, and emitted at once when another key''s event arrives. May swallow intentional very fast re-taps. Only this direction is collapsed: a press followed by a quick release is debounced as usual')
+            [CompletionResult]::new('--suppress-initial', '--suppress-initial', [CompletionResultType]::ParameterName, 'Drop the first press and the first release of each key if they arrive within `--initial-window` of startup, e.g. spurious events injected while the virtual device is set up. With `--analyze`, the window starts at the first event of the capture instead')
+            [CompletionResult]::new('--drop-exact-duplicates', '--drop-exact-duplicates', [CompletionResultType]::ParameterName, 'Drop any event identical in every field (timestamp included) to the event right before it, a driver glitch that no real keystroke can produce')
+            [CompletionResult]::new('--collapse-zero-duration', '--collapse-zero-duration', [CompletionResultType]::ParameterName, 'Drop a key release carrying the same timestamp as the key''s last passed press, a zero-duration keypress left by a hard bounce. The key then stays down until its next release. Devices that report a tap shorter than their polling interval as press and release in one report lose that release too')
+            [CompletionResult]::new('--event-json', '--event-json', [CompletionResultType]::ParameterName, 'Emit one JSON object per processed event (decoded fields and filter decision) to stderr, one per line')
+            [CompletionResult]::new('--event-json-raw', '--event-json-raw', [CompletionResultType]::ParameterName, 'Include the raw `input_event` bytes as a hex string (`raw_hex`) in the `--event-json` stream. Implies `--event-json`')
             [CompletionResult]::new('--verbose', '--verbose', [CompletionResultType]::ParameterName, 'Enable verbose logging (internal state, thread startup, etc)')
+            [CompletionResult]::new('--debounce-rel', '--debounce-rel', [CompletionResultType]::ParameterName, 'Also debounce relative axis events (EV_REL, e.g. scroll wheels): a delta is dropped if one in the same direction on the same axis passed within the debounce time. Can thin out intentional fast scrolling')
+            [CompletionResult]::new('--hide-repeat-stats', '--hide-repeat-stats', [CompletionResultType]::ParameterName, 'Leave the per-key Repeat lines out of human-readable statistics. Repeats are passed through unfiltered, so those lines never show any drops')
+            [CompletionResult]::new('--per-key-histograms', '--per-key-histograms', [CompletionResultType]::ParameterName, 'Print press/release bounce histograms for every key in the per-key human-readable statistics, not just the overall ones')
+            [CompletionResult]::new('--histogram-cumulative', '--histogram-cumulative', [CompletionResultType]::ParameterName, 'Also show the cumulative percentage of timings at each histogram bucket ("X% of bounces were below N ms"), in both human and JSON statistics')
+            [CompletionResult]::new('--histogram-legend', '--histogram-legend', [CompletionResultType]::ParameterName, 'Print the histogram bucket ranges once, as a legend shared by the overall bounce and near-miss histograms, and draw both bars on the same scale so the two distributions can be compared directly')
+            [CompletionResult]::new('--interval-histogram', '--interval-histogram', [CompletionResultType]::ParameterName, 'Record the time between consecutive passed presses (and releases) of each key, whether or not they were near misses, and report it per key and as an overall histogram, e.g. to look at typing rhythm')
+            [CompletionResult]::new('--anonymize', '--anonymize', [CompletionResultType]::ParameterName, 'Replace key names and codes in the statistics reports (human-readable and JSON) with pseudonyms like `key_1a3`, so a report can be shared without revealing what was typed. Counts and timings are kept. The mapping is random per run but the same for every report within it')
+            [CompletionResult]::new('--otel-key-attributes', '--otel-key-attributes', [CompletionResultType]::ParameterName, 'Attach the key name as a `key` attribute to the OTLP processed/passed/dropped counters, so they can be broken down per key. Adds one series per key used')
+            [CompletionResult]::new('--otel-required', '--otel-required', [CompletionResultType]::ParameterName, 'Exit with status 7 if OTLP export can''t be set up or the endpoint doesn''t accept connections at startup, instead of warning and running without telemetry')
             [CompletionResult]::new('-h', '-h', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
             [CompletionResult]::new('--help', '--help', [CompletionResultType]::ParameterName, 'Print help (see more with ''--help'')')
             [CompletionResult]::new('-V', '-V ', [CompletionResultType]::ParameterName, 'Print version')
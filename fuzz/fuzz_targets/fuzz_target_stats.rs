@@ -58,6 +58,8 @@ fuzz_target!(|data: &[u8]| {
         } else {
             None
         },
+        paused: false,
+        filter_latency_ns: None,
     };
 
     // Create a dummy Config (only debounce and near_miss thresholds are used by record_event_info_with_config)
@@ -0,0 +1,283 @@
+//! Async counterpart of the binary's event loop, for driving the filter from
+//! Tokio code (`tokio` feature).
+//!
+//! The input is awaited through [`AsyncFd`] instead of blocking in `libc::read`,
+//! so the loop can share a runtime with the rest of an async pipeline. Statistics
+//! and logging are left to the caller; the `intercept-bounce` binary itself
+//! keeps using the synchronous loop.
+
+use crate::config::Config;
+use crate::event::{self, input_event};
+use crate::filter::BounceFilter;
+use crate::output::{self, OutputRouter};
+use std::io::{self, ErrorKind};
+use std::mem::{size_of, MaybeUninit};
+use std::os::unix::io::{AsRawFd, RawFd};
+use tokio::io::unix::AsyncFd;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tracing::{trace, warn};
+
+/// Reads `input_event`s from a non-blocking file descriptor without blocking
+/// the runtime. Partial events are kept across wake-ups.
+pub struct AsyncEventReader<T: AsRawFd> {
+    fd: AsyncFd<T>,
+    buf: [u8; size_of::<input_event>()],
+    filled: usize,
+    // File status flags of `fd` before it was switched to non-blocking mode.
+    original_flags: libc::c_int,
+}
+
+impl<T: AsRawFd> AsyncEventReader<T> {
+    /// Switches `input` to non-blocking mode and registers it with the current
+    /// Tokio runtime. Must be called from within a runtime with I/O enabled.
+    /// Regular files can't be registered; use a pipe, socket or evdev node.
+    ///
+    /// The mode is a property of the open file, shared with every duplicate of
+    /// the descriptor (e.g. a caller's stdin); the original flags are restored
+    /// when the reader is dropped.
+    pub fn new(input: T) -> io::Result<Self> {
+        let original_flags = set_nonblocking(input.as_raw_fd())?;
+        let fd = AsyncFd::try_new(input).map_err(|e| {
+            let (input, e) = e.into_parts();
+            unsafe { libc::fcntl(input.as_raw_fd(), libc::F_SETFL, original_flags) };
+            e
+        })?;
+        Ok(Self {
+            fd,
+            buf: [0; size_of::<input_event>()],
+            filled: 0,
+            original_flags,
+        })
+    }
+
+    /// Reads the next event, like [`event::read_event_raw`]: `Ok(None)` on a
+    /// clean EOF, `ErrorKind::UnexpectedEof` if the input ends mid-event.
+    pub async fn read_event(&mut self) -> io::Result<Option<input_event>> {
+        while self.filled < self.buf.len() {
+            let mut guard = self.fd.readable().await?;
+            let dest = &mut self.buf[self.filled..];
+            let read = guard.try_io(|fd| {
+                let result = unsafe {
+                    libc::read(
+                        fd.as_raw_fd(),
+                        dest.as_mut_ptr() as *mut libc::c_void,
+                        dest.len(),
+                    )
+                };
+                if result < 0 {
+                    Err(io::Error::last_os_error())
+                } else {
+                    Ok(result as usize)
+                }
+            });
+            match read {
+                Ok(Ok(0)) if self.filled == 0 => return Ok(None),
+                Ok(Ok(0)) => {
+                    let bytes_read = std::mem::take(&mut self.filled);
+                    return Err(io::Error::new(
+                        ErrorKind::UnexpectedEof,
+                        format!(
+                            "EOF reached mid-event (after {bytes_read} of {} bytes)",
+                            self.buf.len()
+                        ),
+                    ));
+                }
+                Ok(Ok(n)) => self.filled += n,
+                Ok(Err(e)) if e.kind() == ErrorKind::Interrupted => {}
+                Ok(Err(e)) => return Err(e),
+                // Readiness was stale (`try_io` cleared it); wait again.
+                Err(_would_block) => {}
+            }
+        }
+        self.filled = 0;
+        let mut event = MaybeUninit::<input_event>::uninit();
+        // Safety: input_event is plain old data and `buf` holds exactly its size.
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                self.buf.as_ptr(),
+                event.as_mut_ptr() as *mut u8,
+                self.buf.len(),
+            );
+            Ok(Some(event.assume_init()))
+        }
+    }
+}
+
+impl<T: AsRawFd> Drop for AsyncEventReader<T> {
+    fn drop(&mut self) {
+        let fd = self.fd.get_ref().as_raw_fd();
+        if unsafe { libc::fcntl(fd, libc::F_SETFL, self.original_flags) } < 0 {
+            warn!(error = %io::Error::last_os_error(), "Failed to restore input file flags");
+        }
+    }
+}
+
+/// Adds `O_NONBLOCK` to the file status flags of `fd`, returning the flags it
+/// had before.
+fn set_nonblocking(fd: RawFd) -> io::Result<libc::c_int> {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    if unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(flags)
+}
+
+/// Event counts of a finished [`run_main_loop_async`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AsyncLoopSummary {
+    /// Events read from the input.
+    pub events_read: u64,
+    /// Events dropped as bounces.
+    pub events_dropped: u64,
+    /// Non-key events dropped by `--passthrough-types`.
+    pub events_type_filtered: u64,
+    /// Key events with a value other than 0, 1 or 2 dropped by `--strict-values`.
+    pub invalid_values_dropped: u64,
+    /// MSC_SCAN events dropped with their key event by `--drop-orphan-scancodes`.
+    pub scancodes_dropped: u64,
+}
+
+/// Reads events from `input` until EOF, debounces them with `filter` and
+/// writes the passed events to `output`, like the binary's loop. `filter` is
+/// configured from `cfg` with [`BounceFilter::apply_config`] first, and the
+/// decisions around it (`--strict-values`, `--passthrough-types`,
+/// `--drop-orphan-scancodes`, `--synthesize-syn`) are the binary's own
+/// [`output`] functions. Dropping the future stops the loop; the filter keeps
+/// its state and can be used again.
+///
+/// `--collapse-press-release` and the statistics/logging options are not
+/// handled here.
+pub async fn run_main_loop_async<T: AsRawFd>(
+    input: T,
+    cfg: &Config,
+    filter: &mut BounceFilter,
+    mut output: impl AsyncWrite + Unpin,
+) -> io::Result<AsyncLoopSummary> {
+    filter.apply_config(cfg);
+    let mut reader = AsyncEventReader::new(input)?;
+    let mut summary = AsyncLoopSummary::default();
+    let mut router = OutputRouter::default();
+    loop {
+        let ev = match reader.read_event().await {
+            Ok(Some(ev)) => ev,
+            Ok(None) => break,
+            Err(e) if cfg.tolerate_partial && e.kind() == ErrorKind::UnexpectedEof => {
+                warn!(error = %e, "Discarding partial event at end of input (--tolerate-partial)");
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+        summary.events_read += 1;
+        if output::rejects_value(cfg, &ev) {
+            warn!(
+                code = ev.code,
+                value = ev.value,
+                "Dropping key event with invalid value (--strict-values)"
+            );
+            summary.invalid_values_dropped += 1;
+            continue;
+        }
+        let skip_debounce = output::skips_debounce(cfg, &ev);
+        let info = filter.check_event(&ev, cfg.debounce_time(), skip_debounce);
+        if info.is_bounce {
+            trace!(code = ev.code, value = ev.value, diff_us = ?info.diff_us, "Dropped bounce");
+            summary.events_dropped += 1;
+        }
+        let routed = router.route(cfg, &ev, info.is_bounce);
+        summary.scancodes_dropped += u64::from(routed.scancode_dropped);
+        summary.events_type_filtered += u64::from(routed.type_filtered);
+        for ev in [routed.scancode, routed.event, routed.syn]
+            .into_iter()
+            .flatten()
+        {
+            output.write_all(event::event_as_bytes(&ev)).await?;
+        }
+    }
+    if let Some(scan) = router.take_pending_scancode() {
+        output.write_all(event::event_as_bytes(&scan)).await?;
+    }
+    output.flush().await?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_main_loop_async, AsyncLoopSummary};
+    use crate::config::Config;
+    use crate::event::{event_as_bytes, input_event};
+    use crate::filter::BounceFilter;
+    use input_linux_sys::{timeval, EV_KEY};
+    use std::io::Write;
+    use std::os::unix::net::UnixStream;
+    use std::time::Duration;
+
+    fn key_ev(ts_us: u64, code: u16, value: i32) -> input_event {
+        input_event {
+            time: timeval {
+                tv_sec: (ts_us / 1_000_000) as i64,
+                tv_usec: (ts_us % 1_000_000) as i64,
+            },
+            type_: EV_KEY as u16,
+            code,
+            value,
+        }
+    }
+
+    #[test]
+    fn async_loop_drops_bounces_and_writes_passed_events() {
+        let cfg = Config::new(
+            Duration::from_millis(10),
+            Duration::from_millis(100),
+            Duration::ZERO,
+            false,
+            false,
+            false,
+            false,
+            "warn".to_string(),
+            None,
+            0,
+            Vec::new(),
+            Vec::new(),
+        );
+        let events = [
+            key_ev(0, 30, 1),
+            key_ev(3_000, 30, 1), // Bounce
+            key_ev(50_000, 30, 0),
+            key_ev(52_000, 30, 0), // Bounce
+        ];
+        let (input, mut writer) = UnixStream::pair().unwrap();
+        for ev in &events {
+            writer.write_all(event_as_bytes(ev)).unwrap();
+        }
+        drop(writer);
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()
+            .unwrap();
+        let mut filter = BounceFilter::new(0);
+        let mut output = Vec::new();
+        let summary = runtime
+            .block_on(run_main_loop_async(input, &cfg, &mut filter, &mut output))
+            .unwrap();
+
+        assert_eq!(
+            summary,
+            AsyncLoopSummary {
+                events_read: 4,
+                events_dropped: 2,
+                events_type_filtered: 0,
+                invalid_values_dropped: 0,
+                scancodes_dropped: 0,
+            }
+        );
+        let expected: Vec<u8> = [events[0], events[2]]
+            .iter()
+            .flat_map(|ev| event_as_bytes(ev).to_vec())
+            .collect();
+        assert_eq!(output, expected);
+    }
+}
@@ -1,4 +1,9 @@
-use clap::{ArgAction, Parser};
+use crate::filter::rule::DropRule;
+use clap::builder::{EnumValueParser, TypedValueParser};
+use clap::parser::ValueSource;
+use clap::{ArgAction, CommandFactory, Parser, ValueEnum};
+use std::ffi::OsString;
+use std::path::PathBuf;
 use std::time::Duration;
 
 /// An Interception Tools filter to eliminate keyboard chatter (switch bounce).
@@ -31,28 +36,99 @@ EXAMPLES:\n\
     DEVICE:\n\
       LINK: \"/dev/input/by-id/usb-Your_Keyboard_Name-event-kbd\" # Replace this!\n\
 \n\
-See README for more details and advanced usage."
+See README for more details and advanced usage.",
+    // A flag given more than once takes its last occurrence.
+    args_override_self = true
 )]
 pub struct Args {
-    /// Debounce time threshold (milliseconds). Duplicate key events (same keycode and value)
+    /// Debounce time threshold. Duplicate key events (same keycode and value)
     /// occurring faster than this threshold are discarded. (Default: 25ms).
     /// The "value" refers to the state of the key: `1` for press, `0` for release, `2` for repeat.
-    /// Only press and release events are debounced. Accepts values like "10ms", "0.5s".
-    #[arg(short = 't', long, default_value = "25ms", value_parser = humantime::parse_duration)]
+    /// Only press and release events are debounced. Accepts values like "10ms", "0.5s",
+    /// "750us" (kept to the exact microsecond, for very fast switches),
+    /// or a frequency such as "50hz" (minimum interval of 1/50 s = 20ms).
+    #[arg(
+        short = 't',
+        long,
+        env = "INTERCEPT_BOUNCE_DEBOUNCE_TIME",
+        default_value = "25ms",
+        value_parser = parse_debounce_time
+    )]
     pub debounce_time: Duration,
 
     // --- Logging & Statistics Options ---
     /// Threshold for logging "near-miss" events. Passed key events
     /// occurring within this time of the previous passed event are logged/counted. (Default: 100ms)
-    /// Accepts values like "100ms", "0.1s".
-    #[arg(long, default_value = "100ms", value_parser = humantime::parse_duration)]
-    pub near_miss_threshold_time: Duration,
+    /// Accepts values like "100ms", "0.1s". A comma-separated list ("30ms,60ms,120ms")
+    /// also counts near misses per band; the largest value is the overall threshold.
+    #[arg(
+        long,
+        visible_alias = "near-miss-threshold",
+        value_name = "DURATION[,DURATION...]",
+        env = "INTERCEPT_BOUNCE_NEAR_MISS",
+        default_value = "100ms",
+        value_delimiter = ',',
+        action = ArgAction::Set,
+        value_parser = humantime::parse_duration
+    )]
+    pub near_miss_threshold_time: Vec<Duration>,
 
     /// Periodically dump statistics to stderr. (Default: 15m).
     /// Set to "0" to disable periodic dumps. Accepts values like "60s", "15m", "1h".
-    #[arg(long, default_value = "15m", value_parser = humantime::parse_duration)]
+    #[arg(
+        long,
+        env = "INTERCEPT_BOUNCE_LOG_INTERVAL",
+        default_value = "15m",
+        value_parser = humantime::parse_duration
+    )]
     pub log_interval: Duration,
 
+    /// Print each periodic dump as a single line (`PERIODIC processed=.. dropped=..
+    /// drop_pct=.. top_key=..`) instead of the full statistics block. The final
+    /// cumulative report is unaffected.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub periodic_compact: bool,
+
+    /// How often the main loop, while waiting for input, and the logger thread
+    /// re-check for shutdown and periodic dumps. Smaller values make shutdown
+    /// snappier at a small CPU cost. Between 1ms and 1s. (Default: 100ms).
+    #[arg(long, default_value = "100ms", value_parser = parse_poll_interval)]
+    pub poll_interval: Duration,
+
+    /// How long shutdown waits for the logger thread to finish (e.g. a slow
+    /// OTLP flush) before exiting without its statistics, with a warning but the
+    /// usual exit status, so a wedged logger can't hang the pipeline. "0" waits
+    /// as long as it takes, so the final statistics and `--persist-state` are
+    /// never lost. (Default: 5s).
+    #[arg(long, value_name = "DURATION", default_value = "5s", value_parser = humantime::parse_duration)]
+    pub shutdown_timeout: Duration,
+
+    /// Shut down on its own, printing the final statistics as usual, once this
+    /// long has passed since startup (monotonic clock), e.g. `60s` for a
+    /// fixed-window measurement. Default: run until EOF or a signal.
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+    pub max_runtime: Option<Duration>,
+
+    /// Exit with status 5 if the run ends without a single key event, e.g. when a
+    /// misconfigured `intercept` stage hits EOF at once. Lets health checks
+    /// (udevmon, systemd) tell a dead pipeline from a clean exit.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub expect_events: bool,
+
+    /// What to do when writing an event to stdout fails: `abort` ends the run,
+    /// `skip` logs the error, drops that event and keeps going, so a transient
+    /// output hiccup doesn't leave the keyboard dead. A dropped release leaves
+    /// its key stuck down until it is pressed again. A closed pipe, or an event
+    /// written only in part, always ends the run.
+    #[arg(long, value_name = "POLICY", value_enum, default_value_t = WriteErrorPolicy::Abort)]
+    pub write_error: WriteErrorPolicy,
+
+    /// Log and count gaps of at least this long between consecutive input events
+    /// (e.g. "5s"). Gaps while a key is held are flagged as possible dropped events
+    /// (USB hiccups), as opposed to an idle keyboard. Disabled by default.
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+    pub gap_threshold: Option<Duration>,
+
     /// Log details of *every* incoming event to stderr ([PASS] or [DROP]).
     #[arg(long, action = clap::ArgAction::SetTrue)]
     pub log_all_events: bool,
@@ -61,18 +137,429 @@ pub struct Args {
     #[arg(long, action = clap::ArgAction::SetTrue)]
     pub log_bounces: bool,
 
+    /// Log a warning for every passed key press or release that was a near miss
+    /// (within `--near-miss-threshold-time` of the previous pass), without needing
+    /// `--log-all-events`: a live view of keys flirting with the debounce time.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub warn_near_miss: bool,
+
     /// List available input devices and their capabilities (requires root).
     #[arg(long, action = clap::ArgAction::SetTrue)]
     pub list_devices: bool,
 
+    /// With `--list-devices`, print the listing as a JSON array on stdout. Devices
+    /// that could not be opened or queried appear with an `error` field.
+    #[arg(long, action = clap::ArgAction::SetTrue, requires = "list_devices")]
+    pub json: bool,
+
+    /// Run a known event sequence through the filter and statistics, print
+    /// PASS/FAIL per check and exit (status 6 on failure). Needs no input.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub self_test: bool,
+
+    /// Offline: read two reports written with `--stats-json` (e.g. from separate
+    /// sessions), add up their counts, histograms and timing samples, and write
+    /// the combined report as JSON to stdout or `--output`. Needs no input.
+    #[arg(long, num_args = 2, value_names = ["A", "B"])]
+    pub merge: Vec<PathBuf>,
+
+    /// With `--merge`, write the combined report to PATH instead of stdout.
+    #[arg(short = 'o', long, value_name = "PATH", requires = "merge")]
+    pub output: Option<PathBuf>,
+
+    /// Read input_event structs from PATH (an evdev node such as /dev/input/eventX,
+    /// or a file of recorded events) instead of standard input. The device is not
+    /// grabbed, so its events still reach other readers too.
+    #[arg(long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+
+    /// Grab the `--input` device exclusively (EVIOCGRAB), as `intercept -g` does:
+    /// other readers, including your desktop session, stop receiving its events
+    /// until intercept-bounce exits. Only filtered events written to stdout remain.
+    #[arg(long, action = clap::ArgAction::SetTrue, requires = "input")]
+    pub grab: bool,
+
+    /// Instead of standard input, read the first keyboard found in /dev/input
+    /// (a device with at least 40 of the regular keyboard keys, so mice and
+    /// media-key devices are skipped) and grab it, as with `--input` and `--grab`.
+    /// Lists the other candidates if there are several. Requires root.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "input")]
+    pub auto_device: bool,
+
     /// Output statistics as JSON format to stderr on exit and periodic dump.
     #[arg(long, action = clap::ArgAction::SetTrue)]
     pub stats_json: bool,
 
+    /// At exit, also write the final statistics as JSON to PATH, whatever the
+    /// format on stderr, so operators keep the human-readable report while a
+    /// pipeline picks up the machine-readable one. Overwrites PATH.
+    #[arg(long, value_name = "PATH")]
+    pub stats_json_file: Option<PathBuf>,
+
+    /// Only filter: don't start the logger thread, so no statistics, periodic
+    /// dumps or event logging, and no per-event hand-off to another thread. For
+    /// the lowest overhead when the filter is already tuned. Can't be combined
+    /// with statistics or event logging options.
+    #[arg(
+        long,
+        action = clap::ArgAction::SetTrue,
+        conflicts_with_all = [
+            "stats_json", "stats_to_stdout", "periodic_compact", "persist_state",
+            "log_all_events", "log_bounces", "warn_near_miss", "event_json", "event_json_raw",
+            "timeline_csv", "dropped_events_file", "expect_events", "analyze",
+            "heatmap_json", "stats_stream_fd", "inspect", "measure_latency", "export_trailer",
+            "stats_json_file",
+        ]
+    )]
+    pub no_stats: bool,
+
+    /// Skip the final statistics if the run ended because the consumer of stdout
+    /// went away (broken pipe), e.g. to keep a udevmon restart loop from
+    /// flooding the logs. Statistics are still printed after EOF or a signal.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub no_stats_on_broken_pipe: bool,
+
+    /// If the logger thread panics mid-run, start a new one (once) and keep
+    /// filtering, instead of stopping with status 1. Statistics collected before
+    /// the panic are lost. For long-running daemons that should heal themselves.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub restart_logger: bool,
+
+    /// Exit with status 1 at the end of a run whose logger thread panicked. A
+    /// panic already ends the run that way on its own; with `--restart-logger`,
+    /// this keeps the status at 1 after the recovery, so monitoring still notices.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub fail_on_logger_panic: bool,
+
+    /// Encoding of the machine-readable statistics. `msgpack` writes each report
+    /// as a MessagePack map with the same fields as the JSON, prefixed by its
+    /// length (4 bytes, big-endian), and implies `--stats-json`. Best combined
+    /// with `--analyze --stats-to-stdout`, away from the log lines on stderr.
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "json",
+        value_parser = EnumValueParser::<StatsFormat>::new().try_map(check_stats_format)
+    )]
+    pub stats_format: StatsFormat,
+
+    /// Stream a CSV timeline of every processed key event to PATH, one row per
+    /// event: `event_us,key_code,key_name,value,decision,diff_us`. `decision` is
+    /// `pass`, `drop` or `near_miss`; `diff_us` is the time since the last passed
+    /// event of the same key and value. Write errors are logged and never stop filtering.
+    #[arg(long, value_name = "PATH")]
+    pub timeline_csv: Option<PathBuf>,
+
+    /// Record every dropped event to PATH as it happens, one JSON object per line
+    /// (timestamp, event type, key, value, time since the last passed event and the
+    /// debounce time in effect), as evidence of chatter. Write errors are logged
+    /// and never stop filtering.
+    #[arg(long, value_name = "PATH")]
+    pub dropped_events_file: Option<PathBuf>,
+
+    /// End `--timeline-csv` and `--dropped-events-file` with a trailer line giving
+    /// the number of records and an FNV-1a checksum of them, written at shutdown,
+    /// so a file cut short (e.g. by a killed process) can be told from a complete one.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub export_trailer: bool,
+
+    /// At exit, write a per-key chatter heatmap to PATH as JSON, for coloring a
+    /// keyboard layout: each key's drops weighted by its drop rate, normalized
+    /// to 0-1 so the hottest key is 1. Covers the persisted totals with `--persist-state`.
+    #[arg(long, value_name = "PATH")]
+    pub heatmap_json: Option<PathBuf>,
+
+    /// Push live statistics to the already open file descriptor FD (e.g. a pipe
+    /// set up by a GUI) every `--stats-stream-interval`, plus once at exit. Each
+    /// frame is a compact JSON object prefixed by its length (u32, big-endian).
+    #[arg(long, value_name = "FD")]
+    pub stats_stream_fd: Option<i32>,
+
+    /// Interval between `--stats-stream-fd` frames. Zero sends only the final frame.
+    #[arg(long, value_name = "DURATION", default_value = "1s", value_parser = humantime::parse_duration)]
+    pub stats_stream_interval: Duration,
+
+    /// Time how long the filter decision (locking the filter and checking the
+    /// event) takes for each event, on a monotonic clock, and report the min/avg/max
+    /// in the statistics. Off by default to save the clock reads.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub measure_latency: bool,
+
+    /// Treat input that ends in the middle of an event (a truncated capture, a
+    /// flaky source) as a clean end of input: the partial event is discarded with
+    /// a warning. Without it, a truncated final event is a read error (exit 1).
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub tolerate_partial: bool,
+
+    /// Drop key events whose value isn't 0 (release), 1 (press) or 2 (repeat),
+    /// with a warning, instead of passing them on and counting them as repeats.
+    /// Such values usually mean a corrupted stream.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub strict_values: bool,
+
+    /// Only write events of these types (names like `EV_SYN` or numbers,
+    /// comma-separated) to stdout; all other non-key events are dropped from the
+    /// stream, e.g. `EV_KEY,EV_SYN` for consumers that only want keys. Key events
+    /// are always debounced and written as usual. Default: all types pass.
+    #[arg(
+        long,
+        value_name = "TYPE[,TYPE...]",
+        value_delimiter = ',',
+        action = ArgAction::Set,
+        value_parser = parse_event_type
+    )]
+    pub passthrough_types: Vec<u16>,
+
+    /// Write only key events to stdout; SYN, MSC, LED and all other events are
+    /// dropped from the stream. Same as `--passthrough-types EV_KEY`.
+    #[arg(long, action = clap::ArgAction::SetTrue, conflicts_with = "passthrough_types")]
+    pub key_events_only: bool,
+
+    /// With `--key-events-only`, write a SYN_REPORT (stamped with the key
+    /// event's time) after each key event, for consumers that only act on a
+    /// report once it is terminated by a SYN.
+    #[arg(long, action = clap::ArgAction::SetTrue, requires = "key_events_only")]
+    pub synthesize_syn: bool,
+
+    /// Drop the MSC_SCAN event the kernel sends right before a key event along
+    /// with that key event when it is dropped, instead of passing on an orphaned
+    /// scancode. Each MSC_SCAN is held back until the decision on the key event
+    /// with the same timestamp is known.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub drop_orphan_scancodes: bool,
+
+    /// Analysis only: filter and collect statistics as usual, but don't write any
+    /// events to stdout. Useful for evaluating a capture or settings offline.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub analyze: bool,
+
+    /// Write statistics (final and periodic) to stdout instead of stderr.
+    /// Requires `--analyze`, since stdout otherwise carries the event stream.
+    #[arg(long, action = clap::ArgAction::SetTrue, requires = "analyze")]
+    pub stats_to_stdout: bool,
+
+    /// Offline tuning: read the whole capture, replay it once per debounce time in
+    /// the comma-separated list (e.g. "5ms,10ms,15ms,20ms") and print a table of
+    /// drop counts and rates per setting to stdout (JSON with `--stats-json`).
+    /// No events are written.
+    #[arg(
+        long,
+        value_name = "DURATION[,DURATION...]",
+        value_delimiter = ',',
+        action = ArgAction::Set,
+        value_parser = humantime::parse_duration
+    )]
+    pub sweep: Vec<Duration>,
+
+    /// Treat a key release followed within the debounce time by a press of the same
+    /// key as chatter and drop both. Releases are held back for up to the debounce
+    /// time to decide this, and emitted at once when another key's event arrives.
+    /// May swallow intentional very fast re-taps. Only this direction is collapsed:
+    /// a press followed by a quick release is debounced as usual.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub collapse_press_release: bool,
+
+    /// Custom drop decision evaluated for each debounce candidate instead of the
+    /// plain `diff_us < debounce_us` check. Variables: diff_us, debounce_us,
+    /// key_code, value, prev_bounces. Example:
+    /// `--drop-rule "diff_us < debounce_us && prev_bounces >= 2"`.
+    #[arg(long, value_name = "EXPR", value_parser = DropRule::parse)]
+    pub drop_rule: Option<DropRule>,
+
+    /// Debounce algorithm. `time` drops an event arriving within the debounce time
+    /// of the last passed one. `count` lets the first `--count-threshold` identical
+    /// events within a debounce-time window through and drops the rest, so fast
+    /// intentional double-taps survive. `burst` passes the first event of a burst
+    /// and drops the rest until the key and value have been quiet for
+    /// `--burst-gap`, however long the burst lasts. `--drop-rule` only applies to `time`.
+    #[arg(long, value_name = "MODE", value_enum, default_value_t = DebounceMode::Time)]
+    pub mode: DebounceMode,
+
+    /// Number of identical events (same key and value) allowed per debounce-time
+    /// window with `--mode count`.
+    #[arg(long, value_name = "N", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub count_threshold: u32,
+
+    /// Silence (no event of the same key and value) that ends a burst with
+    /// `--mode burst`, e.g. "30ms". Default: the debounce time.
+    #[arg(long, value_name = "DURATION", value_parser = humantime::parse_duration)]
+    pub burst_gap: Option<Duration>,
+
+    /// Let KEY pass its first N identical events (same value) per debounce-time
+    /// window before debouncing kicks in, e.g. `--lead-in 183=2` for a macro key
+    /// (KEY_F13) that is double-tapped on purpose. Count mode for just that key: the
+    /// window is `--debounce-time`, and it overrides `--count-threshold` and
+    /// `--drop-rule` for the key. Repeat the flag for more keys.
+    #[arg(long, value_name = "KEY=N", action = ArgAction::Append, value_parser = parse_lead_in)]
+    pub lead_in: Vec<(u16, u32)>,
+
+    /// Key states to debounce, comma-separated: `press`, `release` or both (the
+    /// default). States left out pass unfiltered, e.g. `--debounce-states press`
+    /// for a keyboard that only chatters on presses, so fast releases survive.
+    #[arg(
+        long,
+        value_name = "STATE[,STATE]",
+        value_enum,
+        value_delimiter = ',',
+        default_values_t = [DebounceState::Press, DebounceState::Release]
+    )]
+    pub debounce_states: Vec<DebounceState>,
+
+    /// Pass at most N key repeats per key per second and drop the rest, e.g. to
+    /// contain a failing controller's repeat storm. Dropped repeats count as drops
+    /// in the statistics. Default: unlimited.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_repeat_rate: Option<u32>,
+
+    /// Drop the first press and the first release of each key if they arrive within
+    /// `--initial-window` of startup, e.g. spurious events injected while the
+    /// virtual device is set up. With `--analyze`, the window starts at the first
+    /// event of the capture instead.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub suppress_initial: bool,
+
+    /// Startup window for `--suppress-initial`, measured from process start (event
+    /// clock).
+    #[arg(long, value_name = "DURATION", default_value = "200ms", value_parser = humantime::parse_duration)]
+    pub initial_window: Duration,
+
+    /// Drop any event identical in every field (timestamp included) to the event
+    /// right before it, a driver glitch that no real keystroke can produce.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub drop_exact_duplicates: bool,
+
+    /// Drop a key release carrying the same timestamp as the key's last passed
+    /// press, a zero-duration keypress left by a hard bounce. The key then stays
+    /// down until its next release. Devices that report a tap shorter than their
+    /// polling interval as press and release in one report lose that release too.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub collapse_zero_duration: bool,
+
+    /// Emit one JSON object per processed event (decoded fields and filter decision)
+    /// to stderr, one per line.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub event_json: bool,
+
+    /// Include the raw `input_event` bytes as a hex string (`raw_hex`) in the
+    /// `--event-json` stream. Implies `--event-json`.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub event_json_raw: bool,
+
     /// Enable verbose logging (internal state, thread startup, etc).
     #[arg(long, action = clap::ArgAction::SetTrue)]
     pub verbose: bool,
 
+    /// Also debounce relative axis events (EV_REL, e.g. scroll wheels): a delta is
+    /// dropped if one in the same direction on the same axis passed within the
+    /// debounce time. Can thin out intentional fast scrolling.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub debounce_rel: bool,
+
+    /// Flag keys whose drop rate reaches this percentage (over at least 20 events)
+    /// as likely dead or stuck switches rather than chatter, in the statistics.
+    #[arg(long, value_name = "PCT", default_value = "90", value_parser = parse_percentage)]
+    pub dead_switch_threshold: f64,
+
+    /// Colorize log output and the human-readable statistics (drop rates by severity,
+    /// histogram bars). `auto` colors only when stderr is a terminal and `NO_COLOR` is unset.
+    #[arg(long, value_name = "WHEN", value_enum, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+
+    /// Level of detail of the per-key human-readable statistics: `state` shows
+    /// Press/Release/Repeat lines under each key's total, `key` only the total line.
+    #[arg(long, value_name = "LEVEL", value_enum, default_value_t = StatsGranularity::State)]
+    pub stats_granularity: StatsGranularity,
+
+    /// Leave the per-key Repeat lines out of human-readable statistics. Repeats
+    /// are passed through unfiltered, so those lines never show any drops.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub hide_repeat_stats: bool,
+
+    /// Print press/release bounce histograms for every key in the per-key
+    /// human-readable statistics, not just the overall ones.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub per_key_histograms: bool,
+
+    /// Print per-key bounce histograms for just this key (code or name, e.g.
+    /// `KEY_A`). Repeat the flag for several keys.
+    #[arg(long = "focus-key", value_name = "KEY", action = ArgAction::Append, value_parser = parse_key_identifier)]
+    pub focus_keys: Vec<u16>,
+
+    /// Replace the human-readable statistics with a deep-dive on this one key
+    /// (code or name): per-state counts, bounce and near-miss histograms,
+    /// percentiles, first/last event and its recommended debounce time.
+    #[arg(long, value_name = "KEY", value_parser = parse_key_identifier, conflicts_with = "stats_json")]
+    pub inspect: Option<u16>,
+
+    /// Number of keys listed, most drops first, in the "Chattiest Keys" summary
+    /// that opens the statistics (and in the JSON `top_keys` array). "0" disables it.
+    #[arg(long, value_name = "N", default_value_t = 5)]
+    pub top_keys: usize,
+
+    /// Also show the cumulative percentage of timings at each histogram bucket
+    /// ("X% of bounces were below N ms"), in both human and JSON statistics.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub histogram_cumulative: bool,
+
+    /// Longest histogram bar in characters. Default: fitted to the terminal
+    /// (at most 50), or 50 if statistics don't go to a terminal.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    pub histogram_width: Option<u16>,
+
+    /// Character histogram bars are drawn with, e.g. `█`.
+    #[arg(long, value_name = "C", default_value_t = '#')]
+    pub histogram_char: char,
+
+    /// Print the histogram bucket ranges once, as a legend shared by the overall
+    /// bounce and near-miss histograms, and draw both bars on the same scale so
+    /// the two distributions can be compared directly.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub histogram_legend: bool,
+
+    /// Record the time between consecutive passed presses (and releases) of each
+    /// key, whether or not they were near misses, and report it per key and as an
+    /// overall histogram, e.g. to look at typing rhythm.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub interval_histogram: bool,
+
+    /// Number of decimal places in human-readable timings (e.g. `2` prints
+    /// `1.38 ms`). With 1 or more, times under 1 ms are printed in ms as well
+    /// (`3` prints `0.300 ms`); with 0 they stay whole µs. Default: whole µs,
+    /// one decimal for ms, three for seconds.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(0..=6))]
+    pub time_precision: Option<u8>,
+
+    /// Seed for random sampling in the statistics, reported as `sample_seed` in
+    /// JSON output. Timing samples keep the newest timings per key and state, so
+    /// they don't draw on it yet. Default: 0.
+    #[arg(long, value_name = "N")]
+    pub sample_seed: Option<u64>,
+
+    /// Keep cumulative statistics in FILE across runs: prior counts, histograms and
+    /// timing samples are loaded at startup and the merged totals saved on exit.
+    /// The file is created if it doesn't exist.
+    #[arg(long, value_name = "FILE")]
+    pub persist_state: Option<PathBuf>,
+
+    /// Show custom names for keys in human-readable statistics and log lines,
+    /// read from a TOML FILE of `KEY_NAME = "Label"` (or `code = "Label"`) entries.
+    /// Keys without an alias keep their usual name; JSON output is unchanged.
+    #[arg(long, value_name = "FILE")]
+    pub key_aliases: Option<PathBuf>,
+
+    /// Replace key names and codes in the statistics reports (human-readable and
+    /// JSON) with pseudonyms like `key_1a3`, so a report can be shared without
+    /// revealing what was typed. Counts and timings are kept. The mapping is
+    /// random per run but the same for every report within it.
+    #[arg(long, action = clap::ArgAction::SetTrue)]
+    pub anonymize: bool,
+
+    /// Read settings from a TOML file. Keys are long option names
+    /// (e.g. `debounce_time = "15ms"`); flags given on the command line take
+    /// precedence. The file is re-read on SIGHUP.
+    #[arg(long, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+
     /// Size of the ring buffer for storing recently passed events (for debugging).
     /// Set to 0 to disable. (Default: 0).
     #[arg(long, default_value = "0")]
@@ -89,14 +576,217 @@ pub struct Args {
     #[arg(long = "ignore-key", value_name = "KEY", action = ArgAction::Append, value_parser = parse_key_identifier)]
     pub ignore_keys: Vec<u16>,
 
+    /// Mirror the raw events of this key (code or name), passed and dropped
+    /// alike, to `--tap-fd`; stdout is unaffected. Narrower than a full capture
+    /// when only one key is suspect. Repeat the flag for several keys.
+    #[arg(long = "tap", value_name = "KEY", action = ArgAction::Append, value_parser = parse_key_identifier, requires = "tap_fd")]
+    pub tap_keys: Vec<u16>,
+
+    /// Already open file descriptor (e.g. `3>tap.bin` in the shell) receiving the
+    /// `--tap` keys' events as binary `input_event` structs, like stdout.
+    #[arg(long, value_name = "FD", requires = "tap_keys")]
+    pub tap_fd: Option<i32>,
+
     // --- OpenTelemetry Export ---
     /// OTLP endpoint URL for exporting traces and metrics (e.g., "http://localhost:4317").
     #[arg(long)]
     pub otel_endpoint: Option<String>,
+
+    /// Attach the key name as a `key` attribute to the OTLP processed/passed/dropped
+    /// counters, so they can be broken down per key. Adds one series per key used.
+    #[arg(long)]
+    pub otel_key_attributes: bool,
+
+    /// How often OTLP metrics are exported and batched spans sent (e.g. "5s").
+    /// The exporter defaults (60s for metrics) can swallow a short diagnostic
+    /// run; everything is flushed at shutdown regardless.
+    #[arg(long, value_name = "DURATION", requires = "otel_endpoint", value_parser = parse_otel_flush_interval)]
+    pub otel_flush_interval: Option<Duration>,
+
+    /// Exit with status 7 if OTLP export can't be set up or the endpoint doesn't
+    /// accept connections at startup, instead of warning and running without
+    /// telemetry.
+    #[arg(long, action = clap::ArgAction::SetTrue, requires = "otel_endpoint")]
+    pub otel_required: bool,
 }
 
 pub fn parse_args() -> Args {
-    Args::parse()
+    try_parse_args(std::env::args_os()).unwrap_or_else(|e| e.exit())
+}
+
+/// Debounce algorithm (`--mode`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DebounceMode {
+    Time,
+    Count,
+    Burst,
+}
+
+/// A key state that can be debounced (`--debounce-states`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DebounceState {
+    Press,
+    Release,
+}
+
+/// Encoding of machine-readable statistics (`--stats-format`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatsFormat {
+    /// Pretty-printed JSON objects.
+    Json,
+    /// Length-prefixed MessagePack maps (requires the `msgpack` build feature).
+    Msgpack,
+}
+
+/// Handling of failed writes to stdout (`--write-error`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum WriteErrorPolicy {
+    /// End the run on the first failed write.
+    Abort,
+    /// Log the failure, lose that event and continue. A lost release leaves
+    /// its key stuck down.
+    Skip,
+}
+
+/// Per-key detail in human-readable statistics (`--stats-granularity`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatsGranularity {
+    /// One combined line per key.
+    Key,
+    /// The key's combined line plus one line per key state.
+    State,
+}
+
+/// When to use ANSI colors in human-readable statistics (`--color`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+/// Parses `argv`, applying the settings of a `--config` file if one is given.
+/// Also used to re-read the configuration on SIGHUP.
+pub fn try_parse_args<I, T>(argv: I) -> Result<Args, clap::Error>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    let argv: Vec<OsString> = argv.into_iter().map(Into::into).collect();
+    let args = Args::try_parse_from(&argv)?;
+    let Some(path) = &args.config else {
+        return Ok(args);
+    };
+
+    let settings = crate::config::config_file_args(path)
+        .map_err(|msg| Args::command().error(clap::error::ErrorKind::Io, msg))?;
+    // A flag on the command line replaces the file's setting outright, repeatable
+    // options included, rather than adding to its values.
+    let command = Args::command();
+    let matches = command.clone().try_get_matches_from(&argv)?;
+    let on_command_line = |long: &str| {
+        command
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(long))
+            .is_some_and(|arg| {
+                matches.value_source(arg.get_id().as_str()) == Some(ValueSource::CommandLine)
+            })
+    };
+    let mut merged = Vec::with_capacity(argv.len());
+    merged.extend(argv.first().cloned());
+    for (long, args) in settings {
+        if !on_command_line(&long) {
+            merged.extend(args);
+        }
+    }
+    merged.extend(argv.into_iter().skip(1));
+    Args::try_parse_from(merged)
+}
+
+/// Parses the `--poll-interval` duration, limited to 1ms..=1s: shorter would
+/// busy-loop, longer would make shutdown sluggish.
+fn parse_poll_interval(value: &str) -> Result<Duration, String> {
+    let interval = humantime::parse_duration(value.trim()).map_err(|e| e.to_string())?;
+    if !(Duration::from_millis(1)..=Duration::from_secs(1)).contains(&interval) {
+        return Err(format!(
+            "Poll interval '{value}' must be between 1ms and 1s"
+        ));
+    }
+    Ok(interval)
+}
+
+/// Rejects `--stats-format msgpack` in builds without the `msgpack` feature, so
+/// the mistake surfaces while parsing (at startup or on reload) rather than at
+/// the first report.
+fn check_stats_format(format: StatsFormat) -> Result<StatsFormat, String> {
+    if format == StatsFormat::Msgpack && !cfg!(feature = "msgpack") {
+        return Err(
+            "'msgpack' needs a build with the `msgpack` feature (cargo build --features msgpack)"
+                .to_string(),
+        );
+    }
+    Ok(format)
+}
+
+fn parse_otel_flush_interval(value: &str) -> Result<Duration, String> {
+    let interval = humantime::parse_duration(value.trim()).map_err(|e| e.to_string())?;
+    if interval.is_zero() {
+        return Err(format!(
+            "OTLP flush interval '{value}' must be greater than zero"
+        ));
+    }
+    Ok(interval)
+}
+
+/// Parses a percentage between 0 and 100 (inclusive).
+fn parse_percentage(value: &str) -> Result<f64, String> {
+    let pct: f64 = value
+        .trim()
+        .trim_end_matches('%')
+        .parse()
+        .map_err(|_| format!("Invalid percentage '{value}'"))?;
+    if !(0.0..=100.0).contains(&pct) {
+        return Err(format!("Percentage '{value}' must be between 0 and 100"));
+    }
+    Ok(pct)
+}
+
+/// Parses a debounce window given either as a duration ("15ms") or as a
+/// frequency ("50hz"), which is converted to its period rounded to the microsecond.
+fn parse_debounce_time(value: &str) -> Result<Duration, String> {
+    let trimmed = value.trim();
+    let lower = trimmed.to_ascii_lowercase();
+    let Some(freq) = lower.strip_suffix("hz") else {
+        return humantime::parse_duration(trimmed).map_err(|e| e.to_string());
+    };
+
+    let hz: f64 = freq.trim().parse().map_err(|_| {
+        format!("Invalid frequency '{value}'. Expected a number followed by 'hz', like 50hz")
+    })?;
+    if !hz.is_finite() || hz <= 0.0 {
+        return Err(format!("Frequency '{value}' must be greater than zero"));
+    }
+    Ok(Duration::from_micros((1_000_000.0 / hz).round() as u64))
+}
+
+/// Parses a `--lead-in` entry, `KEY=N` with a key code or name and N >= 1.
+fn parse_lead_in(value: &str) -> Result<(u16, u32), String> {
+    let (key, count) = value
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid lead-in '{value}'. Expected KEY=N, like KEY_ENTER=2"))?;
+    let code = parse_key_identifier(key.trim())?;
+    match count.trim().parse::<u32>() {
+        Ok(count) if count >= 1 => Ok((code, count)),
+        _ => Err(format!(
+            "Invalid lead-in count in '{value}'. Expected a whole number of at least 1"
+        )),
+    }
+}
+
+fn parse_event_type(value: &str) -> Result<u16, String> {
+    crate::filter::keynames::resolve_event_type(value).ok_or_else(|| {
+        format!("Unknown event type '{value}'. Provide either a numeric type or a name like EV_SYN")
+    })
 }
 
 fn parse_key_identifier(value: &str) -> Result<u16, String> {
@@ -106,3 +796,213 @@ fn parse_key_identifier(value: &str) -> Result<u16, String> {
         )
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Args, DebounceState};
+    use crate::config::Config;
+    use clap::Parser;
+    use std::time::Duration;
+
+    fn parse(debounce: &str) -> Result<Args, clap::Error> {
+        Args::try_parse_from(["intercept-bounce", "--debounce-time", debounce])
+    }
+
+    #[test]
+    fn debounce_time_accepts_hz() {
+        let cfg = Config::from(&parse("50hz").unwrap());
+        assert_eq!(cfg.debounce_us(), 20_000);
+
+        let cfg = Config::from(&parse("2.5Hz").unwrap());
+        assert_eq!(cfg.debounce_us(), 400_000);
+    }
+
+    #[test]
+    fn debounce_time_still_accepts_durations() {
+        assert_eq!(Config::from(&parse("15ms").unwrap()).debounce_us(), 15_000);
+        assert_eq!(Config::from(&parse("0ms").unwrap()).debounce_us(), 0);
+    }
+
+    #[test]
+    fn debounce_time_accepts_microseconds() {
+        assert_eq!(Config::from(&parse("750us").unwrap()).debounce_us(), 750);
+        assert_eq!(Config::from(&parse("750µs").unwrap()).debounce_us(), 750);
+        assert_eq!(Config::from(&parse("0.75ms").unwrap()).debounce_us(), 750);
+        assert_eq!(
+            Config::from(&parse("1ms 250us").unwrap()).debounce_us(),
+            1_250
+        );
+    }
+
+    #[test]
+    fn lead_in_takes_a_key_and_a_count() {
+        let args = Args::try_parse_from([
+            "intercept-bounce",
+            "--lead-in",
+            "KEY_ENTER=2",
+            "--lead-in",
+            "183=3",
+        ])
+        .unwrap();
+        assert_eq!(args.lead_in, vec![(28, 2), (183, 3)]);
+        for bad in ["KEY_ENTER", "KEY_ENTER=0", "KEY_ENTER=x", "KEY_NOPE=2"] {
+            assert!(
+                Args::try_parse_from(["intercept-bounce", "--lead-in", bad]).is_err(),
+                "{bad}"
+            );
+        }
+    }
+
+    #[test]
+    fn debounce_states_default_to_press_and_release() {
+        let args = Args::try_parse_from(["intercept-bounce"]).unwrap();
+        assert_eq!(
+            args.debounce_states,
+            vec![DebounceState::Press, DebounceState::Release]
+        );
+        let args =
+            Args::try_parse_from(["intercept-bounce", "--debounce-states", "press"]).unwrap();
+        assert_eq!(args.debounce_states, vec![DebounceState::Press]);
+        assert!(Args::try_parse_from(["intercept-bounce", "--debounce-states", "repeat"]).is_err());
+    }
+
+    #[test]
+    fn shutdown_timeout_defaults_to_five_seconds() {
+        let args = Args::try_parse_from(["intercept-bounce"]).unwrap();
+        assert_eq!(args.shutdown_timeout, Duration::from_secs(5));
+        let args = Args::try_parse_from(["intercept-bounce", "--shutdown-timeout", "0"]).unwrap();
+        assert!(args.shutdown_timeout.is_zero());
+    }
+
+    #[test]
+    fn debounce_time_rejects_non_positive_hz() {
+        assert!(parse("0hz").is_err());
+        assert!(parse("-50hz").is_err());
+        assert!(parse("hz").is_err());
+    }
+
+    #[test]
+    fn count_threshold_only_applies_in_count_mode() {
+        let cfg = |argv: &[&str]| Config::from(&Args::try_parse_from(argv).unwrap());
+        assert_eq!(cfg(&["intercept-bounce"]).count_threshold, None);
+        assert_eq!(
+            cfg(&["intercept-bounce", "--count-threshold", "2"]).count_threshold,
+            None
+        );
+        assert_eq!(
+            cfg(&["intercept-bounce", "--mode", "count"]).count_threshold,
+            Some(1)
+        );
+        assert_eq!(
+            cfg(&[
+                "intercept-bounce",
+                "--mode",
+                "count",
+                "--count-threshold",
+                "2"
+            ])
+            .count_threshold,
+            Some(2)
+        );
+        assert!(Args::try_parse_from(["intercept-bounce", "--count-threshold", "0"]).is_err());
+    }
+
+    #[test]
+    fn burst_gap_only_applies_in_burst_mode() {
+        let cfg = |argv: &[&str]| Config::from(&Args::try_parse_from(argv).unwrap());
+        let time = cfg(&["intercept-bounce", "--burst-gap", "30ms"]);
+        assert!(!time.burst_mode);
+        let burst = cfg(&["intercept-bounce", "--mode", "burst"]);
+        assert!(burst.burst_mode);
+        assert_eq!(burst.burst_gap, None);
+        let burst = cfg(&["intercept-bounce", "--mode", "burst", "--burst-gap", "30ms"]);
+        assert_eq!(burst.burst_gap, Some(Duration::from_millis(30)));
+        assert_eq!(burst.count_threshold, None);
+    }
+
+    #[test]
+    fn tap_keys_accumulate_and_need_a_tap_fd() {
+        let argv = [
+            "intercept-bounce",
+            "--tap",
+            "KEY_B",
+            "--tap",
+            "30",
+            "--tap",
+            "KEY_B",
+            "--tap-fd",
+            "3",
+        ];
+        let cfg = Config::from(&Args::try_parse_from(argv).unwrap());
+        assert_eq!(cfg.tap_keys, [30, 48]);
+        assert_eq!(cfg.tap_fd, Some(3));
+        assert!(cfg.taps_key(48));
+        assert!(!cfg.taps_key(31));
+
+        assert!(Args::try_parse_from(["intercept-bounce", "--tap", "KEY_A"]).is_err());
+        assert!(Args::try_parse_from(["intercept-bounce", "--tap-fd", "3"]).is_err());
+    }
+
+    #[test]
+    fn drop_rule_is_compiled_at_parse_time() {
+        let args = Args::try_parse_from([
+            "intercept-bounce",
+            "--drop-rule",
+            "diff_us < debounce_us && prev_bounces >= 2",
+        ])
+        .unwrap();
+        let cfg = Config::from(&args);
+        assert_eq!(
+            cfg.drop_rule.as_ref().map(|r| r.source()),
+            Some("diff_us < debounce_us && prev_bounces >= 2")
+        );
+
+        let err = Args::try_parse_from(["intercept-bounce", "--drop-rule", "uptime > 5"])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Unknown variable 'uptime'"), "{err}");
+    }
+
+    #[test]
+    fn near_miss_threshold_accepts_a_list_of_bands() {
+        let cfg = |argv: &[&str]| Config::from(&Args::try_parse_from(argv).unwrap());
+
+        let single = cfg(&["intercept-bounce", "--near-miss-threshold", "80ms"]);
+        assert_eq!(single.near_miss_threshold_us(), 80_000);
+        assert!(single.near_miss_bands.is_empty());
+
+        let layered = cfg(&[
+            "intercept-bounce",
+            "--near-miss-threshold",
+            "120ms,30ms,60ms",
+        ]);
+        assert_eq!(layered.near_miss_threshold_us(), 120_000);
+        assert_eq!(
+            layered.near_miss_bands,
+            [30, 60, 120].map(std::time::Duration::from_millis)
+        );
+        assert_eq!(layered.near_miss_band(30_000), Some(0));
+        assert_eq!(layered.near_miss_band(30_001), Some(1));
+        assert_eq!(layered.near_miss_band(120_001), None);
+
+        // A later occurrence replaces the list rather than extending it.
+        let overridden = cfg(&[
+            "intercept-bounce",
+            "--near-miss-threshold",
+            "30ms,60ms",
+            "--near-miss-threshold",
+            "90ms",
+        ]);
+        assert_eq!(overridden.near_miss_threshold_us(), 90_000);
+        assert!(overridden.near_miss_bands.is_empty());
+    }
+
+    #[test]
+    fn poll_interval_is_range_checked() {
+        let parse_poll = |v: &str| Args::try_parse_from(["intercept-bounce", "--poll-interval", v]);
+        let cfg = Config::from(&parse_poll("5ms").unwrap());
+        assert_eq!(cfg.poll_interval, std::time::Duration::from_millis(5));
+        assert!(parse_poll("500us").is_err());
+        assert!(parse_poll("2s").is_err());
+    }
+}
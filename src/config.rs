@@ -1,3 +1,13 @@
+use crate::cli::{ColorChoice, DebounceState, StatsFormat, StatsGranularity, WriteErrorPolicy};
+use crate::filter::keynames::get_key_name;
+use crate::filter::rule::DropRule;
+use crate::filter::stats::{LoadedReport, StatsCollector, DEFAULT_HISTOGRAM_WIDTH};
+use crate::util;
+use input_linux_sys::EV_KEY;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 #[derive(Clone, Debug)]
@@ -8,13 +18,156 @@ pub struct Config {
     pub log_all_events: bool,
     pub log_bounces: bool,
     pub stats_json: bool,
+    // Encoding of machine-readable statistics (used when `stats_json` is set)
+    pub stats_format: StatsFormat,
+    // File receiving the final JSON report, whatever the stderr format
+    pub stats_json_file: Option<PathBuf>,
+    // Filter only: no logger thread, statistics or event logging
+    pub no_stats: bool,
+    // Skip the final statistics when stdout's consumer went away
+    pub no_stats_on_broken_pipe: bool,
+    // Replace a panicked logger thread once instead of stopping
+    pub restart_logger: bool,
+    // Exit nonzero after a restarted logger panic
+    pub fail_on_logger_panic: bool,
     pub verbose: bool,
     // Add log filter string
     pub log_filter: String,
     // OTLP endpoint
     pub otel_endpoint: Option<String>,
+    // Tag the main loop's OTLP event counters with the key
+    pub otel_key_attributes: bool,
+    // OTLP metric export / span batch interval (None = exporter defaults)
+    pub otel_flush_interval: Option<Duration>,
+    // Fail at startup, instead of running without telemetry, if OTLP can't be set up
+    pub otel_required: bool,
     // Ring buffer size for debugging
     pub ring_buffer_size: usize,
+    // Log a warning for each near-miss press/release pass
+    pub warn_near_miss: bool,
+    // Emit one JSON object per processed event to stderr
+    pub event_json: bool,
+    // Include the raw event bytes (hex) in event JSON
+    pub event_json_raw: bool,
+    // Treat a release followed quickly by a re-press of the same key as chatter
+    pub collapse_press_release: bool,
+    // Custom drop decision replacing the plain debounce window check
+    pub drop_rule: Option<DropRule>,
+    // Startup window in which the first press/release per key is dropped (`--suppress-initial`)
+    pub initial_window: Option<Duration>,
+    // Drop events identical (timestamp included) to the preceding one
+    pub drop_exact_duplicates: bool,
+    // Drop releases with the same timestamp as the key's last passed press
+    pub collapse_zero_duration: bool,
+    // Events allowed per key/value and debounce window (`--mode count`); None = time mode
+    pub count_threshold: Option<u32>,
+    // `--mode burst`: drop the rest of a burst after its first event, and the
+    // silence that ends a burst (None = the debounce time)
+    pub burst_mode: bool,
+    pub burst_gap: Option<Duration>,
+    // Key repeats passed per key per second (`--max-repeat-rate`). None = unlimited.
+    pub max_repeat_rate: Option<u32>,
+    // Per-key events allowed per debounce window (`--lead-in KEY=N`)
+    pub lead_in: Vec<(u16, u32)>,
+    // Key states subject to debouncing (`--debounce-states`); the others pass unfiltered
+    pub debounce_states: Vec<DebounceState>,
+    // Per-key detail in human-readable stats (key totals only, or per state too)
+    pub stats_granularity: StatsGranularity,
+    // Omit the (always drop-free) Repeat lines from human-readable per-key stats
+    pub hide_repeat_stats: bool,
+    // Upper bounds of the near-miss bands, ascending (empty = single threshold)
+    pub near_miss_bands: Vec<Duration>,
+    // Per-key bounce histograms for all keys, or only the focused ones
+    pub per_key_histograms: bool,
+    pub focus_keys: Vec<u16>,
+    // Single key reported on instead of the full human-readable stats (`--inspect`)
+    pub inspect_key: Option<u16>,
+    // Length of the leading "chattiest keys" ranking (0 = omitted)
+    pub top_keys: usize,
+    // One-line periodic dumps instead of full statistics blocks
+    pub periodic_compact: bool,
+    // Print cumulative percentages alongside histogram buckets
+    pub histogram_cumulative: bool,
+    // Shared bucket legend and bar scale for the overall histograms
+    pub histogram_legend: bool,
+    // Longest histogram bar (`--histogram-width`, or fitted to the terminal)
+    pub histogram_width: usize,
+    // Character histogram bars are drawn with
+    pub histogram_char: char,
+    // Histogram of intervals between passed presses/releases per key state
+    pub interval_histogram: bool,
+    // Decimal places for human-readable timings (None = built-in defaults)
+    pub time_precision: Option<usize>,
+    // Seed for random sampling in the statistics (None = `DEFAULT_SAMPLE_SEED`)
+    pub sample_seed: Option<u64>,
+    // File that carries cumulative statistics across runs
+    pub persist_state: Option<PathBuf>,
+    // File of custom key display names (`--key-aliases`), loaded at startup
+    pub key_aliases_file: Option<PathBuf>,
+    // Display names from `key_aliases_file` by key code
+    pub key_aliases: HashMap<u16, String>,
+    // Replace key names and codes in statistics reports with pseudonyms
+    pub anonymize: bool,
+    // Pseudonymous code for each real key code, drawn at startup if `anonymize` is set
+    pub key_pseudonyms: Vec<u16>,
+    // Per-key drop percentage at which a key is reported as a likely dead switch
+    pub dead_switch_threshold: f64,
+    // Debounce same-direction EV_REL deltas (scroll wheel chatter)
+    pub debounce_rel: bool,
+    // Re-check interval for the main loop after EINTR and the logger's receive timeout
+    pub poll_interval: Duration,
+    // Bound on waiting for the logger thread at shutdown (zero = wait indefinitely)
+    pub shutdown_timeout: Duration,
+    // Stop by itself this long after startup
+    pub max_runtime: Option<Duration>,
+    // Fail (exit 5) if the run saw no key events at all
+    pub expect_events: bool,
+    // End the run, or skip the event, when a write to stdout fails
+    pub write_error: WriteErrorPolicy,
+    // Use ANSI colors in human-readable statistics (`--color`, resolved at startup)
+    pub color: bool,
+    // Report silences in the event stream at least this long (None = disabled)
+    pub gap_threshold: Option<Duration>,
+    // CSV file receiving a row per processed key event
+    pub timeline_csv: Option<PathBuf>,
+    // JSON Lines file receiving every dropped event
+    pub dropped_events_file: Option<PathBuf>,
+    // Close the export files with a record count and checksum line
+    pub export_trailer: bool,
+    // Per-key chatter heatmap written at exit
+    pub heatmap_json: Option<PathBuf>,
+    // File descriptor receiving length-prefixed live statistics frames
+    pub stats_stream_fd: Option<i32>,
+    // Interval between statistics frames on `stats_stream_fd`
+    pub stats_stream_interval: Duration,
+    // Keys whose raw events are mirrored to `tap_fd` (sorted, deduplicated)
+    pub tap_keys: Vec<u16>,
+    // File descriptor receiving the tapped keys' events
+    pub tap_fd: Option<i32>,
+    // Device or file to read events from instead of stdin
+    pub input: Option<PathBuf>,
+    // Exclusively grab the --input device for the whole run
+    pub grab: bool,
+    // Pick and grab a keyboard from /dev/input at startup instead of reading stdin
+    pub auto_device: bool,
+    // Time the filter decision of each event and report it in the statistics
+    pub measure_latency: bool,
+    // End input cleanly, instead of failing, on EOF in the middle of an event
+    pub tolerate_partial: bool,
+    // Drop key events with a value other than 0, 1 or 2
+    pub strict_values: bool,
+    // Drop the MSC_SCAN paired with a dropped key event
+    pub drop_orphan_scancodes: bool,
+    // Non-key event types written to stdout, sorted (empty = all types)
+    pub passthrough_types: Vec<u16>,
+    // Write a SYN_REPORT after each key event written (with --key-events-only)
+    pub synthesize_syn: bool,
+    // Don't write events to stdout; only filter and collect statistics
+    pub analyze: bool,
+    // Write statistics to stdout instead of stderr (requires `analyze`)
+    pub stats_to_stdout: bool,
+    // Debounce times to compare offline instead of filtering (`--sweep`; empty = off)
+    pub sweep: Vec<Duration>,
     debounce_keys: Vec<u16>,
     ignored_keys: Vec<u16>,
 }
@@ -49,10 +202,82 @@ impl Config {
             log_all_events,
             log_bounces,
             stats_json,
+            stats_format: StatsFormat::Json,
+            stats_json_file: None,
+            no_stats: false,
+            no_stats_on_broken_pipe: false,
+            restart_logger: false,
+            fail_on_logger_panic: false,
             verbose,
             log_filter,
             otel_endpoint,
+            otel_key_attributes: false,
+            otel_flush_interval: None,
+            otel_required: false,
             ring_buffer_size,
+            warn_near_miss: false,
+            event_json: false,
+            event_json_raw: false,
+            collapse_press_release: false,
+            drop_rule: None,
+            count_threshold: None,
+            burst_mode: false,
+            burst_gap: None,
+            max_repeat_rate: None,
+            lead_in: Vec::new(),
+            debounce_states: vec![DebounceState::Press, DebounceState::Release],
+            initial_window: None,
+            drop_exact_duplicates: false,
+            collapse_zero_duration: false,
+            stats_granularity: StatsGranularity::State,
+            hide_repeat_stats: false,
+            near_miss_bands: Vec::new(),
+            per_key_histograms: false,
+            focus_keys: Vec::new(),
+            inspect_key: None,
+            top_keys: 5,
+            periodic_compact: false,
+            histogram_cumulative: false,
+            histogram_width: DEFAULT_HISTOGRAM_WIDTH,
+            histogram_char: '#',
+            histogram_legend: false,
+            interval_histogram: false,
+            time_precision: None,
+            sample_seed: None,
+            persist_state: None,
+            key_aliases_file: None,
+            key_aliases: HashMap::new(),
+            anonymize: false,
+            key_pseudonyms: Vec::new(),
+            dead_switch_threshold: 90.0,
+            debounce_rel: false,
+            poll_interval: Duration::from_millis(100),
+            shutdown_timeout: Duration::from_secs(5),
+            max_runtime: None,
+            expect_events: false,
+            write_error: WriteErrorPolicy::Abort,
+            color: false,
+            gap_threshold: None,
+            timeline_csv: None,
+            dropped_events_file: None,
+            export_trailer: false,
+            heatmap_json: None,
+            stats_stream_fd: None,
+            stats_stream_interval: Duration::from_secs(1),
+            tap_keys: Vec::new(),
+            tap_fd: None,
+            input: None,
+            grab: false,
+            auto_device: false,
+            measure_latency: false,
+            tolerate_partial: false,
+            strict_values: false,
+            drop_orphan_scancodes: false,
+            passthrough_types: Vec::new(),
+            synthesize_syn: false,
+            analyze: false,
+            stats_to_stdout: false,
+            sweep: Vec::new(),
             debounce_keys,
             ignored_keys,
         }
@@ -69,6 +294,19 @@ impl Config {
         self.log_interval
     }
 
+    /// Copy of this configuration with the settings a loaded stats report was
+    /// collected with, so a `--merge` report states them rather than the defaults.
+    #[must_use]
+    pub fn with_report_settings(&self, report: &LoadedReport) -> Self {
+        let mut cfg = self.clone();
+        cfg.debounce_time = report.debounce_time;
+        cfg.near_miss_threshold = report.near_miss_threshold;
+        cfg.near_miss_bands = report.near_miss_bands.clone();
+        cfg.log_interval = report.log_interval;
+        cfg.interval_histogram = report.interval_histogram;
+        cfg
+    }
+
     pub fn ignored_keys(&self) -> &[u16] {
         &self.ignored_keys
     }
@@ -85,10 +323,36 @@ impl Config {
         self.ignored_keys.binary_search(&key_code).is_err()
     }
 
+    /// Whether events of this type may be written to stdout (`--passthrough-types`).
+    /// Key events are always subject to the filter alone.
+    pub fn passes_event_type(&self, type_: u16) -> bool {
+        self.passthrough_types.is_empty()
+            || i32::from(type_) == EV_KEY
+            || self.passthrough_types.binary_search(&type_).is_ok()
+    }
+
+    /// Name shown for a key in human-readable output: its `--key-aliases` entry,
+    /// or the canonical name.
+    pub fn key_name(&self, key_code: u16) -> &str {
+        self.key_aliases
+            .get(&key_code)
+            .map_or_else(|| get_key_name(key_code), String::as_str)
+    }
+
     pub fn is_key_ignored(&self, key_code: u16) -> bool {
         !self.should_debounce(key_code)
     }
 
+    /// Whether events of this key are mirrored to `--tap-fd`.
+    pub fn taps_key(&self, key_code: u16) -> bool {
+        self.tap_keys.binary_search(&key_code).is_ok()
+    }
+
+    /// Whether the human-readable stats include bounce histograms for this key.
+    pub fn shows_key_histogram(&self, key_code: u16) -> bool {
+        self.per_key_histograms || self.focus_keys.contains(&key_code)
+    }
+
     // Provide accessor methods that return u64 microseconds for internal use
     pub fn debounce_us(&self) -> u64 {
         self.debounce_time
@@ -102,11 +366,74 @@ impl Config {
             .try_into()
             .unwrap_or(u64::MAX)
     }
+    /// Index of the near-miss band `diff_us` falls into: the first band whose
+    /// upper bound is at least `diff_us`. `None` without bands or past the last one.
+    pub fn near_miss_band(&self, diff_us: u64) -> Option<usize> {
+        self.near_miss_bands
+            .iter()
+            .position(|band| diff_us as u128 <= band.as_micros())
+    }
+
     pub fn log_interval_us(&self) -> u64 {
         self.log_interval.as_micros().try_into().unwrap_or(u64::MAX)
     }
 }
 
+/// Reads a TOML config file and converts its settings into command-line arguments,
+/// so they go through exactly the same parsing and validation as real flags.
+/// Returns each setting's long option name (`debounce-time`) with its arguments.
+///
+/// Keys are long option names, with `_` or `-` (`debounce_time = "15ms"`). Booleans
+/// enable a flag when `true`, arrays repeat the flag (`debounce_key = ["KEY_A", 30]`).
+pub fn config_file_args(path: &Path) -> Result<Vec<(String, Vec<OsString>)>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read config file {}: {e}", path.display()))?;
+    let table: toml::Table = text
+        .parse()
+        .map_err(|e| format!("Invalid config file {}: {e}", path.display()))?;
+
+    let scalar = |key: &str, value: &toml::Value| -> Result<String, String> {
+        match value {
+            toml::Value::String(s) => Ok(s.clone()),
+            toml::Value::Integer(n) => Ok(n.to_string()),
+            toml::Value::Float(f) => Ok(f.to_string()),
+            _ => Err(format!(
+                "Unsupported value for '{key}' in config file {}",
+                path.display()
+            )),
+        }
+    };
+
+    let mut settings = Vec::new();
+    for (key, value) in &table {
+        if key == "config" {
+            return Err(format!(
+                "Config file {} cannot reference another config file",
+                path.display()
+            ));
+        }
+        let long = key.replace('_', "-");
+        let flag = format!("--{long}");
+        let mut args = Vec::new();
+        match value {
+            toml::Value::Boolean(true) => args.push(OsString::from(flag)),
+            toml::Value::Boolean(false) => {}
+            toml::Value::Array(items) => {
+                for item in items {
+                    args.push(OsString::from(&flag));
+                    args.push(OsString::from(scalar(key, item)?));
+                }
+            }
+            other => {
+                args.push(OsString::from(flag));
+                args.push(OsString::from(scalar(key, other)?));
+            }
+        }
+        settings.push((long, args));
+    }
+    Ok(settings)
+}
+
 impl From<&crate::cli::Args> for Config {
     fn from(a: &crate::cli::Args) -> Self {
         // Determine default log filter based on verbosity
@@ -119,9 +446,14 @@ impl From<&crate::cli::Args> for Config {
         let log_filter =
             std::env::var("RUST_LOG").unwrap_or_else(|_| default_log_filter.to_string()); // Keep to_string
 
-        Config::new(
+        // Several thresholds form bands; the outermost one is the near-miss cutoff.
+        let mut near_miss_bands = a.near_miss_threshold_time.clone();
+        near_miss_bands.sort_unstable();
+        near_miss_bands.dedup();
+
+        let mut cfg = Config::new(
             a.debounce_time,
-            a.near_miss_threshold_time,
+            near_miss_bands.last().copied().unwrap_or_default(),
             a.log_interval,
             a.log_all_events,
             a.log_bounces,
@@ -132,13 +464,111 @@ impl From<&crate::cli::Args> for Config {
             a.ring_buffer_size,
             a.debounce_keys.clone(),
             a.ignore_keys.clone(),
-        )
+        );
+        cfg.event_json = a.event_json || a.event_json_raw;
+        cfg.event_json_raw = a.event_json_raw;
+        cfg.otel_key_attributes = a.otel_key_attributes;
+        cfg.otel_flush_interval = a.otel_flush_interval;
+        cfg.otel_required = a.otel_required;
+        cfg.collapse_press_release = a.collapse_press_release;
+        cfg.drop_rule = a.drop_rule.clone();
+        cfg.initial_window = a.suppress_initial.then_some(a.initial_window);
+        cfg.drop_exact_duplicates = a.drop_exact_duplicates;
+        cfg.collapse_zero_duration = a.collapse_zero_duration;
+        cfg.max_repeat_rate = a.max_repeat_rate;
+        cfg.lead_in = a.lead_in.clone();
+        cfg.debounce_states = a.debounce_states.clone();
+        cfg.count_threshold = match a.mode {
+            crate::cli::DebounceMode::Count => Some(a.count_threshold),
+            crate::cli::DebounceMode::Time | crate::cli::DebounceMode::Burst => None,
+        };
+        cfg.burst_mode = a.mode == crate::cli::DebounceMode::Burst;
+        cfg.burst_gap = a.burst_gap;
+        cfg.stats_granularity = a.stats_granularity;
+        cfg.hide_repeat_stats = a.hide_repeat_stats;
+        if near_miss_bands.len() > 1 {
+            cfg.near_miss_bands = near_miss_bands;
+        }
+        cfg.per_key_histograms = a.per_key_histograms;
+        cfg.focus_keys = a.focus_keys.clone();
+        cfg.inspect_key = a.inspect;
+        cfg.top_keys = a.top_keys;
+        cfg.periodic_compact = a.periodic_compact;
+        cfg.histogram_cumulative = a.histogram_cumulative;
+        cfg.histogram_width = a.histogram_width.map_or_else(
+            || {
+                let stats_fd = if a.stats_to_stdout { 1 } else { 2 };
+                util::terminal_columns(stats_fd).map_or(DEFAULT_HISTOGRAM_WIDTH, |columns| {
+                    StatsCollector::histogram_width_for_columns(columns, a.histogram_cumulative)
+                })
+            },
+            usize::from,
+        );
+        cfg.histogram_char = a.histogram_char;
+        cfg.histogram_legend = a.histogram_legend;
+        cfg.interval_histogram = a.interval_histogram;
+        cfg.time_precision = a.time_precision.map(usize::from);
+        cfg.sample_seed = a.sample_seed;
+        cfg.persist_state = a.persist_state.clone();
+        cfg.key_aliases_file = a.key_aliases.clone();
+        cfg.anonymize = a.anonymize;
+        cfg.dead_switch_threshold = a.dead_switch_threshold;
+        cfg.debounce_rel = a.debounce_rel;
+        cfg.poll_interval = a.poll_interval;
+        cfg.shutdown_timeout = a.shutdown_timeout;
+        cfg.max_runtime = a.max_runtime;
+        cfg.expect_events = a.expect_events;
+        cfg.write_error = a.write_error;
+        cfg.gap_threshold = a.gap_threshold;
+        cfg.timeline_csv = a.timeline_csv.clone();
+        cfg.dropped_events_file = a.dropped_events_file.clone();
+        cfg.export_trailer = a.export_trailer;
+        cfg.heatmap_json = a.heatmap_json.clone();
+        cfg.stats_stream_fd = a.stats_stream_fd;
+        cfg.stats_stream_interval = a.stats_stream_interval;
+        cfg.tap_keys = a.tap_keys.clone();
+        cfg.tap_keys.sort_unstable();
+        cfg.tap_keys.dedup();
+        cfg.tap_fd = a.tap_fd;
+        cfg.input = a.input.clone();
+        cfg.grab = a.grab;
+        cfg.auto_device = a.auto_device;
+        cfg.measure_latency = a.measure_latency;
+        cfg.analyze = a.analyze;
+        cfg.stats_to_stdout = a.stats_to_stdout;
+        cfg.tolerate_partial = a.tolerate_partial;
+        cfg.warn_near_miss = a.warn_near_miss;
+        cfg.stats_format = a.stats_format;
+        cfg.stats_json_file = a.stats_json_file.clone();
+        cfg.no_stats = a.no_stats;
+        cfg.no_stats_on_broken_pipe = a.no_stats_on_broken_pipe;
+        cfg.restart_logger = a.restart_logger;
+        cfg.fail_on_logger_panic = a.fail_on_logger_panic;
+        cfg.stats_json |= a.stats_format == StatsFormat::Msgpack;
+        cfg.strict_values = a.strict_values;
+        cfg.drop_orphan_scancodes = a.drop_orphan_scancodes;
+        cfg.passthrough_types = a.passthrough_types.clone();
+        cfg.passthrough_types.sort_unstable();
+        cfg.passthrough_types.dedup();
+        if a.key_events_only {
+            cfg.passthrough_types = vec![EV_KEY as u16];
+        }
+        cfg.synthesize_syn = a.synthesize_syn;
+        cfg.sweep = a.sweep.clone();
+        cfg.color = match a.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+            }
+        };
+        cfg
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Config;
+    use super::{config_file_args, Config};
     use std::time::Duration;
 
     fn base_config() -> Config {
@@ -234,4 +664,106 @@ mod tests {
         assert!(cfg.should_debounce(0));
         assert!(cfg.should_debounce(u16::MAX));
     }
+
+    #[test]
+    fn config_file_settings_become_arguments() {
+        let path = test_helpers::temp_path("config-test.toml");
+        std::fs::write(
+            &path,
+            "debounce_time = \"15ms\"\nlog-bounces = true\nstats_json = false\ndebounce_key = [\"KEY_A\", 48]\n",
+        )
+        .unwrap();
+        let args = config_file_args(&path);
+
+        let args: Vec<String> = args
+            .unwrap()
+            .into_iter()
+            .flat_map(|(_, args)| args)
+            .map(|a| a.into_string().unwrap())
+            .collect();
+        // toml::Table keeps keys sorted.
+        assert_eq!(
+            args,
+            [
+                "--debounce-key",
+                "KEY_A",
+                "--debounce-key",
+                "48",
+                "--debounce-time",
+                "15ms",
+                "--log-bounces",
+            ]
+        );
+    }
+
+    #[test]
+    fn command_line_overrides_config_file() {
+        let path = test_helpers::temp_path("config-precedence.toml");
+        std::fs::write(
+            &path,
+            "debounce_time = \"15ms\"\nnear_miss_threshold_time = \"80ms\"\n",
+        )
+        .unwrap();
+        let path_str = path.to_str().unwrap().to_string();
+        let args = crate::cli::try_parse_args([
+            "intercept-bounce",
+            "--config",
+            &path_str,
+            "--debounce-time",
+            "30ms",
+        ]);
+
+        let cfg = Config::from(&args.unwrap());
+        assert_eq!(cfg.debounce_us(), 30_000);
+        assert_eq!(cfg.near_miss_threshold_us(), 80_000);
+    }
+
+    #[test]
+    fn command_line_replaces_repeatable_config_file_options() {
+        let path = test_helpers::temp_path("config-repeatable.toml");
+        std::fs::write(&path, "ignore_key = [46]\ndebounce_key = [30, 31]\n").unwrap();
+        let path_str = path.to_str().unwrap().to_string();
+        let args = crate::cli::try_parse_args([
+            "intercept-bounce",
+            "--config",
+            &path_str,
+            "--ignore-key",
+            "48",
+        ]);
+
+        let cfg = Config::from(&args.unwrap());
+        assert_eq!(cfg.ignored_keys(), [48]);
+        // Options not given on the command line keep the file's values.
+        assert_eq!(cfg.debounce_keys(), [30, 31]);
+    }
+
+    #[test]
+    fn otel_flush_interval_is_applied() {
+        let parse = |interval: &str| {
+            crate::cli::try_parse_args([
+                "intercept-bounce",
+                "--otel-endpoint",
+                "http://localhost:4317",
+                "--otel-flush-interval",
+                interval,
+            ])
+        };
+        let cfg = Config::from(&parse("2s").unwrap());
+        assert_eq!(cfg.otel_flush_interval, Some(Duration::from_secs(2)));
+        assert!(parse("0s").is_err());
+
+        let cfg = Config::from(
+            &crate::cli::try_parse_args(["intercept-bounce", "--otel-endpoint", "http://x"])
+                .unwrap(),
+        );
+        assert_eq!(cfg.otel_flush_interval, None);
+    }
+
+    #[test]
+    fn config_file_errors_are_reported() {
+        let missing = std::env::temp_dir().join("intercept-bounce-no-such-config.toml");
+        assert!(config_file_args(&missing)
+            .unwrap_err()
+            .contains("Cannot read config file"));
+    }
 }
@@ -0,0 +1,140 @@
+//! Application-level errors and their mapping to process exit codes.
+
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Errors that end the program early. Each variant maps to one of the exit
+/// codes documented in the man page's EXIT STATUS section.
+#[derive(Debug)]
+pub enum AppError {
+    /// `--list-devices` or `--auto-device` could not scan `/dev/input`.
+    DeviceList(io::Error),
+    /// `--auto-device` found no keyboard among the readable input devices.
+    NoKeyboard,
+    /// Standard input is an interactive terminal rather than an event stream.
+    StdinIsTerminal,
+    /// The `--input` device or file could not be opened.
+    Input(PathBuf, io::Error),
+    /// The `--input` device could not be grabbed (`--grab`), e.g. because it is busy.
+    Grab(PathBuf, io::Error),
+    /// Startup configuration could not be used, e.g. an unreadable `--persist-state` file.
+    Config(String),
+    /// Reading events from stdin or writing them to stdout failed.
+    Io(io::Error),
+    /// `--expect-events` was given, but the run ended without a single key event.
+    NoEvents,
+    /// The logger thread could not be started, disconnected or panicked.
+    LoggerThread(String),
+    /// The configured OpenTelemetry exporter could not be initialized (`--otel-required`).
+    OtelInit(String),
+    /// `--self-test` found this many failed checks.
+    SelfTest(usize),
+}
+
+impl AppError {
+    /// The process exit code for this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::Io(_)
+            | AppError::Input(..)
+            | AppError::Grab(..)
+            | AppError::LoggerThread(_) => 1,
+            AppError::DeviceList(_) | AppError::NoKeyboard => 2,
+            AppError::StdinIsTerminal => 3,
+            AppError::Config(_) => 4,
+            AppError::NoEvents => 5,
+            AppError::SelfTest(_) => 6,
+            AppError::OtelInit(_) => 7,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::DeviceList(e) => write!(f, "Error listing devices: {e}"),
+            AppError::NoKeyboard => write!(
+                f,
+                "No keyboard found for --auto-device (see --list-devices; reading /dev/input usually requires root)"
+            ),
+            AppError::StdinIsTerminal => write!(
+                f,
+                "Standard input is a terminal; expected a stream of input_event structs."
+            ),
+            AppError::Input(path, e) => write!(f, "Cannot open input {}: {e}", path.display()),
+            AppError::Grab(path, e) => write!(
+                f,
+                "Cannot grab {} (is another program such as intercept grabbing it?): {e}",
+                path.display()
+            ),
+            AppError::Config(msg) => write!(f, "{msg}"),
+            AppError::Io(e) => write!(f, "I/O error: {e}"),
+            AppError::NoEvents => write!(
+                f,
+                "No key events were received (--expect-events); is the input pipeline connected to a keyboard?"
+            ),
+            AppError::LoggerThread(msg) => write!(f, "Logger thread error: {msg}"),
+            AppError::OtelInit(msg) => write!(f, "Failed to initialize OpenTelemetry: {msg}"),
+            AppError::SelfTest(failed) => write!(f, "Self-test failed: {failed} check(s) failed"),
+        }
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppError::DeviceList(e)
+            | AppError::Io(e)
+            | AppError::Input(_, e)
+            | AppError::Grab(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(e: io::Error) -> Self {
+        AppError::Io(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AppError;
+    use std::error::Error;
+    use std::io;
+
+    fn io_error() -> io::Error {
+        io::Error::new(io::ErrorKind::PermissionDenied, "denied")
+    }
+
+    #[test]
+    fn each_variant_maps_to_documented_exit_code() {
+        assert_eq!(AppError::Io(io_error()).exit_code(), 1);
+        assert_eq!(
+            AppError::Input("/dev/input/event0".into(), io_error()).exit_code(),
+            1
+        );
+        assert_eq!(AppError::LoggerThread("panicked".into()).exit_code(), 1);
+        assert_eq!(
+            AppError::Grab("/dev/input/event0".into(), io_error()).exit_code(),
+            1
+        );
+        assert_eq!(AppError::DeviceList(io_error()).exit_code(), 2);
+        assert_eq!(AppError::NoKeyboard.exit_code(), 2);
+        assert_eq!(AppError::StdinIsTerminal.exit_code(), 3);
+        assert_eq!(AppError::Config("bad state file".into()).exit_code(), 4);
+        assert_eq!(AppError::NoEvents.exit_code(), 5);
+        assert_eq!(AppError::SelfTest(1).exit_code(), 6);
+        assert_eq!(AppError::OtelInit("no endpoint".into()).exit_code(), 7);
+    }
+
+    #[test]
+    fn io_errors_convert_and_keep_their_source() {
+        let err: AppError = io_error().into();
+        assert!(matches!(err, AppError::Io(_)));
+        assert_eq!(err.source().unwrap().to_string(), "denied");
+        assert!(AppError::StdinIsTerminal.source().is_none());
+    }
+}
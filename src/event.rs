@@ -1,15 +1,28 @@
-use input_linux_sys::{EV_ABS, EV_KEY, EV_LED, EV_MAX, EV_MSC, EV_REL, EV_REP, EV_SYN};
+use input_linux_sys::{
+    EV_ABS, EV_KEY, EV_LED, EV_MAX, EV_MSC, EV_REL, EV_REP, EV_SYN, KEY_ESC, KEY_KPDOT, KEY_MAX,
+};
 // Re-export input_event publicly
 pub use input_linux_sys::input_event;
 
 use libc::{self, c_ulong, ioctl};
+use serde::Serialize;
 use std::fs::{self, OpenOptions};
-use std::io::{self, ErrorKind};
+use std::io::{self, ErrorKind, Write};
 use std::mem::{size_of, MaybeUninit};
 use std::os::unix::fs::OpenOptionsExt;
 use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::{Path, PathBuf};
 use tracing::warn;
 
+// `input_event` must have no padding bytes: passed events are copied by value
+// and written back out, and a copy is only guaranteed to preserve the bytes of
+// its fields. Without padding, stdout carries exactly the bytes read from stdin.
+const _: () = assert!(
+    size_of::<libc::timeval>() == size_of::<libc::time_t>() + size_of::<libc::suseconds_t>()
+        && size_of::<input_event>()
+            == size_of::<libc::timeval>() + 2 * size_of::<u16>() + size_of::<i32>()
+);
+
 /// Reads exactly one `input_event` directly from a raw file descriptor using `libc::read`.
 ///
 /// Handles partial reads by retrying internally.
@@ -37,7 +50,9 @@ pub fn read_event_raw(fd: RawFd) -> io::Result<Option<input_event>> {
                 } else {
                     return Err(io::Error::new(
                         ErrorKind::UnexpectedEof,
-                        "EOF reached mid-event",
+                        format!(
+                            "EOF reached mid-event (after {bytes_read} of {total_bytes} bytes)"
+                        ),
                     ));
                 }
             }
@@ -56,14 +71,23 @@ pub fn read_event_raw(fd: RawFd) -> io::Result<Option<input_event>> {
 
 /// Writes a single `input_event` directly to a raw file descriptor using `libc::write`.
 ///
+/// The event's in-memory bytes are written as-is, so an event read with
+/// `read_event_raw` goes out byte-identical.
+///
 /// Handles partial writes and EINTR signals by retrying.
 /// Returns `Err` on I/O errors.
 pub fn write_event_raw(fd: RawFd, event: &input_event) -> io::Result<()> {
+    write_event_raw_counted(fd, event).map_err(|(e, _)| e)
+}
+
+/// Like [`write_event_raw`], but a failure also reports how many bytes of the
+/// event had already been written. Anything but 0 leaves a torn event in the
+/// stream, misaligning every event after it.
+pub fn write_event_raw_counted(fd: RawFd, event: &input_event) -> Result<(), (io::Error, usize)> {
     let total_bytes = size_of::<input_event>();
     let mut bytes_written = 0;
 
-    let buf: &[u8] =
-        unsafe { std::slice::from_raw_parts(event as *const _ as *const u8, total_bytes) };
+    let buf = event_as_bytes(event);
 
     while bytes_written < total_bytes {
         let result = unsafe {
@@ -78,26 +102,44 @@ pub fn write_event_raw(fd: RawFd, event: &input_event) -> io::Result<()> {
             -1 => {
                 let err = io::Error::last_os_error();
                 if err.kind() != ErrorKind::Interrupted {
-                    return Err(err);
+                    return Err((err, bytes_written));
                 }
             }
             0 => {
-                return Err(io::Error::new(
-                    ErrorKind::WriteZero,
-                    "libc::write returned 0",
+                return Err((
+                    io::Error::new(ErrorKind::WriteZero, "libc::write returned 0"),
+                    bytes_written,
                 ));
             }
             n if n > 0 => {
                 bytes_written += n as usize;
             }
             _ => {
-                return Err(io::Error::other("libc::write returned unexpected value"));
+                return Err((
+                    io::Error::other("libc::write returned unexpected value"),
+                    bytes_written,
+                ));
             }
         }
     }
     Ok(())
 }
 
+/// Returns the raw in-memory bytes of an `input_event`, as read from or written
+/// to an event stream.
+pub fn event_as_bytes(event: &input_event) -> &[u8] {
+    // Safety: input_event is a plain-old-data struct without padding; we only read its bytes.
+    unsafe { std::slice::from_raw_parts(event as *const _ as *const u8, size_of::<input_event>()) }
+}
+
+/// Returns the raw in-memory bytes of an `input_event` as a lowercase hex string.
+pub fn event_to_hex(event: &input_event) -> String {
+    event_as_bytes(event)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
 /// Calculates the event timestamp in microseconds from its timeval struct.
 /// Returns `u64::MAX` if the calculation overflows.
 #[inline]
@@ -115,12 +157,92 @@ pub fn is_key_event(event: &input_event) -> bool {
     i32::from(event.type_) == EV_KEY
 }
 
-/// Lists available input devices and their capabilities. Requires root privileges.
-pub fn list_input_devices() -> io::Result<()> {
-    eprintln!("{:<15} {:<30} Capabilities", "Device", "Name");
-    eprintln!("-------------------------------------------------------------------");
+/// Checks if two events are identical in every field, timestamp included.
+#[inline]
+pub fn is_exact_duplicate(a: &input_event, b: &input_event) -> bool {
+    a.time.tv_sec == b.time.tv_sec
+        && a.time.tv_usec == b.time.tv_usec
+        && a.type_ == b.type_
+        && a.code == b.code
+        && a.value == b.value
+}
+
+/// Checks if the event type is EV_REL.
+#[inline]
+pub fn is_rel_event(event: &input_event) -> bool {
+    i32::from(event.type_) == EV_REL
+}
+
+/// Event types reported in a device's capabilities, with their text-listing labels.
+const CAPABILITY_TYPES: [(i32, &str, &str); 7] = [
+    (EV_SYN, "EV_SYN", "Sync"),
+    (EV_KEY, "EV_KEY", "Keyboard"),
+    (EV_REL, "EV_REL", "Relative"),
+    (EV_ABS, "EV_ABS", "Absolute"),
+    (EV_MSC, "EV_MSC", "Misc"),
+    (EV_LED, "EV_LED", "LEDs"),
+    (EV_REP, "EV_REP", "Repeat"),
+];
+
+/// One `event*` node found by [`scan_input_devices`]. A node that could not be
+/// opened or queried carries an `error` instead of a name and capabilities, so
+/// "exists but unreadable" stays distinguishable from "not there".
+#[derive(Debug, Serialize)]
+pub struct InputDevice {
+    pub path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Supported event types by name (e.g. `EV_KEY`).
+    pub capabilities: Vec<&'static str>,
+    /// How many keys of the classic keyboard block (`KEY_ESC` to `KEY_KPDOT`:
+    /// letters, digits, modifiers, keypad) the device reports. Mice and
+    /// media-key devices also have `EV_KEY`, but few or none of these.
+    pub keyboard_keys: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Keys of the classic keyboard block a device needs to be picked by
+/// `--auto-device`. Full keyboards report all 83, laptop ones close to it.
+pub const MIN_AUTO_DEVICE_KEYS: usize = 40;
+
+impl InputDevice {
+    fn failed(path: PathBuf, error: &io::Error) -> Self {
+        InputDevice {
+            path,
+            name: None,
+            capabilities: Vec::new(),
+            keyboard_keys: 0,
+            error: Some(error.to_string()),
+        }
+    }
+
+    /// Whether the device reports key events.
+    pub fn is_keyboard(&self) -> bool {
+        self.capabilities.contains(&"EV_KEY")
+    }
+}
 
-    let mut entries: Vec<_> = fs::read_dir("/dev/input/")?
+/// The devices `--auto-device` would pick from, in scan order: readable ones
+/// with key events and at least [`MIN_AUTO_DEVICE_KEYS`] keyboard keys, which
+/// rules out mice, power buttons and media-key devices.
+pub fn keyboard_candidates(devices: &[InputDevice]) -> Vec<&InputDevice> {
+    devices
+        .iter()
+        .filter(|device| {
+            device.error.is_none()
+                && device.is_keyboard()
+                && device.keyboard_keys >= MIN_AUTO_DEVICE_KEYS
+        })
+        .collect()
+}
+
+/// Scans `dir` (normally `/dev/input`) for `event*` nodes, ordered by number.
+/// Keyboards are returned along with every node that could not be opened or
+/// queried; other devices (mice, switches without keys, ...) are left out.
+/// Only failing to read `dir` itself is an error.
+pub fn scan_input_devices(dir: &Path) -> io::Result<Vec<InputDevice>> {
+    let mut entries: Vec<_> = fs::read_dir(dir)?
         .filter_map(|entry| {
             let entry = entry.ok()?;
             let path = entry.path();
@@ -137,85 +259,108 @@ pub fn list_input_devices() -> io::Result<()> {
 
     entries.sort_by_key(|(_, num)| *num);
 
-    for (path, _) in entries {
-        let path_str = path.display().to_string();
-        let file = match OpenOptions::new()
-            .read(true)
-            .custom_flags(libc::O_NONBLOCK)
-            .open(&path)
-        {
-            Ok(f) => f,
-            Err(e) => {
-                if e.kind() == ErrorKind::PermissionDenied {
-                    eprintln!("{:<15} {:<30} Permission Denied", path_str, "");
-                    continue;
-                } else {
-                    eprintln!("{:<15} {:<30} Error opening: {e}", path_str, "");
-                    continue;
-                }
-            }
-        };
-        let fd = file.as_raw_fd();
+    Ok(entries
+        .into_iter()
+        .map(|(path, _)| query_input_device(path))
+        .filter(|device| device.error.is_some() || device.is_keyboard())
+        .collect())
+}
 
-        let mut name_buf = [0u8; 256];
-        let device_name = match eviocgname(fd, &mut name_buf) {
-            Ok(name) => name,
-            Err(e) => {
-                warn!(device=%path_str, error=%e, "Could not get device name via EVIOCGNAME ioctl");
-                "<Unknown Name>".to_string()
-            }
-        };
+/// Opens one device node and reads its name and capabilities.
+fn query_input_device(path: PathBuf) -> InputDevice {
+    let file = match OpenOptions::new()
+        .read(true)
+        .custom_flags(libc::O_NONBLOCK)
+        .open(&path)
+    {
+        Ok(f) => f,
+        Err(e) => return InputDevice::failed(path, &e),
+    };
+    let fd = file.as_raw_fd();
 
-        let type_bits_size = (EV_MAX / 8) + 1;
-        let mut type_bits_buf: Vec<u8> = vec![0; type_bits_size as usize];
-        let mut capabilities = Vec::new();
+    let mut type_bits_buf: Vec<u8> = vec![0; (EV_MAX / 8) as usize + 1];
+    if let Err(e) = eviocgbit(fd, 0, &mut type_bits_buf) {
+        warn!(device=%path.display(), error=%e, "Could not get device capabilities via EVIOCGBIT ioctl");
+        return InputDevice::failed(path, &e);
+    }
+    let capabilities = CAPABILITY_TYPES
+        .iter()
+        .filter(|(type_, _, _)| is_bit_set(&type_bits_buf, *type_ as usize))
+        .map(|(_, name, _)| *name)
+        .collect();
 
-        let mut has_ev_key = false;
-        match eviocgbit(fd, 0, &mut type_bits_buf) {
-            Ok(_) => {
-                if is_bit_set(&type_bits_buf, EV_SYN as usize) {
-                    capabilities.push("EV_SYN (Sync)");
-                }
-                if is_bit_set(&type_bits_buf, EV_KEY as usize) {
-                    capabilities.push("EV_KEY (Keyboard)");
-                    has_ev_key = true;
-                }
-                if is_bit_set(&type_bits_buf, EV_REL as usize) {
-                    capabilities.push("EV_REL (Relative)");
-                }
-                if is_bit_set(&type_bits_buf, EV_ABS as usize) {
-                    capabilities.push("EV_ABS (Absolute)");
-                }
-                if is_bit_set(&type_bits_buf, EV_MSC as usize) {
-                    capabilities.push("EV_MSC (Misc)");
-                }
-                if is_bit_set(&type_bits_buf, EV_LED as usize) {
-                    capabilities.push("EV_LED (LEDs)");
-                }
-                if is_bit_set(&type_bits_buf, EV_REP as usize) {
-                    capabilities.push("EV_REP (Repeat)");
-                }
-            }
+    let mut key_bits_buf: Vec<u8> = vec![0; (KEY_MAX / 8) as usize + 1];
+    let keyboard_keys = if is_bit_set(&type_bits_buf, EV_KEY as usize) {
+        match eviocgbit(fd, EV_KEY as u8, &mut key_bits_buf) {
+            Ok(()) => (KEY_ESC..=KEY_KPDOT)
+                .filter(|&code| is_bit_set(&key_bits_buf, code as usize))
+                .count(),
             Err(e) => {
-                warn!(device=%path_str, error=%e, "Could not get device capabilities via EVIOCGBIT ioctl");
-                capabilities.push("Error getting capabilities");
+                warn!(device=%path.display(), error=%e, "Could not get supported keys via EVIOCGBIT ioctl");
+                0
             }
         }
+    } else {
+        0
+    };
 
-        if has_ev_key {
-            eprintln!(
-                "{:<15} {:<30} {}",
-                path_str,
-                device_name,
-                capabilities.join(", ")
-            );
+    let mut name_buf = [0u8; 256];
+    let name = match eviocgname(fd, &mut name_buf) {
+        Ok(name) => name,
+        Err(e) => {
+            warn!(device=%path.display(), error=%e, "Could not get device name via EVIOCGNAME ioctl");
+            "<Unknown Name>".to_string()
         }
+    };
+
+    InputDevice {
+        path,
+        name: Some(name),
+        capabilities,
+        keyboard_keys,
+        error: None,
+    }
+}
+
+/// Lists available input devices and their capabilities, as a table on stderr
+/// or (`json`) as a JSON array on stdout. Requires root privileges.
+pub fn list_input_devices(json: bool) -> io::Result<()> {
+    let devices = scan_input_devices(Path::new("/dev/input/"))?;
 
-        drop(file);
+    if json {
+        let mut stdout = io::stdout().lock();
+        serde_json::to_writer_pretty(&mut stdout, &devices)?;
+        writeln!(stdout)?;
+        return Ok(());
     }
 
+    eprintln!("{:<15} {:<30} Capabilities", "Device", "Name");
     eprintln!("-------------------------------------------------------------------");
-    eprintln!("Only devices with 'EV_KEY (Keyboard)' capability are shown above.");
+    for device in &devices {
+        let path_str = device.path.display().to_string();
+        let details = match &device.error {
+            Some(error) => format!("Error: {error}"),
+            None => device
+                .capabilities
+                .iter()
+                .filter_map(|name| {
+                    CAPABILITY_TYPES
+                        .iter()
+                        .find(|(_, type_name, _)| type_name == name)
+                        .map(|(_, type_name, label)| format!("{type_name} ({label})"))
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        eprintln!(
+            "{:<15} {:<30} {}",
+            path_str,
+            device.name.as_deref().unwrap_or(""),
+            details
+        );
+    }
+    eprintln!("-------------------------------------------------------------------");
+    eprintln!("Only devices with 'EV_KEY (Keyboard)' capability are shown above, plus any that could not be read.");
     eprintln!("You will likely need to run this command with `sudo`.");
 
     Ok(())
@@ -233,7 +378,7 @@ fn is_bit_set(buf: &[u8], bit: usize) -> bool {
     }
 }
 
-// --- Linux ioctl helpers for EVIOCGNAME and EVIOCGBIT ---
+// --- Linux ioctl helpers for EVIOCGNAME, EVIOCGBIT and EVIOCGRAB ---
 
 const EVIOCGNAME_LEN: usize = 256;
 const EVIOCGNAME_IOCTL: c_ulong = ior(b'E', 0x06, EVIOCGNAME_LEN);
@@ -241,10 +386,29 @@ fn eviocgbit_ioctl(ty: u8, len: usize) -> c_ulong {
     ior(b'E', 0x20 + ty, len)
 }
 
+const EVIOCGRAB_IOCTL: c_ulong = iow(b'E', 0x90, size_of::<libc::c_int>());
+
 const fn ior(ty: u8, nr: u8, size: usize) -> c_ulong {
     ((2u64 << 30) | ((size as u64) << 16) | ((ty as u64) << 8) | (nr as u64)) as c_ulong
 }
 
+const fn iow(ty: u8, nr: u8, size: usize) -> c_ulong {
+    ((1u64 << 30) | ((size as u64) << 16) | ((ty as u64) << 8) | (nr as u64)) as c_ulong
+}
+
+/// Grabs (or with `grab == false`, releases) an evdev device exclusively via
+/// EVIOCGRAB. While grabbed, no other reader (X, Wayland, the console) receives
+/// its events. Fails with `EBUSY` if another process holds the grab, and with
+/// `ENOTTY`/`EINVAL` if `fd` is not an evdev device.
+pub fn grab_device(fd: RawFd, grab: bool) -> io::Result<()> {
+    let res = unsafe { ioctl(fd, EVIOCGRAB_IOCTL, libc::c_int::from(grab)) };
+    if res < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(())
+    }
+}
+
 /// Safe wrapper for EVIOCGNAME ioctl
 fn eviocgname(fd: RawFd, buf: &mut [u8; 256]) -> io::Result<String> {
     let res = unsafe { ioctl(fd, EVIOCGNAME_IOCTL, buf.as_mut_ptr()) };
@@ -266,3 +430,83 @@ fn eviocgbit(fd: RawFd, ev_type: u8, buf: &mut [u8]) -> io::Result<()> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        grab_device, keyboard_candidates, scan_input_devices, InputDevice, EVIOCGRAB_IOCTL,
+    };
+    use std::os::unix::io::AsRawFd;
+
+    #[test]
+    fn eviocgrab_matches_kernel_value() {
+        // _IOW('E', 0x90, int) from linux/input.h
+        assert_eq!(EVIOCGRAB_IOCTL, 0x4004_4590);
+    }
+
+    #[test]
+    fn grab_rejects_non_evdev_fd() {
+        let file = std::fs::File::open("/dev/null").unwrap();
+        assert!(grab_device(file.as_raw_fd(), true).is_err());
+    }
+
+    #[test]
+    fn unreadable_nodes_are_listed_with_an_error() {
+        let dir = test_helpers::temp_path("scan");
+        std::fs::create_dir_all(&dir).unwrap();
+        // A node that can't be opened, one that opens but isn't an evdev device,
+        // and a non-event node that is skipped entirely.
+        std::os::unix::fs::symlink(dir.join("missing"), dir.join("event3")).unwrap();
+        std::fs::write(dir.join("event10"), b"").unwrap();
+        std::fs::write(dir.join("mouse0"), b"").unwrap();
+
+        let devices = scan_input_devices(&dir).unwrap();
+
+        let paths: Vec<_> = devices
+            .iter()
+            .map(|d| d.path.file_name().unwrap())
+            .collect();
+        assert_eq!(paths, ["event3", "event10"]);
+        for device in &devices {
+            assert!(device.name.is_none() && device.capabilities.is_empty());
+            assert!(device.error.is_some(), "{device:?}");
+        }
+
+        let json = serde_json::to_value(&devices[0]).unwrap();
+        assert_eq!(json["capabilities"], serde_json::json!([]));
+        assert!(json["error"].as_str().unwrap().contains("No such file"));
+        assert!(json.get("name").is_none());
+    }
+
+    #[test]
+    fn auto_device_prefers_keyboards_over_mice() {
+        let device = |path: &str, capabilities: Vec<&'static str>, keyboard_keys| InputDevice {
+            path: path.into(),
+            name: Some(path.to_string()),
+            capabilities,
+            keyboard_keys,
+            error: None,
+        };
+        let devices = [
+            device("/dev/input/event0", vec!["EV_SYN", "EV_KEY"], 1), // Power button
+            device("/dev/input/event1", vec!["EV_SYN", "EV_KEY", "EV_REL"], 0), // Mouse
+            InputDevice {
+                error: Some("Permission denied".into()),
+                ..device("/dev/input/event2", Vec::new(), 0)
+            },
+            device(
+                "/dev/input/event3",
+                vec!["EV_SYN", "EV_KEY", "EV_MSC", "EV_REP"],
+                83,
+            ),
+            device("/dev/input/event4", vec!["EV_SYN", "EV_KEY", "EV_LED"], 80),
+        ];
+
+        let candidates: Vec<_> = keyboard_candidates(&devices)
+            .iter()
+            .map(|d| d.path.to_str().unwrap())
+            .collect();
+        assert_eq!(candidates, ["/dev/input/event3", "/dev/input/event4"]);
+        assert!(keyboard_candidates(&devices[..3]).is_empty());
+    }
+}
@@ -3,11 +3,16 @@
 // focusing only on the information needed for the immediate bounce decision.
 
 pub mod keynames;
+pub mod rule;
 pub mod stats;
+pub mod sweep;
 
-use crate::event::{self, is_key_event};
+use crate::cli::DebounceState;
+use crate::config::Config;
+use crate::event::{self, is_key_event, is_rel_event};
 use crate::logger::EventInfo;
-use input_linux_sys::{input_event, KEY_MAX};
+use input_linux_sys::{input_event, KEY_MAX, REL_MAX};
+use rule::{DropRule, RuleVars};
 use std::time::Duration;
 
 // Constants for filter state size
@@ -15,6 +20,25 @@ use std::time::Duration;
 pub const FILTER_MAP_SIZE: usize = KEY_MAX as usize + 1;
 /// Number of key states (0=release, 1=press, 2=repeat).
 pub const NUM_KEY_STATES: usize = 3;
+/// Number of relative axis codes tracked by `--debounce-rel` (0 to REL_MAX inclusive).
+pub const REL_MAP_SIZE: usize = REL_MAX as usize + 1;
+
+/// Outcome of [`BounceFilter::collapse_press_release`] for a single event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Collapse {
+    /// Not part of a release/press pair; run the event through `check_event` as usual.
+    Check,
+    /// A key release was held back until its debounce window expires.
+    Held,
+    /// A press arrived within the debounce window of a held release of the same key.
+    /// Both events are chatter and should be dropped.
+    Collapsed {
+        /// The held release that is being discarded together with the press.
+        release: input_event,
+        /// Time between the release and the press (microseconds).
+        gap_us: u64,
+    },
+}
 
 /// Holds the minimal state required for bounce filtering decisions.
 ///
@@ -34,6 +58,55 @@ pub struct BounceFilter {
     overall_first_event_us: Option<u64>,
     // Timestamp of the very last event processed, used for calculating total runtime.
     overall_last_event_us: Option<u64>,
+    // Key releases held back by `--collapse-press-release`, oldest first.
+    pending_releases: Vec<input_event>,
+    // Optional `--drop-rule` replacing the plain `diff < debounce_time` check.
+    drop_rule: Option<DropRule>,
+    // Per key code: consecutive events that arrived within the debounce window
+    // (exposed to drop rules as `prev_bounces`).
+    consecutive_bounces: [u32; FILTER_MAP_SIZE],
+    // `--mode count`: events of the same key code/value allowed per window. None = time mode.
+    count_threshold: Option<u32>,
+    // Start of the current count window per key code/value (u64::MAX = none yet),
+    // and the number of events seen in it.
+    count_window_start_us: [[u64; NUM_KEY_STATES]; FILTER_MAP_SIZE],
+    count_window_events: [[u32; NUM_KEY_STATES]; FILTER_MAP_SIZE],
+    // `--suppress-initial`: window around startup in which the first event of
+    // each key code/value is dropped, and which slots have had their first event.
+    initial_window: Option<Duration>,
+    // Event-clock time the filter started at, anchoring `initial_window`. Without
+    // it the window starts at the first event seen (replays of a capture).
+    start_us: Option<u64>,
+    seen_initial: [[bool; NUM_KEY_STATES]; FILTER_MAP_SIZE],
+    // Whether events identical to the previous one are dropped (`--drop-exact-duplicates`),
+    // and the previous event checked (dropped or not).
+    drop_exact_duplicates: bool,
+    last_raw_event: Option<input_event>,
+    // Whether a release with the same timestamp as the key's last passed press is
+    // dropped (`--collapse-zero-duration`).
+    collapse_zero_duration: bool,
+    // Whether EV_REL events are debounced (`--debounce-rel`).
+    debounce_rel: bool,
+    // Timestamp of the last passed EV_REL event per axis code and direction
+    // (index 0 = negative delta, 1 = positive delta). u64::MAX = none yet.
+    last_rel_us: [[u64; 2]; REL_MAP_SIZE],
+    // `--max-repeat-rate`: key repeats passed per key code per second. None = unlimited.
+    max_repeat_rate: Option<u32>,
+    // Start of the current one-second repeat window per key code (u64::MAX = none yet),
+    // and the number of repeats passed in it.
+    repeat_window_start_us: [u64; FILTER_MAP_SIZE],
+    repeat_window_passed: [u32; FILTER_MAP_SIZE],
+    // `--lead-in`: per key code, events of the same value allowed per window
+    // (count mode for that key only). 0 = none.
+    lead_in: [u32; FILTER_MAP_SIZE],
+    // `--mode burst`, the silence that ends a burst (None = the debounce time),
+    // and the timestamp of the last event, passed or dropped, per key code/value.
+    burst_mode: bool,
+    burst_gap: Option<Duration>,
+    burst_last_seen_us: [[u64; NUM_KEY_STATES]; FILTER_MAP_SIZE],
+    // `--debounce-states`: whether key presses and releases are debounced at all.
+    debounce_press: bool,
+    debounce_release: bool,
 }
 
 impl Default for BounceFilter {
@@ -63,13 +136,362 @@ impl BounceFilter {
             ring_buffer_size,
             overall_first_event_us: None,
             overall_last_event_us: None,
+            pending_releases: Vec::new(),
+            drop_rule: None,
+            consecutive_bounces: [0; FILTER_MAP_SIZE],
+            count_threshold: None,
+            count_window_start_us: [[u64::MAX; NUM_KEY_STATES]; FILTER_MAP_SIZE],
+            count_window_events: [[0; NUM_KEY_STATES]; FILTER_MAP_SIZE],
+            initial_window: None,
+            start_us: None,
+            seen_initial: [[false; NUM_KEY_STATES]; FILTER_MAP_SIZE],
+            drop_exact_duplicates: false,
+            last_raw_event: None,
+            collapse_zero_duration: false,
+            debounce_rel: false,
+            last_rel_us: [[u64::MAX; 2]; REL_MAP_SIZE],
+            max_repeat_rate: None,
+            repeat_window_start_us: [u64::MAX; FILTER_MAP_SIZE],
+            repeat_window_passed: [0; FILTER_MAP_SIZE],
+            lead_in: [0; FILTER_MAP_SIZE],
+            burst_mode: false,
+            burst_gap: None,
+            burst_last_seen_us: [[u64::MAX; NUM_KEY_STATES]; FILTER_MAP_SIZE],
+            debounce_press: true,
+            debounce_release: true,
+        }
+    }
+
+    /// Uses `rule` instead of the plain debounce window to decide whether a
+    /// candidate event is dropped. `None` restores the default behaviour.
+    #[must_use]
+    pub fn with_drop_rule(mut self, rule: Option<DropRule>) -> Self {
+        self.set_drop_rule(rule);
+        self
+    }
+
+    /// Replaces the drop rule in place, keeping all timing state (used on reload).
+    pub fn set_drop_rule(&mut self, rule: Option<DropRule>) {
+        self.drop_rule = rule;
+    }
+
+    /// Switches to count-based debouncing: within a window of `debounce_time` starting
+    /// at the first event of a burst, the first `threshold` events of the same key code
+    /// and value pass and later ones are dropped. `None` restores time-based debouncing.
+    #[must_use]
+    pub fn with_count_threshold(mut self, threshold: Option<u32>) -> Self {
+        self.set_count_threshold(threshold);
+        self
+    }
+
+    /// Changes the debounce algorithm in place (used on reload).
+    pub fn set_count_threshold(&mut self, threshold: Option<u32>) {
+        self.count_threshold = threshold;
+    }
+
+    /// Switches to burst collapsing: the first event of a burst of the same key code
+    /// and value passes, and every later one is dropped until the key and value
+    /// have been silent for the burst gap. Unlike the time window, which restarts
+    /// at each passed event, a burst lasts as long as events keep coming, so a long
+    /// ragged burst still yields a single event. Drop rules don't apply.
+    #[must_use]
+    pub fn with_burst_mode(mut self, enabled: bool) -> Self {
+        self.set_burst_mode(enabled);
+        self
+    }
+
+    /// Enables or disables burst collapsing in place (used on reload).
+    pub fn set_burst_mode(&mut self, enabled: bool) {
+        self.burst_mode = enabled;
+    }
+
+    /// Silence that ends a burst in burst mode. `None` uses the debounce time.
+    #[must_use]
+    pub fn with_burst_gap(mut self, gap: Option<Duration>) -> Self {
+        self.set_burst_gap(gap);
+        self
+    }
+
+    /// Changes the burst gap in place (used on reload).
+    pub fn set_burst_gap(&mut self, gap: Option<Duration>) {
+        self.burst_gap = gap;
+    }
+
+    /// Drops the first event of each key code and value (press or release) if it
+    /// arrives within `window` of the start time (see [`Self::with_start_time`]),
+    /// to discard spurious events injected during device setup. Without a start
+    /// time, the window begins at the first event seen. `None` disables this.
+    #[must_use]
+    pub fn with_initial_suppression(mut self, window: Option<Duration>) -> Self {
+        self.set_initial_suppression(window);
+        self
+    }
+
+    /// Changes the startup suppression window in place (used on reload).
+    pub fn set_initial_suppression(&mut self, window: Option<Duration>) {
+        self.initial_window = window;
+    }
+
+    /// Sets the time the filter started, in microseconds on the clock of the
+    /// event timestamps (e.g. [`crate::util::realtime_us`] for live input).
+    #[must_use]
+    pub fn with_start_time(mut self, start_us: u64) -> Self {
+        self.start_us = Some(start_us);
+        self
+    }
+
+    /// Drops any event identical in all fields (timestamp included) to the event
+    /// checked right before it, regardless of type or debounce settings.
+    #[must_use]
+    pub fn with_exact_duplicate_drop(mut self, enabled: bool) -> Self {
+        self.set_exact_duplicate_drop(enabled);
+        self
+    }
+
+    /// Enables or disables dropping exact duplicates in place (used on reload).
+    pub fn set_exact_duplicate_drop(&mut self, enabled: bool) {
+        self.drop_exact_duplicates = enabled;
+    }
+
+    /// Drops a key release whose timestamp equals that of the last passed press
+    /// of the same key: a zero-duration keypress, almost always a hard bounce.
+    /// The key then stays down downstream until its next release passes.
+    #[must_use]
+    pub fn with_zero_duration_collapse(mut self, enabled: bool) -> Self {
+        self.set_zero_duration_collapse(enabled);
+        self
+    }
+
+    /// Enables or disables zero-duration collapsing in place (used on reload).
+    pub fn set_zero_duration_collapse(&mut self, enabled: bool) {
+        self.collapse_zero_duration = enabled;
+    }
+
+    /// Enables debouncing of EV_REL events (see [`Self::check_event`]).
+    #[must_use]
+    pub fn with_rel_debounce(mut self, enabled: bool) -> Self {
+        self.set_rel_debounce(enabled);
+        self
+    }
+
+    /// Enables or disables EV_REL debouncing in place (used on reload).
+    pub fn set_rel_debounce(&mut self, enabled: bool) {
+        self.debounce_rel = enabled;
+    }
+
+    /// Passes at most `rate` key repeats per key code per second of event time and
+    /// drops the rest, so a failing controller's repeat storm doesn't flood the
+    /// consumer. Applies to every key, debounced or not. `None` disables the limit.
+    #[must_use]
+    pub fn with_max_repeat_rate(mut self, rate: Option<u32>) -> Self {
+        self.set_max_repeat_rate(rate);
+        self
+    }
+
+    /// Changes the repeat rate limit in place (used on reload).
+    pub fn set_max_repeat_rate(&mut self, rate: Option<u32>) {
+        self.max_repeat_rate = rate;
+    }
+
+    /// Lets each listed key pass its first `n` events of the same value per
+    /// debounce-time window and drop later ones, i.e. count mode for just those
+    /// keys, overriding the count threshold and drop rule for them. Later
+    /// entries for the same key win; codes beyond the key table are ignored.
+    #[must_use]
+    pub fn with_lead_in(mut self, lead_in: &[(u16, u32)]) -> Self {
+        self.set_lead_in(lead_in);
+        self
+    }
+
+    /// Replaces the per-key lead-ins in place (used on reload).
+    pub fn set_lead_in(&mut self, lead_in: &[(u16, u32)]) {
+        self.lead_in = [0; FILTER_MAP_SIZE];
+        for &(code, n) in lead_in {
+            if let Some(slot) = self.lead_in.get_mut(usize::from(code)) {
+                *slot = n;
+            }
+        }
+    }
+
+    /// Debounces only the listed key states; presses or releases left out pass
+    /// unfiltered, like keys that aren't debounced. Repeats are unaffected.
+    #[must_use]
+    pub fn with_debounce_states(mut self, states: &[DebounceState]) -> Self {
+        self.set_debounce_states(states);
+        self
+    }
+
+    /// Replaces the debounced key states in place (used on reload).
+    pub fn set_debounce_states(&mut self, states: &[DebounceState]) {
+        self.debounce_press = states.contains(&DebounceState::Press);
+        self.debounce_release = states.contains(&DebounceState::Release);
+    }
+
+    /// Applies every filter option of `cfg` (drop rule, count mode, burst mode,
+    /// lead-ins, ...), replacing the current ones. Used at startup, on reload
+    /// and for each `--sweep` run; the ring buffer size and start time are
+    /// fixed when the filter is created.
+    pub fn apply_config(&mut self, cfg: &Config) {
+        self.set_drop_rule(cfg.drop_rule.clone());
+        self.set_count_threshold(cfg.count_threshold);
+        self.set_burst_mode(cfg.burst_mode);
+        self.set_burst_gap(cfg.burst_gap);
+        self.set_exact_duplicate_drop(cfg.drop_exact_duplicates);
+        self.set_zero_duration_collapse(cfg.collapse_zero_duration);
+        self.set_initial_suppression(cfg.initial_window);
+        self.set_rel_debounce(cfg.debounce_rel);
+        self.set_max_repeat_rate(cfg.max_repeat_rate);
+        self.set_lead_in(&cfg.lead_in);
+        self.set_debounce_states(&cfg.debounce_states);
+    }
+
+    /// Whether a key press or release is left unfiltered by `--debounce-states`.
+    fn skips_key_state(&self, event: &input_event) -> bool {
+        is_key_event(event)
+            && match event.value {
+                1 => !self.debounce_press,
+                0 => !self.debounce_release,
+                _ => false,
+            }
+    }
+
+    /// Whether a key repeat at `event_us` exceeds `--max-repeat-rate` for its key.
+    /// Windows are one second long, starting at the first repeat after the previous
+    /// window ended. Counts the repeat if it may pass.
+    fn repeat_over_rate(&mut self, code: u16, event_us: u64) -> bool {
+        let (Some(rate), Some(start)) = (
+            self.max_repeat_rate,
+            self.repeat_window_start_us.get_mut(usize::from(code)),
+        ) else {
+            return false;
+        };
+        let passed = &mut self.repeat_window_passed[usize::from(code)];
+        // A window ends after one second, or if time went backwards.
+        if event_us
+            .checked_sub(*start)
+            .is_none_or(|elapsed| elapsed >= 1_000_000)
+        {
+            *start = event_us;
+            *passed = 0;
+        }
+        if *passed >= rate {
+            return true;
+        }
+        *passed += 1;
+        false
+    }
+
+    /// Cross-state chatter handling for `--collapse-press-release`.
+    ///
+    /// A key release is held back instead of being passed immediately. If a press of
+    /// the same key arrives within `debounce_time`, the pair is reported as
+    /// [`Collapse::Collapsed`] and neither event should be written. Releases that are
+    /// not followed by a press in time are returned by [`Self::take_expired_releases`]
+    /// and must then go through `check_event` as normal.
+    ///
+    /// Only key press/release events should be passed here; everything else yields
+    /// [`Collapse::Check`]. Only the release-then-press direction is handled: a
+    /// press followed by a quick release is left to `check_event`, since holding
+    /// presses back would delay every keystroke.
+    pub fn collapse_press_release(
+        &mut self,
+        event: &input_event,
+        debounce_time: Duration,
+    ) -> Collapse {
+        if !is_key_event(event) || debounce_time == Duration::ZERO {
+            return Collapse::Check;
+        }
+        match event.value {
+            0 => {
+                self.pending_releases.push(*event);
+                Collapse::Held
+            }
+            1 => {
+                let event_us = event::event_microseconds(event);
+                let pending = self
+                    .pending_releases
+                    .iter()
+                    .position(|release| release.code == event.code);
+                let Some(idx) = pending else {
+                    return Collapse::Check;
+                };
+                let release = self.pending_releases[idx];
+                let release_us = event::event_microseconds(&release);
+                match event_us.checked_sub(release_us) {
+                    Some(gap_us) if Duration::from_micros(gap_us) < debounce_time => {
+                        self.pending_releases.remove(idx);
+                        Collapse::Collapsed { release, gap_us }
+                    }
+                    // Outside the window (expired releases are normally flushed first).
+                    _ => Collapse::Check,
+                }
+            }
+            _ => Collapse::Check,
         }
     }
 
+    /// Removes and returns held releases whose debounce window has expired by `now_us`,
+    /// oldest first. `None` flushes every held release (e.g. on timeout or EOF).
+    pub fn take_expired_releases(
+        &mut self,
+        now_us: Option<u64>,
+        debounce_time: Duration,
+    ) -> Vec<input_event> {
+        let debounce_us = u64::try_from(debounce_time.as_micros()).unwrap_or(u64::MAX);
+        let (expired, kept): (Vec<_>, Vec<_>) =
+            self.pending_releases
+                .iter()
+                .partition(|release| match now_us {
+                    Some(now) => {
+                        now.saturating_sub(event::event_microseconds(release)) >= debounce_us
+                    }
+                    None => true,
+                });
+        self.pending_releases = kept;
+        expired
+    }
+
+    /// Returns `true` if a release of `key_code` is currently held back.
+    pub fn holds_release_of(&self, key_code: u16) -> bool {
+        self.pending_releases
+            .iter()
+            .any(|release| release.code == key_code)
+    }
+
+    /// Timestamp (microseconds) of the oldest held release, whose debounce
+    /// window ends first. `None` if no release is held.
+    pub fn oldest_pending_release_us(&self) -> Option<u64> {
+        self.pending_releases.first().map(event::event_microseconds)
+    }
+
+    /// Returns `true` if any key releases are currently held back.
+    pub fn has_pending_releases(&self) -> bool {
+        !self.pending_releases.is_empty()
+    }
+
     /// Checks an incoming event against the debounce filter state.
     ///
     /// Determines if the event is a bounce based on the `debounce_time_us`
     /// and the timestamp of the last passed event of the same type.
+    /// If a drop rule is set (see [`Self::with_drop_rule`]), the rule decides instead.
+    /// In count mode (see [`Self::with_count_threshold`]), events are counted per
+    /// window instead and drop rules don't apply. In burst mode (see
+    /// [`Self::with_burst_mode`]), events are dropped until a quiet gap instead.
+    ///
+    /// With exact-duplicate dropping enabled (see [`Self::with_exact_duplicate_drop`]),
+    /// an event identical to the previous one is dropped first, with `diff_us` 0.
+    ///
+    /// With zero-duration collapsing enabled (see [`Self::with_zero_duration_collapse`]),
+    /// a release stamped with the same time as the last passed press of its key is
+    /// dropped, with `diff_us` 0, in every debounce mode.
+    ///
+    /// Presses or releases excluded by [`Self::with_debounce_states`] pass like
+    /// events with `skip_debounce` set.
+    ///
+    /// With EV_REL debouncing enabled (see [`Self::with_rel_debounce`]), relative
+    /// events are dropped if a non-zero delta in the same direction on the same axis
+    /// passed less than `debounce_time` ago. Drop rules don't apply to them.
+    ///
     /// Updates the internal state (`last_event_us`) *only* if the event passes.
     /// Also tracks the overall first and last event timestamps.
     ///
@@ -93,7 +515,39 @@ impl BounceFilter {
         }
         self.overall_last_event_us = Some(event_us);
 
-        if skip_debounce {
+        let previous = self.last_raw_event.replace(*event);
+        if self.drop_exact_duplicates
+            && previous.is_some_and(|prev| event::is_exact_duplicate(&prev, event))
+        {
+            let last_passed_us = if is_key_event(event) {
+                self.last_passed(event.code, event.value)
+            } else {
+                None
+            };
+            return EventInfo {
+                event: *event,
+                event_us,
+                is_bounce: true,
+                diff_us: Some(0),
+                last_passed_us,
+                paused: false,
+                filter_latency_ns: None,
+            };
+        }
+
+        if is_key_event(event) && event.value == 2 && self.repeat_over_rate(event.code, event_us) {
+            return EventInfo {
+                event: *event,
+                event_us,
+                is_bounce: true,
+                diff_us: None,
+                last_passed_us: None,
+                paused: false,
+                filter_latency_ns: None,
+            };
+        }
+
+        if skip_debounce || self.skips_key_state(event) {
             if self.ring_buffer_size > 0 {
                 self.recent_passed_events[self.recent_event_idx] = Some(*event);
                 self.recent_event_idx = (self.recent_event_idx + 1) % self.ring_buffer_size;
@@ -104,9 +558,15 @@ impl BounceFilter {
                 is_bounce: false,
                 diff_us: None,
                 last_passed_us: None,
+                paused: false,
+                filter_latency_ns: None,
             };
         }
 
+        if self.debounce_rel && is_rel_event(event) {
+            return self.check_rel_event(event, event_us, debounce_time);
+        }
+
         // --- Early returns for non-debounced events ---
         // Pass non-key events or key repeats immediately
         if !is_key_event(event) || event.value == 2 {
@@ -121,6 +581,8 @@ impl BounceFilter {
                 is_bounce: false,
                 diff_us: None,
                 last_passed_us: None, // No relevant last_passed_us for non-debounced events
+                paused: false,
+                filter_latency_ns: None,
             };
         }
 
@@ -140,6 +602,68 @@ impl BounceFilter {
                 is_bounce: false,
                 diff_us: None,
                 last_passed_us: None,
+                paused: false,
+                filter_latency_ns: None,
+            };
+        }
+
+        if self.is_initial_artifact(event_us, key_code_idx, key_value_idx) {
+            return EventInfo {
+                event: *event,
+                event_us,
+                is_bounce: true,
+                diff_us: None,
+                last_passed_us: None,
+                paused: false,
+                filter_latency_ns: None,
+            };
+        }
+
+        if self.collapse_zero_duration
+            && event.value == 0
+            && self.last_event_us[key_code_idx][1] == event_us
+        {
+            return EventInfo {
+                event: *event,
+                event_us,
+                is_bounce: true,
+                diff_us: Some(0),
+                last_passed_us: self.last_passed(event.code, 0),
+                paused: false,
+                filter_latency_ns: None,
+            };
+        }
+
+        let lead_in = Some(self.lead_in[key_code_idx]).filter(|&n| n > 0);
+        if let Some(threshold) = lead_in.or(self.count_threshold) {
+            return self.check_count_event(event, event_us, debounce_time, threshold);
+        }
+        if self.burst_mode {
+            let gap = self.burst_gap.unwrap_or(debounce_time);
+            return self.check_burst_event(event, event_us, gap);
+        }
+
+        // Fast path: nothing falls within a zero window, so every event passes
+        // without comparing timestamps. Drop rules may still drop, so they take the
+        // general path.
+        if debounce_time.is_zero() && self.drop_rule.is_none() {
+            let last_passed_us = std::mem::replace(
+                &mut self.last_event_us[key_code_idx][key_value_idx],
+                event_us,
+            );
+            self.consecutive_bounces[key_code_idx] = 0;
+            if self.ring_buffer_size > 0 {
+                self.recent_passed_events[self.recent_event_idx] = Some(*event);
+                self.recent_event_idx = (self.recent_event_idx + 1) % self.ring_buffer_size;
+            }
+            return EventInfo {
+                event: *event,
+                event_us,
+                is_bounce: false,
+                diff_us: None,
+                last_passed_us: (last_passed_us != u64::MAX).then_some(last_passed_us),
+                paused: false,
+                filter_latency_ns: None,
             };
         }
 
@@ -149,6 +673,7 @@ impl BounceFilter {
         // If no previous event passed for this key/value, it cannot be a bounce. Record and pass.
         if last_passed_us == u64::MAX {
             self.last_event_us[key_code_idx][key_value_idx] = event_us;
+            self.consecutive_bounces[key_code_idx] = 0;
             // Record passed event in ring buffer if enabled
             if self.ring_buffer_size > 0 {
                 self.recent_passed_events[self.recent_event_idx] = Some(*event);
@@ -160,6 +685,8 @@ impl BounceFilter {
                 is_bounce: false,
                 diff_us: None,
                 last_passed_us: None, // No previous passed event for this key/value
+                paused: false,
+                filter_latency_ns: None,
             };
         }
 
@@ -168,7 +695,25 @@ impl BounceFilter {
 
         if let Some(diff_us) = diff_us_opt {
             // Check if the difference is within the debounce window.
-            if debounce_time > Duration::ZERO && Duration::from_micros(diff_us) < debounce_time {
+            let within_window =
+                debounce_time > Duration::ZERO && Duration::from_micros(diff_us) < debounce_time;
+            let prev_bounces = self.consecutive_bounces[key_code_idx];
+            self.consecutive_bounces[key_code_idx] = if within_window {
+                prev_bounces.saturating_add(1)
+            } else {
+                0
+            };
+            let drop = match &self.drop_rule {
+                Some(rule) => rule.should_drop(&RuleVars {
+                    diff_us,
+                    debounce_us: u64::try_from(debounce_time.as_micros()).unwrap_or(u64::MAX),
+                    key_code: event.code,
+                    value: event.value,
+                    prev_bounces,
+                }),
+                None => within_window,
+            };
+            if drop {
                 // It's a bounce! Return bounce info. Do NOT update last_event_us or ring buffer.
                 return EventInfo {
                     event: *event,
@@ -176,10 +721,15 @@ impl BounceFilter {
                     is_bounce: true,
                     diff_us: Some(diff_us),
                     last_passed_us: Some(last_passed_us),
+                    paused: false,
+                    filter_latency_ns: None,
                 };
             }
+        } else {
+            self.consecutive_bounces[key_code_idx] = 0;
         }
-        // If time went backwards (checked_sub returned None), or diff_us >= debounce_time, it's not a bounce.
+        // If time went backwards (checked_sub returned None), or diff_us >= debounce_time
+        // (or the drop rule said so), it's not a bounce.
 
         // --- Event Passed ---
         // If we reach here, the event is NOT a bounce. Record as passed.
@@ -197,7 +747,198 @@ impl BounceFilter {
             is_bounce: false,
             diff_us: None, // Not a bounce, so no bounce diff_us
             last_passed_us: Some(last_passed_us),
+            paused: false,
+            filter_latency_ns: None,
+        }
+    }
+
+    /// Returns `true` if this is the first event of its key code/value and arrived
+    /// within the `--suppress-initial` window. Marks the slot as seen either way.
+    /// Events stamped shortly before the start time (queued while starting up)
+    /// count as inside the window.
+    fn is_initial_artifact(&mut self, event_us: u64, code_idx: usize, value_idx: usize) -> bool {
+        if std::mem::replace(&mut self.seen_initial[code_idx][value_idx], true) {
+            return false;
+        }
+        let (Some(window), Some(start_us)) = (
+            self.initial_window,
+            self.start_us.or(self.overall_first_event_us),
+        ) else {
+            return false;
+        };
+        Duration::from_micros(event_us.abs_diff(start_us)) < window
+    }
+
+    /// Count-mode branch of `check_event` for key press/release events with valid
+    /// indices. A window opens at the first event after the previous one expired;
+    /// events beyond `threshold` within it are dropped.
+    fn check_count_event(
+        &mut self,
+        event: &input_event,
+        event_us: u64,
+        debounce_time: Duration,
+        threshold: u32,
+    ) -> EventInfo {
+        let (code_idx, value_idx) = (event.code as usize, event.value as usize);
+        let last_passed_us = self.last_event_us[code_idx][value_idx];
+        let last_passed_us = (last_passed_us != u64::MAX).then_some(last_passed_us);
+
+        let window_start_us = self.count_window_start_us[code_idx][value_idx];
+        let in_window = window_start_us != u64::MAX
+            && event_us
+                .checked_sub(window_start_us)
+                .is_some_and(|elapsed| Duration::from_micros(elapsed) < debounce_time);
+        if in_window {
+            self.count_window_events[code_idx][value_idx] += 1;
+        } else {
+            self.count_window_start_us[code_idx][value_idx] = event_us;
+            self.count_window_events[code_idx][value_idx] = 1;
+        }
+
+        if self.count_window_events[code_idx][value_idx] > threshold {
+            return EventInfo {
+                event: *event,
+                event_us,
+                is_bounce: true,
+                diff_us: last_passed_us.and_then(|last| event_us.checked_sub(last)),
+                last_passed_us,
+                paused: false,
+                filter_latency_ns: None,
+            };
         }
+
+        self.last_event_us[code_idx][value_idx] = event_us;
+        if self.ring_buffer_size > 0 {
+            self.recent_passed_events[self.recent_event_idx] = Some(*event);
+            self.recent_event_idx = (self.recent_event_idx + 1) % self.ring_buffer_size;
+        }
+        EventInfo {
+            event: *event,
+            event_us,
+            is_bounce: false,
+            diff_us: None,
+            last_passed_us,
+            paused: false,
+            filter_latency_ns: None,
+        }
+    }
+
+    /// Burst-mode branch of `check_event`: drops the event if any event of the same
+    /// key code and value, passed or dropped, arrived less than `gap` before it.
+    fn check_burst_event(
+        &mut self,
+        event: &input_event,
+        event_us: u64,
+        gap: Duration,
+    ) -> EventInfo {
+        let (code_idx, value_idx) = (event.code as usize, event.value as usize);
+        let last_passed_us = self.last_event_us[code_idx][value_idx];
+        let last_passed_us = (last_passed_us != u64::MAX).then_some(last_passed_us);
+
+        let last_seen_us =
+            std::mem::replace(&mut self.burst_last_seen_us[code_idx][value_idx], event_us);
+        let in_burst = last_seen_us != u64::MAX
+            && event_us
+                .checked_sub(last_seen_us)
+                .is_some_and(|quiet| Duration::from_micros(quiet) < gap);
+        if in_burst {
+            return EventInfo {
+                event: *event,
+                event_us,
+                is_bounce: true,
+                diff_us: last_passed_us.and_then(|last| event_us.checked_sub(last)),
+                last_passed_us,
+                paused: false,
+                filter_latency_ns: None,
+            };
+        }
+
+        self.last_event_us[code_idx][value_idx] = event_us;
+        if self.ring_buffer_size > 0 {
+            self.recent_passed_events[self.recent_event_idx] = Some(*event);
+            self.recent_event_idx = (self.recent_event_idx + 1) % self.ring_buffer_size;
+        }
+        EventInfo {
+            event: *event,
+            event_us,
+            is_bounce: false,
+            diff_us: None,
+            last_passed_us,
+            paused: false,
+            filter_latency_ns: None,
+        }
+    }
+
+    /// EV_REL branch of `check_event`: REL events have no press/release, only signed
+    /// deltas, so the window is tracked per axis code and direction of movement.
+    fn check_rel_event(
+        &mut self,
+        event: &input_event,
+        event_us: u64,
+        debounce_time: Duration,
+    ) -> EventInfo {
+        let code_idx = event.code as usize;
+        let mut info = EventInfo {
+            event: *event,
+            event_us,
+            is_bounce: false,
+            diff_us: None,
+            last_passed_us: None,
+            paused: false,
+            filter_latency_ns: None,
+        };
+        // Zero deltas carry no movement, and unknown axes have no state.
+        if event.value != 0 && code_idx < REL_MAP_SIZE {
+            let direction = usize::from(event.value > 0);
+            let last_passed_us = self.last_rel_us[code_idx][direction];
+            if last_passed_us != u64::MAX {
+                info.last_passed_us = Some(last_passed_us);
+                if let Some(diff_us) = event_us.checked_sub(last_passed_us) {
+                    if debounce_time > Duration::ZERO
+                        && Duration::from_micros(diff_us) < debounce_time
+                    {
+                        info.is_bounce = true;
+                        info.diff_us = Some(diff_us);
+                        return info;
+                    }
+                }
+            }
+            self.last_rel_us[code_idx][direction] = event_us;
+        }
+
+        if self.ring_buffer_size > 0 {
+            self.recent_passed_events[self.recent_event_idx] = Some(*event);
+            self.recent_event_idx = (self.recent_event_idx + 1) % self.ring_buffer_size;
+        }
+        info
+    }
+
+    /// Returns the timestamp (µs) of the last passed event for a key code and value,
+    /// or `None` if none has passed yet or the code/value is out of range.
+    pub fn last_passed(&self, code: u16, value: i32) -> Option<u64> {
+        let (code_idx, value_idx) = Self::slot(code, value)?;
+        let last_us = self.last_event_us[code_idx][value_idx];
+        (last_us != u64::MAX).then_some(last_us)
+    }
+
+    /// Forgets the last passed event for a key code and value (and its count-mode
+    /// window or burst), so the next such event passes, along with the key's run of
+    /// consecutive bounces seen by drop rules. Out-of-range codes/values are ignored.
+    pub fn reset_key(&mut self, code: u16, value: i32) {
+        if let Some((code_idx, value_idx)) = Self::slot(code, value) {
+            self.last_event_us[code_idx][value_idx] = u64::MAX;
+            self.consecutive_bounces[code_idx] = 0;
+            self.count_window_start_us[code_idx][value_idx] = u64::MAX;
+            self.count_window_events[code_idx][value_idx] = 0;
+            self.burst_last_seen_us[code_idx][value_idx] = u64::MAX;
+        }
+    }
+
+    /// Maps a key code and value to state array indices, if in range.
+    fn slot(code: u16, value: i32) -> Option<(usize, usize)> {
+        let code_idx = code as usize;
+        let value_idx = usize::try_from(value).ok()?;
+        (code_idx < FILTER_MAP_SIZE && value_idx < NUM_KEY_STATES).then_some((code_idx, value_idx))
     }
 
     /// Returns the total duration based on the first and last event timestamps seen.
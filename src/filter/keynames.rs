@@ -1,4 +1,11 @@
-use input_linux_sys::{EV_ABS, EV_KEY, EV_LED, EV_MSC, EV_REL, EV_SYN};
+use crate::filter::FILTER_MAP_SIZE;
+use crate::util;
+use input_linux_sys::{
+    EV_ABS, EV_KEY, EV_LED, EV_MAX, EV_MSC, EV_REL, EV_REP, EV_SND, EV_SW, EV_SYN,
+};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::OnceLock;
 
 static KEY_NAMES: phf::Map<u16, &'static str> = phf::phf_map! {
     0u16 => "KEY_RESERVED",
@@ -113,6 +120,30 @@ pub fn get_key_name(code: u16) -> &'static str {
     KEY_NAMES.get(&code).copied().unwrap_or("UNKNOWN")
 }
 
+/// Stand-in name of a pseudonymous key code in `--anonymize` reports, e.g. `key_1a3`.
+pub fn pseudonym_name(code: u16) -> &'static str {
+    static NAMES: OnceLock<Vec<String>> = OnceLock::new();
+    NAMES
+        .get_or_init(|| {
+            (0..FILTER_MAP_SIZE)
+                .map(|c| format!("key_{c:03x}"))
+                .collect()
+        })
+        .get(usize::from(code))
+        .map_or("UNKNOWN", String::as_str)
+}
+
+/// A random permutation of all key codes drawn from `seed`: entry `code` is the
+/// pseudonymous code reported in place of `code` under `--anonymize`.
+pub fn pseudonym_codes(seed: u64) -> Vec<u16> {
+    let mut codes: Vec<u16> = (0..FILTER_MAP_SIZE as u16).collect();
+    let mut rng = util::SampleRng::new(seed);
+    for i in (1..codes.len()).rev() {
+        codes.swap(i, rng.below(i as u64 + 1) as usize);
+    }
+    codes
+}
+
 /// Resolve a key identifier (numeric code or symbolic name) to a key code.
 /// The lookup is case-insensitive for symbolic names.
 #[inline]
@@ -136,6 +167,36 @@ pub fn resolve_key_code(identifier: &str) -> Option<u16> {
     })
 }
 
+/// Resolves an event type given as a number or a name like `EV_REL`
+/// (case-insensitive).
+pub fn resolve_event_type(identifier: &str) -> Option<u16> {
+    let trimmed = identifier.trim();
+    if let Ok(type_) = trimmed.parse::<u16>() {
+        return Some(type_);
+    }
+    let normalized = trimmed.to_ascii_uppercase();
+    (0..=EV_MAX as u16).find(|&type_| get_event_type_name(type_) == normalized)
+}
+
+/// Loads `--key-aliases`: a TOML table mapping key names or codes to display
+/// names, e.g. `KEY_YEN = "Yen"` or `183 = "Macro 1"`.
+pub fn load_key_aliases(path: &Path) -> Result<HashMap<u16, String>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Cannot read key aliases file {}: {e}", path.display()))?;
+    let table: HashMap<String, String> = toml::from_str(&text)
+        .map_err(|e| format!("Invalid key aliases file {}: {e}", path.display()))?;
+    table
+        .into_iter()
+        .map(|(key, alias)| {
+            resolve_key_code(&key)
+                .map(|code| (code, alias))
+                .ok_or_else(|| {
+                    format!("Unknown key '{key}' in key aliases file {}", path.display())
+                })
+        })
+        .collect()
+}
+
 #[inline]
 pub fn get_event_type_name(type_: u16) -> &'static str {
     match i32::from(type_) {
@@ -145,16 +206,66 @@ pub fn get_event_type_name(type_: u16) -> &'static str {
         EV_ABS => "EV_ABS",
         EV_MSC => "EV_MSC",
         EV_LED => "EV_LED",
+        EV_SW => "EV_SW",
+        EV_SND => "EV_SND",
+        EV_REP => "EV_REP",
         _ => "Unknown",
     }
 }
 
+/// Name of an event value, interpreted according to the event type: key
+/// states for EV_KEY, on/off for switches, LEDs and sounds, the direction of an
+/// EV_REL delta. Returns `None` for types whose value is just a number
+/// (EV_ABS positions, EV_MSC scancodes, ...), which is best shown as is.
 #[inline]
-pub fn get_value_name(value: i32) -> &'static str {
-    match value {
-        0 => "Release",
-        1 => "Press",
-        2 => "Repeat",
-        _ => "Unknown",
+pub fn get_value_name(type_: u16, value: i32) -> Option<&'static str> {
+    let name = match (i32::from(type_), value) {
+        (EV_KEY, 0) => "Release",
+        (EV_KEY, 1) => "Press",
+        (EV_KEY, 2) => "Repeat",
+        (EV_KEY, _) => "Unknown",
+        (EV_SW | EV_LED | EV_SND, 0) => "Off",
+        (EV_SW | EV_LED | EV_SND, _) => "On",
+        (EV_REL, v) if v > 0 => "Positive",
+        (EV_REL, v) if v < 0 => "Negative",
+        (EV_REL, _) => "Zero",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{get_value_name, resolve_event_type};
+    use input_linux_sys::{EV_ABS, EV_KEY, EV_LED, EV_REL, EV_SW, EV_SYN};
+
+    #[test]
+    fn key_values_keep_their_state_names() {
+        assert_eq!(get_value_name(EV_KEY as u16, 0), Some("Release"));
+        assert_eq!(get_value_name(EV_KEY as u16, 1), Some("Press"));
+        assert_eq!(get_value_name(EV_KEY as u16, 2), Some("Repeat"));
+        assert_eq!(get_value_name(EV_KEY as u16, 7), Some("Unknown"));
+    }
+
+    #[test]
+    fn value_names_follow_the_event_type() {
+        // A lid switch closing and opening, a Caps Lock LED turning off.
+        assert_eq!(get_value_name(EV_SW as u16, 1), Some("On"));
+        assert_eq!(get_value_name(EV_SW as u16, 0), Some("Off"));
+        assert_eq!(get_value_name(EV_LED as u16, 0), Some("Off"));
+        // Scroll wheel notches up and down.
+        assert_eq!(get_value_name(EV_REL as u16, 1), Some("Positive"));
+        assert_eq!(get_value_name(EV_REL as u16, -3), Some("Negative"));
+        // Absolute positions are plain numbers.
+        assert_eq!(get_value_name(EV_ABS as u16, 2), None);
+    }
+
+    #[test]
+    fn event_types_resolve_by_name_or_number() {
+        assert_eq!(resolve_event_type("EV_REL"), Some(EV_REL as u16));
+        assert_eq!(resolve_event_type("ev_syn"), Some(EV_SYN as u16));
+        assert_eq!(resolve_event_type("1"), Some(EV_KEY as u16));
+        assert_eq!(resolve_event_type("EV_BOGUS"), None);
+        assert_eq!(resolve_event_type("Unknown"), None);
     }
 }
@@ -0,0 +1,436 @@
+// Minimal expression language for `--drop-rule`.
+//
+// A rule is parsed once at startup into a small AST and evaluated for every
+// debounce candidate (a key press/release with a previous passed event of the
+// same key and state). Only a fixed set of integer variables is available, so
+// a rule can't do anything other than compute a drop/pass decision.
+//
+// Grammar (C-like precedence, all values are i64, comparisons yield 0/1):
+//   expr    := or
+//   or      := and ("||" and)*
+//   and     := cmp ("&&" cmp)*
+//   cmp     := sum (("<" | "<=" | ">" | ">=" | "==" | "!=") sum)?
+//   sum     := product (("+" | "-") product)*
+//   product := unary (("*" | "/" | "%") unary)*
+//   unary   := ("!" | "-") unary | primary
+//   primary := INTEGER | VARIABLE | "(" expr ")"
+
+use std::fmt;
+
+/// Deepest nesting of parentheses and unary operators a rule may use. The
+/// parser and evaluator recurse once per level, so this bounds their stack use.
+const MAX_DEPTH: usize = 32;
+
+/// Variables available to a drop rule, filled in per candidate event.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RuleVars {
+    /// Time since the last passed event with the same key code and value (µs).
+    pub diff_us: u64,
+    /// Configured `--debounce-time` (µs).
+    pub debounce_us: u64,
+    /// Key code of the event.
+    pub key_code: u16,
+    /// Key value of the event (0=release, 1=press).
+    pub value: i32,
+    /// Number of consecutive preceding events of this key that arrived within
+    /// the debounce window, whether or not the rule dropped them.
+    pub prev_bounces: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Var {
+    DiffUs,
+    DebounceUs,
+    KeyCode,
+    Value,
+    PrevBounces,
+}
+
+impl Var {
+    const ALL: [(&'static str, Var); 5] = [
+        ("diff_us", Var::DiffUs),
+        ("debounce_us", Var::DebounceUs),
+        ("key_code", Var::KeyCode),
+        ("value", Var::Value),
+        ("prev_bounces", Var::PrevBounces),
+    ];
+
+    fn from_name(name: &str) -> Option<Var> {
+        Self::ALL.iter().find(|(n, _)| *n == name).map(|(_, v)| *v)
+    }
+
+    fn get(self, vars: &RuleVars) -> i64 {
+        match self {
+            Var::DiffUs => i64::try_from(vars.diff_us).unwrap_or(i64::MAX),
+            Var::DebounceUs => i64::try_from(vars.debounce_us).unwrap_or(i64::MAX),
+            Var::KeyCode => i64::from(vars.key_code),
+            Var::Value => i64::from(vars.value),
+            Var::PrevBounces => i64::from(vars.prev_bounces),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinOp {
+    Or,
+    And,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Int(i64),
+    Var(Var),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, vars: &RuleVars) -> i64 {
+        match self {
+            Expr::Int(n) => *n,
+            Expr::Var(v) => v.get(vars),
+            Expr::Not(e) => i64::from(e.eval(vars) == 0),
+            Expr::Neg(e) => e.eval(vars).wrapping_neg(),
+            Expr::Binary(op, lhs, rhs) => {
+                let l = lhs.eval(vars);
+                // Short-circuit the logical operators.
+                match op {
+                    BinOp::Or if l != 0 => return 1,
+                    BinOp::And if l == 0 => return 0,
+                    _ => {}
+                }
+                let r = rhs.eval(vars);
+                match op {
+                    BinOp::Or | BinOp::And => i64::from(r != 0),
+                    BinOp::Lt => i64::from(l < r),
+                    BinOp::Le => i64::from(l <= r),
+                    BinOp::Gt => i64::from(l > r),
+                    BinOp::Ge => i64::from(l >= r),
+                    BinOp::Eq => i64::from(l == r),
+                    BinOp::Ne => i64::from(l != r),
+                    BinOp::Add => l.wrapping_add(r),
+                    BinOp::Sub => l.wrapping_sub(r),
+                    BinOp::Mul => l.wrapping_mul(r),
+                    // Division by zero evaluates to 0 rather than panicking.
+                    BinOp::Div => l.checked_div(r).unwrap_or(0),
+                    BinOp::Rem => l.checked_rem(r).unwrap_or(0),
+                }
+            }
+        }
+    }
+}
+
+/// A compiled `--drop-rule` expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropRule {
+    source: String,
+    expr: Expr,
+}
+
+impl DropRule {
+    /// Parses a rule expression. Errors describe the offending position.
+    pub fn parse(source: &str) -> Result<DropRule, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.parse_or()?;
+        if let Some(tok) = parser.peek() {
+            return Err(format!("Unexpected '{tok}' in drop rule '{source}'"));
+        }
+        Ok(DropRule {
+            source: source.to_string(),
+            expr,
+        })
+    }
+
+    /// Evaluates the rule. Returns `true` if the event should be dropped.
+    pub fn should_drop(&self, vars: &RuleVars) -> bool {
+        self.expr.eval(vars) != 0
+    }
+
+    /// The rule as written on the command line.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl fmt::Display for DropRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Int(i64),
+    Ident(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Int(n) => write!(f, "{n}"),
+            Token::Ident(s) => f.write_str(s),
+            Token::Op(op) => f.write_str(op),
+            Token::LParen => f.write_str("("),
+            Token::RParen => f.write_str(")"),
+        }
+    }
+}
+
+// Longest operators first so "<=" isn't read as "<" followed by "=".
+const OPERATORS: [&str; 15] = [
+    "||", "&&", "<=", ">=", "==", "!=", "<", ">", "+", "-", "*", "/", "%", "!", "=",
+];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let bytes = source.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'_') {
+                i += 1;
+            }
+            let digits = source[start..i].replace('_', "");
+            let n = digits
+                .parse::<i64>()
+                .map_err(|_| format!("Number '{}' is out of range", &source[start..i]))?;
+            tokens.push(Token::Int(n));
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(source[start..i].to_string()));
+        } else if c == '(' {
+            tokens.push(Token::LParen);
+            i += 1;
+        } else if c == ')' {
+            tokens.push(Token::RParen);
+            i += 1;
+        } else if let Some(op) = OPERATORS.iter().find(|op| source[i..].starts_with(**op)) {
+            if *op == "=" {
+                return Err(format!("Use '==' for comparison in drop rule '{source}'"));
+            }
+            tokens.push(Token::Op(op));
+            i += op.len();
+        } else {
+            return Err(format!(
+                "Unexpected character '{}' at position {i} in drop rule '{source}'",
+                &source[i..].chars().next().unwrap_or(c)
+            ));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    // Parentheses and unary operators currently open.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    /// Consumes the next token if it is one of `ops`, returning the matched operator.
+    fn eat_op(&mut self, ops: &[&'static str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses one nesting level deeper with `parse`, failing past [`MAX_DEPTH`].
+    fn nested(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<Expr, String>,
+    ) -> Result<Expr, String> {
+        if self.depth == MAX_DEPTH {
+            return Err(format!(
+                "Drop rule is nested too deeply (at most {MAX_DEPTH} levels)"
+            ));
+        }
+        self.depth += 1;
+        let expr = parse(self);
+        self.depth -= 1;
+        expr
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_and()?;
+        while self.eat_op(&["||"]).is_some() {
+            let rhs = self.parse_and()?;
+            lhs = Expr::Binary(BinOp::Or, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_cmp()?;
+        while self.eat_op(&["&&"]).is_some() {
+            let rhs = self.parse_cmp()?;
+            lhs = Expr::Binary(BinOp::And, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_cmp(&mut self) -> Result<Expr, String> {
+        let lhs = self.parse_sum()?;
+        let op = match self.eat_op(&["<", "<=", ">", ">=", "==", "!="]) {
+            Some("<") => BinOp::Lt,
+            Some("<=") => BinOp::Le,
+            Some(">") => BinOp::Gt,
+            Some(">=") => BinOp::Ge,
+            Some("==") => BinOp::Eq,
+            Some("!=") => BinOp::Ne,
+            _ => return Ok(lhs),
+        };
+        let rhs = self.parse_sum()?;
+        Ok(Expr::Binary(op, Box::new(lhs), Box::new(rhs)))
+    }
+
+    fn parse_sum(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_product()?;
+        while let Some(op) = self.eat_op(&["+", "-"]) {
+            let op = if op == "+" { BinOp::Add } else { BinOp::Sub };
+            let rhs = self.parse_product()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_product(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.parse_unary()?;
+        while let Some(op) = self.eat_op(&["*", "/", "%"]) {
+            let op = match op {
+                "*" => BinOp::Mul,
+                "/" => BinOp::Div,
+                _ => BinOp::Rem,
+            };
+            let rhs = self.parse_unary()?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, String> {
+        match self.eat_op(&["!", "-"]) {
+            Some("!") => Ok(Expr::Not(Box::new(self.nested(Self::parse_unary)?))),
+            Some(_) => Ok(Expr::Neg(Box::new(self.nested(Self::parse_unary)?))),
+            None => self.parse_primary(),
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Int(n)) => Ok(Expr::Int(n)),
+            Some(Token::Ident(name)) => Var::from_name(&name).map(Expr::Var).ok_or_else(|| {
+                let known: Vec<&str> = Var::ALL.iter().map(|(n, _)| *n).collect();
+                format!(
+                    "Unknown variable '{name}' in drop rule (available: {})",
+                    known.join(", ")
+                )
+            }),
+            Some(Token::LParen) => {
+                let expr = self.nested(Self::parse_or)?;
+                match self.next() {
+                    Some(Token::RParen) => Ok(expr),
+                    _ => Err("Missing ')' in drop rule".to_string()),
+                }
+            }
+            Some(tok) => Err(format!("Unexpected '{tok}' in drop rule")),
+            None => Err("Unexpected end of drop rule".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DropRule, RuleVars};
+
+    fn vars(diff_us: u64, prev_bounces: u32) -> RuleVars {
+        RuleVars {
+            diff_us,
+            debounce_us: 10_000,
+            key_code: 30,
+            value: 1,
+            prev_bounces,
+        }
+    }
+
+    #[test]
+    fn evaluates_precedence_and_arithmetic() {
+        let rule = DropRule::parse("diff_us * 2 < debounce_us || key_code == 31").unwrap();
+        assert!(rule.should_drop(&vars(4_000, 0)));
+        assert!(!rule.should_drop(&vars(6_000, 0)));
+
+        let rule = DropRule::parse("!(value == 0) && -diff_us > -debounce_us").unwrap();
+        assert!(rule.should_drop(&vars(9_999, 0)));
+        assert!(!rule.should_drop(&vars(10_000, 0)));
+    }
+
+    #[test]
+    fn division_by_zero_is_zero() {
+        let rule = DropRule::parse("diff_us / (prev_bounces - prev_bounces) == 0").unwrap();
+        assert!(rule.should_drop(&vars(5, 3)));
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        assert!(DropRule::parse("").is_err());
+        assert!(DropRule::parse("diff_us <").is_err());
+        assert!(DropRule::parse("(diff_us < 5").is_err());
+        assert!(DropRule::parse("diff_us = 5").is_err());
+        assert!(DropRule::parse("uptime > 5").is_err());
+        assert!(DropRule::parse("diff_us < 5 5").is_err());
+        assert!(DropRule::parse("diff_us; rm -rf /").is_err());
+    }
+
+    #[test]
+    fn rejects_rules_nested_too_deeply() {
+        let nested =
+            |depth: usize| format!("{}diff_us{} < 5", "(".repeat(depth), ")".repeat(depth));
+        assert!(DropRule::parse(&nested(32)).is_ok());
+        let err = DropRule::parse(&nested(33)).unwrap_err();
+        assert!(err.contains("nested too deeply"), "{err}");
+        assert!(DropRule::parse(&nested(100_000)).is_err());
+        assert!(DropRule::parse(&format!("{}diff_us", "!".repeat(100_000))).is_err());
+    }
+}
@@ -1,13 +1,16 @@
 // This module defines the StatsCollector struct and related types
 // used by the logger thread to accumulate and report statistics.
+use crate::cli::{StatsFormat, StatsGranularity};
 use crate::filter::{FILTER_MAP_SIZE, NUM_KEY_STATES};
 
-use crate::filter::keynames::{get_key_name, get_value_name};
+use crate::filter::keynames::{get_key_name, get_value_name, pseudonym_name};
 use crate::logger::EventInfo;
 use crate::util;
-use serde::Serialize;
+use input_linux_sys::EV_KEY;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::io::Write;
+use std::path::Path;
 use std::time::Duration;
 
 // Define histogram bucket boundaries in milliseconds.
@@ -19,7 +22,33 @@ pub const NUM_HISTOGRAM_BUCKETS: usize = HISTOGRAM_BUCKET_BOUNDARIES_MS.len() +
 pub const MAX_BOUNCE_TIMING_SAMPLES: usize = 512;
 pub const MAX_NEAR_MISS_TIMING_SAMPLES: usize = 512;
 
-#[derive(Debug, Clone)]
+/// Percentile of observed bounce timings used as the basis for debounce recommendations.
+pub const RECOMMENDATION_PERCENTILE: f64 = 99.0;
+/// Safety margin (µs) added on top of the bounce percentile when recommending a debounce time.
+pub const RECOMMENDATION_MARGIN_US: u64 = 1_000;
+/// Drop rates below this percentage are shown in green with `--color`.
+pub const DROP_RATE_OK_PCT: f64 = 1.0;
+/// Drop rates at or above this percentage are shown in red with `--color` (yellow in between).
+pub const DROP_RATE_HIGH_PCT: f64 = 10.0;
+/// Seed used for random sampling unless `--sample-seed` gives another.
+pub const DEFAULT_SAMPLE_SEED: u64 = 0;
+/// Minimum key events before a key's drop rate is judged against `--dead-switch-threshold`.
+pub const DEAD_SWITCH_MIN_EVENTS: u64 = 20;
+/// Longest histogram bar, in characters, unless `--histogram-width` says otherwise.
+pub const DEFAULT_HISTOGRAM_WIDTH: usize = 50;
+/// Shortest bar picked to fit a narrow terminal.
+const MIN_AUTO_HISTOGRAM_WIDTH: usize = 10;
+// Characters of a histogram line besides the bar: label, count, percentage, brackets.
+const HISTOGRAM_LINE_OVERHEAD: usize = 31;
+// Extra characters of the `, cum NN.N%` column (`--histogram-cumulative`).
+const HISTOGRAM_CUMULATIVE_OVERHEAD: usize = 11;
+
+/// The most recent timings, at most `capacity` of them, oldest first.
+///
+/// Persisted as a plain list of samples; on load only the newest
+/// `MAX_BOUNCE_TIMING_SAMPLES` are kept so state files stay bounded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "Vec<u64>", into = "Vec<u64>")]
 pub struct TimingSamples {
     data: VecDeque<u64>,
     capacity: usize,
@@ -31,6 +60,16 @@ impl TimingSamples {
         Self { data, capacity }
     }
 
+    /// Rebuilds samples from a report's list, oldest first, keeping the newest
+    /// `capacity` of them.
+    fn from_report(values: Vec<u64>, capacity: usize) -> Self {
+        let mut samples = Self::with_capacity(capacity);
+        for value in values {
+            samples.push(value);
+        }
+        samples
+    }
+
     pub fn push(&mut self, value: u64) {
         if self.capacity == 0 {
             return;
@@ -52,6 +91,59 @@ impl TimingSamples {
     pub fn to_vec(&self) -> Vec<u64> {
         self.data.iter().copied().collect()
     }
+
+    /// Appends another set of samples, oldest first, evicting as usual.
+    pub fn merge(&mut self, other: &TimingSamples) {
+        for &value in &other.data {
+            self.push(value);
+        }
+    }
+
+    /// Returns the nearest-rank percentile (0-100) of the retained samples.
+    pub fn percentile(&self, pct: f64) -> Option<u64> {
+        percentile_of(self.to_vec(), pct)
+    }
+}
+
+/// Nearest-rank percentile of an unsorted set of values. Returns `None` if empty.
+fn percentile_of(mut values: Vec<u64>, pct: f64) -> Option<u64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let rank = ((pct.clamp(0.0, 100.0) / 100.0) * values.len() as f64).ceil() as usize;
+    Some(values[rank.saturating_sub(1).min(values.len() - 1)])
+}
+
+/// Formats a drop rate as `12.34%`, coloured by severity when `color` is set.
+fn format_drop_rate(pct: f64, color: bool) -> String {
+    let severity = if pct < DROP_RATE_OK_PCT {
+        util::Color::Green
+    } else if pct < DROP_RATE_HIGH_PCT {
+        util::Color::Yellow
+    } else {
+        util::Color::Red
+    };
+    util::paint(&format!("{pct:.2}%"), severity, color)
+}
+
+/// How `format_histogram_human` labels rows and scales bars.
+#[derive(Debug, Clone, Copy)]
+enum BucketLabels {
+    /// Each row names its bucket range; bars scale to the histogram's fullest bucket.
+    Inline,
+    /// Rows show bucket indices from a legend the caller prints once; bars scale
+    /// to `max_count` so histograms printed together share one scale.
+    Legend { max_count: u64 },
+}
+
+/// Turns a bounce percentile into a debounce recommendation: adds the safety
+/// margin and rounds up to a whole millisecond so it can be passed to `--debounce-time`.
+fn recommend_from_percentile(percentile_us: u64) -> u64 {
+    percentile_us
+        .saturating_add(RECOMMENDATION_MARGIN_US)
+        .div_ceil(1000)
+        .saturating_mul(1000)
 }
 
 impl Default for TimingSamples {
@@ -60,7 +152,114 @@ impl Default for TimingSamples {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+impl From<Vec<u64>> for TimingSamples {
+    fn from(values: Vec<u64>) -> Self {
+        Self::from_report(values, MAX_BOUNCE_TIMING_SAMPLES)
+    }
+}
+
+impl From<TimingSamples> for Vec<u64> {
+    fn from(samples: TimingSamples) -> Self {
+        samples.data.into()
+    }
+}
+
+/// Events per second, counted over 1-second windows of event timestamps.
+/// Idle seconds between the first and the latest event count towards the average.
+/// Number of 0.1-wide buckets of `WindowRatioHistogram`, covering ratios 0.0-2.0.
+pub const WINDOW_RATIO_BUCKETS: usize = 20;
+
+/// Distribution of debounce decisions relative to the debounce window: the
+/// time since the previous event of a key divided by the debounce time.
+/// Bounces fall below 1.0, passes at or above it; passes at 2.0 or more are
+/// ordinary typing and aren't counted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowRatioHistogram {
+    pub buckets: [u64; WINDOW_RATIO_BUCKETS],
+}
+
+impl WindowRatioHistogram {
+    /// Counts a decision made `diff_us` after the previous event, with a debounce
+    /// time of `debounce_us`. Ignored with no debounce time or a ratio of 2.0 or more.
+    pub fn record(&mut self, diff_us: u64, debounce_us: u64) {
+        if debounce_us == 0 {
+            return;
+        }
+        let bucket = u128::from(diff_us) * 10 / u128::from(debounce_us);
+        if let Some(count) = self.buckets.get_mut(bucket as usize) {
+            *count += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: &WindowRatioHistogram) {
+        for (dest, source) in self.buckets.iter_mut().zip(&other.buckets) {
+            *dest += source;
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.buckets.iter().sum()
+    }
+
+    /// Lower ratio bound of bucket `index`, e.g. 0.5 for the 0.5-0.6 bucket.
+    pub fn bucket_start(index: usize) -> f64 {
+        index as f64 / 10.0
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventRate {
+    events: u64,
+    /// 1-second windows spanned so far, the current one included.
+    seconds: u64,
+    peak: u64,
+    current_window: Option<u64>,
+    current_count: u64,
+}
+
+impl EventRate {
+    /// Counts one event with timestamp `event_us`. Timestamps that go backwards
+    /// are counted in the current window.
+    pub fn record(&mut self, event_us: u64) {
+        let window = event_us / 1_000_000;
+        match self.current_window {
+            Some(current) if window <= current => self.current_count += 1,
+            Some(current) => {
+                self.seconds += window - current;
+                self.current_window = Some(window);
+                self.current_count = 1;
+            }
+            None => {
+                self.seconds = self.seconds.max(1);
+                self.current_window = Some(window);
+                self.current_count = 1;
+            }
+        }
+        self.events += 1;
+        self.peak = self.peak.max(self.current_count);
+    }
+
+    /// Folds the rate of another run into this one. Its open window is not continued.
+    pub fn merge(&mut self, other: &EventRate) {
+        self.events += other.events;
+        self.seconds += other.seconds;
+        self.peak = self.peak.max(other.peak);
+    }
+
+    /// Average events per second over the spanned windows, `None` before the first event.
+    pub fn average(&self) -> Option<f64> {
+        (self.seconds > 0).then(|| self.events as f64 / self.seconds as f64)
+    }
+
+    /// Most events seen in a single 1-second window.
+    pub fn peak(&self) -> u64 {
+        self.peak
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TimingSummary {
     count: u64,
     sum_us: u128,
@@ -82,6 +281,36 @@ impl TimingSummary {
         });
     }
 
+    /// Folds another summary into this one.
+    pub fn merge(&mut self, other: &TimingSummary) {
+        self.count = self.count.saturating_add(other.count);
+        self.sum_us = self.sum_us.saturating_add(other.sum_us);
+        self.min_us = match (self.min_us, other.min_us) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.max_us = match (self.max_us, other.max_us) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    /// Rebuilds a summary from reported values. The sum is recovered from the
+    /// (rounded) average, so later averages may be off by a fraction of a µs.
+    fn from_report(
+        count: u64,
+        avg_us: Option<u64>,
+        min_us: Option<u64>,
+        max_us: Option<u64>,
+    ) -> Self {
+        Self {
+            count,
+            sum_us: u128::from(avg_us.unwrap_or(0)) * u128::from(count),
+            min_us,
+            max_us,
+        }
+    }
+
     pub fn count(&self) -> u64 {
         self.count
     }
@@ -104,7 +333,7 @@ impl TimingSummary {
 }
 
 /// Represents a histogram of timing values.
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TimingHistogram {
     // Counts per bucket. Index 0 is for values < boundary[0], index N is for values >= boundary[N-1].
     pub buckets: [u64; NUM_HISTOGRAM_BUCKETS],
@@ -112,9 +341,10 @@ pub struct TimingHistogram {
     pub count: u64,
     // Sum of all timings recorded (in microseconds) for calculating average.
     pub sum_us: u64,
-    // Optional: Store min/max directly if needed, otherwise calculate from raw data if kept.
-    // pub min_us: u64,
-    // pub max_us: u64,
+    // Sum of squared timings (µs²) for calculating the standard deviation.
+    #[serde(default)]
+    pub sum_sq_us: u128,
+    // Min/max aren't kept here; `TimingSummary` tracks them next to each histogram.
 }
 
 impl Default for TimingHistogram {
@@ -123,6 +353,7 @@ impl Default for TimingHistogram {
             buckets: [0; NUM_HISTOGRAM_BUCKETS],
             count: 0,
             sum_us: 0,
+            sum_sq_us: 0,
         }
     }
 }
@@ -144,18 +375,62 @@ impl TimingHistogram {
         self.buckets[bucket_index] += 1;
         self.count += 1;
         self.sum_us = self.sum_us.saturating_add(timing_us); // Use saturating_add
-                                                             // Optional: Update min/max
-                                                             // self.min_us = self.min_us.min(timing_us);
-                                                             // self.max_us = self.max_us.max(timing_us);
+        self.sum_sq_us = self
+            .sum_sq_us
+            .saturating_add(u128::from(timing_us) * u128::from(timing_us));
     }
 
     /// Calculates the average timing in microseconds. Returns 0 if count is 0.
     pub fn average_us(&self) -> u64 {
-        if self.count > 0 {
-            self.sum_us / self.count
-        } else {
-            0
+        self.sum_us.checked_div(self.count).unwrap_or(0)
+    }
+
+    /// Population standard deviation of the recorded timings in microseconds.
+    /// Returns 0 if count is 0.
+    pub fn stddev_us(&self) -> u64 {
+        if self.count == 0 {
+            return 0;
+        }
+        let n = self.count as f64;
+        let mean = self.sum_us as f64 / n;
+        // Clamp: rounding can push the variance of identical samples slightly below zero.
+        let variance = (self.sum_sq_us as f64 / n - mean * mean).max(0.0);
+        variance.sqrt().round() as u64
+    }
+
+    /// Rebuilds a histogram from its JSON form. The sums are recovered from the
+    /// rounded average and standard deviation.
+    fn from_json(json: &TimingHistogramJson) -> Result<Self, String> {
+        if json.buckets.len() != NUM_HISTOGRAM_BUCKETS {
+            return Err(format!(
+                "histogram has {} buckets, expected {NUM_HISTOGRAM_BUCKETS}",
+                json.buckets.len()
+            ));
+        }
+        let mut histogram = TimingHistogram::default();
+        for (dest, bucket) in histogram.buckets.iter_mut().zip(&json.buckets) {
+            *dest = bucket.count;
+        }
+        let (avg, stddev) = (u128::from(json.avg_us), u128::from(json.stddev_us));
+        histogram.count = json.count;
+        histogram.sum_us = json.avg_us.saturating_mul(json.count);
+        histogram.sum_sq_us = (stddev * stddev + avg * avg).saturating_mul(u128::from(json.count));
+        Ok(histogram)
+    }
+
+    /// Cumulative percentage of recorded timings at or below each bucket's upper
+    /// boundary. Non-decreasing, and 100.0 at the last bucket if any data exists.
+    pub fn cumulative_percentages(&self) -> [f64; NUM_HISTOGRAM_BUCKETS] {
+        let mut cumulative = [0.0; NUM_HISTOGRAM_BUCKETS];
+        if self.count == 0 {
+            return cumulative;
         }
+        let mut running = 0u64;
+        for (i, &bucket_count) in self.buckets.iter().enumerate() {
+            running += bucket_count;
+            cumulative[i] = (running as f64 / self.count as f64) * 100.0;
+        }
+        cumulative
     }
 
     // Add methods like get_buckets(), get_count() if needed externally.
@@ -173,7 +448,7 @@ pub struct Meta {
 
 /// Statistics for a specific key value state (press/release/repeat).
 /// Holds the count of dropped events and the timing differences for those drops.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyValueStats {
     /// Total events processed (passed + dropped) for this specific key state.
     pub total_processed: u64,
@@ -187,6 +462,9 @@ pub struct KeyValueStats {
     pub bounce_summary: TimingSummary,
     /// Sampled bounce timings retained for debugging/JSON output.
     pub bounce_samples: TimingSamples,
+    /// Time between consecutive passed events of this key state (`--interval-histogram`).
+    #[serde(default)]
+    pub pass_interval_histogram: TimingHistogram,
 }
 
 impl Default for KeyValueStats {
@@ -198,6 +476,7 @@ impl Default for KeyValueStats {
             bounce_histogram: TimingHistogram::default(),
             bounce_summary: TimingSummary::default(),
             bounce_samples: TimingSamples::with_capacity(MAX_BOUNCE_TIMING_SAMPLES),
+            pass_interval_histogram: TimingHistogram::default(),
         }
     }
 }
@@ -210,10 +489,48 @@ impl KeyValueStats {
         self.bounce_histogram.record(value);
         self.bounce_samples.push(value);
     }
+
+    /// Rebuilds the stats of one key state from a JSON report.
+    fn from_json(json: &KeyValueStatsJson) -> Result<Self, String> {
+        Ok(Self {
+            total_processed: json.total_processed,
+            passed_count: json.passed_count,
+            dropped_count: json.dropped_count,
+            bounce_histogram: TimingHistogram::from_json(&json.bounce_histogram)?,
+            bounce_summary: TimingSummary::from_report(
+                json.timings_total_count,
+                json.avg_us,
+                json.min_us,
+                json.max_us,
+            ),
+            bounce_samples: TimingSamples::from_report(
+                json.timings_us.clone(),
+                MAX_BOUNCE_TIMING_SAMPLES,
+            ),
+            pass_interval_histogram: match &json.pass_interval_histogram {
+                Some(histogram) => TimingHistogram::from_json(histogram)?,
+                None => TimingHistogram::default(),
+            },
+        })
+    }
+
+    /// Folds the counts and timings of another (older or newer) run into this one.
+    pub fn merge(&mut self, other: &KeyValueStats) {
+        self.total_processed += other.total_processed;
+        self.passed_count += other.passed_count;
+        self.dropped_count += other.dropped_count;
+        StatsCollector::accumulate_histogram(&mut self.bounce_histogram, &other.bounce_histogram);
+        self.bounce_summary.merge(&other.bounce_summary);
+        self.bounce_samples.merge(&other.bounce_samples);
+        StatsCollector::accumulate_histogram(
+            &mut self.pass_interval_histogram,
+            &other.pass_interval_histogram,
+        );
+    }
 }
 
 /// Statistics for passed events that were near misses for a specific key value state.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NearMissStats {
     /// Aggregated statistics for near-miss timings.
     pub summary: TimingSummary,
@@ -221,6 +538,9 @@ pub struct NearMissStats {
     pub histogram: TimingHistogram,
     /// Sampled near-miss timings retained for debugging/JSON output.
     pub samples: TimingSamples,
+    /// Near misses per band when several thresholds are given, innermost first.
+    #[serde(default)]
+    pub band_counts: Vec<u64>,
 }
 
 impl Default for NearMissStats {
@@ -229,6 +549,7 @@ impl Default for NearMissStats {
             summary: TimingSummary::default(),
             histogram: TimingHistogram::default(),
             samples: TimingSamples::with_capacity(MAX_NEAR_MISS_TIMING_SAMPLES),
+            band_counts: Vec::new(),
         }
     }
 }
@@ -241,35 +562,192 @@ impl NearMissStats {
         self.histogram.record(value);
         self.samples.push(value);
     }
+
+    /// Counts a near miss in band `band` (see `Config::near_miss_band`).
+    pub fn record_band(&mut self, band: usize) {
+        if self.band_counts.len() <= band {
+            self.band_counts.resize(band + 1, 0);
+        }
+        self.band_counts[band] += 1;
+    }
+
+    /// Band counts padded with zeros to `bands` entries, for reporting.
+    pub fn band_counts_padded(&self, bands: usize) -> Vec<u64> {
+        let mut counts = self.band_counts.clone();
+        if counts.len() < bands {
+            counts.resize(bands, 0);
+        }
+        counts
+    }
+
+    /// Folds the near-miss timings of another run into this one.
+    pub fn merge(&mut self, other: &NearMissStats) {
+        self.summary.merge(&other.summary);
+        StatsCollector::accumulate_histogram(&mut self.histogram, &other.histogram);
+        self.samples.merge(&other.samples);
+        if self.band_counts.len() < other.band_counts.len() {
+            self.band_counts.resize(other.band_counts.len(), 0);
+        }
+        for (dest, source) in self.band_counts.iter_mut().zip(&other.band_counts) {
+            *dest += source;
+        }
+    }
 }
 
 /// Aggregated statistics for a specific key code, containing stats for each value state.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct KeyStats {
     pub press: KeyValueStats,
     pub release: KeyValueStats,
     pub repeat: KeyValueStats,
+    /// Whether the key is currently held, based on the last passed press/release.
+    /// `None` until the first press or release is seen. Not persisted across runs.
+    #[serde(skip)]
+    pub is_pressed: Option<bool>,
+    /// Passed events that broke the press/release alternation
+    /// (press while already pressed, or release while already released).
+    pub protocol_violations: u64,
+    /// Passed events whose previous event with the same key value was dropped.
+    pub recovered_passes: u64,
+    /// Press/release/press/release sequences that look like one keystroke typed twice.
+    #[serde(default)]
+    pub double_characters: u64,
+    /// Timestamps (µs) of the first and the latest event of this key, passed or dropped.
+    #[serde(default)]
+    pub first_event_us: Option<u64>,
+    #[serde(default)]
+    pub last_event_us: Option<u64>,
+}
+
+impl KeyStats {
+    /// Suggested `--debounce-time` (µs) for this key, based on the p99 of the
+    /// sampled press/release bounce timings plus a safety margin.
+    /// Returns `None` if the key had no recorded bounces.
+    pub fn recommended_debounce_us(&self) -> Option<u64> {
+        let mut samples = self.press.bounce_samples.to_vec();
+        samples.extend(self.release.bounce_samples.to_vec());
+        percentile_of(samples, RECOMMENDATION_PERCENTILE).map(recommend_from_percentile)
+    }
+
+    /// Events of this key dropped in any state.
+    pub fn total_dropped(&self) -> u64 {
+        self.press.dropped_count + self.release.dropped_count + self.repeat.dropped_count
+    }
+
+    /// Percentage of this key's processed events that were dropped,
+    /// or `None` if the key saw no events.
+    pub fn drop_percentage(&self) -> Option<f64> {
+        let processed =
+            self.press.total_processed + self.release.total_processed + self.repeat.total_processed;
+        let dropped =
+            self.press.dropped_count + self.release.dropped_count + self.repeat.dropped_count;
+        (processed > 0).then(|| (dropped as f64 / processed as f64) * 100.0)
+    }
+
+    /// Whether the drop rate is so high (over enough events) that the switch is more
+    /// likely stuck, dead or misconfigured than chattering.
+    pub fn is_likely_dead_switch(&self, threshold_pct: f64) -> bool {
+        let processed =
+            self.press.total_processed + self.release.total_processed + self.repeat.total_processed;
+        processed >= DEAD_SWITCH_MIN_EVENTS
+            && self
+                .drop_percentage()
+                .is_some_and(|pct| pct >= threshold_pct)
+    }
+
+    /// Folds another run's stats for this key into this one. The key state
+    /// comes from `other`, which is assumed to be the more recent run.
+    pub fn merge(&mut self, other: &KeyStats) {
+        self.press.merge(&other.press);
+        self.release.merge(&other.release);
+        self.repeat.merge(&other.repeat);
+        if other.is_pressed.is_some() {
+            self.is_pressed = other.is_pressed;
+        }
+        self.protocol_violations += other.protocol_violations;
+        self.recovered_passes += other.recovered_passes;
+        self.double_characters += other.double_characters;
+        self.first_event_us = self.first_event_us.or(other.first_event_us);
+        self.last_event_us = other.last_event_us.or(self.last_event_us);
+    }
+
+    /// Notes the timestamp of an event of this key, passed or dropped.
+    fn record_event_time(&mut self, event_us: u64) {
+        self.first_event_us.get_or_insert(event_us);
+        self.last_event_us = Some(event_us);
+    }
+
+    /// Tracks the expected press/release alternation for a passed event,
+    /// counting a violation when the key repeats its previous state.
+    fn record_key_state(&mut self, value: i32) {
+        let pressed = match value {
+            1 => true,
+            0 => false,
+            _ => return, // Repeats don't change the key state
+        };
+        if self.is_pressed == Some(pressed) {
+            self.protocol_violations += 1;
+        }
+        self.is_pressed = Some(pressed);
+    }
 }
 
 /// Structure for serializing per-key drop statistics in JSON.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 struct PerKeyStatsJson {
     key_code: u16,
+    #[serde(skip_deserializing)]
     key_name: &'static str,
     total_processed: u64,
     total_dropped: u64,
     drop_percentage: f64,
+    likely_dead_switch: bool,
+    protocol_violations: u64,
+    recovered_passes: u64,
+    double_characters: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_event_us: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_event_us: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recommended_debounce_us: Option<u64>,
     stats: KeyStatsJson, // Detailed stats for each state
 }
 
-/// Structure for serializing detailed key value stats in JSON.
+/// Structure for serializing an entry of the `top_keys` ranking in JSON.
 #[derive(Serialize, Debug)]
+struct TopKeyJson {
+    key_code: u16,
+    key_name: &'static str,
+    total_dropped: u64,
+    drop_percentage: f64,
+}
+
+/// One key of the `--heatmap-json` export.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct HeatmapKey {
+    pub key_code: u16,
+    pub key_name: &'static str,
+    pub total_processed: u64,
+    pub total_dropped: u64,
+    pub drop_percentage: f64,
+    /// Chatter intensity in `[0, 1]`: the key's weighted drops relative to the
+    /// hottest key's.
+    pub intensity: f64,
+}
+
+/// Structure for serializing detailed key value stats in JSON.
+#[derive(Serialize, Deserialize, Debug)]
 struct KeyValueStatsJson {
     total_processed: u64,
     passed_count: u64,
     dropped_count: u64,
     drop_rate: f64,
     timings_us: Vec<u64>, // Sampled timings
+    /// `true` if `timings_us` holds only a sample of the recorded timings.
+    timings_sampled: bool,
+    /// Number of timings recorded, of which `timings_us` is a (possibly complete) sample.
+    timings_total_count: u64,
     bounce_histogram: TimingHistogramJson,
     #[serde(skip_serializing_if = "Option::is_none")]
     min_us: Option<u64>,
@@ -277,10 +755,13 @@ struct KeyValueStatsJson {
     max_us: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     avg_us: Option<u64>,
+    /// Intervals between passed events of this state (`--interval-histogram` only).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pass_interval_histogram: Option<TimingHistogramJson>,
 }
 
 /// Structure for serializing detailed key stats in JSON.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 struct KeyStatsJson {
     press: KeyValueStatsJson,
     release: KeyValueStatsJson,
@@ -288,32 +769,43 @@ struct KeyStatsJson {
 }
 
 /// Structure for serializing histogram data in JSON.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 struct TimingHistogramJson {
     buckets: Vec<HistogramBucketJson>,
     count: u64,
     avg_us: u64,
-    // min_us: u64, // Optional
-    // max_us: u64, // Optional
+    stddev_us: u64,
+    /// Cumulative percentage per bucket (only with `--histogram-cumulative`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cumulative: Option<Vec<f64>>,
 }
 
 /// Structure for serializing a single histogram bucket in JSON.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 struct HistogramBucketJson {
     min_ms: u64,
     max_ms: Option<u64>, // None for the last bucket (>= max boundary)
+    // The same bounds in microseconds, exact even for sub-millisecond buckets
+    min_us: u64,
+    max_us: Option<u64>,
     count: u64,
 }
 
 /// Structure for serializing near-miss statistics in JSON.
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 struct NearMissStatsJson {
     key_code: u16,
     key_value: i32,
+    #[serde(skip_deserializing)]
     key_name: &'static str,
+    #[serde(skip_deserializing)]
     value_name: &'static str,
     count: usize,
     timings_us: Vec<u64>, // Sampled timings
+    /// `true` if `timings_us` holds only a sample of the recorded timings.
+    timings_sampled: bool,
+    /// Number of timings recorded, of which `timings_us` is a (possibly complete) sample.
+    timings_total_count: u64,
     near_miss_histogram: TimingHistogramJson,
     #[serde(skip_serializing_if = "Option::is_none")]
     min_us: Option<u64>,
@@ -321,11 +813,66 @@ struct NearMissStatsJson {
     max_us: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     avg_us: Option<u64>,
+    /// Near misses per `near_miss_bands_us` band; only present when several thresholds are given.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    band_counts: Option<Vec<u64>>,
+}
+
+/// The parts of a `--stats-json` report needed to rebuild its statistics (`--merge`).
+/// The settings, core counters and per-key stats are required, so a file that
+/// isn't a report is rejected rather than read as an all-zero one.
+#[derive(Deserialize, Debug)]
+struct StatsReportJson {
+    runtime_us: Option<u64>,
+    debounce_time_us: u64,
+    near_miss_threshold_us: u64,
+    near_miss_bands_us: Option<Vec<u64>>,
+    log_interval_us: u64,
+    key_events_processed: u64,
+    key_events_passed: u64,
+    key_events_dropped: u64,
+    #[serde(default)]
+    key_events_paused: u64,
+    #[serde(default)]
+    recovered_passes: u64,
+    #[serde(default)]
+    double_characters: u64,
+    #[serde(default)]
+    double_characters_passed: u64,
+    #[serde(default)]
+    stream_gaps: u64,
+    #[serde(default)]
+    stream_gaps_while_held: u64,
+    #[serde(default)]
+    sample_seed: u64,
+    overall_interval_histogram: Option<TimingHistogramJson>,
+    #[serde(default)]
+    window_ratio_histogram: [u64; WINDOW_RATIO_BUCKETS],
+    per_key_stats: Vec<PerKeyStatsJson>,
+    #[serde(default)]
+    per_key_near_miss_stats: Vec<NearMissStatsJson>,
+}
+
+/// A `--stats-json` report read back by `StatsCollector::load_report`: the
+/// statistics it carries and the settings they were collected with.
+#[derive(Debug, Clone)]
+pub struct LoadedReport {
+    pub stats: StatsCollector,
+    /// Runtime of the reported run, if it was a final (cumulative) report.
+    pub runtime_us: Option<u64>,
+    pub debounce_time: Duration,
+    pub near_miss_threshold: Duration,
+    pub near_miss_bands: Vec<Duration>,
+    pub log_interval: Duration,
+    /// Whether the report carried pass interval histograms (`--interval-histogram`).
+    pub interval_histogram: bool,
 }
 
 /// Top-level statistics collector. Owned and managed by the logger thread.
 /// Accumulates counts, drop timings, and near-miss timings for all processed events.
-#[derive(Debug, Clone)]
+/// Serializable so cumulative stats can be carried across runs (`--persist-state`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StatsCollector {
     /// Total count of key events processed (passed or dropped).
     pub key_events_processed: u64,
@@ -333,6 +880,30 @@ pub struct StatsCollector {
     pub key_events_passed: u64,
     /// Total count of key events dropped by the filter.
     pub key_events_dropped: u64,
+    /// Total count of key events passed unfiltered while filtering was paused.
+    /// These are not included in the processed/passed/dropped counts above.
+    pub key_events_paused: u64,
+    /// Passed key events that immediately followed a drop of the same key and value,
+    /// i.e. the real keystroke getting through after chatter was rejected.
+    pub recovered_passes: u64,
+    /// Silences in the event stream of at least `--gap-threshold`.
+    pub stream_gaps: u64,
+    /// Stream gaps that happened while at least one key was held down, which
+    /// suggests lost events (a connection hiccup) rather than an idle keyboard.
+    pub stream_gaps_while_held: u64,
+    /// Likely "double character" events: a press, release, press, release of one
+    /// key with the re-press within the debounce time of the release.
+    pub double_characters: u64,
+    /// Double-character events whose second press was passed, i.e. typed twice.
+    pub double_characters_passed: u64,
+    /// Rate of input events of any type, recorded by the logger thread.
+    pub event_rate: EventRate,
+    /// Time the main thread spent on the filter decision per event, in
+    /// nanoseconds despite the field names of `TimingSummary` (`--measure-latency`).
+    pub filter_latency_ns: TimingSummary,
+    /// Bounces and near-boundary passes by their distance from the previous
+    /// event as a fraction of the debounce time.
+    pub window_ratio_histogram: WindowRatioHistogram,
     /// Holds aggregated drop stats per key code. Uses a fixed-size array for O(1) lookup.
     pub per_key_stats: Vec<KeyStats>,
     /// Holds near-miss stats per key code and value. Indexed by `keycode * 3 + value`.
@@ -341,6 +912,20 @@ pub struct StatsCollector {
     pub overall_bounce_histogram: TimingHistogram,
     /// Overall histogram for all near_miss timings. Aggregated before reporting.
     pub overall_near_miss_histogram: TimingHistogram,
+    /// Overall histogram of press/release pass intervals. Aggregated before reporting.
+    pub overall_interval_histogram: TimingHistogram,
+    /// Seed for random sampling (`--sample-seed`), reported with the stats.
+    /// Not persisted.
+    #[serde(skip)]
+    pub sample_seed: u64,
+    /// Timestamps of the first and last event of a periodic report's interval,
+    /// set by the logger just before printing. Not persisted.
+    #[serde(skip)]
+    pub interval_us: Option<(u64, u64)>,
+    /// Why the run ended (`EOF`, `SIGINT`, ...), set by the main thread just
+    /// before printing the final report. Not persisted.
+    #[serde(skip)]
+    pub shutdown_reason: Option<String>,
 }
 
 // Implement Default to allow std::mem::take in logger.
@@ -351,9 +936,16 @@ impl Default for StatsCollector {
 }
 
 impl StatsCollector {
-    /// Creates a new StatsCollector with pre-allocated storage.
+    /// Creates a new StatsCollector with pre-allocated storage and the default sample seed.
     #[must_use]
     pub fn with_capacity() -> Self {
+        Self::with_sample_seed(DEFAULT_SAMPLE_SEED)
+    }
+
+    /// Creates a new StatsCollector for sampling seeded from `seed`. Timing
+    /// samples keep the newest timings and don't draw on it.
+    #[must_use]
+    pub fn with_sample_seed(seed: u64) -> Self {
         // Allocate the arrays on the heap using Box::new
         let per_key_stats = vec![KeyStats::default(); FILTER_MAP_SIZE];
         let per_key_near_miss_stats =
@@ -363,10 +955,23 @@ impl StatsCollector {
             key_events_processed: 0,
             key_events_passed: 0,
             key_events_dropped: 0,
+            key_events_paused: 0,
+            recovered_passes: 0,
+            stream_gaps: 0,
+            stream_gaps_while_held: 0,
+            double_characters: 0,
+            double_characters_passed: 0,
+            event_rate: EventRate::default(),
+            filter_latency_ns: TimingSummary::default(),
+            window_ratio_histogram: WindowRatioHistogram::default(),
             per_key_stats,
             per_key_near_miss_stats,
             overall_bounce_histogram: TimingHistogram::default(),
             overall_near_miss_histogram: TimingHistogram::default(),
+            overall_interval_histogram: TimingHistogram::default(),
+            sample_seed: seed,
+            interval_us: None,
+            shutdown_reason: None,
         }
     }
 
@@ -380,11 +985,22 @@ impl StatsCollector {
     ) {
         use crate::event::is_key_event;
 
+        if let Some(latency_ns) = info.filter_latency_ns {
+            self.filter_latency_ns.record(latency_ns);
+        }
+
         // Only process EV_KEY events for these statistics.
         if !is_key_event(&info.event) {
             return;
         }
 
+        // Events that bypassed the filter while paused are counted separately so
+        // they don't skew drop rates or near-miss timings.
+        if info.paused {
+            self.key_events_paused += 1;
+            return;
+        }
+
         self.key_events_processed += 1;
 
         // Get mutable access to the specific KeyValueStats for this event, if valid
@@ -397,6 +1013,7 @@ impl StatsCollector {
             return;
         }
 
+        self.per_key_stats[key_code_idx].record_event_time(info.event_us);
         let value_stats = match info.event.value {
             1 => &mut self.per_key_stats[key_code_idx].press,
             0 => &mut self.per_key_stats[key_code_idx].release,
@@ -413,16 +1030,33 @@ impl StatsCollector {
             value_stats.dropped_count += 1; // Increment drop count for this state
             if let Some(diff) = info.diff_us {
                 value_stats.record_bounce_timing(diff); // Record aggregate + histogram
+                self.window_ratio_histogram
+                    .record(diff, config.debounce_us());
             }
         } else {
             // Event passed the filter.
             self.key_events_passed += 1;
             // Increment passed count
             value_stats.passed_count += 1;
+            // Auto-repeat intervals only reflect the repeat rate, so they're left out.
+            if config.interval_histogram && info.event.value != 2 {
+                if let Some(interval) = info
+                    .last_passed_us
+                    .and_then(|last_us| info.event_us.checked_sub(last_us))
+                {
+                    value_stats.pass_interval_histogram.record(interval);
+                }
+            }
+            // Check the passed event against the key's press/release state
+            self.per_key_stats[key_code_idx].record_key_state(info.event.value);
 
             // Check for near-miss on passed events
             if let Some(last_us) = info.last_passed_us {
                 if let Some(diff) = info.event_us.checked_sub(last_us) {
+                    if info.event.value != 2 {
+                        self.window_ratio_histogram
+                            .record(diff, config.debounce_us());
+                    }
                     // Check if the difference is within the near-miss window (debounce_time <= diff <= threshold)
                     // The filter ensures diff >= debounce_time for passed events.
                     // Here, we check against the near_miss threshold.
@@ -431,20 +1065,373 @@ impl StatsCollector {
                         let idx = key_code_idx * NUM_KEY_STATES + key_value_idx;
                         // Bounds check is already done at the start of the function
                         self.per_key_near_miss_stats[idx].record_timing(diff); // Record aggregate + histogram
+                        if let Some(band) = config.near_miss_band(diff) {
+                            self.per_key_near_miss_stats[idx].record_band(band);
+                        }
                     }
                 }
             }
         }
     }
 
+    /// Adds the counts, histograms and timing samples of `other` to this collector.
+    /// Samples from `other` are treated as newer and evict the oldest ones.
+    /// Overall histograms are rebuilt by `aggregate_histograms` before reporting.
+    pub fn merge(&mut self, other: &StatsCollector) {
+        self.key_events_processed += other.key_events_processed;
+        self.key_events_passed += other.key_events_passed;
+        self.key_events_dropped += other.key_events_dropped;
+        self.key_events_paused += other.key_events_paused;
+        self.recovered_passes += other.recovered_passes;
+        self.double_characters += other.double_characters;
+        self.stream_gaps += other.stream_gaps;
+        self.stream_gaps_while_held += other.stream_gaps_while_held;
+        self.double_characters_passed += other.double_characters_passed;
+        self.event_rate.merge(&other.event_rate);
+        self.filter_latency_ns.merge(&other.filter_latency_ns);
+        self.window_ratio_histogram
+            .merge(&other.window_ratio_histogram);
+        // Report the seed of the run being folded in (the latest session).
+        self.sample_seed = other.sample_seed;
+        for (dest, source) in self.per_key_stats.iter_mut().zip(&other.per_key_stats) {
+            dest.merge(source);
+        }
+        for (dest, source) in self
+            .per_key_near_miss_stats
+            .iter_mut()
+            .zip(&other.per_key_near_miss_stats)
+        {
+            dest.merge(source);
+        }
+    }
+
+    /// Loads statistics saved by `save_state`. Returns `Ok(None)` if the file
+    /// doesn't exist yet (first run).
+    pub fn load_state(path: &Path) -> Result<Option<Self>, String> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Cannot read state file {}: {e}", path.display())),
+        };
+        let stats: StatsCollector = serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid state file {}: {e}", path.display()))?;
+        if stats.per_key_stats.len() != FILTER_MAP_SIZE
+            || stats.per_key_near_miss_stats.len() != FILTER_MAP_SIZE * NUM_KEY_STATES
+        {
+            return Err(format!(
+                "State file {} was written for a different key table size",
+                path.display()
+            ));
+        }
+        Ok(Some(stats))
+    }
+
+    /// Reads a report written with `--stats-json` and rebuilds the statistics in
+    /// it, for `--merge`. Counts, histograms and timing samples are restored;
+    /// the event rate and filter latency aren't.
+    pub fn load_report(path: &Path) -> Result<LoadedReport, String> {
+        let invalid = |e: String| format!("Invalid stats report {}: {e}", path.display());
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read stats report {}: {e}", path.display()))?;
+        let report: StatsReportJson =
+            serde_json::from_str(&text).map_err(|e| invalid(e.to_string()))?;
+
+        let mut stats = StatsCollector::with_capacity();
+        stats.key_events_processed = report.key_events_processed;
+        stats.key_events_passed = report.key_events_passed;
+        stats.key_events_dropped = report.key_events_dropped;
+        stats.key_events_paused = report.key_events_paused;
+        stats.recovered_passes = report.recovered_passes;
+        stats.double_characters = report.double_characters;
+        stats.double_characters_passed = report.double_characters_passed;
+        stats.stream_gaps = report.stream_gaps;
+        stats.stream_gaps_while_held = report.stream_gaps_while_held;
+        stats.sample_seed = report.sample_seed;
+        stats.window_ratio_histogram.buckets = report.window_ratio_histogram;
+
+        for entry in &report.per_key_stats {
+            let key_stats = stats
+                .per_key_stats
+                .get_mut(usize::from(entry.key_code))
+                .ok_or_else(|| invalid(format!("key code {} out of range", entry.key_code)))?;
+            key_stats.press = KeyValueStats::from_json(&entry.stats.press).map_err(invalid)?;
+            key_stats.release = KeyValueStats::from_json(&entry.stats.release).map_err(invalid)?;
+            key_stats.repeat = KeyValueStats::from_json(&entry.stats.repeat).map_err(invalid)?;
+            key_stats.protocol_violations = entry.protocol_violations;
+            key_stats.recovered_passes = entry.recovered_passes;
+            key_stats.double_characters = entry.double_characters;
+            key_stats.first_event_us = entry.first_event_us;
+            key_stats.last_event_us = entry.last_event_us;
+        }
+
+        for entry in &report.per_key_near_miss_stats {
+            let idx = usize::try_from(entry.key_value)
+                .ok()
+                .filter(|&value| value < NUM_KEY_STATES)
+                .map(|value| usize::from(entry.key_code) * NUM_KEY_STATES + value);
+            let near_miss = idx
+                .and_then(|idx| stats.per_key_near_miss_stats.get_mut(idx))
+                .ok_or_else(|| {
+                    invalid(format!(
+                        "near-miss entry for key {} value {} out of range",
+                        entry.key_code, entry.key_value
+                    ))
+                })?;
+            *near_miss = NearMissStats {
+                summary: TimingSummary::from_report(
+                    entry.count as u64,
+                    entry.avg_us,
+                    entry.min_us,
+                    entry.max_us,
+                ),
+                histogram: TimingHistogram::from_json(&entry.near_miss_histogram)
+                    .map_err(invalid)?,
+                samples: TimingSamples::from_report(
+                    entry.timings_us.clone(),
+                    MAX_NEAR_MISS_TIMING_SAMPLES,
+                ),
+                band_counts: entry.band_counts.clone().unwrap_or_default(),
+            };
+        }
+
+        Ok(LoadedReport {
+            stats,
+            runtime_us: report.runtime_us,
+            debounce_time: Duration::from_micros(report.debounce_time_us),
+            near_miss_threshold: Duration::from_micros(report.near_miss_threshold_us),
+            near_miss_bands: report
+                .near_miss_bands_us
+                .unwrap_or_default()
+                .into_iter()
+                .map(Duration::from_micros)
+                .collect(),
+            log_interval: Duration::from_micros(report.log_interval_us),
+            interval_histogram: report.overall_interval_histogram.is_some(),
+        })
+    }
+
+    /// Writes the statistics to `path` as JSON, replacing the file atomically
+    /// (write to a temporary sibling, then rename) so a crash never leaves it truncated.
+    pub fn save_state(&self, path: &Path) -> std::io::Result<()> {
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let json = serde_json::to_vec(self).map_err(std::io::Error::other)?;
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    /// Suggested `--debounce-time` (µs) covering every key that chattered: the
+    /// largest of the per-key recommendations. Returns `None` if nothing bounced.
+    pub fn recommended_debounce_us(&self) -> Option<u64> {
+        self.per_key_stats
+            .iter()
+            .filter_map(KeyStats::recommended_debounce_us)
+            .max()
+    }
+
+    /// Counts a passed event that immediately followed a drop of the same key
+    /// code and value. The logger thread decides which events qualify.
+    pub fn record_recovered_pass(&mut self, info: &EventInfo) {
+        self.recovered_passes += 1;
+        if let Some(stats) = self.per_key_stats.get_mut(info.event.code as usize) {
+            stats.recovered_passes += 1;
+        }
+    }
+
+    /// Counts a "double character" sequence of `key_code` detected by the logger
+    /// thread; `reached_output` if its second press was passed.
+    pub fn record_double_character(&mut self, key_code: u16, reached_output: bool) {
+        self.double_characters += 1;
+        if reached_output {
+            self.double_characters_passed += 1;
+        }
+        if let Some(stats) = self.per_key_stats.get_mut(key_code as usize) {
+            stats.double_characters += 1;
+        }
+    }
+
+    /// Counts a gap in the event stream detected by the logger thread.
+    pub fn record_stream_gap(&mut self, keys_held: bool) {
+        self.stream_gaps += 1;
+        if keys_held {
+            self.stream_gaps_while_held += 1;
+        }
+    }
+
+    /// Percentage of processed key events that were dropped, or `None` before
+    /// the first key event.
+    pub fn overall_drop_percentage(&self) -> Option<f64> {
+        (self.key_events_processed > 0)
+            .then(|| self.key_events_dropped as f64 / self.key_events_processed as f64 * 100.0)
+    }
+
+    /// Average bounce time over all keys and states, or `None` if nothing bounced.
+    /// Unlike `overall_bounce_histogram`, this doesn't need aggregation first.
+    pub fn overall_bounce_average_us(&self) -> Option<u64> {
+        let mut summary = TimingSummary::default();
+        for key_stats in &self.per_key_stats {
+            for value_stats in [&key_stats.press, &key_stats.release, &key_stats.repeat] {
+                summary.merge(&value_stats.bounce_summary);
+            }
+        }
+        summary.average_us()
+    }
+
+    /// Key repeats dropped over all keys; only `--max-repeat-rate` (and exact
+    /// duplicates) drop repeats.
+    pub fn repeats_dropped(&self) -> u64 {
+        self.per_key_stats
+            .iter()
+            .map(|stats| stats.repeat.dropped_count)
+            .sum()
+    }
+
+    /// Number of distinct keys with at least one drop, and of keys that saw any
+    /// processed event: "chatter on N of M keys used".
+    pub fn chattering_keys(&self) -> (usize, usize) {
+        self.per_key_stats
+            .iter()
+            .filter(|stats| stats.drop_percentage().is_some())
+            .fold((0, 0), |(chattering, used), stats| {
+                (
+                    chattering + usize::from(stats.total_dropped() > 0),
+                    used + 1,
+                )
+            })
+    }
+
+    /// Codes of the (at most) `n` keys with the most drops, most first. Ties go
+    /// to the higher drop rate, then the lower key code. Keys without drops are left out.
+    pub fn top_keys(&self, n: usize) -> Vec<u16> {
+        let mut keys: Vec<(usize, &KeyStats)> = self
+            .per_key_stats
+            .iter()
+            .enumerate()
+            .filter(|(_, stats)| stats.total_dropped() > 0)
+            .collect();
+        keys.sort_by(|(code_a, a), (code_b, b)| {
+            b.total_dropped()
+                .cmp(&a.total_dropped())
+                .then(
+                    b.drop_percentage()
+                        .partial_cmp(&a.drop_percentage())
+                        .unwrap_or(std::cmp::Ordering::Equal),
+                )
+                .then(code_a.cmp(code_b))
+        });
+        keys.into_iter()
+            .take(n)
+            .map(|(code, _)| code as u16)
+            .collect()
+    }
+
+    /// Chatter intensity of every key with processed events, by key code, for
+    /// coloring a keyboard layout (`--heatmap-json`). A key's weighted drops are
+    /// its drops times its drop rate (`dropped² / processed`), so a key needs both
+    /// volume and a high rate to run hot; the intensity is that figure divided by
+    /// the largest one, making the hottest key 1.0 and keys without drops 0.0.
+    pub fn heatmap(&self) -> Vec<HeatmapKey> {
+        let weighted: Vec<(usize, &KeyStats, u64, f64)> = self
+            .per_key_stats
+            .iter()
+            .enumerate()
+            .filter_map(|(code, stats)| {
+                let processed = stats.press.total_processed
+                    + stats.release.total_processed
+                    + stats.repeat.total_processed;
+                let dropped = stats.total_dropped();
+                (processed > 0).then(|| {
+                    let weight = dropped as f64 * dropped as f64 / processed as f64;
+                    (code, stats, processed, weight)
+                })
+            })
+            .collect();
+        let max_weight = weighted.iter().map(|&(.., w)| w).fold(0.0, f64::max);
+        weighted
+            .into_iter()
+            .map(|(code, stats, processed, weight)| HeatmapKey {
+                key_code: code as u16,
+                key_name: get_key_name(code as u16),
+                total_processed: processed,
+                total_dropped: stats.total_dropped(),
+                drop_percentage: stats.drop_percentage().unwrap_or(0.0),
+                intensity: if max_weight > 0.0 {
+                    weight / max_weight
+                } else {
+                    0.0
+                },
+            })
+            .collect()
+    }
+
+    /// Writes the `--heatmap-json` export: the keys of [`Self::heatmap`] as a
+    /// JSON object `{"keys": [...]}`.
+    pub fn write_heatmap_json(&self, mut writer: impl Write) -> std::io::Result<()> {
+        #[derive(Serialize)]
+        struct Heatmap {
+            keys: Vec<HeatmapKey>,
+        }
+        serde_json::to_writer_pretty(
+            &mut writer,
+            &Heatmap {
+                keys: self.heatmap(),
+            },
+        )?;
+        writeln!(writer)
+    }
+
+    /// Writes one `--stats-stream-fd` frame: the overall counts and the keys of
+    /// [`Self::heatmap`] as compact JSON, prefixed by its length (u32, big-endian)
+    /// so a reader can split the stream without parsing it.
+    pub fn write_stats_frame(&self, seq: u64, mut writer: impl Write) -> std::io::Result<()> {
+        #[derive(Serialize)]
+        struct StatsFrame {
+            seq: u64,
+            key_events_processed: u64,
+            key_events_passed: u64,
+            key_events_dropped: u64,
+            drop_percentage: f64,
+            keys: Vec<HeatmapKey>,
+        }
+        let bytes = serde_json::to_vec(&StatsFrame {
+            seq,
+            key_events_processed: self.key_events_processed,
+            key_events_passed: self.key_events_passed,
+            key_events_dropped: self.key_events_dropped,
+            drop_percentage: self.overall_drop_percentage().unwrap_or(0.0),
+            keys: self.heatmap(),
+        })?;
+        let len = u32::try_from(bytes.len()).map_err(std::io::Error::other)?;
+        writer.write_all(&len.to_be_bytes())?;
+        writer.write_all(&bytes)?;
+        writer.flush()
+    }
+
+    /// Total press/release protocol violations across all keys.
+    pub fn protocol_violations(&self) -> u64 {
+        self.per_key_stats
+            .iter()
+            .map(|stats| stats.protocol_violations)
+            .sum()
+    }
+
     /// Aggregates per-key histograms into the overall histograms.
     /// Should be called before generating reports.
     pub fn aggregate_histograms(&mut self) {
         // Reset overall histograms (important if called multiple times, e.g., periodic)
         self.overall_bounce_histogram = TimingHistogram::default();
         self.overall_near_miss_histogram = TimingHistogram::default();
+        self.overall_interval_histogram = TimingHistogram::default();
 
         for key_stats in self.per_key_stats.iter() {
+            Self::accumulate_histogram(
+                &mut self.overall_interval_histogram,
+                &key_stats.press.pass_interval_histogram,
+            );
+            Self::accumulate_histogram(
+                &mut self.overall_interval_histogram,
+                &key_stats.release.pass_interval_histogram,
+            );
             // Aggregate bounce histograms
             Self::accumulate_histogram(
                 &mut self.overall_bounce_histogram,
@@ -472,6 +1459,7 @@ impl StatsCollector {
         if source.count > 0 {
             dest.count += source.count;
             dest.sum_us = dest.sum_us.saturating_add(source.sum_us);
+            dest.sum_sq_us = dest.sum_sq_us.saturating_add(source.sum_sq_us);
             for i in 0..NUM_HISTOGRAM_BUCKETS {
                 dest.buckets[i] += source.buckets[i];
             }
@@ -481,8 +1469,39 @@ impl StatsCollector {
         }
     }
 
+    /// Human-readable range of histogram bucket `i`, e.g. `2-4ms`.
+    fn bucket_label(i: usize) -> String {
+        if i == 0 {
+            format!("< {}ms", HISTOGRAM_BUCKET_BOUNDARIES_MS[0])
+        } else if i == NUM_HISTOGRAM_BUCKETS - 1 {
+            format!(
+                ">= {}ms",
+                HISTOGRAM_BUCKET_BOUNDARIES_MS[NUM_HISTOGRAM_BUCKETS - 2]
+            )
+        } else {
+            format!(
+                "{}-{}ms",
+                HISTOGRAM_BUCKET_BOUNDARIES_MS[i - 1],
+                HISTOGRAM_BUCKET_BOUNDARIES_MS[i]
+            )
+        }
+    }
+
+    /// The `--histogram-legend` header: every bucket's index and range, once.
+    fn format_histogram_legend() -> String {
+        let entries: Vec<String> = (0..NUM_HISTOGRAM_BUCKETS)
+            .map(|i| format!("[{i}] {}", Self::bucket_label(i)))
+            .collect();
+        format!("  {}\n", entries.join("  "))
+    }
+
     /// Formats a `TimingHistogram` into a human-readable string representation.
-    fn format_histogram_human(histogram: &TimingHistogram) -> String {
+    fn format_histogram_human(
+        histogram: &TimingHistogram,
+        config: &crate::config::Config,
+        labels: BucketLabels,
+    ) -> String {
+        let (cumulative, precision) = (config.histogram_cumulative, config.time_precision);
         if histogram.count == 0 {
             return "No data".to_string();
         }
@@ -491,69 +1510,231 @@ impl StatsCollector {
         let total_count = histogram.count;
 
         // Determine max bucket count for scaling the bar
-        let max_bucket_count = histogram.buckets.iter().copied().max().unwrap_or(0);
+        let max_bucket_count = match labels {
+            BucketLabels::Inline => histogram.buckets.iter().copied().max().unwrap_or(0),
+            BucketLabels::Legend { max_count } => max_count,
+        };
         let bar_scale = if max_bucket_count > 0 {
-            50.0 / max_bucket_count as f64
+            config.histogram_width as f64 / max_bucket_count as f64
         } else {
             0.0
-        }; // Max bar width 50 chars
+        };
+        let cumulative_pct = histogram.cumulative_percentages();
 
-        for i in 0..NUM_HISTOGRAM_BUCKETS {
-            let bucket_count = histogram.buckets[i];
+        for (i, (&bucket_count, &cum)) in histogram.buckets.iter().zip(&cumulative_pct).enumerate()
+        {
             let percentage = if total_count > 0 {
                 (bucket_count as f64 / total_count as f64) * 100.0
             } else {
                 0.0
             };
 
-            let label = if i == 0 {
-                format!("< {}ms", HISTOGRAM_BUCKET_BOUNDARIES_MS[0])
-            } else if i == NUM_HISTOGRAM_BUCKETS - 1 {
-                format!(
-                    ">= {}ms",
-                    HISTOGRAM_BUCKET_BOUNDARIES_MS[NUM_HISTOGRAM_BUCKETS - 2]
-                )
-            } else {
-                format!(
-                    "{}-{}ms",
-                    HISTOGRAM_BUCKET_BOUNDARIES_MS[i - 1],
-                    HISTOGRAM_BUCKET_BOUNDARIES_MS[i]
-                )
+            let label = match labels {
+                BucketLabels::Inline => Self::bucket_label(i),
+                BucketLabels::Legend { .. } => format!("[{i}]"),
             };
 
             let bar_width = (bucket_count as f64 * bar_scale).round() as usize;
-            let bar = "#".repeat(bar_width);
+            let bar = util::paint(
+                &config.histogram_char.to_string().repeat(bar_width),
+                util::Color::Cyan,
+                config.color,
+            );
 
-            output.push_str(&format!(
-                "  {label:<10}: {bucket_count:<5} ({percentage:>5.1}%) [{bar}]\n"
-            ));
+            if cumulative {
+                output.push_str(&format!(
+                    "  {label:<10}: {bucket_count:<5} ({percentage:>5.1}%, cum {cum:>5.1}%) [{bar}]\n"
+                ));
+            } else {
+                output.push_str(&format!(
+                    "  {label:<10}: {bucket_count:<5} ({percentage:>5.1}%) [{bar}]\n"
+                ));
+            }
         }
 
         let avg_us = histogram.average_us();
         output.push_str(&format!(
-            "  Total: {}, Avg: {}\n",
+            "  Total: {}, Avg: {}, Stddev: {}\n",
             total_count,
-            util::format_us(avg_us)
+            util::format_us(avg_us, precision),
+            util::format_us(histogram.stddev_us(), precision)
         ));
 
         output
     }
 
-    /// Formats human-readable statistics summary and writes it to the provided writer.
-    /// Returns an io::Result to handle potential write errors.
-    pub fn format_stats_human_readable(
-        &mut self, // Needs to be mutable to aggregate histograms
+    /// Writes the decision/debounce-window ratio section, if anything was recorded.
+    fn write_window_ratio_histogram(
+        &self,
         config: &crate::config::Config,
-        report_type: &str,
-        mut writer: impl Write, // Accept a generic writer
+        mut writer: impl Write,
     ) -> std::io::Result<()> {
-        // Aggregate histograms before reporting
-        self.aggregate_histograms();
-
-        writeln!(writer, "\n--- Overall Statistics ({report_type}) ---")?;
+        let histogram = &self.window_ratio_histogram;
+        let total = histogram.total();
+        if total == 0 {
+            return Ok(());
+        }
         writeln!(
             writer,
-            "Key Events Processed: {}",
+            "\n--- Decisions Relative to Debounce Time (time since previous event / {}) ---",
+            util::format_duration(config.debounce_time(), config.time_precision)
+        )?;
+        let max_count = histogram.buckets.iter().copied().max().unwrap_or(0);
+        let bar_scale = config.histogram_width as f64 / max_count as f64;
+        for (i, &count) in histogram.buckets.iter().enumerate() {
+            if count == 0 {
+                continue;
+            }
+            let start = WindowRatioHistogram::bucket_start(i);
+            let label = format!("{start:.1}-{:.1}", start + 0.1);
+            let kind = if start < 1.0 { "bounce" } else { "pass" };
+            let percentage = count as f64 / total as f64 * 100.0;
+            let bar = util::paint(
+                &config
+                    .histogram_char
+                    .to_string()
+                    .repeat((count as f64 * bar_scale).round() as usize),
+                util::Color::Cyan,
+                config.color,
+            );
+            writeln!(
+                writer,
+                "  {label:<10}: {count:<5} ({percentage:>5.1}%) {kind:<6} [{bar}]"
+            )?;
+        }
+        writeln!(writer, "  Total: {total}")
+    }
+
+    /// Longest histogram bar that keeps a line within `columns` characters,
+    /// capped at the default width.
+    pub fn histogram_width_for_columns(columns: usize, cumulative: bool) -> usize {
+        let overhead = HISTOGRAM_LINE_OVERHEAD
+            + if cumulative {
+                HISTOGRAM_CUMULATIVE_OVERHEAD
+            } else {
+                0
+            };
+        columns
+            .saturating_sub(overhead)
+            .clamp(MIN_AUTO_HISTOGRAM_WIDTH, DEFAULT_HISTOGRAM_WIDTH)
+    }
+
+    /// Formats band counts as `<=30ms: 2, <=60ms: 1`, pairing each count with its band's upper bound.
+    fn format_band_counts(counts: &[u64], config: &crate::config::Config) -> String {
+        config
+            .near_miss_bands
+            .iter()
+            .zip(counts)
+            .map(|(band, count)| {
+                format!(
+                    "<={}: {count}",
+                    util::format_duration(*band, config.time_precision)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Writes the press and release bounce histograms of one key, indented under
+    /// its per-key block, if `--per-key-histograms` or `--focus-key` selects it.
+    /// Writes one `State (Value): Processed: ...` line per key state that saw events.
+    /// Repeats are left out with `--hide-repeat-stats`.
+    fn write_key_states(
+        mut writer: impl Write,
+        stats: &KeyStats,
+        config: &crate::config::Config,
+    ) -> std::io::Result<()> {
+        let mut states = vec![
+            ("Press", 1, &stats.press, false),
+            ("Release", 0, &stats.release, false),
+        ];
+        // Repeats bypass debouncing, so unless they are rate limited this line
+        // only shows how many there were.
+        if !config.hide_repeat_stats {
+            let passthrough = config.max_repeat_rate.is_none();
+            states.push(("Repeat", 2, &stats.repeat, passthrough));
+        }
+        for (value_name, value_code, value_stats, passthrough) in states {
+            if value_stats.total_processed == 0 {
+                continue;
+            }
+            let drop_rate =
+                (value_stats.dropped_count as f64 / value_stats.total_processed as f64) * 100.0;
+            write!(
+                writer,
+                "  {:<7} ({}): Processed: {}, Passed: {}, Dropped: {} ({})",
+                value_name,
+                value_code,
+                value_stats.total_processed,
+                value_stats.passed_count,
+                value_stats.dropped_count,
+                format_drop_rate(drop_rate, config.color)
+            )?;
+            if let Some(min) = value_stats.bounce_summary.min_us() {
+                let max = value_stats.bounce_summary.max_us().unwrap_or(min);
+                let avg = value_stats.bounce_summary.average_us().unwrap_or(min);
+                writeln!(
+                    writer,
+                    " (Bounce Time: {} / {} / {}, Stddev: {}, n={})",
+                    util::format_us(min, config.time_precision),
+                    util::format_us(avg, config.time_precision),
+                    util::format_us(max, config.time_precision),
+                    util::format_us(
+                        value_stats.bounce_histogram.stddev_us(),
+                        config.time_precision
+                    ),
+                    value_stats.bounce_summary.count()
+                )?;
+            } else if passthrough {
+                writeln!(writer, " (passthrough, never debounced)")?;
+            } else {
+                writeln!(writer)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_key_histograms(
+        mut writer: impl Write,
+        key_code: u16,
+        stats: &KeyStats,
+        config: &crate::config::Config,
+    ) -> std::io::Result<()> {
+        if !config.shows_key_histogram(key_code) {
+            return Ok(());
+        }
+        for (value_name, value_stats) in [("Press", &stats.press), ("Release", &stats.release)] {
+            if value_stats.bounce_histogram.count == 0 {
+                continue;
+            }
+            writeln!(writer, "  {value_name} Bounce Histogram:")?;
+            let histogram = Self::format_histogram_human(
+                &value_stats.bounce_histogram,
+                config,
+                BucketLabels::Inline,
+            );
+            for line in histogram.lines() {
+                writeln!(writer, "  {line}")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Formats human-readable statistics summary and writes it to the provided writer.
+    /// Returns an io::Result to handle potential write errors.
+    pub fn format_stats_human_readable(
+        &mut self, // Needs to be mutable to aggregate histograms
+        config: &crate::config::Config,
+        report_type: &str,
+        mut writer: impl Write, // Accept a generic writer
+    ) -> std::io::Result<()> {
+        // Aggregate histograms before reporting
+        self.aggregate_histograms();
+
+        writeln!(writer, "\n--- Overall Statistics ({report_type}) ---")?;
+        writeln!(
+            writer,
+            "Key Events Processed: {}",
             self.key_events_processed
         )?;
         writeln!(writer, "Key Events Passed:   {}", self.key_events_passed)?;
@@ -563,29 +1744,150 @@ impl StatsCollector {
         } else {
             0.0
         };
-        writeln!(writer, "Percentage Dropped:  {percentage:.2}%")?;
+        writeln!(
+            writer,
+            "Percentage Dropped:  {}",
+            format_drop_rate(percentage, config.color)
+        )?;
+        if self.recovered_passes > 0 {
+            writeln!(writer, "Recovered Passes:    {}", self.recovered_passes)?;
+        }
+        let (keys_chattering, keys_used) = self.chattering_keys();
+        writeln!(
+            writer,
+            "Keys Chattering:     {keys_chattering} of {keys_used} keys used"
+        )?;
+        if self.double_characters > 0 {
+            writeln!(
+                writer,
+                "Likely Double-Character Events: {} ({} reached the output)",
+                self.double_characters, self.double_characters_passed
+            )?;
+        }
+        if let Some(rate) = config.max_repeat_rate {
+            writeln!(
+                writer,
+                "Repeats Dropped (over {rate}/s): {}",
+                self.repeats_dropped()
+            )?;
+        }
+        if let Some(threshold) = config.gap_threshold {
+            writeln!(
+                writer,
+                "Stream Gaps (>= {}): {} ({} while keys were held)",
+                util::format_duration(threshold, config.time_precision),
+                self.stream_gaps,
+                self.stream_gaps_while_held
+            )?;
+        }
+        if self.key_events_paused > 0 {
+            writeln!(
+                writer,
+                "Key Events Passed While Paused: {}",
+                self.key_events_paused
+            )?;
+        }
+        if let Some(average) = self.event_rate.average() {
+            writeln!(
+                writer,
+                "Event Rate (all events): {average:.1}/s average, {}/s peak",
+                self.event_rate.peak()
+            )?;
+        }
+        if let Some(min) = self.filter_latency_ns.min_us() {
+            let avg = self.filter_latency_ns.average_us().unwrap_or(min);
+            let max = self.filter_latency_ns.max_us().unwrap_or(min);
+            writeln!(
+                writer,
+                "Filter Latency:      {} / {} / {} (Min / Avg / Max, n={})",
+                util::format_ns(min, config.time_precision),
+                util::format_ns(avg, config.time_precision),
+                util::format_ns(max, config.time_precision),
+                self.filter_latency_ns.count()
+            )?;
+        }
+
+        let top_keys = self.top_keys(config.top_keys);
+        if !top_keys.is_empty() {
+            writeln!(
+                writer,
+                "\n--- Top {} Chattiest Keys (by drops) ---",
+                top_keys.len()
+            )?;
+            for (rank, &code) in top_keys.iter().enumerate() {
+                let stats = &self.per_key_stats[code as usize];
+                writeln!(
+                    writer,
+                    "  {}. {} ({code}): {} dropped ({})",
+                    rank + 1,
+                    display_key_name(config, code),
+                    stats.total_dropped(),
+                    format_drop_rate(stats.drop_percentage().unwrap_or(0.0), config.color)
+                )?;
+            }
+        }
+
+        let labels = if config.histogram_legend {
+            writeln!(
+                writer,
+                "\n--- Histogram Buckets (shared by the overall histograms) ---"
+            )?;
+            write!(writer, "{}", Self::format_histogram_legend())?;
+            let interval_buckets: &[u64] = if config.interval_histogram {
+                &self.overall_interval_histogram.buckets
+            } else {
+                &[]
+            };
+            let max_count = self
+                .overall_bounce_histogram
+                .buckets
+                .iter()
+                .chain(&self.overall_near_miss_histogram.buckets)
+                .chain(interval_buckets)
+                .copied()
+                .max()
+                .unwrap_or(0);
+            BucketLabels::Legend { max_count }
+        } else {
+            BucketLabels::Inline
+        };
 
         // Overall Bounce Histogram
         writeln!(writer, "\n--- Overall Bounce Timing Histogram ---")?;
         write!(
             writer,
             "{}",
-            Self::format_histogram_human(&self.overall_bounce_histogram)
+            Self::format_histogram_human(&self.overall_bounce_histogram, config, labels)
         )?;
 
         // Overall Near-Miss Histogram
         writeln!(
             writer,
             "\n--- Overall Near-Miss Timing Histogram (Passed within {}) ---",
-            util::format_duration(config.near_miss_threshold())
+            util::format_duration(config.near_miss_threshold(), config.time_precision)
         )?;
         write!(
             writer,
             "{}",
-            Self::format_histogram_human(&self.overall_near_miss_histogram)
+            Self::format_histogram_human(&self.overall_near_miss_histogram, config, labels)
         )?;
 
+        self.write_window_ratio_histogram(config, &mut writer)?;
+
+        if config.interval_histogram {
+            writeln!(
+                writer,
+                "\n--- Overall Pass Interval Histogram (between passed presses/releases of a key) ---"
+            )?;
+            write!(
+                writer,
+                "{}",
+                Self::format_histogram_human(&self.overall_interval_histogram, config, labels)
+            )?;
+        }
+
         let mut any_drops = false;
+        let per_state = config.stats_granularity == StatsGranularity::State;
         for key_code in 0..self.per_key_stats.len() {
             let stats = &self.per_key_stats[key_code];
             let total_drops_for_key = stats.press.dropped_count
@@ -601,14 +1903,16 @@ impl StatsCollector {
                 if !any_drops {
                     writeln!(writer, "\n--- Dropped Event Statistics Per Key ---")?;
                     writeln!(writer, "Format: Key [Name] (Code):")?;
-                    writeln!(
-                        writer,
-                        "  State (Value): Processed: <count>, Passed: <count>, Dropped: <count> (<rate>%) (Bounce Time: Min / Avg / Max)"
-                    )?;
+                    if per_state {
+                        writeln!(
+                            writer,
+                            "  State (Value): Processed: <count>, Passed: <count>, Dropped: <count> (<rate>%) (Bounce Time: Min / Avg / Max, Stddev, n=<samples>)"
+                        )?;
+                    }
                     any_drops = true;
                 }
 
-                let key_name = get_key_name(key_code as u16);
+                let key_name = display_key_name(config, key_code as u16);
                 writeln!(writer, "\nKey [{key_name}] ({key_code}):")?;
                 // Calculate total processed for this key
                 let total_processed_for_key = stats.press.total_processed
@@ -627,52 +1931,16 @@ impl StatsCollector {
                 };
                 writeln!(
                     writer, // Updated summary line format
-                    "  Total Processed: {total_processed_for_key}, Passed: {total_passed_for_key}, Dropped: {total_drops_for_key} ({key_drop_percentage:.2}%)"
+                    "  Total Processed: {total_processed_for_key}, Passed: {total_passed_for_key}, Dropped: {total_drops_for_key} ({})",
+                    format_drop_rate(key_drop_percentage, config.color)
                 )?;
+                if !per_state {
+                    Self::write_key_histograms(&mut writer, key_code as u16, stats, config)?;
+                    continue;
+                }
 
-                // Use a closure that captures writer mutably
-                let mut print_value_stats = |value_name: &str,
-                                             value_code: i32,
-                                             value_stats: &KeyValueStats|
-                 -> std::io::Result<()> {
-                    if value_stats.total_processed > 0 {
-                        // Calculate drop rate for this specific state
-                        let drop_rate = if value_stats.total_processed > 0 {
-                            (value_stats.dropped_count as f64 / value_stats.total_processed as f64)
-                                * 100.0
-                        } else {
-                            0.0
-                        };
-                        write!(
-                            writer,
-                            "  {:<7} ({}): Processed: {}, Passed: {}, Dropped: {} ({:.2}%)",
-                            value_name,
-                            value_code,
-                            value_stats.total_processed,
-                            value_stats.passed_count,
-                            value_stats.dropped_count,
-                            drop_rate
-                        )?;
-                        if let Some(min) = value_stats.bounce_summary.min_us() {
-                            let max = value_stats.bounce_summary.max_us().unwrap_or(min);
-                            let avg = value_stats.bounce_summary.average_us().unwrap_or(min);
-                            writeln!(
-                                writer,
-                                " (Bounce Time: {} / {} / {})",
-                                util::format_us(min),
-                                util::format_us(avg),
-                                util::format_us(max)
-                            )?;
-                        } else {
-                            writeln!(writer)?;
-                        }
-                    }
-                    Ok(())
-                };
-
-                print_value_stats("Press", 1, &stats.press)?;
-                print_value_stats("Release", 0, &stats.release)?;
-                print_value_stats("Repeat", 2, &stats.repeat)?; // Include repeat stats line if processed
+                Self::write_key_states(&mut writer, stats, config)?;
+                Self::write_key_histograms(&mut writer, key_code as u16, stats, config)?;
             }
         }
         if !any_drops {
@@ -687,45 +1955,166 @@ impl StatsCollector {
                     writeln!(
                         writer,
                         "\n--- Passed Event Near-Miss Statistics (Passed within {}) ---",
-                        util::format_duration(config.near_miss_threshold())
+                        util::format_duration(config.near_miss_threshold(), config.time_precision)
                     )?;
                     writeln!(
                         writer,
-                        "Format: Key [Name] (Code, Value): Count (Near-Miss Time: Min / Avg / Max)"
+                        "Format: Key [Name] (Code, Value): Count (Near-Miss Time: Min / Avg / Max, Stddev)"
                     )?;
                     any_near_miss = true;
                 }
 
                 let key_code = (idx / NUM_KEY_STATES) as u16;
                 let key_value = (idx % NUM_KEY_STATES) as i32;
-                let key_name = get_key_name(key_code);
+                let key_name = display_key_name(config, key_code);
 
                 let min = near_miss_stats.summary.min_us().unwrap_or(0);
                 let max = near_miss_stats.summary.max_us().unwrap_or(min);
                 let avg = near_miss_stats.summary.average_us().unwrap_or(min);
                 let count = near_miss_stats.summary.count();
 
-                writeln!(
+                write!(
                     writer,
-                    "  Key [{}] ({}, {}): {} (Near-Miss Time: {} / {} / {})",
+                    "  Key [{}] ({}, {}): {} (Near-Miss Time: {} / {} / {}, Stddev: {})",
                     key_name,
                     key_code,
                     key_value,
                     count,
-                    util::format_us(min),
-                    util::format_us(avg),
-                    util::format_us(max)
+                    util::format_us(min, config.time_precision),
+                    util::format_us(avg, config.time_precision),
+                    util::format_us(max, config.time_precision),
+                    util::format_us(near_miss_stats.histogram.stddev_us(), config.time_precision)
                 )?;
+                if !config.near_miss_bands.is_empty() {
+                    write!(
+                        writer,
+                        ", Bands: {}",
+                        Self::format_band_counts(
+                            &near_miss_stats.band_counts_padded(config.near_miss_bands.len()),
+                            config
+                        )
+                    )?;
+                }
+                writeln!(writer)?;
+            }
+        }
+        if any_near_miss && !config.near_miss_bands.is_empty() {
+            let mut totals = vec![0u64; config.near_miss_bands.len()];
+            for near_miss_stats in &self.per_key_near_miss_stats {
+                for (total, count) in totals.iter_mut().zip(&near_miss_stats.band_counts) {
+                    *total += count;
+                }
             }
+            writeln!(
+                writer,
+                "Near misses by band: {}",
+                Self::format_band_counts(&totals, config)
+            )?;
         }
         if !any_near_miss {
             writeln!(
                 writer,
                 "\n--- No near-miss events recorded (< {}) ---",
-                util::format_duration(config.near_miss_threshold())
+                util::format_duration(config.near_miss_threshold(), config.time_precision)
+            )?;
+        }
+
+        if config.interval_histogram && self.overall_interval_histogram.count > 0 {
+            writeln!(writer, "\n--- Pass Intervals Per Key ---")?;
+            writeln!(
+                writer,
+                "Format: Key [Name] (Code, Value): Intervals (Avg, Stddev)"
+            )?;
+            for (key_code, stats) in self.per_key_stats.iter().enumerate() {
+                for (value, kv_stats) in [(1, &stats.press), (0, &stats.release)] {
+                    let histogram = &kv_stats.pass_interval_histogram;
+                    if histogram.count == 0 {
+                        continue;
+                    }
+                    writeln!(
+                        writer,
+                        "  Key [{}] ({}, {}): {} (Avg: {}, Stddev: {})",
+                        display_key_name(config, key_code as u16),
+                        key_code,
+                        value,
+                        histogram.count,
+                        util::format_us(histogram.average_us(), config.time_precision),
+                        util::format_us(histogram.stddev_us(), config.time_precision)
+                    )?;
+                }
+            }
+        }
+
+        if self.protocol_violations() > 0 {
+            writeln!(writer, "\n--- Key State Violations ---")?;
+            writeln!(
+                writer,
+                "Passed press while pressed, or release while released:"
+            )?;
+            for (key_code, stats) in self.per_key_stats.iter().enumerate() {
+                if stats.protocol_violations > 0 {
+                    writeln!(
+                        writer,
+                        "  Key [{}] ({}): {}",
+                        display_key_name(config, key_code as u16),
+                        key_code,
+                        stats.protocol_violations
+                    )?;
+                }
+            }
+            writeln!(writer, "Total: {}", self.protocol_violations())?;
+        }
+
+        let mut dead_switches = self
+            .per_key_stats
+            .iter()
+            .enumerate()
+            .filter(|(_, stats)| stats.is_likely_dead_switch(config.dead_switch_threshold))
+            .peekable();
+        if dead_switches.peek().is_some() {
+            writeln!(writer, "\n--- Possible Dead Switches ---")?;
+            for (key_code, stats) in dead_switches {
+                writeln!(
+                    writer,
+                    "  {}: Key [{}] ({}) dropped {:.2}% of its events. This looks like a failing switch or misconfiguration, not chatter; raising --debounce-time won't fix it.",
+                    util::paint("WARNING", util::Color::Red, config.color),
+                    display_key_name(config, key_code as u16),
+                    key_code,
+                    stats.drop_percentage().unwrap_or(0.0)
+                )?;
+            }
+        }
+
+        if let Some(overall_us) = self.recommended_debounce_us() {
+            writeln!(writer, "\n--- Recommended Debounce Time ---")?;
+            writeln!(
+                writer,
+                "Based on p{RECOMMENDATION_PERCENTILE:.0} bounce time + {} margin:",
+                util::format_us(RECOMMENDATION_MARGIN_US, config.time_precision)
+            )?;
+            for (key_code, stats) in self.per_key_stats.iter().enumerate() {
+                if let Some(rec_us) = stats.recommended_debounce_us() {
+                    writeln!(
+                        writer,
+                        "  Key [{}] ({}): {}",
+                        display_key_name(config, key_code as u16),
+                        key_code,
+                        util::format_duration(Duration::from_micros(rec_us), None)
+                    )?;
+                }
+            }
+            writeln!(
+                writer,
+                "Overall: --debounce-time {} (current: {})",
+                // Stays in humantime form so the suggestion can be pasted as a flag value.
+                util::format_duration(Duration::from_micros(overall_us), None),
+                util::format_duration(config.debounce_time(), config.time_precision)
             )?;
         }
 
+        if let Some(reason) = &self.shutdown_reason {
+            writeln!(writer, "\nShutdown reason: {reason}")?;
+        }
         writeln!(
             writer,
             "----------------------------------------------------------"
@@ -733,6 +2122,241 @@ impl StatsCollector {
         Ok(()) // Return Ok(()) at the end of the function
     }
 
+    /// Writes the `--inspect` report: everything recorded for one key (per-state
+    /// counts, bounce and near-miss histograms, percentiles, first/last event and
+    /// recommended debounce time) and nothing about other keys or the totals.
+    pub fn format_key_inspection(
+        &self,
+        config: &crate::config::Config,
+        key_code: u16,
+        report_type: &str,
+        mut writer: impl Write,
+    ) -> std::io::Result<()> {
+        let key_name = display_key_name(config, key_code);
+        writeln!(
+            writer,
+            "\n--- Key Inspection: [{key_name}] ({key_code}) ({report_type}) ---"
+        )?;
+        let Some(stats) = self.per_key_stats.get(usize::from(key_code)) else {
+            writeln!(writer, "No events recorded for this key.")?;
+            return Ok(());
+        };
+        let processed = stats.press.total_processed
+            + stats.release.total_processed
+            + stats.repeat.total_processed;
+        if processed == 0 {
+            writeln!(writer, "No events recorded for this key.")?;
+            return Ok(());
+        }
+        let dropped = stats.total_dropped();
+        writeln!(
+            writer,
+            "Total Processed: {processed}, Passed: {}, Dropped: {dropped} ({})",
+            processed - dropped,
+            format_drop_rate(stats.drop_percentage().unwrap_or(0.0), config.color)
+        )?;
+        if let (Some(first), Some(last)) = (stats.first_event_us, stats.last_event_us) {
+            writeln!(
+                writer,
+                "First Event: {}, Last Event: {} (active for {})",
+                util::format_us(first, config.time_precision),
+                util::format_us(last, config.time_precision),
+                util::format_us(last.saturating_sub(first), config.time_precision)
+            )?;
+        }
+
+        writeln!(writer, "\n--- States ---")?;
+        Self::write_key_states(&mut writer, stats, config)?;
+
+        let mut samples = stats.press.bounce_samples.to_vec();
+        samples.extend(stats.release.bounce_samples.to_vec());
+        if !samples.is_empty() {
+            let [p50, p90, p99] = [50.0, 90.0, 99.0].map(|pct| {
+                util::format_us(
+                    percentile_of(samples.clone(), pct).unwrap_or(0),
+                    config.time_precision,
+                )
+            });
+            writeln!(
+                writer,
+                "Bounce Percentiles: p50 {p50}, p90 {p90}, p99 {p99}"
+            )?;
+        }
+
+        for (value_name, value, value_stats) in
+            [("Press", 1, &stats.press), ("Release", 0, &stats.release)]
+        {
+            writeln!(writer, "\n--- {value_name} Bounce Timing Histogram ---")?;
+            write!(
+                writer,
+                "{}",
+                Self::format_histogram_human(
+                    &value_stats.bounce_histogram,
+                    config,
+                    BucketLabels::Inline
+                )
+            )?;
+            let near_miss =
+                &self.per_key_near_miss_stats[usize::from(key_code) * NUM_KEY_STATES + value];
+            writeln!(
+                writer,
+                "\n--- {value_name} Near-Miss Timing Histogram (Passed within {}) ---",
+                util::format_duration(config.near_miss_threshold(), config.time_precision)
+            )?;
+            write!(
+                writer,
+                "{}",
+                Self::format_histogram_human(&near_miss.histogram, config, BucketLabels::Inline)
+            )?;
+        }
+
+        writeln!(writer)?;
+        match stats.recommended_debounce_us() {
+            Some(us) => writeln!(
+                writer,
+                "Recommended Debounce Time: {}",
+                util::format_us(us, config.time_precision)
+            )?,
+            None => writeln!(
+                writer,
+                "Recommended Debounce Time: none (no bounces recorded)"
+            )?,
+        }
+        Ok(())
+    }
+
+    /// Copy of the statistics, and of `config`, with every per-key entry and focus
+    /// key moved to its pseudonymous code (`--anonymize`), so reports built from
+    /// them name no real key. Overall counts and timings are unchanged.
+    #[must_use]
+    pub fn anonymized(
+        &self,
+        config: &crate::config::Config,
+    ) -> (StatsCollector, crate::config::Config) {
+        let mut stats = self.clone();
+        let mut config = config.clone();
+        for (code, &pseudonym) in config.key_pseudonyms.iter().enumerate() {
+            let pseudonym = usize::from(pseudonym);
+            stats.per_key_stats[pseudonym] = self.per_key_stats[code].clone();
+            for value in 0..NUM_KEY_STATES {
+                stats.per_key_near_miss_stats[pseudonym * NUM_KEY_STATES + value] =
+                    self.per_key_near_miss_stats[code * NUM_KEY_STATES + value].clone();
+            }
+        }
+        for code in config
+            .focus_keys
+            .iter_mut()
+            .chain(config.inspect_key.as_mut())
+        {
+            if let Some(&pseudonym) = config.key_pseudonyms.get(usize::from(*code)) {
+                *code = pseudonym;
+            }
+        }
+        (stats, config)
+    }
+
+    /// Prints a report in the configured format (`--stats-json` or human-readable)
+    /// to stderr, or to stdout with `--stats-to-stdout`. Anonymized with `--anonymize`.
+    pub fn print_stats(
+        &mut self,
+        config: &crate::config::Config,
+        runtime_us: Option<u64>,
+        report_type: &str,
+    ) {
+        if config.anonymize {
+            let (mut stats, config) = self.anonymized(config);
+            stats.emit_stats(&config, runtime_us, report_type);
+        } else {
+            self.emit_stats(config, runtime_us, report_type);
+        }
+    }
+
+    fn emit_stats(
+        &mut self,
+        config: &crate::config::Config,
+        runtime_us: Option<u64>,
+        report_type: &str,
+    ) {
+        if config.stats_to_stdout {
+            let mut stdout = std::io::stdout().lock();
+            self.write_stats(config, runtime_us, report_type, &mut stdout);
+            let _ = stdout.flush();
+        } else {
+            self.write_stats(config, runtime_us, report_type, std::io::stderr().lock());
+        }
+    }
+
+    fn write_stats(
+        &mut self,
+        config: &crate::config::Config,
+        runtime_us: Option<u64>,
+        report_type: &str,
+        writer: impl Write,
+    ) {
+        if config.stats_json {
+            let _ = self.write_report(config, runtime_us, report_type, config.stats_format, writer);
+        } else if let Some(key_code) = config.inspect_key {
+            let _ = self.format_key_inspection(config, key_code, report_type, writer);
+        } else {
+            // Ignore write errors, as there's not much we can do.
+            let _ = self.format_stats_human_readable(config, report_type, writer);
+        }
+    }
+
+    /// Prints the one-line `--periodic-compact` summary to stderr (stdout with
+    /// `--stats-to-stdout`).
+    pub fn print_compact_summary(&self, config: &crate::config::Config, label: &str) {
+        let anonymized;
+        let (stats, key_name): (&Self, fn(u16) -> &'static str) = if config.anonymize {
+            anonymized = self.anonymized(config).0;
+            (&anonymized, pseudonym_name)
+        } else {
+            (self, get_key_name)
+        };
+        if config.stats_to_stdout {
+            let mut stdout = std::io::stdout().lock();
+            let _ = stats.write_compact_summary(label, key_name, &mut stdout);
+            let _ = stdout.flush();
+        } else {
+            let _ = stats.write_compact_summary(label, key_name, std::io::stderr().lock());
+        }
+    }
+
+    /// Writes a single-line summary: `LABEL processed=N dropped=N drop_pct=P top_key=KEY(P%)`.
+    /// The top key is the one with the most drops, shown with its own drop
+    /// percentage, or `none` if nothing was dropped.
+    pub fn format_compact_summary(&self, label: &str, writer: impl Write) -> std::io::Result<()> {
+        self.write_compact_summary(label, get_key_name, writer)
+    }
+
+    fn write_compact_summary(
+        &self,
+        label: &str,
+        key_name: fn(u16) -> &'static str,
+        mut writer: impl Write,
+    ) -> std::io::Result<()> {
+        let drop_pct = if self.key_events_processed > 0 {
+            (self.key_events_dropped as f64 / self.key_events_processed as f64) * 100.0
+        } else {
+            0.0
+        };
+        let top_key = match self.top_keys(1).first() {
+            Some(&code) => format!(
+                "{}({:.1}%)",
+                key_name(code),
+                self.per_key_stats[code as usize]
+                    .drop_percentage()
+                    .unwrap_or(0.0)
+            ),
+            None => "none".to_string(),
+        };
+        writeln!(
+            writer,
+            "{label} processed={} dropped={} drop_pct={drop_pct:.2} top_key={top_key}",
+            self.key_events_processed, self.key_events_dropped
+        )
+    }
+
     /// Prints human-readable statistics summary to stderr by calling format_stats_human_readable.
     pub fn print_stats_to_stderr(&mut self, config: &crate::config::Config, report_type: &str) {
         // Ignore potential write errors when writing to stderr, as there's not much we can do.
@@ -741,7 +2365,7 @@ impl StatsCollector {
     }
 
     /// Helper to create JSON representation of a TimingHistogram.
-    fn create_histogram_json(histogram: &TimingHistogram) -> TimingHistogramJson {
+    fn create_histogram_json(histogram: &TimingHistogram, cumulative: bool) -> TimingHistogramJson {
         let mut buckets_json = Vec::with_capacity(NUM_HISTOGRAM_BUCKETS);
         for i in 0..NUM_HISTOGRAM_BUCKETS {
             let min_ms = if i == 0 {
@@ -757,6 +2381,8 @@ impl StatsCollector {
             buckets_json.push(HistogramBucketJson {
                 min_ms,
                 max_ms,
+                min_us: min_ms * 1_000,
+                max_us: max_ms.map(|ms| ms * 1_000),
                 count: histogram.buckets[i],
             });
         }
@@ -764,6 +2390,8 @@ impl StatsCollector {
             buckets: buckets_json,
             count: histogram.count,
             avg_us: histogram.average_us(),
+            stddev_us: histogram.stddev_us(),
+            cumulative: cumulative.then(|| histogram.cumulative_percentages().to_vec()),
             // min_us: histogram.min_us, // Optional
             // max_us: histogram.max_us, // Optional
         }
@@ -771,13 +2399,62 @@ impl StatsCollector {
 
     /// Prints statistics in JSON format to the given writer.
     /// Includes runtime provided externally (calculated in main thread).
+    /// Fails with the writer's error, so a truncated report isn't taken for a whole one.
     pub fn print_stats_json(
         &mut self,
         config: &crate::config::Config,
         runtime_us: Option<u64>,
         report_type: &str,
+        writer: impl Write,
+    ) -> std::io::Result<()> {
+        self.write_report(config, runtime_us, report_type, StatsFormat::Json, writer)
+    }
+
+    /// Writes the JSON report to `writer` whatever `--stats-json` says, e.g. for
+    /// `--stats-json-file`. Anonymized with `--anonymize`.
+    pub fn export_stats_json(
+        &mut self,
+        config: &crate::config::Config,
+        runtime_us: Option<u64>,
+        report_type: &str,
+        writer: impl Write,
+    ) -> std::io::Result<()> {
+        if config.anonymize {
+            let (mut stats, config) = self.anonymized(config);
+            stats.print_stats_json(&config, runtime_us, report_type, writer)
+        } else {
+            self.print_stats_json(config, runtime_us, report_type, writer)
+        }
+    }
+
+    /// Prints statistics as one length-prefixed MessagePack map with the same
+    /// fields as the JSON report (`--stats-format msgpack`).
+    pub fn print_stats_msgpack(
+        &mut self,
+        config: &crate::config::Config,
+        runtime_us: Option<u64>,
+        report_type: &str,
+        writer: impl Write,
+    ) -> std::io::Result<()> {
+        self.write_report(
+            config,
+            runtime_us,
+            report_type,
+            StatsFormat::Msgpack,
+            writer,
+        )
+    }
+
+    /// Builds the machine-readable report and writes it in `format`, returning
+    /// the first write error.
+    fn write_report(
+        &mut self,
+        config: &crate::config::Config,
+        runtime_us: Option<u64>,
+        report_type: &str,
+        format: StatsFormat,
         mut writer: impl Write,
-    ) {
+    ) -> std::io::Result<()> {
         // Aggregate histograms before reporting
         self.aggregate_histograms();
 
@@ -794,7 +2471,7 @@ impl StatsCollector {
             if total_processed_for_key > 0 {
                 // Include keys with any activity (passed or dropped)
                 let key_code = key_code_usize as u16;
-                let key_name = get_key_name(key_code);
+                let key_name = report_key_name(config, key_code);
                 let drop_percentage = if total_processed_for_key > 0 {
                     (total_dropped_for_key as f64 / total_processed_for_key as f64) * 100.0
                 } else {
@@ -814,10 +2491,22 @@ impl StatsCollector {
                         dropped_count: kv_stats.dropped_count,
                         drop_rate,
                         timings_us: kv_stats.bounce_samples.to_vec(),
-                        bounce_histogram: Self::create_histogram_json(&kv_stats.bounce_histogram),
+                        timings_sampled: kv_stats.bounce_summary.count()
+                            > kv_stats.bounce_samples.len() as u64,
+                        timings_total_count: kv_stats.bounce_summary.count(),
+                        bounce_histogram: Self::create_histogram_json(
+                            &kv_stats.bounce_histogram,
+                            config.histogram_cumulative,
+                        ),
                         min_us: kv_stats.bounce_summary.min_us(),
                         max_us: kv_stats.bounce_summary.max_us(),
                         avg_us: kv_stats.bounce_summary.average_us(),
+                        pass_interval_histogram: config.interval_histogram.then(|| {
+                            Self::create_histogram_json(
+                                &kv_stats.pass_interval_histogram,
+                                config.histogram_cumulative,
+                            )
+                        }),
                     }
                 };
 
@@ -836,6 +2525,13 @@ impl StatsCollector {
                     total_processed: total_processed_for_key,
                     total_dropped: total_dropped_for_key,
                     drop_percentage,
+                    likely_dead_switch: stats.is_likely_dead_switch(config.dead_switch_threshold),
+                    protocol_violations: stats.protocol_violations,
+                    recovered_passes: stats.recovered_passes,
+                    double_characters: stats.double_characters,
+                    first_event_us: stats.first_event_us,
+                    last_event_us: stats.last_event_us,
+                    recommended_debounce_us: stats.recommended_debounce_us(),
                     stats: detailed_stats_json, // Use the new detailed struct // Add lifetime here
                 });
             }
@@ -847,8 +2543,8 @@ impl StatsCollector {
             if near_miss_stats.summary.count() > 0 {
                 let key_code = (idx / NUM_KEY_STATES) as u16;
                 let key_value = (idx % NUM_KEY_STATES) as i32;
-                let key_name = get_key_name(key_code);
-                let value_name = get_value_name(key_value);
+                let key_name = report_key_name(config, key_code);
+                let value_name = get_value_name(EV_KEY as u16, key_value).unwrap_or("Unknown");
 
                 near_miss_json_vec.push(NearMissStatsJson {
                     key_code,
@@ -857,10 +2553,18 @@ impl StatsCollector {
                     value_name,
                     count: near_miss_stats.summary.count() as usize,
                     timings_us: near_miss_stats.samples.to_vec(),
-                    near_miss_histogram: Self::create_histogram_json(&near_miss_stats.histogram),
+                    timings_sampled: near_miss_stats.summary.count()
+                        > near_miss_stats.samples.len() as u64,
+                    timings_total_count: near_miss_stats.summary.count(),
+                    near_miss_histogram: Self::create_histogram_json(
+                        &near_miss_stats.histogram,
+                        config.histogram_cumulative,
+                    ),
                     min_us: near_miss_stats.summary.min_us(),
                     max_us: near_miss_stats.summary.max_us(),
                     avg_us: near_miss_stats.summary.average_us(),
+                    band_counts: (!config.near_miss_bands.is_empty())
+                        .then(|| near_miss_stats.band_counts_padded(config.near_miss_bands.len())),
                 });
             }
         }
@@ -872,9 +2576,20 @@ impl StatsCollector {
             runtime_us: Option<u64>,
             #[serde(skip_serializing_if = "Option::is_none")]
             runtime_human: Option<String>,
+            // Event-time bounds of the interval (periodic reports with events only)
+            #[serde(skip_serializing_if = "Option::is_none")]
+            interval_start_us: Option<u64>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            interval_end_us: Option<u64>,
+            // Why the run ended (final report only)
+            #[serde(skip_serializing_if = "Option::is_none")]
+            shutdown_reason: Option<&'a str>,
             // Add raw config values as well for machine readability
             debounce_time_us: u64,
             near_miss_threshold_us: u64,
+            // Upper bounds of the near-miss bands, innermost first (several thresholds only)
+            #[serde(skip_serializing_if = "Option::is_none")]
+            near_miss_bands_us: Option<Vec<u64>>,
             log_interval_us: u64,
             debounce_time_human: String,
             near_miss_threshold_human: String,
@@ -882,25 +2597,72 @@ impl StatsCollector {
             key_events_processed: u64,
             key_events_passed: u64,
             key_events_dropped: u64,
+            key_events_paused: u64,
+            recovered_passes: u64,
+            // Distinct keys with any drop, out of the keys with any processed event
+            keys_chattering: usize,
+            keys_used: usize,
+            // Press/release/press/release sequences, and those whose second press passed
+            double_characters: u64,
+            double_characters_passed: u64,
+            // Key repeats dropped, e.g. by `--max-repeat-rate`
+            repeats_dropped: u64,
+            stream_gaps: u64,
+            stream_gaps_while_held: u64,
+            // Input events of any type per second, over 1-second windows
+            #[serde(skip_serializing_if = "Option::is_none")]
+            avg_events_per_sec: Option<f64>,
+            peak_events_per_sec: u64,
+            // Time spent on the filter decision per event (`--measure-latency`)
+            #[serde(skip_serializing_if = "Option::is_none")]
+            filter_latency_min_ns: Option<u64>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            filter_latency_avg_ns: Option<u64>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            filter_latency_max_ns: Option<u64>,
+            protocol_violations: u64,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            recommended_debounce_us: Option<u64>,
+            sample_seed: u64,
+            // Keys with the most drops, most first (`--top-keys`)
+            top_keys: Vec<TopKeyJson>,
             // Overall Histograms
             overall_bounce_histogram: TimingHistogramJson,
             overall_near_miss_histogram: TimingHistogramJson,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            overall_interval_histogram: Option<TimingHistogramJson>,
+            // Decisions per 0.1-wide bucket of (time since previous event / debounce time), 0.0-2.0
+            window_ratio_histogram: [u64; WINDOW_RATIO_BUCKETS],
             // Per-Key and Per-Near-Miss details
             per_key_stats: Vec<PerKeyStatsJson>,
             per_key_near_miss_stats: Vec<NearMissStatsJson>,
         }
 
-        let runtime_human = runtime_us.map(|us| util::format_duration(Duration::from_micros(us)));
-        let debounce_human = util::format_duration(config.debounce_time());
-        let near_miss_human = util::format_duration(config.near_miss_threshold());
-        let log_interval_human = util::format_duration(config.log_interval());
+        let runtime_human = runtime_us
+            .map(|us| util::format_duration(Duration::from_micros(us), config.time_precision));
+        let debounce_human = util::format_duration(config.debounce_time(), config.time_precision);
+        let near_miss_human =
+            util::format_duration(config.near_miss_threshold(), config.time_precision);
+        let log_interval_human =
+            util::format_duration(config.log_interval(), config.time_precision);
 
+        let (keys_chattering, keys_used) = self.chattering_keys();
         let report = ReportData {
             report_type,
             runtime_us, // Will be None for periodic reports
             runtime_human,
+            interval_start_us: self.interval_us.map(|(start, _)| start),
+            interval_end_us: self.interval_us.map(|(_, end)| end),
+            shutdown_reason: self.shutdown_reason.as_deref(),
             debounce_time_us: config.debounce_us(), // Add raw value
             near_miss_threshold_us: config.near_miss_threshold_us(), // Add raw value
+            near_miss_bands_us: (!config.near_miss_bands.is_empty()).then(|| {
+                config
+                    .near_miss_bands
+                    .iter()
+                    .map(|band| band.as_micros() as u64)
+                    .collect()
+            }),
             log_interval_us: config.log_interval_us(), // Add raw value
             debounce_time_human: debounce_human,
             near_miss_threshold_human: near_miss_human,
@@ -908,10 +2670,51 @@ impl StatsCollector {
             key_events_processed: self.key_events_processed,
             key_events_passed: self.key_events_passed,
             key_events_dropped: self.key_events_dropped,
-            overall_bounce_histogram: Self::create_histogram_json(&self.overall_bounce_histogram),
+            key_events_paused: self.key_events_paused,
+            recovered_passes: self.recovered_passes,
+            keys_chattering,
+            keys_used,
+            double_characters: self.double_characters,
+            double_characters_passed: self.double_characters_passed,
+            repeats_dropped: self.repeats_dropped(),
+            stream_gaps: self.stream_gaps,
+            stream_gaps_while_held: self.stream_gaps_while_held,
+            avg_events_per_sec: self.event_rate.average(),
+            peak_events_per_sec: self.event_rate.peak(),
+            filter_latency_min_ns: self.filter_latency_ns.min_us(),
+            filter_latency_avg_ns: self.filter_latency_ns.average_us(),
+            filter_latency_max_ns: self.filter_latency_ns.max_us(),
+            protocol_violations: self.protocol_violations(),
+            recommended_debounce_us: self.recommended_debounce_us(),
+            sample_seed: self.sample_seed,
+            top_keys: self
+                .top_keys(config.top_keys)
+                .into_iter()
+                .map(|code| {
+                    let stats = &self.per_key_stats[code as usize];
+                    TopKeyJson {
+                        key_code: code,
+                        key_name: report_key_name(config, code),
+                        total_dropped: stats.total_dropped(),
+                        drop_percentage: stats.drop_percentage().unwrap_or(0.0),
+                    }
+                })
+                .collect(),
+            overall_bounce_histogram: Self::create_histogram_json(
+                &self.overall_bounce_histogram,
+                config.histogram_cumulative,
+            ),
             overall_near_miss_histogram: Self::create_histogram_json(
                 &self.overall_near_miss_histogram,
+                config.histogram_cumulative,
             ),
+            overall_interval_histogram: config.interval_histogram.then(|| {
+                Self::create_histogram_json(
+                    &self.overall_interval_histogram,
+                    config.histogram_cumulative,
+                )
+            }),
+            window_ratio_histogram: self.window_ratio_histogram.buckets,
             per_key_stats: per_key_stats_json_vec, // Use the prepared Vec
             per_key_near_miss_stats: near_miss_json_vec, // Use the prepared Vec
         };
@@ -919,7 +2722,51 @@ impl StatsCollector {
         // We are printing individual reports (cumulative or periodic) as separate JSON objects
         // to stderr. The logger thread handles the overall structure (e.g., a list of periodic
         // reports).
-        let _ = serde_json::to_writer_pretty(&mut writer, &report);
-        let _ = writeln!(writer);
+        match format {
+            StatsFormat::Json => {
+                serde_json::to_writer_pretty(&mut writer, &report)?;
+                writeln!(writer)
+            }
+            StatsFormat::Msgpack => write_msgpack_frame(&report, &mut writer),
+        }
+    }
+}
+
+/// Writes `value` as a MessagePack map prefixed by its length (u32, big-endian),
+/// so consecutive reports in one stream can be split without decoding them.
+#[cfg(feature = "msgpack")]
+fn write_msgpack_frame(value: &impl Serialize, writer: &mut impl Write) -> std::io::Result<()> {
+    let bytes = rmp_serde::to_vec_named(value).map_err(std::io::Error::other)?;
+    let len = u32::try_from(bytes.len()).map_err(std::io::Error::other)?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(&bytes)?;
+    writer.flush()
+}
+
+#[cfg(not(feature = "msgpack"))]
+fn write_msgpack_frame(_value: &impl Serialize, _writer: &mut impl Write) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "built without the `msgpack` feature",
+    ))
+}
+
+/// Name of a key in the human-readable report: its alias or canonical name, or
+/// under `--anonymize` the pseudonym of the (already remapped) code.
+fn display_key_name(config: &crate::config::Config, key_code: u16) -> &str {
+    if config.anonymize {
+        pseudonym_name(key_code)
+    } else {
+        config.key_name(key_code)
+    }
+}
+
+/// Name of a key in the JSON report: its canonical name, or under `--anonymize`
+/// the pseudonym of the (already remapped) code.
+fn report_key_name(config: &crate::config::Config, key_code: u16) -> &'static str {
+    if config.anonymize {
+        pseudonym_name(key_code)
+    } else {
+        get_key_name(key_code)
     }
 }
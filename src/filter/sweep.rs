@@ -0,0 +1,102 @@
+//! Offline debounce-time sweep (`--sweep`).
+//!
+//! A recorded capture is replayed through a fresh BounceFilter for each candidate
+//! debounce time, so the resulting drop counts can be compared side by side and
+//! the knee of the curve picked as the setting.
+
+use super::BounceFilter;
+use crate::config::Config;
+use crate::event::is_key_event;
+use crate::output;
+use crate::util;
+use input_linux_sys::input_event;
+use serde::Serialize;
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// Outcome of replaying a capture with one debounce time.
+#[derive(Debug, Clone, Serialize)]
+pub struct SweepResult {
+    #[serde(skip)]
+    pub debounce_time: Duration,
+    pub debounce_us: u64,
+    pub key_events_processed: u64,
+    pub key_events_dropped: u64,
+    pub drop_percentage: f64,
+}
+
+/// Replays `events` once per debounce time, shortest first, with the filter
+/// options of `cfg` (drop rule, count mode, key lists, ...). Release/press
+/// collapsing (`--collapse-press-release`) is not simulated.
+pub fn sweep(
+    events: &[input_event],
+    cfg: &Config,
+    debounce_times: &[Duration],
+) -> Vec<SweepResult> {
+    let mut debounce_times = debounce_times.to_vec();
+    debounce_times.sort_unstable();
+    debounce_times.dedup();
+
+    debounce_times
+        .into_iter()
+        .map(|debounce_time| {
+            let mut filter = BounceFilter::new(0);
+            filter.apply_config(cfg);
+            let (mut processed, mut dropped) = (0u64, 0u64);
+            for ev in events {
+                let is_key = is_key_event(ev);
+                let skip_debounce = output::skips_debounce(cfg, ev);
+                let info = filter.check_event(ev, debounce_time, skip_debounce);
+                if is_key {
+                    processed += 1;
+                    dropped += u64::from(info.is_bounce);
+                }
+            }
+            SweepResult {
+                debounce_time,
+                debounce_us: u64::try_from(debounce_time.as_micros()).unwrap_or(u64::MAX),
+                key_events_processed: processed,
+                key_events_dropped: dropped,
+                drop_percentage: if processed > 0 {
+                    dropped as f64 / processed as f64 * 100.0
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect()
+}
+
+/// Writes the sweep as a table, one row per debounce time.
+pub fn format_sweep_table(
+    results: &[SweepResult],
+    config: &Config,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let processed = results.first().map_or(0, |r| r.key_events_processed);
+    writeln!(
+        writer,
+        "--- Debounce Time Sweep ({processed} key events) ---"
+    )?;
+    writeln!(
+        writer,
+        "{:>12}  {:>10}  {:>9}",
+        "Debounce", "Dropped", "Drop Rate"
+    )?;
+    for result in results {
+        writeln!(
+            writer,
+            "{:>12}  {:>10}  {:>8.2}%",
+            util::format_duration(result.debounce_time, config.time_precision),
+            result.key_events_dropped,
+            result.drop_percentage
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the sweep as a JSON array of results.
+pub fn format_sweep_json(results: &[SweepResult], mut writer: impl Write) -> io::Result<()> {
+    serde_json::to_writer_pretty(&mut writer, results)?;
+    writeln!(writer)
+}
@@ -1,10 +1,14 @@
 // Module declarations for the library crate.
 
+#[cfg(feature = "tokio")]
+pub mod async_loop;
 pub mod cli;
 pub mod config;
+pub mod error;
 pub mod event;
 pub mod filter;
 pub mod logger;
+pub mod output;
 pub mod telemetry;
 pub mod util;
 
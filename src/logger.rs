@@ -4,25 +4,80 @@
 
 use crate::config::Config;
 use crate::event;
-use crate::filter::keynames::{get_event_type_name, get_key_name};
-use crate::filter::stats::StatsCollector;
+use crate::filter::keynames::{get_event_type_name, get_key_name, get_value_name};
+use crate::filter::stats::{StatsCollector, DEFAULT_SAMPLE_SEED};
+use crate::filter::{FILTER_MAP_SIZE, NUM_KEY_STATES};
 use crate::util;
 use crossbeam_channel::{Receiver, RecvTimeoutError};
 
-use chrono::Local;
+use chrono::{DateTime, Local};
 use input_linux_sys::{input_event, EV_MSC, EV_SYN};
-use opentelemetry::metrics::{Counter, Meter};
-use std::io;
-use std::sync::atomic::{AtomicBool, Ordering};
+use opentelemetry::metrics::{Counter, Histogram, Meter, ObservableGauge, Unit};
+use opentelemetry::KeyValue;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::os::fd::RawFd;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tracing::info;
+use tracing::{info, warn};
 use tracing::{instrument, Span};
 
+/// OTLP attributes identifying the key and state of a key event: `key` (the
+/// canonical key name, not a `--key-aliases` entry) and `state` (press, release
+/// or repeat). Cardinality is bounded by the number of keys.
+pub fn otel_key_attributes(event: &input_event) -> [KeyValue; 2] {
+    [
+        KeyValue::new("key", get_key_name(event.code)),
+        KeyValue::new(
+            "state",
+            get_value_name(event.type_, event.value).unwrap_or("Unknown"),
+        ),
+    ]
+}
+
+/// Latest overall figures of the cumulative statistics, read by the OTLP
+/// observable gauges. Values are stored as `f64` bits so the gauge callbacks
+/// can read them from the exporter's thread without locking the stats.
+#[derive(Debug, Default)]
+pub struct LiveGauges {
+    drop_percentage: AtomicU64,
+    bounce_average_us: AtomicU64,
+}
+
+impl LiveGauges {
+    /// Refreshes the figures from `stats`. The bounce average only changes when
+    /// a bounce was recorded, so it is recomputed just then.
+    pub fn update(&self, stats: &StatsCollector, bounce_recorded: bool) {
+        let drop_percentage = stats.overall_drop_percentage().unwrap_or(0.0);
+        self.drop_percentage
+            .store(drop_percentage.to_bits(), Ordering::Relaxed);
+        if bounce_recorded {
+            let average = stats.overall_bounce_average_us().unwrap_or(0) as f64;
+            self.bounce_average_us
+                .store(average.to_bits(), Ordering::Relaxed);
+        }
+    }
+
+    /// Current overall drop percentage (0 before the first key event).
+    pub fn drop_percentage(&self) -> f64 {
+        f64::from_bits(self.drop_percentage.load(Ordering::Relaxed))
+    }
+
+    /// Current overall bounce average in microseconds (0 if nothing bounced).
+    pub fn bounce_average_us(&self) -> f64 {
+        f64::from_bits(self.bounce_average_us.load(Ordering::Relaxed))
+    }
+}
+
 /// Represents a message sent from the main thread to the logger thread.
 pub enum LogMessage {
     /// Contains detailed information about a single processed event.
     Event(EventInfo),
+    /// Replaces the logger's configuration after a SIGHUP reload.
+    Reload(Arc<Config>),
 }
 
 /// Detailed information about a single processed event, sent to the logger.
@@ -39,8 +94,136 @@ pub struct EventInfo {
     /// Timestamp (µs) of the previous event of the same type that *passed* the filter.
     /// This is needed by the logger thread to calculate near-miss statistics.
     pub last_passed_us: Option<u64>,
+    /// `true` if filtering was paused and the event bypassed the debounce check.
+    pub paused: bool,
+    /// Time (ns) the main thread spent locking the filter and running the
+    /// check for this event. Only measured with `--measure-latency`.
+    pub filter_latency_ns: Option<u64>,
+}
+
+/// A file the logger streams records to, with the record count and checksum
+/// behind its `--export-trailer`.
+struct ExportFile {
+    writer: BufWriter<File>,
+    records: u64,
+    checksum: u64,
 }
 
+impl ExportFile {
+    fn new(writer: BufWriter<File>) -> Self {
+        Self {
+            writer,
+            records: 0,
+            checksum: util::FNV1A_OFFSET,
+        }
+    }
+
+    /// Appends one record, `line` without its newline.
+    fn write_record(&mut self, line: &str) -> io::Result<()> {
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.write_all(b"\n")?;
+        self.records += 1;
+        self.checksum = util::fnv1a64(util::fnv1a64(self.checksum, line.as_bytes()), b"\n");
+        Ok(())
+    }
+
+    /// FNV-1a checksum of the records written so far, newlines included, as 16 hex digits.
+    fn checksum_hex(&self) -> String {
+        format!("{:016x}", self.checksum)
+    }
+
+    /// Writes the `trailer` line, if any, after the records and flushes.
+    fn finish(mut self, trailer: Option<String>) -> io::Result<()> {
+        if let Some(trailer) = trailer {
+            writeln!(self.writer, "{trailer}")?;
+        }
+        self.writer.flush()
+    }
+}
+
+/// Creates a file the logger streams records to (`what` names it in the log),
+/// writing `header` first if given. Errors are logged and leave that output
+/// disabled; filtering carries on regardless.
+fn open_stream_file(path: &Path, header: Option<&str>, what: &str) -> Option<BufWriter<File>> {
+    let result = File::create(path).and_then(|file| {
+        let mut writer = BufWriter::new(file);
+        if let Some(header) = header {
+            writeln!(writer, "{header}")?;
+        }
+        Ok(writer)
+    });
+    match result {
+        Ok(writer) => Some(writer),
+        Err(e) => {
+            tracing::warn!(path = %path.display(), error = %e, "Cannot create {what}, continuing without it");
+            None
+        }
+    }
+}
+
+/// Duplicates the `--stats-stream-fd` descriptor, so the logger owns its copy and
+/// a restarted logger can open it again. Logs a warning and returns `None` if
+/// the descriptor isn't open.
+fn open_stats_stream(fd: RawFd) -> Option<File> {
+    match util::dup_fd(fd) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            tracing::warn!(fd, error = %e, "Cannot use stats stream descriptor, continuing without it");
+            None
+        }
+    }
+}
+
+/// A press or release of one key, as remembered for double-character detection.
+#[derive(Debug, Clone, Copy)]
+struct KeyEdge {
+    pressed: bool,
+    event_us: u64,
+    passed: bool,
+}
+
+/// Key events sampled before judging whether the input's timestamps are usable.
+const TIMESTAMP_CHECK_EVENTS: u32 = 16;
+
+/// Watches the first key events for zero timestamps or timestamps repeating
+/// the previous key event's, which make every diff zero and leave time-based
+/// debouncing (and the timing statistics) meaningless.
+#[derive(Debug, Default)]
+struct TimestampCheck {
+    last_us: Option<u64>,
+    checked: u32,
+    suspect: u32,
+    done: bool,
+}
+
+impl TimestampCheck {
+    /// Records a key event's timestamp. Returns `true`, once, if the sample is
+    /// complete and most of its timestamps were suspect.
+    fn record(&mut self, event_us: u64) -> bool {
+        if self.done {
+            return false;
+        }
+        let suspect = event_us == 0 || self.last_us == Some(event_us);
+        self.last_us = Some(event_us);
+        self.checked += 1;
+        self.suspect += u32::from(suspect);
+        self.checked >= TIMESTAMP_CHECK_EVENTS && self.finish()
+    }
+
+    /// Judges a run that ended before the sample was complete, like `record`.
+    fn finish(&mut self) -> bool {
+        if self.done || self.checked < 2 {
+            return false;
+        }
+        self.done = true;
+        self.suspect * 2 > self.checked
+    }
+}
+
+/// Source of the wall-clock time stamped on periodic dumps. `Local::now` in
+/// production; tests can supply a fixed time for reproducible output.
+pub type WallClock = fn() -> DateTime<Local>;
+
 /// Manages the state and execution loop for the logger thread.
 pub struct Logger {
     receiver: Receiver<LogMessage>,
@@ -52,9 +235,46 @@ pub struct Logger {
 
     last_dump_time: Instant,
     first_event_us: Option<u64>,
+    // Whether the last key event for each key code/value was dropped.
+    // Indexed by `keycode * NUM_KEY_STATES + value`, used to spot recovered passes.
+    last_was_drop: Vec<bool>,
+    // Timestamp of the most recent event of any type, for `--gap-threshold`.
+    last_event_us: Option<u64>,
+    // Data-quality guard against zero or frozen event timestamps.
+    timestamp_check: TimestampCheck,
+    // Up to three most recent presses/releases of each key, indexed by key code,
+    // to spot the press/release/press/release "double character" pattern.
+    recent_edges: Vec<Vec<KeyEdge>>,
+    // Timestamps of the first and latest event since the last periodic dump.
+    interval_bounds_us: Option<(u64, u64)>,
+    // Seed for random sampling in every stats collector (`--sample-seed`).
+    sample_seed: u64,
+    // Open `--timeline-csv` file. Dropped (with a warning) after a write error.
+    timeline: Option<ExportFile>,
+    // Open `--dropped-events-file`. Dropped (with a warning) after a write error.
+    dropped_events: Option<ExportFile>,
+    // `--stats-stream-fd` descriptor. Dropped (with a warning) after a write error.
+    stats_stream: Option<File>,
+    // When the last stats stream frame was written, and how many were written.
+    last_stream_time: Instant,
+    stream_frames: u64,
+    // Wall-clock time source for periodic dumps.
+    wallclock: WallClock,
 
     // Optional OTLP Meter for logger-specific metrics
     otel_meter: Option<Meter>,
+    // Figures behind the OTLP gauges, kept up to date only if a meter is set.
+    live_gauges: Arc<LiveGauges>,
+    // Dropped key events per key name, only if a meter is set.
+    key_drop_counter: Option<Counter<u64>>,
+    // Test hook (`test-hooks` feature only): panic on an event of this key code,
+    // from `INTERCEPT_BOUNCE_TEST_PANIC_ON_KEY`, to exercise logger panic handling.
+    #[cfg(feature = "test-hooks")]
+    panic_on_key: Option<u16>,
+    // Test hook (`test-hooks` feature only): sleep this long before each message,
+    // from `INTERCEPT_BOUNCE_TEST_LOGGER_DELAY_US`, to force channel backpressure.
+    #[cfg(feature = "test-hooks")]
+    message_delay: Option<Duration>,
 }
 
 impl Logger {
@@ -65,18 +285,67 @@ impl Logger {
         config: Arc<Config>,
         otel_meter: Option<Meter>,
     ) -> Self {
+        let sample_seed = config.sample_seed.unwrap_or(DEFAULT_SAMPLE_SEED);
+        tracing::debug!(sample_seed, "Using sample seed");
+        let timeline = config.timeline_csv.as_deref().and_then(|path| {
+            open_stream_file(
+                path,
+                Some("event_us,key_code,key_name,value,decision,diff_us"),
+                "event timeline",
+            )
+            .map(ExportFile::new)
+        });
+        let dropped_events = config.dropped_events_file.as_deref().and_then(|path| {
+            open_stream_file(path, None, "dropped events file").map(ExportFile::new)
+        });
+        let stats_stream = config.stats_stream_fd.and_then(open_stats_stream);
+        let key_drop_counter = otel_meter.as_ref().map(|m| {
+            m.u64_counter("events.dropped_by_key")
+                .with_description("Key events dropped (bounced), by key")
+                .init()
+        });
         Logger {
             receiver,
             logger_running,
             config,
-            cumulative_stats: StatsCollector::with_capacity(),
-            interval_stats: StatsCollector::with_capacity(),
+            cumulative_stats: StatsCollector::with_sample_seed(sample_seed),
+            interval_stats: StatsCollector::with_sample_seed(sample_seed),
             last_dump_time: Instant::now(),
             first_event_us: None,
+            last_was_drop: vec![false; FILTER_MAP_SIZE * NUM_KEY_STATES],
+            last_event_us: None,
+            timestamp_check: TimestampCheck::default(),
+            recent_edges: vec![Vec::new(); FILTER_MAP_SIZE],
+            interval_bounds_us: None,
+            sample_seed,
+            timeline,
+            dropped_events,
+            stats_stream,
+            last_stream_time: Instant::now(),
+            stream_frames: 0,
+            wallclock: Local::now,
             otel_meter,
+            live_gauges: Arc::default(),
+            key_drop_counter,
+            #[cfg(feature = "test-hooks")]
+            panic_on_key: std::env::var("INTERCEPT_BOUNCE_TEST_PANIC_ON_KEY")
+                .ok()
+                .and_then(|code| code.parse().ok()),
+            #[cfg(feature = "test-hooks")]
+            message_delay: std::env::var("INTERCEPT_BOUNCE_TEST_LOGGER_DELAY_US")
+                .ok()
+                .and_then(|us| us.parse().ok())
+                .map(Duration::from_micros),
         }
     }
 
+    /// Replaces the wall-clock time source used for periodic dumps.
+    #[must_use]
+    pub fn with_wallclock(mut self, wallclock: WallClock) -> Self {
+        self.wallclock = wallclock;
+        self
+    }
+
     /// Manages the logger thread's main loop.
     ///
     /// It receives messages from the main thread, processes them (logging and stats),
@@ -86,15 +355,16 @@ impl Logger {
     /// Returns the final cumulative statistics upon exit.
     pub fn run(&mut self) -> StatsCollector {
         tracing::debug!("Logger thread started");
-        let log_interval = self.config.log_interval();
-        let check_interval = Duration::from_millis(100); // Used for periodic checks
+        let check_interval = self.config.poll_interval; // Used for periodic checks
 
         // --- OTLP Metrics Setup (in logger thread) ---
-        let near_miss_counter: Option<Counter<u64>> = self.otel_meter.as_ref().map(|m| {
-            m.u64_counter("events.near_miss")
-                .with_description("Passed events that were near misses")
+        let near_miss_histogram: Option<Histogram<u64>> = self.otel_meter.as_ref().map(|m| {
+            m.u64_histogram("events.near_miss_timing")
+                .with_description("Time since the last passed event for near-miss events")
+                .with_unit(Unit::new("us"))
                 .init()
         });
+        let _gauges = self.otel_meter.as_ref().map(|m| self.register_gauges(m));
 
         loop {
             // Check running flag first
@@ -104,13 +374,15 @@ impl Logger {
                 );
                 while let Ok(msg) = self.receiver.try_recv() {
                     tracing::trace!("Draining channel: Processing message after shutdown signal");
-                    self.process_message(msg, &near_miss_counter);
+                    self.process_message(msg, &near_miss_histogram);
                 }
                 tracing::debug!("Finished draining channel. Exiting run loop");
                 break;
             }
 
             // Check periodic stats dump timer
+            // Re-read each iteration: the interval can change on configuration reload.
+            let log_interval = self.config.log_interval();
             if log_interval > Duration::ZERO && self.last_dump_time.elapsed() >= log_interval {
                 tracing::debug!("Triggering periodic stats dump");
                 self.dump_periodic_stats();
@@ -118,11 +390,20 @@ impl Logger {
                 tracing::debug!("Periodic stats dump complete. Timer reset");
             }
 
+            let stream_interval = self.config.stats_stream_interval;
+            if self.stats_stream.is_some()
+                && stream_interval > Duration::ZERO
+                && self.last_stream_time.elapsed() >= stream_interval
+            {
+                self.write_stats_frame();
+                self.last_stream_time = Instant::now();
+            }
+
             // Receive messages with timeout
             match self.receiver.recv_timeout(check_interval) {
                 Ok(msg) => {
                     tracing::trace!("Logger thread received message from channel");
-                    self.process_message(msg, &near_miss_counter);
+                    self.process_message(msg, &near_miss_histogram);
                     tracing::trace!("Logger thread finished processing message");
                 }
                 Err(RecvTimeoutError::Timeout) => {
@@ -136,7 +417,7 @@ impl Logger {
                         tracing::trace!(
                             "Logger thread draining channel: Processing message after disconnect"
                         );
-                        self.process_message(msg, &near_miss_counter);
+                        self.process_message(msg, &near_miss_histogram);
                     }
                     tracing::warn!("Finished draining channel. Exiting run loop");
                     break; // Exit loop on disconnect
@@ -144,15 +425,77 @@ impl Logger {
             }
         } // End loop
 
+        // A final frame, so the reader sees the totals the run ended with.
+        self.write_stats_frame();
+        // `--export-trailer`: a closing line per export file, so a consumer can
+        // tell a complete file from one cut short.
+        let trailers = self.config.export_trailer;
+        if let Some(timeline) = self.timeline.take() {
+            let trailer = trailers.then(|| {
+                format!(
+                    "# records={} fnv1a64={}",
+                    timeline.records,
+                    timeline.checksum_hex()
+                )
+            });
+            if let Err(e) = timeline.finish(trailer) {
+                tracing::warn!(error = %e, "Failed to finish event timeline");
+            }
+        }
+        if let Some(dropped_events) = self.dropped_events.take() {
+            let trailer = trailers.then(|| {
+                serde_json::json!({
+                    "trailer": true,
+                    "records": dropped_events.records,
+                    "fnv1a64": dropped_events.checksum_hex(),
+                })
+                .to_string()
+            });
+            if let Err(e) = dropped_events.finish(trailer) {
+                tracing::warn!(error = %e, "Failed to finish dropped events file");
+            }
+        }
+        if self.timestamp_check.finish() {
+            self.warn_unusable_timestamps();
+        }
+
         tracing::debug!("Run loop exited. Preparing final stats");
         tracing::debug!("Taking cumulative_stats for return");
         std::mem::take(&mut self.cumulative_stats)
     }
 
+    /// Registers observable gauges for the overall drop rate and bounce average,
+    /// reporting the live cumulative statistics at each export.
+    fn register_gauges(&self, meter: &Meter) -> (ObservableGauge<f64>, ObservableGauge<f64>) {
+        let gauges = Arc::clone(&self.live_gauges);
+        let drop_percentage = meter
+            .f64_observable_gauge("stats.drop_percentage")
+            .with_description("Percentage of key events dropped so far")
+            .with_unit(Unit::new("%"))
+            .with_callback(move |observer| observer.observe(gauges.drop_percentage(), &[]))
+            .init();
+        let gauges = Arc::clone(&self.live_gauges);
+        let bounce_average = meter
+            .f64_observable_gauge("stats.bounce_average")
+            .with_description("Average time between a passed key event and its dropped bounce")
+            .with_unit(Unit::new("us"))
+            .with_callback(move |observer| observer.observe(gauges.bounce_average_us(), &[]))
+            .init();
+        (drop_percentage, bounce_average)
+    }
+
     /// Processes a single message received from the main thread.
     /// Updates statistics and performs logging if enabled.
-    #[instrument(name = "logger_process_message", skip(self, msg, near_miss_counter), fields(event_type=tracing::field::Empty, is_bounce=tracing::field::Empty))]
-    pub fn process_message(&mut self, msg: LogMessage, near_miss_counter: &Option<Counter<u64>>) {
+    #[instrument(name = "logger_process_message", skip(self, msg, near_miss_histogram), fields(event_type=tracing::field::Empty, is_bounce=tracing::field::Empty))]
+    pub fn process_message(
+        &mut self,
+        msg: LogMessage,
+        near_miss_histogram: &Option<Histogram<u64>>,
+    ) {
+        #[cfg(feature = "test-hooks")]
+        if let Some(delay) = self.message_delay {
+            std::thread::sleep(delay);
+        }
         match msg {
             LogMessage::Event(data) => {
                 // Log EventInfo fields individually at trace level
@@ -167,36 +510,89 @@ impl Logger {
                 // Record event details in the current span
                 Span::current().record("event_type", data.event.type_);
                 Span::current().record("is_bounce", data.is_bounce);
+                #[cfg(feature = "test-hooks")]
+                if self.panic_on_key == Some(data.event.code) && event::is_key_event(&data.event) {
+                    panic!(
+                        "INTERCEPT_BOUNCE_TEST_PANIC_ON_KEY: key {}",
+                        data.event.code
+                    );
+                }
+
+                if event::is_key_event(&data.event) && self.timestamp_check.record(data.event_us) {
+                    self.warn_unusable_timestamps();
+                }
+                // Before recording the event, so the held-key state is the one during the gap.
+                self.track_stream_gap(&data);
+                self.cumulative_stats.event_rate.record(data.event_us);
+                self.interval_stats.event_rate.record(data.event_us);
+                self.interval_bounds_us = Some(match self.interval_bounds_us {
+                    Some((start, _)) => (start, data.event_us),
+                    None => (data.event_us, data.event_us),
+                });
 
                 self.cumulative_stats
                     .record_event_info_with_config(&data, &self.config);
                 self.interval_stats
                     .record_event_info_with_config(&data, &self.config);
+                if self.otel_meter.is_some() && event::is_key_event(&data.event) {
+                    self.live_gauges
+                        .update(&self.cumulative_stats, data.is_bounce);
+                }
+                if let Some(counter) = &self.key_drop_counter {
+                    if data.is_bounce && event::is_key_event(&data.event) {
+                        counter.add(1, &[KeyValue::new("key", get_key_name(data.event.code))]);
+                    }
+                }
+
+                let recovered = self.track_recovery(&data);
+                if recovered {
+                    self.cumulative_stats.record_recovered_pass(&data);
+                    self.interval_stats.record_recovered_pass(&data);
+                }
+                if let Some(reached_output) = self.track_double_character(&data) {
+                    let code = data.event.code;
+                    self.cumulative_stats
+                        .record_double_character(code, reached_output);
+                    self.interval_stats
+                        .record_double_character(code, reached_output);
+                }
 
                 if self.first_event_us.is_none() {
                     self.first_event_us = Some(data.event_us);
                     tracing::trace!(ts = data.event_us, "Logger recorded first event timestamp");
                 }
 
-                // --- Increment Near-Miss Counter ---
-                if !data.is_bounce && event::is_key_event(&data.event) {
-                    if let Some(last_us) = data.last_passed_us {
-                        if let Some(diff) = data.event_us.checked_sub(last_us) {
-                            if diff <= self.config.near_miss_threshold_us() {
-                                if let Some(counter) = near_miss_counter {
-                                    counter.add(1, &[]);
-                                }
-                            }
-                        }
+                // --- Record Near-Miss Timing ---
+                let near_miss_us = if !data.is_bounce && event::is_key_event(&data.event) {
+                    data.last_passed_us
+                        .and_then(|last_us| data.event_us.checked_sub(last_us))
+                        .filter(|&diff| diff <= self.config.near_miss_threshold_us())
+                } else {
+                    None
+                };
+                if let Some(diff) = near_miss_us {
+                    if let Some(histogram) = near_miss_histogram {
+                        histogram.record(diff, &otel_key_attributes(&data.event));
+                    }
+                    // Auto-repeats always follow each other closely; only real presses/releases count.
+                    if self.config.warn_near_miss && data.event.value != 2 {
+                        self.warn_near_miss(&data, diff);
                     }
                 }
 
+                if self.config.event_json {
+                    self.write_event_json(&data, &mut io::stderr().lock());
+                }
+
+                self.write_timeline_row(&data);
+                self.write_dropped_event(&data);
+
                 if self.config.log_all_events {
                     if data.event.type_ == EV_SYN as u16 || data.event.type_ == EV_MSC as u16 {
                         return; // Skip logging SYN/MSC events even in log-all mode
                     }
                     tracing::trace!("Logger logging all events");
-                    self.log_event_detailed(&data);
+                    self.log_event_detailed(&data, recovered);
                 } else if self.config.log_bounces
                     && data.is_bounce
                     && event::is_key_event(&data.event)
@@ -205,42 +601,283 @@ impl Logger {
                     self.log_simple_bounce_detailed(&data);
                 }
             }
+            LogMessage::Reload(config) => {
+                tracing::debug!("Logger applying reloaded configuration");
+                self.config = config;
+            }
+        }
+    }
+
+    /// Remembers whether this key event was dropped and returns `true` if it passed
+    /// right after a drop of the same key code and value (a "recovered" pass).
+    fn track_recovery(&mut self, data: &EventInfo) -> bool {
+        if data.paused || !event::is_key_event(&data.event) {
+            return false;
+        }
+        let (code, value) = (data.event.code as usize, data.event.value as usize);
+        if code >= FILTER_MAP_SIZE || value >= NUM_KEY_STATES {
+            return false;
         }
+        let idx = code * NUM_KEY_STATES + value;
+        let previous_dropped = std::mem::replace(&mut self.last_was_drop[idx], data.is_bounce);
+        previous_dropped && !data.is_bounce
+    }
+
+    /// Returns `Some(second_press_passed)` when this release completes a press,
+    /// release, press, release sequence of its key in which the re-press came
+    /// within the debounce time of the first release: too quick for a human
+    /// double tap, so likely one keystroke that chattered into two ("aa").
+    /// Looks at every press/release as read, dropped or not.
+    fn track_double_character(&mut self, data: &EventInfo) -> Option<bool> {
+        if data.paused || !event::is_key_event(&data.event) || data.event.value > 1 {
+            return None;
+        }
+        let debounce_us = self.config.debounce_us();
+        let history = self.recent_edges.get_mut(data.event.code as usize)?;
+        let edge = KeyEdge {
+            pressed: data.event.value == 1,
+            event_us: data.event_us,
+            passed: !data.is_bounce,
+        };
+        let detected = match history.as_slice() {
+            [press, release, repress]
+                if press.pressed
+                    && !release.pressed
+                    && repress.pressed
+                    && !edge.pressed
+                    && repress.event_us.saturating_sub(release.event_us) <= debounce_us =>
+            {
+                Some(repress.passed)
+            }
+            _ => None,
+        };
+        if detected.is_some() {
+            // Start over, so one quadruple is never counted twice.
+            history.clear();
+        } else {
+            if history.len() == 3 {
+                history.remove(0);
+            }
+            history.push(edge);
+        }
+        detected
+    }
+
+    /// Detects unusually long silences in the event stream (`--gap-threshold`).
+    /// A gap while a key is held is suspicious: held keys normally produce a steady
+    /// stream of repeats, so it points at lost events rather than an idle keyboard.
+    fn track_stream_gap(&mut self, data: &EventInfo) {
+        let previous_us = self.last_event_us.replace(data.event_us);
+        let Some(threshold) = self.config.gap_threshold else {
+            return;
+        };
+        let Some(gap_us) = previous_us.and_then(|prev| data.event_us.checked_sub(prev)) else {
+            return;
+        };
+        if Duration::from_micros(gap_us) < threshold {
+            return;
+        }
+
+        let keys_held = self
+            .cumulative_stats
+            .per_key_stats
+            .iter()
+            .any(|stats| stats.is_pressed == Some(true));
+        self.cumulative_stats.record_stream_gap(keys_held);
+        self.interval_stats.record_stream_gap(keys_held);
+        let gap = util::format_us(gap_us, self.config.time_precision);
+        if keys_held {
+            info!(%gap, keys_held, "Gap in event stream while a key was held (possible dropped events)");
+        } else {
+            info!(%gap, keys_held, "Gap in event stream (keyboard idle)");
+        }
+    }
+
+    /// Warns that the input's timestamps can't be used for debouncing.
+    fn warn_unusable_timestamps(&self) {
+        warn!(
+            checked = self.timestamp_check.checked,
+            suspect = self.timestamp_check.suspect,
+            "Most key events have zero or identical timestamps: debouncing and timing statistics are meaningless for this input. Check the event source or replay tool"
+        );
     }
 
     /// Dumps the current interval statistics to stderr.
     #[instrument(name = "dump_periodic_stats", skip(self))]
     fn dump_periodic_stats(&mut self) {
-        let wallclock = Local::now().format("%Y-%m-%d %H:%M:%S%.3f").to_string();
+        let wallclock = (self.wallclock)()
+            .format("%Y-%m-%d %H:%M:%S%.3f")
+            .to_string();
         tracing::info!(target: "stats", kind = "periodic", wallclock = %wallclock, "Periodic stats dump");
 
         let mut interval_stats_clone = self.interval_stats.clone();
-        if self.config.stats_json {
-            tracing::debug!("Logger thread printing periodic stats in JSON format");
-            interval_stats_clone.print_stats_json(
-                &self.config,
-                None, // Runtime is only for cumulative
-                "Periodic",
-                &mut io::stderr().lock(),
-            );
-            tracing::debug!("Logger thread finished printing periodic stats in JSON format");
+        interval_stats_clone.interval_us = self.interval_bounds_us.take();
+        tracing::debug!(
+            json = self.config.stats_json,
+            "Logger thread printing periodic stats"
+        );
+        if self.config.periodic_compact {
+            interval_stats_clone.print_compact_summary(&self.config, "PERIODIC");
         } else {
-            tracing::debug!("Logger thread printing periodic stats in human-readable format");
-            interval_stats_clone.print_stats_to_stderr(&self.config, "Periodic");
-            tracing::debug!(
-                "Logger thread finished printing periodic stats in human-readable format"
-            );
+            // Runtime is only for cumulative reports.
+            interval_stats_clone.print_stats(&self.config, None, "Periodic");
         }
+        tracing::debug!("Logger thread finished printing periodic stats");
 
         tracing::debug!("Logger thread resetting interval stats");
-        self.interval_stats = StatsCollector::with_capacity();
+        self.interval_stats = StatsCollector::with_sample_seed(self.sample_seed);
         tracing::debug!("Logger thread interval stats reset");
     }
 
+    /// Pushes the cumulative statistics as one `--stats-stream-fd` frame.
+    fn write_stats_frame(&mut self) {
+        let Some(stream) = self.stats_stream.as_mut() else {
+            return;
+        };
+        if let Err(e) = self
+            .cumulative_stats
+            .write_stats_frame(self.stream_frames, stream)
+        {
+            tracing::warn!(error = %e, "Failed to write stats stream, disabling it");
+            self.stats_stream = None;
+            return;
+        }
+        self.stream_frames += 1;
+    }
+
+    /// Writes a single event as one line of JSON (the `--event-json` stream).
+    /// Includes the raw `input_event` bytes as hex when `--event-json-raw` is set.
+    fn write_event_json<W: io::Write>(&self, data: &EventInfo, writer: &mut W) {
+        #[derive(Serialize)]
+        struct EventJson<'a> {
+            status: &'a str,
+            event_us: u64,
+            event_type: u16,
+            event_type_name: &'a str,
+            event_code: u16,
+            event_value: i32,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            key_name: Option<&'a str>,
+            is_bounce: bool,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            diff_us: Option<u64>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            last_passed_us: Option<u64>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            raw_hex: Option<String>,
+        }
+
+        let record = EventJson {
+            status: if data.is_bounce { "DROP" } else { "PASS" },
+            event_us: data.event_us,
+            event_type: data.event.type_,
+            event_type_name: get_event_type_name(data.event.type_),
+            event_code: data.event.code,
+            event_value: data.event.value,
+            key_name: event::is_key_event(&data.event).then(|| get_key_name(data.event.code)),
+            is_bounce: data.is_bounce,
+            diff_us: data.diff_us,
+            last_passed_us: data.last_passed_us,
+            raw_hex: self
+                .config
+                .event_json_raw
+                .then(|| event::event_to_hex(&data.event)),
+        };
+
+        let _ = serde_json::to_writer(&mut *writer, &record);
+        let _ = writeln!(writer);
+    }
+
+    /// Appends a processed key event to the `--timeline-csv` file, if any.
+    /// A failed write disables the timeline rather than interrupting filtering.
+    fn write_timeline_row(&mut self, data: &EventInfo) {
+        if data.paused || !event::is_key_event(&data.event) {
+            return;
+        }
+        let Some(timeline) = self.timeline.as_mut() else {
+            return;
+        };
+
+        let diff_us = if data.is_bounce {
+            data.diff_us
+        } else {
+            data.last_passed_us
+                .and_then(|last_us| data.event_us.checked_sub(last_us))
+        };
+        let decision = match diff_us {
+            _ if data.is_bounce => "drop",
+            Some(diff) if diff <= self.config.near_miss_threshold_us() => "near_miss",
+            _ => "pass",
+        };
+        let diff = diff_us.map(|d| d.to_string()).unwrap_or_default();
+
+        let row = format!(
+            "{},{},{},{},{},{}",
+            data.event_us,
+            data.event.code,
+            get_key_name(data.event.code),
+            data.event.value,
+            decision,
+            diff
+        );
+        if let Err(e) = timeline.write_record(&row) {
+            tracing::warn!(error = %e, "Failed to write event timeline, disabling it");
+            self.timeline = None;
+        }
+    }
+
+    /// Appends a dropped event to the `--dropped-events-file`, if any, flushing it
+    /// right away so the record survives a crash. A failed write disables the file.
+    fn write_dropped_event(&mut self, data: &EventInfo) {
+        if !data.is_bounce {
+            return;
+        }
+        let Some(file) = self.dropped_events.as_mut() else {
+            return;
+        };
+
+        #[derive(Serialize)]
+        struct DroppedEventJson<'a> {
+            event_us: u64,
+            event_type: u16,
+            event_type_name: &'a str,
+            code: u16,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            key_name: Option<&'a str>,
+            value: i32,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            value_name: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            diff_us: Option<u64>,
+            debounce_us: u64,
+        }
+
+        let record = DroppedEventJson {
+            event_us: data.event_us,
+            event_type: data.event.type_,
+            event_type_name: get_event_type_name(data.event.type_),
+            code: data.event.code,
+            key_name: event::is_key_event(&data.event).then(|| get_key_name(data.event.code)),
+            value: data.event.value,
+            value_name: get_value_name(data.event.type_, data.event.value),
+            diff_us: data.diff_us,
+            debounce_us: self.config.debounce_us(),
+        };
+
+        let result = serde_json::to_string(&record)
+            .map_err(io::Error::from)
+            .and_then(|line| file.write_record(&line))
+            .and_then(|()| file.writer.flush());
+        if let Err(e) = result {
+            tracing::warn!(error = %e, "Failed to write dropped events file, disabling it");
+            self.dropped_events = None;
+        }
+    }
+
     /// Adapts logic from the old BounceFilter::log_event.
     /// Logs details of a single event (passed or dropped) using tracing.
-    #[instrument(name = "log_event_detailed", skip(self, data), fields(status=tracing::field::Empty, key_code=data.event.code))]
-    fn log_event_detailed(&self, data: &EventInfo) {
+    #[instrument(name = "log_event_detailed", skip(self, data, recovered), fields(status=tracing::field::Empty, key_code=data.event.code))]
+    fn log_event_detailed(&self, data: &EventInfo, recovered: bool) {
         let status = if data.is_bounce { "DROP" } else { "PASS" };
 
         let relative_us = data
@@ -249,22 +886,20 @@ impl Logger {
 
         let type_name = get_event_type_name(data.event.type_);
 
-        let (key_name_str, value_name_str) = if event::is_key_event(&data.event) {
-            let key_name = get_key_name(data.event.code);
-            let value_name = match data.event.value {
-                0 => "Release",
-                1 => "Press",
-                2 => "Repeat",
-                _ => "Unknown",
-            };
-            (key_name, value_name)
+        let key_name_str = if event::is_key_event(&data.event) {
+            self.config.key_name(data.event.code)
         } else {
-            ("", "") // Not a key event, no key/value names
+            "" // Not a key event, no key name
         };
+        // Switches, LEDs and wheels get a name too; plain numeric values don't.
+        let value_name_str = get_value_name(data.event.type_, data.event.value).unwrap_or("");
 
         let bounce_info_str = if data.is_bounce && event::is_key_event(&data.event) {
             if let Some(diff) = data.diff_us {
-                format!(" (Bounce Time: {})", util::format_us(diff))
+                format!(
+                    " (Bounce Time: {})",
+                    util::format_us(diff, self.config.time_precision)
+                )
             } else {
                 " (Bounce Time: N/A)".to_string()
             }
@@ -273,26 +908,31 @@ impl Logger {
             "".to_string()
         };
 
-        let near_miss_info_str = if !data.is_bounce && event::is_key_event(&data.event) {
-            if let Some(last_us) = data.last_passed_us {
-                if let Some(diff) = data.event_us.checked_sub(last_us) {
-                    if Duration::from_micros(diff) >= self.config.debounce_time()
+        // Time since the last passed event, if this passed key event is a near-miss.
+        let near_miss_diff = if !data.is_bounce && event::is_key_event(&data.event) {
+            data.last_passed_us
+                .and_then(|last_us| data.event_us.checked_sub(last_us))
+                .filter(|&diff| {
+                    Duration::from_micros(diff) >= self.config.debounce_time()
                         && Duration::from_micros(diff) <= self.config.near_miss_threshold()
-                    {
-                        format!(" (Diff since last passed: {})", util::format_us(diff))
-                    } else {
-                        "".to_string()
-                    }
-                } else {
-                    "".to_string()
-                }
-            } else {
-                "".to_string()
-            }
+                })
         } else {
-            // Not a passed key event or no previous passed event
-            "".to_string()
+            // Not a passed key event
+            None
         };
+        // How far outside the debounce window the near-miss landed.
+        let near_miss_margin_us =
+            near_miss_diff.map(|diff| diff.saturating_sub(self.config.debounce_us()));
+
+        let near_miss_info_str = match near_miss_diff {
+            Some(diff) => format!(
+                " (Diff since last passed: {})",
+                util::format_us(diff, self.config.time_precision)
+            ),
+            None => "".to_string(),
+        };
+
+        let recovered_info_str = if recovered { " (Recovered)" } else { "" };
 
         let relative_human = format_relative_us(relative_us);
         let key_info_str = if event::is_key_event(&data.event) {
@@ -316,12 +956,39 @@ impl Logger {
             bounce_time_us = data.diff_us,
             bounce_info = %bounce_info_str,
             near_miss_diff_us = if !data.is_bounce && event::is_key_event(&data.event) { data.event_us.checked_sub(data.last_passed_us.unwrap_or(0)) } else { None },
+            near_miss_margin_us,
             near_miss_info = %near_miss_info_str,
-            "[{status}] {relative_human} {type_name} ({}, {value_name_str} {}){key_info_str}{bounce_info_str}{near_miss_info_str}",
+            recovered,
+            "[{status}] {relative_human} {type_name} ({}, {value_name_str} {}){key_info_str}{bounce_info_str}{near_miss_info_str}{recovered_info_str}",
             data.event.code, data.event.value
         );
     }
 
+    /// Logs a passed key event that landed within the near-miss threshold of the
+    /// previous pass (`--warn-near-miss`).
+    fn warn_near_miss(&self, data: &EventInfo, diff_us: u64) {
+        let code = data.event.code;
+        let key_name = self.config.key_name(code);
+        let value_name = get_value_name(data.event.type_, data.event.value).unwrap_or("Unknown");
+        let relative_us = data
+            .event_us
+            .saturating_sub(self.first_event_us.unwrap_or(data.event_us));
+        let diff_human = util::format_us(diff_us, self.config.time_precision);
+        let debounce_human =
+            util::format_duration(self.config.debounce_time(), self.config.time_precision);
+        warn!(
+            status = "NEAR-MISS",
+            relative_us,
+            event_code = code,
+            event_value = data.event.value,
+            key_name,
+            value_name,
+            near_miss_diff_us = diff_us,
+            "[NEAR-MISS] {} Key [{key_name}] ({code}, {value_name}) passed {diff_human} after the previous one (debounce time {debounce_human})",
+            format_relative_us(relative_us)
+        );
+    }
+
     /// Adapts logic from the old BounceFilter::log_simple_bounce.
     /// This is used when only `--log-bounces` is enabled. Logs only dropped key events.
     #[instrument(name = "log_simple_bounce_detailed", skip(self, data), fields(key_code=data.event.code))]
@@ -330,21 +997,19 @@ impl Logger {
         let code = data.event.code;
         let value = data.event.value;
         let type_name = get_event_type_name(data.event.type_);
-        let key_name = get_key_name(code);
+        let key_name = self.config.key_name(code);
 
-        let value_name = match value {
-            0 => "Release",
-            1 => "Press",
-            2 => "Repeat", // Should not happen based on call site logic, but handle defensively
-            _ => "Unknown",
-        };
+        let value_name = get_value_name(data.event.type_, value).unwrap_or("Unknown");
 
         let relative_us = data
             .event_us
             .saturating_sub(self.first_event_us.unwrap_or(data.event_us));
 
         let bounce_info_str = if let Some(diff) = data.diff_us {
-            format!(" (Bounce Time: {})", util::format_us(diff))
+            format!(
+                " (Bounce Time: {})",
+                util::format_us(diff, self.config.time_precision)
+            )
         } else {
             " (Bounce Time: N/A)".to_string()
         };
@@ -381,3 +1046,353 @@ fn format_relative_us(relative_us: u64) -> String {
     };
     format!("{s:<10}") // Keep format! here for padding
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{EventInfo, LogMessage, Logger};
+    use crate::config::Config;
+    use chrono::{DateTime, Local, NaiveDate};
+    use input_linux_sys::{input_event, timeval, EV_KEY};
+    use std::io::{self, Write};
+    use std::sync::atomic::AtomicBool;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    fn fixed_clock() -> DateTime<Local> {
+        NaiveDate::from_ymd_opt(2024, 1, 2)
+            .and_then(|date| date.and_hms_milli_opt(3, 4, 5, 678))
+            .and_then(|time| time.and_local_timezone(Local).single())
+            .unwrap()
+    }
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn periodic_dump_uses_the_injected_wallclock() {
+        let mut cfg = Config::new(
+            Duration::from_millis(25),
+            Duration::from_millis(100),
+            Duration::from_secs(15 * 60),
+            false,
+            false,
+            false,
+            false,
+            "intercept_bounce=info".to_string(),
+            None,
+            0,
+            Vec::new(),
+            Vec::new(),
+        );
+        cfg.periodic_compact = true;
+        let (_sender, receiver) = crossbeam_channel::unbounded::<LogMessage>();
+        let mut logger = Logger::new(
+            receiver,
+            Arc::new(AtomicBool::new(true)),
+            Arc::new(cfg),
+            None,
+        )
+        .with_wallclock(fixed_clock);
+
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        tracing::subscriber::with_default(subscriber, || logger.dump_periodic_stats());
+
+        let output = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        assert!(
+            output.contains("wallclock=2024-01-02 03:04:05.678"),
+            "Periodic dump without the fixed wallclock: {output}"
+        );
+    }
+
+    fn key_info(
+        event_us: u64,
+        value: i32,
+        is_bounce: bool,
+        last_passed_us: Option<u64>,
+    ) -> EventInfo {
+        EventInfo {
+            event: input_event {
+                time: timeval {
+                    tv_sec: (event_us / 1_000_000) as i64,
+                    tv_usec: (event_us % 1_000_000) as i64,
+                },
+                type_: EV_KEY as u16,
+                code: 30,
+                value,
+            },
+            event_us,
+            is_bounce,
+            diff_us: is_bounce.then(|| event_us - last_passed_us.unwrap()),
+            last_passed_us,
+            paused: false,
+            filter_latency_ns: None,
+        }
+    }
+
+    #[test]
+    fn live_gauges_follow_the_cumulative_stats() {
+        let cfg = Config::new(
+            Duration::from_millis(25),
+            Duration::from_millis(100),
+            Duration::ZERO,
+            false,
+            false,
+            false,
+            false,
+            "intercept_bounce=info".to_string(),
+            None,
+            0,
+            Vec::new(),
+            Vec::new(),
+        );
+        let (_sender, receiver) = crossbeam_channel::unbounded::<LogMessage>();
+        let mut logger = Logger::new(
+            receiver,
+            Arc::new(AtomicBool::new(true)),
+            Arc::new(cfg),
+            Some(opentelemetry::global::meter("test")),
+        );
+        let gauges = Arc::clone(&logger.live_gauges);
+        assert_eq!(gauges.drop_percentage(), 0.0);
+
+        for info in [
+            key_info(0, 1, false, None),
+            key_info(4_000, 1, true, Some(0)),
+            key_info(100_000, 0, false, None),
+            key_info(106_000, 0, true, Some(100_000)),
+        ] {
+            logger.process_message(LogMessage::Event(info), &None);
+        }
+
+        assert_eq!(gauges.drop_percentage(), 50.0);
+        assert_eq!(gauges.bounce_average_us(), 5_000.0);
+    }
+
+    /// Hands a `ManualReader` to a meter provider while keeping access to it.
+    #[cfg(feature = "otel")]
+    #[derive(Debug, Clone)]
+    struct SharedReader(Arc<opentelemetry_sdk::metrics::ManualReader>);
+
+    #[cfg(feature = "otel")]
+    impl opentelemetry_sdk::metrics::reader::TemporalitySelector for SharedReader {
+        fn temporality(
+            &self,
+            kind: opentelemetry_sdk::metrics::InstrumentKind,
+        ) -> opentelemetry_sdk::metrics::data::Temporality {
+            self.0.temporality(kind)
+        }
+    }
+
+    #[cfg(feature = "otel")]
+    impl opentelemetry_sdk::metrics::reader::AggregationSelector for SharedReader {
+        fn aggregation(
+            &self,
+            kind: opentelemetry_sdk::metrics::InstrumentKind,
+        ) -> opentelemetry_sdk::metrics::Aggregation {
+            self.0.aggregation(kind)
+        }
+    }
+
+    #[cfg(feature = "otel")]
+    impl opentelemetry_sdk::metrics::reader::MetricReader for SharedReader {
+        fn register_pipeline(
+            &self,
+            pipeline: std::sync::Weak<opentelemetry_sdk::metrics::Pipeline>,
+        ) {
+            self.0.register_pipeline(pipeline)
+        }
+
+        fn collect(
+            &self,
+            rm: &mut opentelemetry_sdk::metrics::data::ResourceMetrics,
+        ) -> opentelemetry::metrics::Result<()> {
+            self.0.collect(rm)
+        }
+
+        fn force_flush(&self) -> opentelemetry::metrics::Result<()> {
+            self.0.force_flush()
+        }
+
+        fn shutdown(&self) -> opentelemetry::metrics::Result<()> {
+            self.0.shutdown()
+        }
+    }
+
+    #[cfg(feature = "otel")]
+    #[test]
+    fn near_miss_histogram_carries_the_key_attributes() {
+        use opentelemetry::metrics::MeterProvider;
+        use opentelemetry::{Key, Value};
+        use opentelemetry_sdk::metrics::data::{self, ResourceMetrics};
+        use opentelemetry_sdk::metrics::reader::MetricReader;
+        use opentelemetry_sdk::metrics::{ManualReader, SdkMeterProvider};
+        use opentelemetry_sdk::Resource;
+
+        let reader = SharedReader(Arc::new(ManualReader::builder().build()));
+        let provider = SdkMeterProvider::builder()
+            .with_reader(reader.clone())
+            .build();
+        let meter = provider.meter("test");
+        let histogram = Some(meter.u64_histogram("events.near_miss_timing").init());
+
+        let cfg = Config::new(
+            Duration::from_millis(25),
+            Duration::from_millis(100),
+            Duration::ZERO,
+            false,
+            false,
+            false,
+            false,
+            "intercept_bounce=info".to_string(),
+            None,
+            0,
+            Vec::new(),
+            Vec::new(),
+        );
+        let (_sender, receiver) = crossbeam_channel::unbounded::<LogMessage>();
+        let mut logger = Logger::new(
+            receiver,
+            Arc::new(AtomicBool::new(true)),
+            Arc::new(cfg),
+            Some(meter),
+        );
+        for info in [
+            key_info(0, 1, false, None),
+            key_info(60_000, 0, false, None),
+            // Passed 40ms after the last release: a near-miss.
+            key_info(100_000, 1, false, Some(60_000)),
+        ] {
+            logger.process_message(LogMessage::Event(info), &histogram);
+        }
+
+        let mut metrics = ResourceMetrics {
+            resource: Resource::empty(),
+            scope_metrics: Vec::new(),
+        };
+        reader.collect(&mut metrics).unwrap();
+        let near_miss = metrics
+            .scope_metrics
+            .iter()
+            .flat_map(|scope| &scope.metrics)
+            .find(|metric| metric.name == "events.near_miss_timing")
+            .expect("near-miss histogram not exported");
+        let points = &near_miss
+            .data
+            .as_any()
+            .downcast_ref::<data::Histogram<u64>>()
+            .unwrap()
+            .data_points;
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].count, 1);
+        let mut attributes: Vec<_> = points[0].attributes.iter().collect();
+        attributes.sort_by_key(|(key, _)| key.as_str());
+        assert_eq!(
+            attributes,
+            [
+                (&Key::from_static_str("key"), &Value::from("KEY_A")),
+                (&Key::from_static_str("state"), &Value::from("Press")),
+            ]
+        );
+    }
+
+    #[cfg(feature = "otel")]
+    #[test]
+    fn key_drop_counter_counts_drops_per_key() {
+        use opentelemetry::metrics::MeterProvider;
+        use opentelemetry::{Key, Value};
+        use opentelemetry_sdk::metrics::data::{self, ResourceMetrics};
+        use opentelemetry_sdk::metrics::reader::MetricReader;
+        use opentelemetry_sdk::metrics::{ManualReader, SdkMeterProvider};
+        use opentelemetry_sdk::Resource;
+
+        let reader = SharedReader(Arc::new(ManualReader::builder().build()));
+        let provider = SdkMeterProvider::builder()
+            .with_reader(reader.clone())
+            .build();
+
+        let cfg = Config::new(
+            Duration::from_millis(25),
+            Duration::from_millis(100),
+            Duration::ZERO,
+            false,
+            false,
+            false,
+            false,
+            "intercept_bounce=info".to_string(),
+            None,
+            0,
+            Vec::new(),
+            Vec::new(),
+        );
+        let (_sender, receiver) = crossbeam_channel::unbounded::<LogMessage>();
+        let mut logger = Logger::new(
+            receiver,
+            Arc::new(AtomicBool::new(true)),
+            Arc::new(cfg),
+            Some(provider.meter("test")),
+        );
+        let mut key_b_bounce = key_info(210_000, 1, true, Some(200_000));
+        key_b_bounce.event.code = 48;
+        for info in [
+            key_info(0, 1, false, None),
+            key_info(4_000, 1, true, Some(0)),
+            key_info(100_000, 0, false, None),
+            key_info(106_000, 0, true, Some(100_000)),
+            key_b_bounce,
+        ] {
+            logger.process_message(LogMessage::Event(info), &None);
+        }
+
+        let mut metrics = ResourceMetrics {
+            resource: Resource::empty(),
+            scope_metrics: Vec::new(),
+        };
+        reader.collect(&mut metrics).unwrap();
+        let drops = metrics
+            .scope_metrics
+            .iter()
+            .flat_map(|scope| &scope.metrics)
+            .find(|metric| metric.name == "events.dropped_by_key")
+            .expect("per-key drop counter not exported");
+        let mut points: Vec<_> = drops
+            .data
+            .as_any()
+            .downcast_ref::<data::Sum<u64>>()
+            .unwrap()
+            .data_points
+            .iter()
+            .map(|point| {
+                let attributes: Vec<_> = point
+                    .attributes
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.clone()))
+                    .collect();
+                (attributes, point.value)
+            })
+            .collect();
+        points.sort_by_key(|(_, value)| *value);
+        let key = Key::from_static_str("key");
+        assert_eq!(
+            points,
+            [
+                (vec![(key.clone(), Value::from("KEY_B"))], 1),
+                (vec![(key, Value::from("KEY_A"))], 2),
+            ]
+        );
+    }
+}
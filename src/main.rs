@@ -1,51 +1,103 @@
 // Orchestrates command-line parsing, thread setup, the main event loop,
 // signal handling, and final shutdown/stats reporting.
 
-use crossbeam_channel::{bounded, Receiver, Sender, TrySendError};
+use crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender, TrySendError};
 use signal_hook::consts::signal::*;
 use signal_hook::iterator::Signals;
-use std::io::{self, ErrorKind};
+use std::ffi::OsString;
+use std::io::{self, ErrorKind, Write};
 use std::os::fd::RawFd;
 use std::os::unix::io::AsRawFd;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc, Mutex,
+    atomic::{AtomicBool, AtomicI32, Ordering},
+    Arc, Mutex, MutexGuard,
 };
 use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use event::{event_microseconds, list_input_devices, read_event_raw, write_event_raw};
+use event::{event_microseconds, list_input_devices, read_event_raw, write_event_raw_counted};
+use input_linux_sys::{EV_KEY, EV_MSC, EV_SYN};
+use intercept_bounce::error::AppError;
 use intercept_bounce::event;
-use intercept_bounce::filter::stats::StatsCollector;
-use intercept_bounce::filter::BounceFilter;
+use intercept_bounce::filter::stats::{LoadedReport, StatsCollector};
+use intercept_bounce::filter::{keynames, sweep, BounceFilter, Collapse};
 use intercept_bounce::logger;
-use intercept_bounce::telemetry::init_tracing;
+use intercept_bounce::output::{self, OutputRouter};
+use intercept_bounce::telemetry::{self, init_tracing};
 use intercept_bounce::{cli, config::Config, util};
-use logger::{LogMessage, Logger};
+use logger::{EventInfo, LogMessage, Logger};
 use tracing::{debug, error, info, instrument, trace, warn};
 
-use opentelemetry::global as otel_global;
+use opentelemetry::metrics::Meter;
 
 // Capacity for the channel between the main event loop and the logger thread.
 const LOGGER_QUEUE_CAPACITY: usize = 1024;
+// Share of events (in percent) lost to logger backpressure above which the
+// final warning calls the statistics unreliable.
+const UNRELIABLE_STATS_DROP_PERCENTAGE: f64 = 1.0;
 
 /// State for the main processing thread.
 struct MainState {
-    log_sender: Sender<LogMessage>,
+    /// Channel to the logger thread; `None` with `--no-stats`.
+    log_sender: Option<Sender<LogMessage>>,
+    /// The logger thread behind `log_sender`, joined at shutdown.
+    logger_thread: Option<LoggerHandle>,
+    /// Whether the logger thread panicked and was restarted (`--restart-logger`).
+    logger_restarted: bool,
     warned_about_dropping: bool,
     currently_dropping: bool,
     total_dropped_log_messages: u64,
+    /// Events read from the input and handed to `process_event`.
+    events_processed: u64,
+    /// Input events that passed the filter and were due on stdout.
+    events_passed: u64,
+    /// SYN_REPORTs made up by the filter itself and due on stdout.
+    events_synthesized: u64,
+    /// Events, and their bytes, that were actually written to stdout.
+    events_written: u64,
+    bytes_written: u64,
+    /// Failed writes dropped under `--write-error skip`.
+    write_errors_skipped: u64,
+    /// Partial events discarded at the end of input under `--tolerate-partial`.
+    partial_events_discarded: u64,
+    /// Passed events not written because their type isn't in `--passthrough-types`.
+    events_type_filtered: u64,
+    /// Key events with a value other than 0, 1 or 2 dropped under `--strict-values`.
+    invalid_values_dropped: u64,
+    /// MSC_SCAN held back by `--drop-orphan-scancodes` until its key event is decided.
+    output: OutputRouter,
+    /// MSC_SCAN events dropped along with their key event (`--drop-orphan-scancodes`).
+    scancodes_dropped: u64,
+    /// Whether the loop ended because `--max-runtime` ran out.
+    max_runtime_reached: bool,
+    /// `--tap-fd` descriptor. Dropped (with a warning) after a write error.
+    tap: Option<std::fs::File>,
+    /// Timestamp of the last event read and when it was read, giving the
+    /// event clock's time for held `--collapse-press-release` windows.
+    last_read: Option<(u64, Instant)>,
 }
 
 /// Context information passed to the main event loop.
 struct MainLoopContext<'a> {
     main_running: &'a Arc<AtomicBool>,
+    filtering_paused: &'a Arc<AtomicBool>,
+    // Set by SIGHUP; the main loop re-reads the config file before the next event.
+    reload_requested: &'a Arc<AtomicBool>,
+    // Original command line, re-parsed (with the config file) on reload.
+    argv: &'a [OsString],
     stdin_fd: RawFd,
     stdout_fd: RawFd,
     bounce_filter: &'a Arc<Mutex<BounceFilter>>,
-    cfg: &'a Arc<Config>,
+    // Cleared on shutdown; also handed to a restarted logger thread.
+    logger_running: &'a Arc<AtomicBool>,
+    otel_meter: Option<Meter>,
+    // Current configuration; replaced when a reload succeeds.
+    cfg: Arc<Config>,
     check_interval: Duration,
+    // When `--max-runtime` runs out, measured from startup.
+    deadline: Option<Instant>,
 }
 
 /// Optional OpenTelemetry counters used in the main loop.
@@ -76,9 +128,79 @@ impl std::fmt::Display for MainLoopError {
     }
 }
 
+/// Why the main loop ended, reported with the final statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ShutdownReason {
+    Eof,
+    Signal(i32),
+    BrokenPipe,
+    WriteError,
+    ReadError,
+    LoggerDisconnected,
+    MaxRuntime,
+}
+
+impl ShutdownReason {
+    /// Derives the reason from how the loop returned, the terminating signal
+    /// recorded by the signal thread (0 = none) and whether `--max-runtime` ran out.
+    fn from_loop(
+        result: &Result<(), MainLoopError>,
+        signal: i32,
+        max_runtime_reached: bool,
+    ) -> Self {
+        match result {
+            Ok(()) if signal != 0 => ShutdownReason::Signal(signal),
+            Ok(()) if max_runtime_reached => ShutdownReason::MaxRuntime,
+            Ok(()) => ShutdownReason::Eof,
+            Err(MainLoopError::StdoutBrokenPipe) => ShutdownReason::BrokenPipe,
+            Err(MainLoopError::StdoutWriteError(_)) => ShutdownReason::WriteError,
+            Err(MainLoopError::StdinReadError(_)) => ShutdownReason::ReadError,
+            Err(MainLoopError::LoggerDisconnected) => ShutdownReason::LoggerDisconnected,
+        }
+    }
+}
+
+impl std::fmt::Display for ShutdownReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShutdownReason::Eof => write!(f, "EOF"),
+            ShutdownReason::Signal(SIGINT) => write!(f, "SIGINT"),
+            ShutdownReason::Signal(SIGTERM) => write!(f, "SIGTERM"),
+            ShutdownReason::Signal(SIGQUIT) => write!(f, "SIGQUIT"),
+            ShutdownReason::Signal(sig) => write!(f, "signal {sig}"),
+            ShutdownReason::BrokenPipe => write!(f, "broken pipe"),
+            ShutdownReason::WriteError => write!(f, "write error"),
+            ShutdownReason::ReadError => write!(f, "read error"),
+            ShutdownReason::LoggerDisconnected => write!(f, "logger disconnected"),
+            ShutdownReason::MaxRuntime => write!(f, "max runtime"),
+        }
+    }
+}
+
+impl MainLoopError {
+    /// The error to exit with once shutdown completes. A broken stdout pipe
+    /// means the consumer went away, which is a normal way to stop.
+    fn into_app_error(self) -> Option<AppError> {
+        match self {
+            MainLoopError::StdoutBrokenPipe => None,
+            MainLoopError::StdoutWriteError(e) | MainLoopError::StdinReadError(e) => {
+                Some(AppError::Io(e))
+            }
+            MainLoopError::LoggerDisconnected => {
+                Some(AppError::LoggerThread("channel disconnected".to_string()))
+            }
+        }
+    }
+}
+
 /// Attempts to set the process priority to the highest level (-20 niceness).
 /// Prints a warning if it fails (e.g., due to insufficient permissions).
+/// Skipped without a warning if `INTERCEPT_BOUNCE_NO_PRIORITY=1` is set.
 fn set_high_priority() {
+    if std::env::var_os("INTERCEPT_BOUNCE_NO_PRIORITY").is_some_and(|v| v == "1") {
+        debug!("INTERCEPT_BOUNCE_NO_PRIORITY=1, leaving the process priority alone");
+        return;
+    }
     #[cfg(target_os = "linux")]
     {
         debug!("Attempting to set high process priority (niceness -20)...");
@@ -98,6 +220,40 @@ fn set_high_priority() {
     }
 }
 
+/// Picks the device for `--auto-device`: the first keyboard in `/dev/input`,
+/// warning with the others if there is more than one.
+fn select_auto_device() -> Result<PathBuf, AppError> {
+    let devices =
+        event::scan_input_devices(Path::new("/dev/input/")).map_err(AppError::DeviceList)?;
+    let candidates = event::keyboard_candidates(&devices);
+    let Some(chosen) = candidates.first() else {
+        return Err(AppError::NoKeyboard);
+    };
+    if candidates.len() > 1 {
+        let others: Vec<String> = candidates[1..]
+            .iter()
+            .map(|device| {
+                format!(
+                    "{} ({})",
+                    device.path.display(),
+                    device.name.as_deref().unwrap_or("")
+                )
+            })
+            .collect();
+        warn!(
+            chosen = %chosen.path.display(),
+            others = %others.join(", "),
+            "Several keyboards found for --auto-device; using the first. Pass --input to pick another"
+        );
+    }
+    info!(
+        path = %chosen.path.display(),
+        name = chosen.name.as_deref().unwrap_or(""),
+        "Selected input device (--auto-device)"
+    );
+    Ok(chosen.path.clone())
+}
+
 /// Sets the main and logger running flags to false and logs the shutdown reason.
 fn trigger_shutdown(
     reason: &str,
@@ -109,76 +265,205 @@ fn trigger_shutdown(
     logger_running.store(false, Ordering::SeqCst);
 }
 
-fn main() -> io::Result<()> {
+fn main() {
+    // The single place where errors are reported and mapped to exit codes.
+    if let Err(e) = run() {
+        error!("{e}");
+        if matches!(e, AppError::StdinIsTerminal) {
+            info!(
+                "Usage hint: pipe events in, e.g. `intercept -g $DEVNODE | intercept-bounce | uinput -d $DEVNODE`, or use --list-devices to inspect devices."
+            );
+        }
+        exit(e.exit_code());
+    }
+}
+
+fn run() -> Result<(), AppError> {
+    let started = Instant::now();
+    let argv: Vec<OsString> = std::env::args_os().collect();
     let args = cli::parse_args();
-    let cfg = Arc::new(Config::from(&args));
-    let otel_meter = init_tracing(&cfg);
+    let mut cfg = Config::from(&args);
+    let otel_meter = init_tracing(&cfg)?;
+    if let Some(path) = &cfg.key_aliases_file {
+        cfg.key_aliases = keynames::load_key_aliases(path).map_err(AppError::Config)?;
+        info!(path = %path.display(), count = cfg.key_aliases.len(), "Loaded key aliases");
+    }
+    if cfg.anonymize {
+        cfg.key_pseudonyms = keynames::pseudonym_codes(util::random_seed());
+    }
+    if cfg.auto_device && !args.list_devices && !args.self_test && args.merge.is_empty() {
+        cfg.input = Some(select_auto_device()?);
+        cfg.grab = true;
+    }
+    let cfg = Arc::new(cfg);
+
+    if cfg.otel_endpoint.is_some() && !cfg!(feature = "otel") {
+        return Err(AppError::Config(
+            "--otel-endpoint needs a build with the `otel` feature".to_string(),
+        ));
+    }
+
+    if args.self_test {
+        return run_self_test();
+    }
+
+    if !args.merge.is_empty() {
+        return run_merge(&args.merge, args.output.as_deref(), &cfg);
+    }
 
     if args.list_devices {
         info!("Scanning input devices (requires read access to /dev/input/event*)...");
-        match list_input_devices() {
-            Ok(_) => {
-                info!("Device listing complete. Exiting.");
-            }
-            Err(e) => {
-                error!("Error listing devices: {e}");
-                info!("Exiting due to device listing error.");
-                exit(2);
-            }
-        }
+        list_input_devices(args.json).map_err(AppError::DeviceList)?;
+        info!("Device listing complete. Exiting.");
         return Ok(());
     }
 
+    // Events come from `--input` if given, otherwise from stdin. The file is
+    // kept open for the whole run; its fd stands in for stdin.
+    let input_file = match &cfg.input {
+        Some(path) => {
+            Some(std::fs::File::open(path).map_err(|e| AppError::Input(path.clone(), e))?)
+        }
+        None => None,
+    };
+    let stdin_fd = match &input_file {
+        Some(file) => file.as_raw_fd(),
+        None => io::stdin().as_raw_fd(),
+    };
+    // On an interactive terminal the read would block forever waiting for
+    // binary input_event structs, so bail out with a usage hint instead of
+    // hanging silently.
+    if input_file.is_none() && unsafe { libc::isatty(stdin_fd) } == 1 {
+        return Err(AppError::StdinIsTerminal);
+    }
+    // `--grab` requires `--input`, so the path is always set here.
+    if let (true, Some(path)) = (cfg.grab, &cfg.input) {
+        event::grab_device(stdin_fd, true).map_err(|e| AppError::Grab(path.clone(), e))?;
+        info!(path = %path.display(), "Grabbed input device exclusively");
+    }
+
+    if !cfg.sweep.is_empty() {
+        return run_sweep(stdin_fd, &cfg);
+    }
+
+    // Load statistics from earlier runs up front, so a bad state file is
+    // reported immediately rather than after a whole session.
+    let prior_stats = match cfg.persist_state.as_deref().map(StatsCollector::load_state) {
+        Some(Ok(stats)) => {
+            if stats.is_some() {
+                info!("Loaded statistics from previous runs");
+            }
+            stats
+        }
+        Some(Err(e)) => return Err(AppError::Config(e)),
+        None => None,
+    };
+
     set_high_priority();
 
-    // Create BounceFilter with the configured ring buffer size
-    let bounce_filter = Arc::new(Mutex::new(BounceFilter::new(cfg.ring_buffer_size)));
+    // Create BounceFilter with the configured ring buffer size and filter options
+    let mut filter = BounceFilter::new(cfg.ring_buffer_size);
+    // A capture under `--analyze` was recorded long before this run started.
+    if !cfg.analyze {
+        filter = filter.with_start_time(util::realtime_us());
+    }
+    filter.apply_config(&cfg);
+    let bounce_filter = Arc::new(Mutex::new(filter));
     let final_stats_printed = Arc::new(AtomicBool::new(false));
     let main_running = Arc::new(AtomicBool::new(true));
     let logger_running = Arc::new(AtomicBool::new(true));
+    // Toggled by SIGUSR1. While set, events bypass the BounceFilter entirely
+    // (its state is frozen, not updated) and are passed through unfiltered.
+    let filtering_paused = Arc::new(AtomicBool::new(false));
+    let reload_requested = Arc::new(AtomicBool::new(false));
+    // The signal that ended the run, if any (0 = none).
+    let shutdown_signal = Arc::new(AtomicI32::new(0));
 
-    let (log_sender, log_receiver): (Sender<LogMessage>, Receiver<LogMessage>) =
-        bounded(LOGGER_QUEUE_CAPACITY);
-    let logger_cfg = Arc::clone(&cfg);
-    let logger_running_clone_for_logger = Arc::clone(&logger_running);
-    let logger_otel_meter = otel_meter.clone();
-    let logger_handle: JoinHandle<StatsCollector> = thread::spawn(move || {
-        let mut logger = Logger::new(
-            log_receiver,
-            logger_running_clone_for_logger,
-            logger_cfg,
-            logger_otel_meter,
-        );
-        logger.run()
-    });
+    // With `--no-stats` there is no logger thread or channel: the main loop only
+    // filters and writes.
+    let (log_sender, logger_thread) = if cfg.no_stats {
+        info!("Statistics disabled (--no-stats), not starting the logger thread");
+        (None, None)
+    } else {
+        let (sender, handle, done) = spawn_logger(&cfg, &logger_running, otel_meter.clone())?;
+        (Some(sender), Some((handle, done)))
+    };
 
     // --- Signal Handling Thread ---
-    let mut signals = Signals::new([SIGTERM, SIGINT, SIGQUIT])?;
+    let mut signals = Signals::new([SIGTERM, SIGINT, SIGQUIT, SIGUSR1, SIGHUP])?;
     let main_running_signal = Arc::clone(&main_running);
     let logger_running_signal = Arc::clone(&logger_running);
+    let filtering_paused_signal = Arc::clone(&filtering_paused);
+    let reload_requested_signal = Arc::clone(&reload_requested);
+    let shutdown_signal_handler = Arc::clone(&shutdown_signal);
+    let has_config_file = args.config.is_some();
     thread::spawn(move || {
-        if let Some(sig) = signals.forever().next() {
+        for sig in signals.forever() {
+            if sig == SIGUSR1 {
+                // fetch_xor returns the previous value; the new state is its negation.
+                let paused = !filtering_paused_signal.fetch_xor(true, Ordering::SeqCst);
+                if paused {
+                    info!("Filtering paused (SIGUSR1). Passing all events unfiltered.");
+                } else {
+                    info!("Filtering resumed (SIGUSR1).");
+                }
+                continue;
+            }
+            if sig == SIGHUP {
+                if has_config_file {
+                    // Set the flag before logging so anything reacting to the log
+                    // line is guaranteed to see the reload.
+                    reload_requested_signal.store(true, Ordering::SeqCst);
+                    info!(
+                        "Reload requested (SIGHUP). Applying configuration before the next event."
+                    );
+                } else {
+                    info!("Received SIGHUP, but no --config file was given. Ignoring.");
+                }
+                continue;
+            }
+            // Recorded before the loop can see the shutdown, so it reports the signal.
+            shutdown_signal_handler.store(sig, Ordering::SeqCst);
             // `sig` is used in format string
             let reason = format!("Received signal {sig}");
             // Ensure final stats are printed by the signal handler if it triggers shutdown.
             trigger_shutdown(&reason, &main_running_signal, &logger_running_signal);
+            break;
         }
     });
 
     info!("Starting main event loop");
-    let stdin_fd = io::stdin().as_raw_fd();
-    info!(stdin_fd, "Reading from standard input");
+    match &cfg.input {
+        Some(path) => info!(stdin_fd, path = %path.display(), "Reading from input file"),
+        None => info!(stdin_fd, "Reading from standard input"),
+    }
     let stdout_fd = io::stdout().as_raw_fd();
-    debug!(stdout_fd, debounce = %util::format_duration(cfg.debounce_time()), "Using stdout FD and debounce time.");
+    debug!(stdout_fd, debounce = %util::format_duration(cfg.debounce_time(), cfg.time_precision), "Using stdout FD and debounce time.");
 
     let mut main_state = MainState {
         log_sender,
+        logger_thread,
+        logger_restarted: false,
         warned_about_dropping: false,
         currently_dropping: false,
         total_dropped_log_messages: 0,
+        events_processed: 0,
+        events_passed: 0,
+        events_synthesized: 0,
+        events_written: 0,
+        bytes_written: 0,
+        write_errors_skipped: 0,
+        partial_events_discarded: 0,
+        events_type_filtered: 0,
+        invalid_values_dropped: 0,
+        output: OutputRouter::default(),
+        scancodes_dropped: 0,
+        max_runtime_reached: false,
+        tap: cfg.tap_fd.and_then(open_tap),
+        last_read: None,
     };
 
-    let check_interval = Duration::from_millis(100); // Interval to sleep on EINTR
+    let check_interval = cfg.poll_interval; // Interval to re-check for shutdown while waiting
 
     // --- OTLP Metrics Setup ---
     let otel_counters = OtelCounters {
@@ -205,41 +490,92 @@ fn main() -> io::Result<()> {
     };
 
     // Group arguments for the main loop function.
-    let main_loop_context = MainLoopContext {
+    let mut main_loop_context = MainLoopContext {
         main_running: &main_running,
+        filtering_paused: &filtering_paused,
+        reload_requested: &reload_requested,
+        argv: &argv,
         stdin_fd,
         stdout_fd,
         bounce_filter: &bounce_filter,
-        cfg: &cfg,
+        logger_running: &logger_running,
+        otel_meter: otel_meter.clone(),
+        cfg: Arc::clone(&cfg),
         check_interval,
+        deadline: cfg.max_runtime.map(|max_runtime| started + max_runtime),
     };
 
-    // Run the main event processing loop.
-    run_main_loop(
-        &main_loop_context,
-        &mut main_state,
-        &otel_counters,
-        &logger_running,
+    // Run the main event processing loop. A fatal error is reported only after
+    // shutdown has completed and the final statistics have been printed.
+    let loop_result = run_main_loop(&mut main_loop_context, &mut main_state, &otel_counters);
+    let shutdown_reason = ShutdownReason::from_loop(
+        &loop_result,
+        shutdown_signal.load(Ordering::SeqCst),
+        main_state.max_runtime_reached,
     );
+    let broken_pipe = shutdown_reason == ShutdownReason::BrokenPipe;
+    let mut exit_error = loop_result.err().and_then(MainLoopError::into_app_error);
 
-    info!("Main event loop finished");
+    info!(reason = %shutdown_reason, "Main event loop finished");
+    if cfg.grab {
+        // Closing the device would release the grab too, but hand the keyboard
+        // back before the (possibly slow) shutdown below.
+        match event::grab_device(stdin_fd, false) {
+            Ok(()) => debug!("Released input device grab"),
+            Err(e) => warn!(error = %e, "Failed to release input device grab"),
+        }
+    }
+    // Report final statistics against the configuration in effect at exit.
+    let cfg = main_loop_context.cfg;
 
     debug!("Starting shutdown process");
     // Drop the sender to signal the logger thread to finish processing remaining messages.
     drop(main_state.log_sender);
 
-    debug!("Waiting for logger thread to join...");
-    // Make final_stats mutable so we can call mutable methods on it
-    let mut final_stats = match logger_handle.join() {
-        Ok(stats) => {
-            debug!("Logger thread joined successfully");
-            stats
+    // No statistics at all with `--no-stats`, or if the logger didn't hand them over.
+    let mut final_stats =
+        main_state
+            .logger_thread
+            .take()
+            .and_then(|(logger_handle, logger_done_receiver)| {
+                debug!("Waiting for logger thread to join...");
+                match join_logger(logger_handle, &logger_done_receiver, cfg.shutdown_timeout) {
+                    Ok(stats) => {
+                        debug!("Logger thread joined successfully");
+                        stats
+                    }
+                    Err(e) => {
+                        exit_error.get_or_insert(e);
+                        None
+                    }
+                }
+            });
+
+    if main_state.logger_restarted && cfg.fail_on_logger_panic {
+        exit_error.get_or_insert(AppError::LoggerThread(
+            "panicked and was restarted (--fail-on-logger-panic)".to_string(),
+        ));
+    }
+
+    if let Some(stats) = final_stats.as_mut() {
+        // `--expect-events` judges this session alone, not the persisted totals.
+        if cfg.expect_events && stats.key_events_processed + stats.key_events_paused == 0 {
+            exit_error.get_or_insert(AppError::NoEvents);
         }
-        Err(e) => {
-            error!(panic_info = ?e, "Logger thread panicked"); // Keep ?e for debug info
-            StatsCollector::with_capacity() // Return empty stats on panic
+
+        // Fold this session into the persisted totals and save them before reporting,
+        // so the state survives even if printing to stderr fails.
+        if let Some(path) = &cfg.persist_state {
+            if let Some(mut merged) = prior_stats {
+                merged.merge(stats);
+                *stats = merged;
+            }
+            save_persisted_stats(stats, path);
         }
-    };
+        if let Some(path) = &cfg.heatmap_json {
+            save_heatmap(stats, path);
+        }
+    }
 
     // Use an atomic swap on `final_stats_printed`. If this thread successfully
     // changes it from `false` to `true`, it takes responsibility for printing
@@ -258,21 +594,66 @@ fn main() -> io::Result<()> {
             }
         };
 
-        if cfg.stats_json {
-            info!(target: "stats", stats_kind = "cumulative", format = "json", "Emitting final statistics");
-            final_stats.print_stats_json(&cfg, runtime_us, "Cumulative", &mut io::stderr().lock());
-        } else {
-            info!(target: "stats", stats_kind = "cumulative", format = "human", "Emitting final statistics");
-            final_stats.print_stats_to_stderr(&cfg, "Cumulative");
-            if let Some(rt) = runtime_us {
-                info!(runtime = %util::format_duration(Duration::from_micros(rt)), "Total Runtime");
-                // Keep %util::...
+        if let Some(stats) = final_stats.as_mut() {
+            stats.shutdown_reason = Some(shutdown_reason.to_string());
+        }
+        match final_stats.as_mut() {
+            Some(_) if broken_pipe && cfg.no_stats_on_broken_pipe => info!(
+                "Consumer went away (broken pipe), skipping final statistics (--no-stats-on-broken-pipe)"
+            ),
+            Some(final_stats) if cfg.stats_json => {
+                info!(target: "stats", stats_kind = "cumulative", format = "json", "Emitting final statistics");
+                final_stats.print_stats(&cfg, runtime_us, "Cumulative");
+            }
+            Some(final_stats) => {
+                info!(target: "stats", stats_kind = "cumulative", format = "human", "Emitting final statistics");
+                final_stats.print_stats(&cfg, runtime_us, "Cumulative");
+                if let Some(rt) = runtime_us {
+                    info!(runtime = %util::format_duration(Duration::from_micros(rt), cfg.time_precision), "Total Runtime");
+                    // Keep %util::...
+                }
             }
+            None => debug!("No statistics to report (--no-stats, or the logger didn't finish)"),
+        }
+        if let (Some(stats), Some(path)) = (final_stats.as_mut(), &cfg.stats_json_file) {
+            save_stats_json(stats, &cfg, runtime_us, path);
         }
         if main_state.total_dropped_log_messages > 0 {
+            let dropped = main_state.total_dropped_log_messages;
+            let lost_percentage =
+                dropped as f64 / main_state.events_processed.max(1) as f64 * 100.0;
+            if lost_percentage > UNRELIABLE_STATS_DROP_PERCENTAGE {
+                warn!(
+                    count = dropped,
+                    events_processed = main_state.events_processed,
+                    "STATISTICS ARE UNRELIABLE: {lost_percentage:.2}% of events never reached the logger due to backpressure"
+                );
+            } else {
+                warn!(
+                    count = dropped,
+                    events_processed = main_state.events_processed,
+                    "Total log messages dropped due to logger backpressure ({lost_percentage:.2}% of events)"
+                );
+            }
+        }
+        // What the filter passed versus what actually reached the consumer.
+        info!(
+            events_passed = main_state.events_passed,
+            events_synthesized = main_state.events_synthesized,
+            events_written = main_state.events_written,
+            bytes_written = main_state.bytes_written,
+            write_errors_skipped = main_state.write_errors_skipped,
+            partial_events_discarded = main_state.partial_events_discarded,
+            events_type_filtered = main_state.events_type_filtered,
+            invalid_values_dropped = main_state.invalid_values_dropped,
+            scancodes_dropped = main_state.scancodes_dropped,
+            "Output totals"
+        );
+        let events_due = main_state.events_passed + main_state.events_synthesized;
+        if !cfg.analyze && main_state.events_written < events_due {
             warn!(
-                count = main_state.total_dropped_log_messages,
-                "Total log messages dropped due to logger backpressure"
+                count = events_due - main_state.events_written,
+                "Passed events that never reached stdout"
             );
         }
     } else {
@@ -280,8 +661,10 @@ fn main() -> io::Result<()> {
     }
 
     // --- OTLP Shutdown ---
-    otel_global::shutdown_tracer_provider();
-    // Meter provider shutdown is handled implicitly by dropping the provider instance if it exists.
+    telemetry::shutdown_otel();
+    if let Some(e) = exit_error {
+        return Err(e);
+    }
     info!("Application exiting successfully");
     Ok(())
 }
@@ -298,15 +681,96 @@ fn process_event(
 ) -> Result<(), MainLoopError> {
     let event_us = event_microseconds(ev);
     trace!(event_us, "Processing event");
+    main_state.events_processed += 1;
+    if event::is_key_event(ev) && ctx.cfg.taps_key(ev.code) {
+        write_tap(main_state, ev);
+    }
 
     // Increment OTLP processed counter if available.
     if let Some(counter) = &otel_counters.events_processed {
-        counter.add(1, &[]);
+        counter.add(1, &counter_attributes(&ctx.cfg, ev));
+    }
+
+    // Out-of-protocol key values are corruption, not chatter: keep them away from
+    // the filter and the statistics, which would count them as repeats.
+    if output::rejects_value(&ctx.cfg, ev) {
+        main_state.invalid_values_dropped += 1;
+        warn!(
+            key = %ctx.cfg.key_name(ev.code),
+            value = ev.value,
+            event_us,
+            "Dropping key event with invalid value (--strict-values)"
+        );
+        return Ok(());
+    }
+
+    let skip_debounce = output::skips_debounce(&ctx.cfg, ev);
+    let paused = ctx.filtering_paused.load(Ordering::Relaxed);
+
+    if ctx.cfg.collapse_press_release {
+        // Releases held for longer than the debounce window were not chatter;
+        // emit them before handling the event that just arrived. Any event other
+        // than a report separator, a scancode or the held key's own press emits
+        // every held release first, so no other key overtakes it: a Shift release
+        // must not land after the next letter's press.
+        let keeps_held = match i32::from(ev.type_) {
+            EV_SYN | EV_MSC => true,
+            EV_KEY => lock_filter(ctx).holds_release_of(ev.code),
+            _ => false,
+        };
+        let flush_until = keeps_held.then_some(event_us);
+        flush_pending_releases(ctx, main_state, otel_counters, flush_until, true)?;
+
+        if !paused && !skip_debounce {
+            let collapse = lock_filter(ctx).collapse_press_release(ev, ctx.cfg.debounce_time());
+            match collapse {
+                Collapse::Check => {}
+                Collapse::Held => {
+                    trace!("Holding key release to check for release/press chatter");
+                    return Ok(());
+                }
+                Collapse::Collapsed { release, gap_us } => {
+                    trace!(gap_us, "Collapsing release/press chatter pair");
+                    let release_info = EventInfo {
+                        event: release,
+                        event_us: event_microseconds(&release),
+                        is_bounce: true,
+                        diff_us: None,
+                        last_passed_us: None,
+                        paused: false,
+                        filter_latency_ns: None,
+                    };
+                    dispatch_event_info(release_info, ctx, main_state, otel_counters)?;
+                    let press_info = EventInfo {
+                        event: *ev,
+                        event_us,
+                        is_bounce: true,
+                        diff_us: Some(gap_us),
+                        last_passed_us: None,
+                        paused: false,
+                        filter_latency_ns: None,
+                    };
+                    return dispatch_event_info(press_info, ctx, main_state, otel_counters);
+                }
+            }
+        }
     }
 
-    let skip_debounce = !ctx.cfg.should_debounce(ev.code);
-    let event_info = {
-        match ctx.bounce_filter.lock() {
+    let event_info = if paused {
+        // Bypass the filter entirely so its state stays frozen while paused.
+        trace!("Filtering paused, bypassing BounceFilter");
+        EventInfo {
+            event: *ev,
+            event_us,
+            is_bounce: false,
+            diff_us: None,
+            last_passed_us: None,
+            paused: true,
+            filter_latency_ns: None,
+        }
+    } else {
+        let started = ctx.cfg.measure_latency.then(Instant::now);
+        let mut info = match ctx.bounce_filter.lock() {
             Ok(mut filter) => {
                 let info = filter.check_event(ev, ctx.cfg.debounce_time(), skip_debounce);
                 trace!(is_bounce = info.is_bounce, diff_us = ?info.diff_us, last_passed_us = ?info.last_passed_us, "BounceFilter check_event returned");
@@ -320,17 +784,33 @@ fn process_event(
                 trace!(is_bounce = info.is_bounce, diff_us = ?info.diff_us, last_passed_us = ?info.last_passed_us, "BounceFilter check_event (poisoned) returned");
                 info
             }
-        }
+        };
+        info.filter_latency_ns =
+            started.map(|t| u64::try_from(t.elapsed().as_nanos()).unwrap_or(u64::MAX));
+        info
     };
 
+    dispatch_event_info(event_info, ctx, main_state, otel_counters)
+}
+
+/// Sends the filter decision for an event to the logger thread and writes the
+/// event to stdout if it passed.
+fn dispatch_event_info(
+    event_info: EventInfo,
+    ctx: &MainLoopContext,
+    main_state: &mut MainState,
+    otel_counters: &OtelCounters,
+) -> Result<(), MainLoopError> {
     // Extract the event and bounce status *before* event_info is moved.
+    // Passed events are written exactly as read; nothing on this path modifies them.
     let event_to_write = event_info.event;
     let is_bounce = event_info.is_bounce;
 
     // Send event info to logger thread.
-    match main_state
-        .log_sender
-        .try_send(LogMessage::Event(event_info)) // event_info is moved here
+    let Some(log_sender) = &main_state.log_sender else {
+        return write_decision(ctx, main_state, otel_counters, &event_to_write, is_bounce);
+    };
+    match log_sender.try_send(LogMessage::Event(event_info)) // event_info is moved here
     {
         Ok(_) => {
             if main_state.currently_dropping {
@@ -355,57 +835,758 @@ fn process_event(
         }
         Err(TrySendError::Disconnected(_)) => {
             // Logger thread terminated unexpectedly.
-            return Err(MainLoopError::LoggerDisconnected);
+            restart_logger(ctx, main_state)?;
+        }
+    }
+
+    write_decision(ctx, main_state, otel_counters, &event_to_write, is_bounce)
+}
+
+/// Replaces a logger thread that went away mid-run, which it only does by
+/// panicking, if `--restart-logger` allows it and it hasn't been restarted
+/// before. Statistics collected so far die with the old thread. Otherwise the
+/// disconnect ends the main loop; the panic is reported when the thread is joined.
+fn restart_logger(ctx: &MainLoopContext, main_state: &mut MainState) -> Result<(), MainLoopError> {
+    if !ctx.cfg.restart_logger || main_state.logger_restarted {
+        return Err(MainLoopError::LoggerDisconnected);
+    }
+    if let Some((handle, _)) = main_state.logger_thread.take() {
+        if let Err(e) = handle.join() {
+            error!(panic_info = ?e, "Logger thread panicked");
+        }
+    }
+    main_state.logger_restarted = true;
+    match spawn_logger(&ctx.cfg, ctx.logger_running, ctx.otel_meter.clone()) {
+        Ok((sender, handle, done)) => {
+            warn!("Restarted the logger thread (--restart-logger); statistics so far are lost");
+            main_state.log_sender = Some(sender);
+            main_state.logger_thread = Some((handle, done));
+            Ok(())
+        }
+        Err(e) => {
+            error!(error = %e, "Failed to restart the logger thread");
+            main_state.log_sender = None;
+            Err(MainLoopError::LoggerDisconnected)
         }
     }
+}
 
-    // Write non-bounced events to stdout.
-    if !is_bounce {
+/// Writes a passed event to stdout and counts the filter decision.
+fn write_decision(
+    ctx: &MainLoopContext,
+    main_state: &mut MainState,
+    otel_counters: &OtelCounters,
+    event_to_write: &event::input_event,
+    is_bounce: bool,
+) -> Result<(), MainLoopError> {
+    let counter = if is_bounce {
+        trace!("Event dropped by filter (bounce).");
+        &otel_counters.events_dropped
+    } else {
         trace!("Event passed filter. Writing to stdout...");
-        if let Some(counter) = &otel_counters.events_passed {
-            counter.add(1, &[]);
+        &otel_counters.events_passed
+    };
+    if let Some(counter) = counter {
+        counter.add(1, &counter_attributes(&ctx.cfg, event_to_write));
+    }
+    let routed = main_state.output.route(&ctx.cfg, event_to_write, is_bounce);
+    write_routed(ctx, main_state, routed)
+}
+
+/// Attributes for the OTLP event counters: the key and state of key events with
+/// `--otel-key-attributes`, none otherwise.
+fn counter_attributes(cfg: &Config, ev: &event::input_event) -> Vec<opentelemetry::KeyValue> {
+    if cfg.otel_key_attributes && event::is_key_event(ev) {
+        logger::otel_key_attributes(ev).to_vec()
+    } else {
+        Vec::new()
+    }
+}
+
+/// `--self-test`: runs a known event sequence through a fresh `BounceFilter`
+/// and `StatsCollector` with a 25ms debounce time and checks the decisions and
+/// counts, printing one PASS/FAIL line per check to stdout. Command-line filter
+/// options are ignored so the expectations always hold.
+fn run_self_test() -> Result<(), AppError> {
+    const KEY_A: u16 = 30;
+    const KEY_B: u16 = 48;
+    let cfg = Config::new(
+        Duration::from_millis(25),
+        Duration::from_millis(100),
+        Duration::ZERO,
+        false,
+        false,
+        false,
+        false,
+        String::new(),
+        None,
+        0,
+        Vec::new(),
+        Vec::new(),
+    );
+    let key_ev = |ms: u64, code: u16, value: i32| event::input_event {
+        time: input_linux_sys::timeval {
+            tv_sec: (ms / 1_000) as _,
+            tv_usec: ((ms % 1_000) * 1_000) as _,
+        },
+        type_: EV_KEY as u16,
+        code,
+        value,
+    };
+    // (event, expected to be dropped)
+    let sequence = [
+        (key_ev(0, KEY_A, 1), false),
+        (key_ev(5, KEY_A, 1), true), // 5ms press bounce
+        (key_ev(100, KEY_A, 0), false),
+        (key_ev(103, KEY_A, 0), true), // 3ms release bounce
+        (key_ev(200, KEY_A, 1), false),
+        (key_ev(205, KEY_B, 1), false), // Other keys are independent
+        (key_ev(210, KEY_A, 2), false), // Repeats are never debounced
+    ];
+
+    let mut filter = BounceFilter::new(0);
+    let mut stats = StatsCollector::with_capacity();
+    let mut checks: Vec<(String, bool)> = Vec::new();
+    for (ev, expect_drop) in &sequence {
+        let info = filter.check_event(ev, cfg.debounce_time(), false);
+        checks.push((
+            format!(
+                "{} {} at {}ms is {}",
+                keynames::get_key_name(ev.code),
+                keynames::get_value_name(ev.type_, ev.value).unwrap_or("?"),
+                event_microseconds(ev) / 1_000,
+                if *expect_drop { "dropped" } else { "passed" }
+            ),
+            info.is_bounce == *expect_drop,
+        ));
+        stats.record_event_info_with_config(&info, &cfg);
+    }
+    let counts = (
+        stats.key_events_processed,
+        stats.key_events_passed,
+        stats.key_events_dropped,
+    );
+    checks.push((
+        format!("statistics count 7 processed, 5 passed, 2 dropped (got {counts:?})"),
+        counts == (7, 5, 2),
+    ));
+    let average = stats.overall_bounce_average_us();
+    checks.push((
+        format!("statistics average bounce time is 4000us (got {average:?})"),
+        average == Some(4_000),
+    ));
+
+    let mut stdout = io::stdout().lock();
+    for (description, passed) in &checks {
+        writeln!(
+            stdout,
+            "{} {description}",
+            if *passed { "PASS" } else { "FAIL" }
+        )?;
+    }
+    let failed = checks.iter().filter(|(_, passed)| !passed).count();
+    if failed > 0 {
+        return Err(AppError::SelfTest(failed));
+    }
+    writeln!(stdout, "Self-test passed ({} checks)", checks.len())?;
+    Ok(())
+}
+
+/// `--merge`: combines two `--stats-json` reports into one, written as JSON to
+/// `output` (stdout if `None`).
+fn run_merge(reports: &[PathBuf], output: Option<&Path>, cfg: &Config) -> Result<(), AppError> {
+    let mut loaded = reports
+        .iter()
+        .map(|path| StatsCollector::load_report(path).map_err(AppError::Config));
+    let (Some(first), Some(second)) = (loaded.next(), loaded.next()) else {
+        return Err(AppError::Config(
+            "--merge needs two stats reports".to_string(),
+        ));
+    };
+    let (first, second) = (first?, second?);
+    // Band counts are summed index by index, which only adds up for the same bands.
+    if first.near_miss_bands != second.near_miss_bands {
+        let bands = |report: &LoadedReport| {
+            report
+                .near_miss_bands
+                .iter()
+                .map(|band| util::format_duration(*band, None))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        return Err(AppError::Config(format!(
+            "Cannot merge reports with different near-miss bands ({} vs {})",
+            bands(&first),
+            bands(&second)
+        )));
+    }
+    if first.debounce_time != second.debounce_time
+        || first.near_miss_threshold != second.near_miss_threshold
+    {
+        warn!(
+            first_debounce = ?first.debounce_time,
+            second_debounce = ?second.debounce_time,
+            "Merging reports taken with different debounce or near-miss settings; the first report's are shown"
+        );
+    }
+
+    let mut report_cfg = cfg.with_report_settings(&first);
+    report_cfg.interval_histogram = first.interval_histogram && second.interval_histogram;
+    let runtime_us = first
+        .runtime_us
+        .zip(second.runtime_us)
+        .map(|(a, b)| a.saturating_add(b));
+    let mut stats = first.stats;
+    stats.merge(&second.stats);
+
+    match output {
+        Some(path) => {
+            let mut file = io::BufWriter::new(std::fs::File::create(path)?);
+            stats.print_stats_json(&report_cfg, runtime_us, "Merged", &mut file)?;
+            file.flush()?;
         }
+        None => {
+            let mut stdout = io::stdout().lock();
+            stats.print_stats_json(&report_cfg, runtime_us, "Merged", &mut stdout)?;
+            stdout.flush()?;
+        }
+    }
+    info!(
+        processed = stats.key_events_processed,
+        dropped = stats.key_events_dropped,
+        "Merged stats reports"
+    );
+    Ok(())
+}
 
-        if let Err(e) = write_event_raw(ctx.stdout_fd, &event_to_write) {
-            return if e.kind() == ErrorKind::BrokenPipe {
-                Err(MainLoopError::StdoutBrokenPipe)
-            } else {
-                Err(MainLoopError::StdoutWriteError(e))
-            };
+/// `--sweep`: buffers the whole input, replays it once per debounce time and
+/// prints the comparison to stdout.
+fn run_sweep(stdin_fd: RawFd, cfg: &Config) -> Result<(), AppError> {
+    let mut events = Vec::new();
+    loop {
+        match read_event_raw(stdin_fd) {
+            Ok(Some(ev)) => events.push(ev),
+            Ok(None) => break,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) if cfg.tolerate_partial && e.kind() == ErrorKind::UnexpectedEof => {
+                warn!(error = %e, "Discarding partial event at end of input (--tolerate-partial)");
+                break;
+            }
+            Err(e) => return Err(AppError::Io(e)),
         }
-        trace!("Successfully wrote event to stdout");
+    }
+    info!(
+        events = events.len(),
+        settings = cfg.sweep.len(),
+        "Sweeping debounce times over the buffered input"
+    );
+
+    let results = sweep::sweep(&events, cfg, &cfg.sweep);
+    let mut stdout = io::stdout().lock();
+    if cfg.stats_json {
+        sweep::format_sweep_json(&results, &mut stdout)?;
     } else {
-        trace!("Event dropped by filter (bounce).");
-        if let Some(counter) = &otel_counters.events_dropped {
-            counter.add(1, &[]);
+        sweep::format_sweep_table(&results, cfg, &mut stdout)?;
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+/// A logger thread's handle and completion signal.
+type LoggerHandle = (JoinHandle<StatsCollector>, Receiver<()>);
+
+/// The running logger thread: its channel, handle and completion signal.
+type LoggerThread = (Sender<LogMessage>, JoinHandle<StatsCollector>, Receiver<()>);
+
+/// Starts the logger thread. Returns the sender for its channel, its handle and
+/// a receiver that is signalled when it finishes, so shutdown can wait for it
+/// with a timeout.
+fn spawn_logger(
+    cfg: &Arc<Config>,
+    logger_running: &Arc<AtomicBool>,
+    otel_meter: Option<Meter>,
+) -> Result<LoggerThread, AppError> {
+    let (log_sender, log_receiver): (Sender<LogMessage>, Receiver<LogMessage>) =
+        bounded(LOGGER_QUEUE_CAPACITY);
+    let logger_cfg = Arc::clone(cfg);
+    let logger_running = Arc::clone(logger_running);
+    let (logger_done_sender, logger_done_receiver) = bounded::<()>(1);
+    let logger_handle = thread::Builder::new()
+        .name("logger".to_string())
+        .spawn(move || {
+            let mut logger = Logger::new(log_receiver, logger_running, logger_cfg, otel_meter);
+            let stats = logger.run();
+            let _ = logger_done_sender.send(());
+            stats
+        })
+        .map_err(|e| AppError::LoggerThread(format!("cannot spawn: {e}")))?;
+    Ok((log_sender, logger_handle, logger_done_receiver))
+}
+
+/// Copies the settings that are fixed at startup from `current` into `new`, and
+/// returns the names of those the reloaded configuration tried to change.
+fn keep_restart_only_settings(new: &mut Config, current: &Config) -> Vec<&'static str> {
+    let mut changed = Vec::new();
+    macro_rules! keep {
+        ($($field:ident),+ $(,)?) => {$(
+            if new.$field != current.$field {
+                changed.push(stringify!($field));
+                new.$field.clone_from(&current.$field);
+            }
+        )+};
+    }
+    keep!(
+        ring_buffer_size,
+        otel_endpoint,
+        otel_flush_interval,
+        otel_required,
+        log_filter,
+        persist_state,
+        key_aliases_file,
+        anonymize,
+        no_stats,
+        poll_interval,
+        sample_seed,
+        timeline_csv,
+        dropped_events_file,
+        heatmap_json,
+        stats_json_file,
+        stats_stream_fd,
+        tap_fd,
+        max_runtime,
+        input,
+        grab,
+        auto_device,
+        analyze,
+        stats_to_stdout,
+    );
+    // Derived at startup from the settings above (log level, alias file,
+    // `--anonymize`), so they follow them rather than being compared.
+    new.verbose = current.verbose;
+    new.key_aliases.clone_from(&current.key_aliases);
+    new.key_pseudonyms.clone_from(&current.key_pseudonyms);
+    changed
+}
+
+/// Joins the logger thread, waiting at most `timeout` (zero waits indefinitely)
+/// for it to signal completion on `done`. A logger that is still busy, e.g.
+/// flushing to a stalled OTLP exporter, is left behind with a warning so
+/// shutdown can proceed; there are no statistics then (`Ok(None)`).
+fn join_logger(
+    handle: JoinHandle<StatsCollector>,
+    done: &Receiver<()>,
+    timeout: Duration,
+) -> Result<Option<StatsCollector>, AppError> {
+    if !timeout.is_zero() {
+        // A disconnect without a signal means the logger panicked; join reports it.
+        if let Err(RecvTimeoutError::Timeout) = done.recv_timeout(timeout) {
+            warn!(
+                timeout = %util::format_duration(timeout, None),
+                "Logger thread did not finish in time, exiting without its statistics"
+            );
+            return Ok(None);
         }
     }
+    handle.join().map(Some).map_err(|e| {
+        error!(panic_info = ?e, "Logger thread panicked"); // Keep ?e for debug info
+        AppError::LoggerThread("panicked".to_string())
+    })
+}
 
+/// Duplicates the `--tap-fd` descriptor, or warns and goes on without the tap.
+fn open_tap(fd: RawFd) -> Option<std::fs::File> {
+    match util::dup_fd(fd) {
+        Ok(file) => Some(file),
+        Err(e) => {
+            warn!(fd, error = %e, "Cannot use tap descriptor, continuing without it");
+            None
+        }
+    }
+}
+
+/// Mirrors a raw event of a `--tap` key to the tap descriptor.
+fn write_tap(main_state: &mut MainState, ev: &event::input_event) {
+    let Some(tap) = &mut main_state.tap else {
+        return;
+    };
+    if let Err(e) = tap.write_all(event::event_as_bytes(ev)) {
+        warn!(error = %e, "Failed to write to tap descriptor, closing it");
+        main_state.tap = None;
+    }
+}
+
+/// Saves cumulative statistics to the `--persist-state` file, logging the outcome.
+fn save_persisted_stats(stats: &StatsCollector, path: &std::path::Path) {
+    match stats.save_state(path) {
+        Ok(()) => info!(path = %path.display(), "Saved statistics state"),
+        Err(e) => error!(path = %path.display(), error = %e, "Failed to save statistics state"),
+    }
+}
+
+/// Writes the `--heatmap-json` export, logging the outcome.
+fn save_heatmap(stats: &StatsCollector, path: &std::path::Path) {
+    let result = std::fs::File::create(path).and_then(|file| {
+        let mut writer = io::BufWriter::new(file);
+        stats.write_heatmap_json(&mut writer)?;
+        writer.flush()
+    });
+    match result {
+        Ok(()) => info!(path = %path.display(), "Wrote chatter heatmap"),
+        Err(e) => error!(path = %path.display(), error = %e, "Failed to write chatter heatmap"),
+    }
+}
+
+/// Writes the final report to the `--stats-json-file`, logging the outcome.
+fn save_stats_json(
+    stats: &mut StatsCollector,
+    cfg: &Config,
+    runtime_us: Option<u64>,
+    path: &std::path::Path,
+) {
+    let result = std::fs::File::create(path).and_then(|file| {
+        let mut writer = io::BufWriter::new(file);
+        stats.export_stats_json(cfg, runtime_us, "Cumulative", &mut writer)?;
+        writer.flush()
+    });
+    match result {
+        Ok(()) => info!(path = %path.display(), "Wrote JSON statistics"),
+        Err(e) => error!(path = %path.display(), error = %e, "Failed to write JSON statistics"),
+    }
+}
+
+/// Re-reads the `--config` file (requested via SIGHUP) and applies the new settings.
+/// On any error the current configuration is kept. Fails only if releases held
+/// by a `--collapse-press-release` that was switched off can't be written.
+fn reload_config(
+    ctx: &mut MainLoopContext,
+    main_state: &mut MainState,
+    otel_counters: &OtelCounters,
+) -> Result<(), MainLoopError> {
+    let args = match cli::try_parse_args(ctx.argv) {
+        Ok(args) => args,
+        Err(e) => {
+            warn!(error = %e.to_string().trim_end(), "Configuration reload failed, keeping current settings");
+            return Ok(());
+        }
+    };
+    let mut new_cfg = Config::from(&args);
+    // `--auto-device` chose the input once, at startup.
+    if new_cfg.auto_device && ctx.cfg.auto_device {
+        new_cfg.input = ctx.cfg.input.clone();
+        new_cfg.grab = ctx.cfg.grab;
+    }
+
+    // The ring buffer, OTLP exporter, tracing subscriber, state file and
+    // poll intervals are set up once at startup.
+    let unchanged = keep_restart_only_settings(&mut new_cfg, &ctx.cfg);
+    if !unchanged.is_empty() {
+        warn!(
+            settings = %unchanged.join(", "),
+            "Some changed settings require a restart and were not applied"
+        );
+    }
+
+    lock_filter(ctx).apply_config(&new_cfg);
+    // Nothing would emit the releases still held back once the mode is off.
+    if ctx.cfg.collapse_press_release && !new_cfg.collapse_press_release {
+        flush_pending_releases(ctx, main_state, otel_counters, None, false)?;
+    }
+    let new_cfg = Arc::new(new_cfg);
+    // Use a blocking send: unlike per-event messages, a reload must not be dropped.
+    if let Some(Err(_)) = main_state
+        .log_sender
+        .as_ref()
+        .map(|sender| sender.send(LogMessage::Reload(Arc::clone(&new_cfg))))
+    {
+        warn!("Logger disconnected; reloaded configuration only applies to filtering");
+    }
+    info!(
+        debounce = %util::format_duration(new_cfg.debounce_time(), new_cfg.time_precision),
+        near_miss = %util::format_duration(new_cfg.near_miss_threshold(), new_cfg.time_precision),
+        log_interval = %util::format_duration(new_cfg.log_interval(), new_cfg.time_precision),
+        "Configuration reloaded"
+    );
+    ctx.cfg = new_cfg;
     Ok(())
 }
 
+/// Locks the BounceFilter, recovering the guard if the mutex was poisoned.
+fn lock_filter<'a>(ctx: &'a MainLoopContext) -> MutexGuard<'a, BounceFilter> {
+    ctx.bounce_filter.lock().unwrap_or_else(|poisoned| {
+        error!("FATAL: BounceFilter mutex poisoned in main event loop. Recovering...");
+        poisoned.into_inner()
+    })
+}
+
+/// Emits key releases held by `--collapse-press-release` whose debounce window has
+/// expired by `now_us` (all of them if `None`). Each release still goes through the
+/// regular debounce check. The original report was already passed on without it,
+/// so a SYN_REPORT is written after it, unless `input_follows`: the releases then
+/// go ahead of an event just read, whose report the input closes itself.
+fn flush_pending_releases(
+    ctx: &MainLoopContext,
+    main_state: &mut MainState,
+    otel_counters: &OtelCounters,
+    now_us: Option<u64>,
+    input_follows: bool,
+) -> Result<(), MainLoopError> {
+    let expired = {
+        let mut filter = lock_filter(ctx);
+        if !filter.has_pending_releases() {
+            return Ok(());
+        }
+        filter.take_expired_releases(now_us, ctx.cfg.debounce_time())
+    };
+
+    for release in expired {
+        let info = lock_filter(ctx).check_event(&release, ctx.cfg.debounce_time(), false);
+        let passed = !info.is_bounce;
+        trace!(code = release.code, passed, "Flushing held key release");
+        dispatch_event_info(info, ctx, main_state, otel_counters)?;
+        if passed && !input_follows && ctx.cfg.passes_event_type(EV_SYN as u16) {
+            write_synthesized_syn(ctx, main_state, &release)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the events routed for one filter decision to stdout, unless running
+/// with `--analyze`, and counts them: the event and a scancode released ahead
+/// of it as passed, a `--synthesize-syn` SYN_REPORT as synthesized.
+/// Under `--write-error skip`, a failed write (other than a broken pipe) is
+/// logged and the event is lost instead of ending the run.
+fn write_routed(
+    ctx: &MainLoopContext,
+    main_state: &mut MainState,
+    routed: output::Routed,
+) -> Result<(), MainLoopError> {
+    if routed.scancode_dropped {
+        main_state.scancodes_dropped += 1;
+        trace!("Dropped the scancode paired with the dropped key event");
+    }
+    if routed.type_filtered {
+        main_state.events_type_filtered += 1;
+        trace!("Event type not in --passthrough-types, not written");
+    }
+    for ev in [routed.scancode, routed.event].into_iter().flatten() {
+        main_state.events_passed += 1;
+        write_now(ctx, main_state, &ev)?;
+    }
+    if let Some(syn) = routed.syn {
+        main_state.events_synthesized += 1;
+        write_now(ctx, main_state, &syn)?;
+    }
+    Ok(())
+}
+
+/// Writes a SYN_REPORT that isn't in the input, stamped with the time of the
+/// event it closes the report of. Counted apart from the passed events.
+fn write_synthesized_syn(
+    ctx: &MainLoopContext,
+    main_state: &mut MainState,
+    ev: &event::input_event,
+) -> Result<(), MainLoopError> {
+    main_state.events_synthesized += 1;
+    write_now(ctx, main_state, &output::syn_report(ev))
+}
+
+/// Writes the MSC_SCAN held back by `--drop-orphan-scancodes`, if any.
+fn flush_pending_scancode(
+    ctx: &MainLoopContext,
+    main_state: &mut MainState,
+) -> Result<(), MainLoopError> {
+    match main_state.output.take_pending_scancode() {
+        Some(scan) => {
+            main_state.events_passed += 1;
+            write_now(ctx, main_state, &scan)
+        }
+        None => Ok(()),
+    }
+}
+
+/// Writes one event to stdout and counts it as written.
+fn write_now(
+    ctx: &MainLoopContext,
+    main_state: &mut MainState,
+    ev: &event::input_event,
+) -> Result<(), MainLoopError> {
+    if ctx.cfg.analyze {
+        return Ok(());
+    }
+    if let Err((e, written)) = write_event_raw_counted(ctx.stdout_fd, ev) {
+        return match (e.kind(), ctx.cfg.write_error) {
+            (ErrorKind::BrokenPipe, _) => Err(MainLoopError::StdoutBrokenPipe),
+            (_, cli::WriteErrorPolicy::Abort) => Err(MainLoopError::StdoutWriteError(e)),
+            // Skipping a torn event would misalign every event after it.
+            (_, cli::WriteErrorPolicy::Skip) if written > 0 => {
+                error!(
+                    written,
+                    "Stdout write failed partway through an event, aborting"
+                );
+                Err(MainLoopError::StdoutWriteError(e))
+            }
+            (_, cli::WriteErrorPolicy::Skip) => {
+                main_state.write_errors_skipped += 1;
+                warn!(
+                    error = %e,
+                    skipped = main_state.write_errors_skipped,
+                    "Stdout write failed, event lost (--write-error skip)"
+                );
+                Ok(())
+            }
+        };
+    }
+    main_state.events_written += 1;
+    main_state.bytes_written += std::mem::size_of::<event::input_event>() as u64;
+    trace!("Successfully wrote event to stdout");
+    Ok(())
+}
+
+/// Time left in the debounce window of a release held at `release_us`. The
+/// event clock's current time is taken from the last event read, plus the wall
+/// time since; before any read, the whole window is left.
+fn release_window_left(
+    release_us: u64,
+    debounce_time: Duration,
+    last_read: Option<(u64, Instant)>,
+) -> Duration {
+    let Some((read_us, read_at)) = last_read else {
+        return debounce_time;
+    };
+    let elapsed_us = u64::try_from(read_at.elapsed().as_micros()).unwrap_or(u64::MAX);
+    let deadline_us =
+        release_us.saturating_add(u64::try_from(debounce_time.as_micros()).unwrap_or(u64::MAX));
+    Duration::from_micros(deadline_us.saturating_sub(read_us.saturating_add(elapsed_us)))
+}
+
+/// Waits up to `timeout` for stdin to become readable.
+/// Returns `Ok(false)` on timeout.
+fn wait_for_stdin(fd: RawFd, timeout: Duration) -> io::Result<bool> {
+    let mut pfd = libc::pollfd {
+        fd,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let timeout_ms = timeout.as_millis().clamp(1, libc::c_int::MAX as u128) as libc::c_int;
+    let res = unsafe { libc::poll(&mut pfd, 1, timeout_ms) };
+    if res < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(res > 0)
+}
+
+/// Emits the key releases and scancode still held back when the input ends.
+fn flush_held_events(
+    ctx: &MainLoopContext,
+    main_state: &mut MainState,
+    otel_counters: &OtelCounters,
+) {
+    if let Err(e) = flush_pending_releases(ctx, main_state, otel_counters, None, false) {
+        warn!(error = %e, "Failed to flush held key releases at end of input");
+    }
+    if let Err(e) = flush_pending_scancode(ctx, main_state) {
+        warn!(error = %e, "Failed to flush held scancode at end of input");
+    }
+}
+
 /// The main event reading and processing loop.
 /// Reads events from stdin, processes them using `process_event`,
-/// and handles termination signals or errors.
+/// and handles termination signals or errors. Returns the error that ended
+/// the loop, if any; EOF and signals end it with `Ok(())`.
 #[instrument(name="main_event_loop", skip_all, fields(otel.kind = "consumer"))]
 fn run_main_loop(
-    ctx: &MainLoopContext,
+    ctx: &mut MainLoopContext,
     main_state: &mut MainState,
     otel_counters: &OtelCounters,
-    logger_running: &Arc<AtomicBool>, // Pass logger_running for trigger_shutdown
-) {
+) -> Result<(), MainLoopError> {
     while ctx.main_running.load(Ordering::SeqCst) {
-        match read_event_raw(ctx.stdin_fd) {
+        // With held releases, don't block on stdin past the oldest one's debounce
+        // window: if no press follows in time, the releases must be emitted.
+        let oldest_release_us = if ctx.cfg.collapse_press_release {
+            lock_filter(ctx).oldest_pending_release_us()
+        } else {
+            None
+        };
+        if let Some(release_us) = oldest_release_us {
+            let window_left =
+                release_window_left(release_us, ctx.cfg.debounce_time(), main_state.last_read);
+            match wait_for_stdin(ctx.stdin_fd, window_left) {
+                Ok(true) => {}
+                Ok(false) => {
+                    if let Err(e) =
+                        flush_pending_releases(ctx, main_state, otel_counters, None, false)
+                    {
+                        trigger_shutdown(&e.to_string(), ctx.main_running, ctx.logger_running);
+                        return Err(e);
+                    }
+                    continue;
+                }
+                // EINTR and friends are handled by the read below.
+                Err(_) => {}
+            }
+        }
+
+        // Wake up at least once per poll interval to re-check the running flag:
+        // the signal handlers restart interrupted reads, so a blocking read would
+        // only notice a shutdown signal with the next event. With a runtime limit,
+        // don't block past it either.
+        let mut wait = ctx.check_interval;
+        if let Some(deadline) = ctx.deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                main_state.max_runtime_reached = true;
+                flush_held_events(ctx, main_state, otel_counters);
+                trigger_shutdown(
+                    "Maximum runtime (--max-runtime) reached",
+                    ctx.main_running,
+                    ctx.logger_running,
+                );
+                break;
+            }
+            wait = wait.min(remaining);
+        }
+        match wait_for_stdin(ctx.stdin_fd, wait) {
+            Ok(true) => {}
+            Ok(false) => continue,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {
+                trace!("Wait for input interrupted (EINTR), checking running flag...");
+                continue;
+            }
+            // Anything else shows up again in the read below.
+            Err(_) => {}
+        }
+
+        let read = match read_event_raw(ctx.stdin_fd) {
+            Err(e) if ctx.cfg.tolerate_partial && e.kind() == ErrorKind::UnexpectedEof => {
+                main_state.partial_events_discarded += 1;
+                warn!(error = %e, "Discarding partial event at end of input (--tolerate-partial)");
+                Ok(None)
+            }
+            read => read,
+        };
+        match read {
             Ok(Some(ev)) => {
+                main_state.last_read = Some((event_microseconds(&ev), Instant::now()));
                 // Process the event, handle potential errors that require loop termination.
-                if let Err(e) = process_event(&ev, ctx, main_state, otel_counters) {
-                    trigger_shutdown(&e.to_string(), ctx.main_running, logger_running);
-                    break; // Exit loop on processing error
+                let result = if ctx.reload_requested.swap(false, Ordering::SeqCst) {
+                    reload_config(ctx, main_state, otel_counters)
+                } else {
+                    Ok(())
+                }
+                .and_then(|()| process_event(&ev, ctx, main_state, otel_counters));
+                if let Err(e) = result {
+                    trigger_shutdown(&e.to_string(), ctx.main_running, ctx.logger_running);
+                    return Err(e); // Exit loop on processing error
                 }
             }
             Ok(None) => {
-                // Clean EOF on stdin.
-                trigger_shutdown("EOF received on stdin", ctx.main_running, logger_running);
+                // Clean EOF on stdin. Emit any events still held back first.
+                flush_held_events(ctx, main_state, otel_counters);
+                trigger_shutdown(
+                    "EOF received on stdin",
+                    ctx.main_running,
+                    ctx.logger_running,
+                );
                 break; // Exit loop on EOF
             }
             Err(e) => {
@@ -424,10 +1605,11 @@ fn run_main_loop(
                 } else {
                     // Other read error.
                     let error = MainLoopError::StdinReadError(e); // `e` used in trigger_shutdown
-                    trigger_shutdown(&error.to_string(), ctx.main_running, logger_running);
-                    break; // Exit loop on read error
+                    trigger_shutdown(&error.to_string(), ctx.main_running, ctx.logger_running);
+                    return Err(error); // Exit loop on read error
                 }
             }
         }
     }
+    Ok(())
 }
@@ -0,0 +1,167 @@
+//! Per-event decisions around the [`BounceFilter`](crate::filter::BounceFilter)
+//! that the binary's main loop and [`async_loop`](crate::async_loop) share:
+//! `--strict-values` before the filter, and `--passthrough-types`,
+//! `--drop-orphan-scancodes` and `--synthesize-syn` after it.
+
+use crate::config::Config;
+use crate::event::{event_microseconds, input_event, is_key_event};
+use input_linux_sys::{EV_MSC, EV_SYN, MSC_SCAN, SYN_REPORT};
+
+/// Whether `--strict-values` keeps `ev` away from the filter: a key event with
+/// a value other than 0, 1 or 2 is corruption, not chatter, and would be
+/// counted as a repeat.
+pub fn rejects_value(cfg: &Config, ev: &input_event) -> bool {
+    cfg.strict_values && is_key_event(ev) && !(0..=2).contains(&ev.value)
+}
+
+/// Whether `ev` passes the filter without being debounced. Key allow/ignore
+/// lists refer to key codes only; they don't apply to other event types.
+pub fn skips_debounce(cfg: &Config, ev: &input_event) -> bool {
+    is_key_event(ev) && !cfg.should_debounce(ev.code)
+}
+
+/// What to write for one filter decision, in this order: `scancode`, `event`,
+/// then `syn`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Routed {
+    /// The MSC_SCAN held back by `--drop-orphan-scancodes`, released ahead of
+    /// the next passed event.
+    pub scancode: Option<input_event>,
+    /// The event itself, unless it was dropped, filtered by type or held back.
+    pub event: Option<input_event>,
+    /// A SYN_REPORT closing a passed key event's report (`--synthesize-syn`).
+    pub syn: Option<input_event>,
+    /// The event passed the filter but its type isn't in `--passthrough-types`.
+    pub type_filtered: bool,
+    /// The held MSC_SCAN was dropped along with its bounced key event.
+    pub scancode_dropped: bool,
+}
+
+/// Output state carried from one event to the next: the MSC_SCAN held back by
+/// `--drop-orphan-scancodes` until its key event is decided.
+#[derive(Debug, Default)]
+pub struct OutputRouter {
+    pending_scancode: Option<input_event>,
+}
+
+impl OutputRouter {
+    /// Decides what to write for `ev`, given whether the filter dropped it.
+    pub fn route(&mut self, cfg: &Config, ev: &input_event, is_bounce: bool) -> Routed {
+        let mut routed = Routed::default();
+        if is_bounce {
+            // The kernel stamps a key event and its scancode with the same time.
+            if is_key_event(ev)
+                && self
+                    .pending_scancode
+                    .is_some_and(|scan| event_microseconds(&scan) == event_microseconds(ev))
+            {
+                self.pending_scancode = None;
+                routed.scancode_dropped = true;
+            }
+            return routed;
+        }
+        if !cfg.passes_event_type(ev.type_) {
+            routed.type_filtered = true;
+            return routed;
+        }
+        routed.scancode = self.pending_scancode.take();
+        if cfg.drop_orphan_scancodes
+            && i32::from(ev.type_) == EV_MSC
+            && i32::from(ev.code) == MSC_SCAN
+        {
+            self.pending_scancode = Some(*ev);
+            return routed;
+        }
+        routed.event = Some(*ev);
+        if cfg.synthesize_syn && is_key_event(ev) {
+            routed.syn = Some(syn_report(ev));
+        }
+        routed
+    }
+
+    /// Takes the MSC_SCAN still held back, to write it once the input ends.
+    pub fn take_pending_scancode(&mut self) -> Option<input_event> {
+        self.pending_scancode.take()
+    }
+}
+
+/// A SYN_REPORT that isn't in the input, stamped with the time of the event it
+/// closes the report of.
+pub fn syn_report(ev: &input_event) -> input_event {
+    input_event {
+        time: ev.time,
+        type_: EV_SYN as u16,
+        code: SYN_REPORT as u16,
+        value: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{rejects_value, OutputRouter};
+    use crate::config::Config;
+    use crate::event::input_event;
+    use input_linux_sys::{timeval, EV_KEY, EV_MSC, EV_SYN, MSC_SCAN};
+    use std::time::Duration;
+
+    fn ev(ts_us: u64, type_: i32, code: u16, value: i32) -> input_event {
+        input_event {
+            time: timeval {
+                tv_sec: (ts_us / 1_000_000) as i64,
+                tv_usec: (ts_us % 1_000_000) as i64,
+            },
+            type_: type_ as u16,
+            code,
+            value,
+        }
+    }
+
+    fn config() -> Config {
+        Config::new(
+            Duration::from_millis(10),
+            Duration::from_millis(100),
+            Duration::ZERO,
+            false,
+            false,
+            false,
+            false,
+            "warn".to_string(),
+            None,
+            0,
+            Vec::new(),
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn strict_values_rejects_only_out_of_protocol_key_values() {
+        let mut cfg = config();
+        assert!(!rejects_value(&cfg, &ev(0, EV_KEY, 30, 7)));
+        cfg.strict_values = true;
+        assert!(rejects_value(&cfg, &ev(0, EV_KEY, 30, 7)));
+        assert!(!rejects_value(&cfg, &ev(0, EV_KEY, 30, 2)));
+        assert!(!rejects_value(&cfg, &ev(0, EV_MSC, MSC_SCAN as u16, 7)));
+    }
+
+    #[test]
+    fn held_scancode_goes_out_with_its_key_or_is_dropped_with_it() {
+        let mut cfg = config();
+        cfg.drop_orphan_scancodes = true;
+        cfg.synthesize_syn = true;
+        let mut router = OutputRouter::default();
+
+        let scan = ev(1_000, EV_MSC, MSC_SCAN as u16, 0x70004);
+        let routed = router.route(&cfg, &scan, false);
+        assert!(routed.scancode.is_none() && routed.event.is_none());
+        let key = ev(1_000, EV_KEY, 30, 1);
+        let routed = router.route(&cfg, &key, false);
+        assert_eq!(routed.scancode.map(|e| e.value), Some(0x70004));
+        assert_eq!(routed.event.map(|e| e.code), Some(30));
+        assert_eq!(routed.syn.map(|e| i32::from(e.type_)), Some(EV_SYN));
+
+        router.route(&cfg, &ev(3_000, EV_MSC, MSC_SCAN as u16, 0x70004), false);
+        let routed = router.route(&cfg, &ev(3_000, EV_KEY, 30, 1), true);
+        assert!(routed.scancode_dropped && routed.event.is_none());
+        assert!(router.take_pending_scancode().is_none());
+    }
+}
@@ -1,17 +1,103 @@
 //! OpenTelemetry and Tracing initialization logic.
+//!
+//! The OTLP exporter is only built with the `otel` feature (on by default).
+//! Without it, no Tokio runtime or gRPC stack is linked in and
+//! `--otel-endpoint` is rejected at startup.
 
-use crate::{config::Config, util};
+use crate::{config::Config, error::AppError, util};
 use opentelemetry::global as otel_global;
-use opentelemetry::metrics::{Meter, MeterProvider as _};
+use opentelemetry::metrics::Meter;
+#[cfg(feature = "otel")]
+use opentelemetry::metrics::MeterProvider as _;
+#[cfg(feature = "otel")]
 use opentelemetry_otlp::WithExportConfig;
+#[cfg(feature = "otel")]
 use opentelemetry_sdk::{metrics::SdkMeterProvider, runtime, trace as sdktrace, Resource};
-use tracing::{error, info};
+#[cfg(feature = "otel")]
+use std::net::{TcpStream, ToSocketAddrs};
+#[cfg(feature = "otel")]
+use std::sync::OnceLock;
+#[cfg(feature = "otel")]
+use std::time::Duration;
+use tracing::info;
+#[cfg(feature = "otel")]
+use tracing::warn;
 use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
 // --- OTLP Initialization ---
-fn init_otel(cfg: &Config) -> Option<(SdkMeterProvider, sdktrace::Tracer, Meter)> {
-    let otel_endpoint = cfg.otel_endpoint.as_ref()?;
+#[cfg(feature = "otel")]
+type OtelPipelines = (SdkMeterProvider, sdktrace::Tracer, Meter);
+
+/// The installed meter provider, kept so `shutdown_otel` can flush it.
+#[cfg(feature = "otel")]
+static METER_PROVIDER: OnceLock<SdkMeterProvider> = OnceLock::new();
+
+/// How long `--otel-required` waits for the collector to accept a connection.
+#[cfg(feature = "otel")]
+const OTEL_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Starts a Tokio runtime on its own thread for the exporters' gRPC channels,
+/// which must be created inside a runtime and keep background tasks on it for
+/// the rest of the run.
+#[cfg(feature = "otel")]
+fn spawn_otel_runtime() -> Result<tokio::runtime::Handle, AppError> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| AppError::OtelInit(format!("runtime: {e}")))?;
+    let handle = runtime.handle().clone();
+    std::thread::Builder::new()
+        .name("otel-runtime".to_string())
+        .spawn(move || runtime.block_on(std::future::pending::<()>()))
+        .map_err(|e| AppError::OtelInit(format!("runtime thread: {e}")))?;
+    Ok(handle)
+}
+
+/// Checks that something accepts TCP connections at the endpoint's host and
+/// port (4317, the OTLP/gRPC port, if none is given). The exporters connect
+/// lazily, so without this an unreachable collector only shows up as failed
+/// exports later on.
+#[cfg(feature = "otel")]
+fn probe_endpoint(endpoint: &str) -> Result<(), String> {
+    let authority = endpoint
+        .split_once("://")
+        .map_or(endpoint, |(_, rest)| rest)
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    let has_port = authority
+        .rsplit_once(':')
+        .is_some_and(|(_, port)| port.parse::<u16>().is_ok());
+    let target = if has_port {
+        authority.to_string()
+    } else {
+        format!("{authority}:4317")
+    };
+    let addrs = target
+        .to_socket_addrs()
+        .map_err(|e| format!("cannot resolve {target}: {e}"))?;
+    let mut last_error = format!("no address found for {target}");
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, OTEL_PROBE_TIMEOUT) {
+            Ok(_) => return Ok(()),
+            Err(e) => last_error = format!("cannot connect to {addr}: {e}"),
+        }
+    }
+    Err(last_error)
+}
+
+/// Builds the OTLP pipelines. `Ok(None)` means no endpoint was configured.
+#[cfg(feature = "otel")]
+fn init_otel(cfg: &Config) -> Result<Option<OtelPipelines>, AppError> {
+    let Some(otel_endpoint) = cfg.otel_endpoint.as_ref() else {
+        return Ok(None);
+    };
     info!(endpoint = %otel_endpoint, "Initializing OpenTelemetry exporter...");
+    if cfg.otel_required {
+        probe_endpoint(otel_endpoint).map_err(|e| AppError::OtelInit(format!("endpoint: {e}")))?;
+    }
+    let runtime = spawn_otel_runtime()?;
+    let _runtime_guard = runtime.enter();
 
     // --- Trace Pipeline ---
     let trace_exporter = opentelemetry_otlp::new_exporter()
@@ -21,36 +107,67 @@ fn init_otel(cfg: &Config) -> Option<(SdkMeterProvider, sdktrace::Tracer, Meter)
         opentelemetry::KeyValue::new("service.name", "intercept-bounce"),
         opentelemetry::KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
     ]));
-    let tracer = opentelemetry_otlp::new_pipeline()
+    let mut trace_pipeline = opentelemetry_otlp::new_pipeline()
         .tracing()
         .with_exporter(trace_exporter)
-        .with_trace_config(trace_config)
+        .with_trace_config(trace_config);
+    if let Some(interval) = cfg.otel_flush_interval {
+        trace_pipeline = trace_pipeline.with_batch_config(
+            sdktrace::BatchConfigBuilder::default()
+                .with_scheduled_delay(interval)
+                .build(),
+        );
+    }
+    let tracer = trace_pipeline
         .install_batch(runtime::TokioCurrentThread)
-        .map_err(|e| error!(error = %e, "Failed to initialize OTLP trace pipeline"))
-        .ok()?;
+        .map_err(|e| AppError::OtelInit(format!("trace pipeline: {e}")))?;
 
     // --- Metrics Pipeline ---
     let metrics_exporter = opentelemetry_otlp::new_exporter()
         .tonic()
         .with_endpoint(otel_endpoint);
-    let meter_provider = opentelemetry_otlp::new_pipeline()
+    let mut metrics_pipeline = opentelemetry_otlp::new_pipeline()
         .metrics(runtime::TokioCurrentThread)
-        .with_exporter(metrics_exporter)
+        .with_exporter(metrics_exporter);
+    if let Some(interval) = cfg.otel_flush_interval {
+        metrics_pipeline = metrics_pipeline.with_period(interval);
+    }
+    let meter_provider = metrics_pipeline
         .build()
-        .map_err(|e| error!(error = %e, "Failed to initialize OTLP metrics pipeline"))
-        .ok()?;
+        .map_err(|e| AppError::OtelInit(format!("metrics pipeline: {e}")))?;
 
     otel_global::set_meter_provider(meter_provider.clone());
+    let _ = METER_PROVIDER.set(meter_provider.clone());
     let meter = otel_global::meter_provider().meter("intercept-bounce");
     info!("OpenTelemetry exporter initialized successfully.");
-    Some((meter_provider, tracer, meter))
+    Ok(Some((meter_provider, tracer, meter)))
+}
+
+/// Exports whatever the OTLP pipelines still hold and shuts them down, so the
+/// final counters of a run aren't lost to the export interval. Does nothing
+/// without `--otel-endpoint`.
+pub fn shutdown_otel() {
+    #[cfg(feature = "otel")]
+    if let Some(meter_provider) = METER_PROVIDER.get() {
+        if let Err(e) = meter_provider.force_flush() {
+            warn!(error = %e, "Failed to flush OpenTelemetry metrics");
+        }
+        if let Err(e) = meter_provider.shutdown() {
+            warn!(error = %e, "Failed to shut down OpenTelemetry meter provider");
+        }
+    }
+    otel_global::shutdown_tracer_provider();
 }
 
 /// Initialize tracing subscriber (fmt layer + optional OTLP layer).
 /// Returns the OTLP Meter if OTLP is configured and initialized successfully.
-pub fn init_tracing(cfg: &Config) -> Option<Meter> {
+/// If the exporter fails to initialize, a warning is logged and the run goes on
+/// without telemetry, unless `--otel-required` makes that an error. The fmt
+/// layer is installed either way so the error can be logged.
+pub fn init_tracing(cfg: &Config) -> Result<Option<Meter>, AppError> {
     let fmt_layer = fmt::layer()
         .with_writer(std::io::stderr)
+        .with_ansi(cfg.color)
         .with_target(cfg.verbose)
         .with_level(true);
 
@@ -63,12 +180,31 @@ pub fn init_tracing(cfg: &Config) -> Option<Meter> {
     let registry_base = tracing_subscriber::registry().with(fmt_layer).with(filter);
 
     // Conditionally add OTLP layer and initialize the subscriber
-    let otel_meter = if let Some((_meter_provider, tracer, meter)) = init_otel(cfg) {
-        let otel_layer = tracing_opentelemetry::layer().with_tracer(tracer);
-        registry_base.with(otel_layer).init();
-        Some(meter) // OTLP initialized, return the meter
-    } else {
-        registry_base.init(); // Initialize without OTLP
+    #[cfg(feature = "otel")]
+    let otel_meter = match init_otel(cfg) {
+        Ok(Some((_meter_provider, tracer, meter))) => {
+            let otel_layer = tracing_opentelemetry::layer().with_tracer(tracer);
+            registry_base.with(otel_layer).init();
+            Some(meter) // OTLP initialized, return the meter
+        }
+        Ok(None) => {
+            registry_base.init(); // Initialize without OTLP
+            None
+        }
+        Err(e) if cfg.otel_required => {
+            registry_base.init();
+            return Err(e);
+        }
+        Err(e) => {
+            registry_base.init();
+            warn!(error = %e, "Continuing without OpenTelemetry export");
+            None
+        }
+    };
+    // `main` rejects `--otel-endpoint` in this build once logging is up.
+    #[cfg(not(feature = "otel"))]
+    let otel_meter: Option<Meter> = {
+        registry_base.init();
         None
     };
 
@@ -80,9 +216,9 @@ pub fn init_tracing(cfg: &Config) -> Option<Meter> {
         "intercept-bounce starting"
     );
 
-    info!(debounce = %util::format_duration(cfg.debounce_time()),
-        near_miss = %util::format_duration(cfg.near_miss_threshold()),
-        log_interval = %util::format_duration(cfg.log_interval()),
+    info!(debounce = %util::format_duration(cfg.debounce_time(), cfg.time_precision),
+        near_miss = %util::format_duration(cfg.near_miss_threshold(), cfg.time_precision),
+        log_interval = %util::format_duration(cfg.log_interval(), cfg.time_precision),
         log_all = cfg.log_all_events,
         log_bounces = cfg.log_bounces,
         stats_json = cfg.stats_json,
@@ -91,7 +227,8 @@ pub fn init_tracing(cfg: &Config) -> Option<Meter> {
         otel_endpoint = %cfg.otel_endpoint.as_deref().unwrap_or("<None>"),
         debounce_keys = ?cfg.debounce_keys(),
         ignored_keys = ?cfg.ignored_keys(),
+        drop_rule = %cfg.drop_rule.as_ref().map_or("<None>", |r| r.source()),
         "Configuration loaded");
 
-    otel_meter
+    Ok(otel_meter)
 }
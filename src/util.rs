@@ -3,19 +3,197 @@
 use std::time::Duration;
 
 /// Formats a duration in microseconds into a human-readable string (µs, ms, or s).
+///
+/// `precision` is the number of decimal places (`--time-precision`). With one or
+/// more places, values under a millisecond are printed in ms too (`0.300 ms`), so
+/// the places show sub-millisecond detail. Otherwise they are whole µs. `None`
+/// keeps the default of whole µs, one decimal for ms and three for s.
 #[inline]
-pub fn format_us(us: u64) -> String {
-    if us < 1000 {
+pub fn format_us(us: u64, precision: Option<usize>) -> String {
+    if us < 1000 && precision.unwrap_or(0) == 0 {
         format!("{us} µs")
     } else if us < 1_000_000 {
-        format!("{:.1} ms", us as f64 / 1000.0)
+        let p = precision.unwrap_or(1);
+        format!("{:.p$} ms", us as f64 / 1000.0)
     } else {
-        format!("{:.3} s", us as f64 / 1_000_000.0)
+        let p = precision.unwrap_or(3);
+        format!("{:.p$} s", us as f64 / 1_000_000.0)
     }
 }
 
-/// Formats a `std::time::Duration` into a human-readable string using `humantime`.
+/// Formats a duration in nanoseconds, switching to µs and ms for larger values.
+/// As in [`format_us`], a `precision` of one or more places prints values under
+/// a microsecond in µs.
+pub fn format_ns(ns: u64, precision: Option<usize>) -> String {
+    if ns < 1000 && precision.unwrap_or(0) == 0 {
+        format!("{ns} ns")
+    } else if ns < 1_000_000 {
+        let p = precision.unwrap_or(1);
+        format!("{:.p$} µs", ns as f64 / 1000.0)
+    } else {
+        format_us(ns / 1000, precision)
+    }
+}
+
+/// Small deterministic PRNG (SplitMix64) for seeded shuffles (`--anonymize`).
+/// Not suitable for anything security related.
+#[derive(Debug, Clone)]
+pub struct SampleRng(u64);
+
+impl SampleRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in `0..bound` (`bound` must be non-zero).
+    pub fn below(&mut self, bound: u64) -> u64 {
+        ((u128::from(self.next_u64()) * u128::from(bound)) >> 64) as u64
+    }
+}
+
+/// Starting value (offset basis) of an FNV-1a checksum, i.e. the checksum of no data.
+pub const FNV1A_OFFSET: u64 = 0xCBF2_9CE4_8422_2325;
+
+/// Folds `bytes` into a running 64-bit FNV-1a checksum (`--export-trailer`).
+/// Catches truncated or altered files; not suitable for anything security related.
+pub fn fnv1a64(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &byte in bytes {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01B3);
+    }
+    hash
+}
+
+/// Seed from the kernel's random source, falling back to the wall clock, for
+/// values that shouldn't be guessable from the start time (`--anonymize`).
+pub fn random_seed() -> u64 {
+    let mut seed = [0u8; 8];
+    let read = unsafe { libc::getrandom(seed.as_mut_ptr().cast(), seed.len(), 0) };
+    if read == seed.len() as isize {
+        u64::from_ne_bytes(seed)
+    } else {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0)
+    }
+}
+
+/// Duplicates an already open file descriptor handed over by the caller
+/// (`--stats-stream-fd`, `--tap-fd`), so it can be written and closed as an
+/// owned `File` without touching the original.
+pub fn dup_fd(fd: std::os::fd::RawFd) -> std::io::Result<std::fs::File> {
+    if fd < 0 {
+        return Err(std::io::Error::from_raw_os_error(libc::EBADF));
+    }
+    // Safety: the descriptor is only borrowed for the duration of the dup.
+    let owned = unsafe { std::os::fd::BorrowedFd::borrow_raw(fd) }.try_clone_to_owned()?;
+    Ok(std::fs::File::from(owned))
+}
+
+/// Width in columns of the terminal on `fd`, or `None` if it isn't a terminal.
+pub fn terminal_columns(fd: std::os::fd::RawFd) -> Option<usize> {
+    let mut size = std::mem::MaybeUninit::<libc::winsize>::uninit();
+    // Safety: TIOCGWINSZ only writes a winsize struct through the pointer.
+    let res = unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, size.as_mut_ptr()) };
+    if res != 0 {
+        return None;
+    }
+    let size = unsafe { size.assume_init() };
+    (size.ws_col > 0).then_some(usize::from(size.ws_col))
+}
+
+/// ANSI colours used in human-readable statistics (`--color`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Yellow,
+    Green,
+    Cyan,
+}
+
+/// Wraps `text` in the ANSI escape sequence for `color` if `enabled`, otherwise
+/// returns it unchanged.
+pub fn paint(text: &str, color: Color, enabled: bool) -> String {
+    if !enabled {
+        return text.to_string();
+    }
+    let code = match color {
+        Color::Red => 31,
+        Color::Green => 32,
+        Color::Yellow => 33,
+        Color::Cyan => 36,
+    };
+    format!("\x1b[{code}m{text}\x1b[0m")
+}
+
+/// Formats a `std::time::Duration` into a human-readable string using `humantime`,
+/// or via [`format_us`] when an explicit `precision` is requested.
 #[inline]
-pub fn format_duration(duration: Duration) -> String {
-    humantime::format_duration(duration).to_string()
+pub fn format_duration(duration: Duration, precision: Option<usize>) -> String {
+    match precision {
+        Some(_) => format_us(
+            duration.as_micros().try_into().unwrap_or(u64::MAX),
+            precision,
+        ),
+        None => humantime::format_duration(duration).to_string(),
+    }
+}
+
+/// Current wall-clock time in microseconds since the epoch, the clock evdev
+/// stamps input events with by default.
+pub fn realtime_us() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| u64::try_from(d.as_micros()).unwrap_or(u64::MAX))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fnv1a64, format_duration, format_ns, format_us, FNV1A_OFFSET};
+    use std::time::Duration;
+
+    #[test]
+    fn fnv1a64_matches_reference_values() {
+        assert_eq!(fnv1a64(FNV1A_OFFSET, b""), FNV1A_OFFSET);
+        assert_eq!(fnv1a64(FNV1A_OFFSET, b"a"), 0xAF63_DC4C_8601_EC8C);
+        // Checksums can be built up piecewise.
+        assert_eq!(
+            fnv1a64(fnv1a64(FNV1A_OFFSET, b"foo"), b"bar"),
+            fnv1a64(FNV1A_OFFSET, b"foobar")
+        );
+    }
+
+    #[test]
+    fn default_precision_keeps_existing_format() {
+        assert_eq!(format_us(300, None), "300 µs");
+        assert_eq!(format_us(1_250, None), "1.2 ms");
+        assert_eq!(format_us(2_500_000, None), "2.500 s");
+        assert_eq!(format_duration(Duration::from_millis(15), None), "15ms");
+    }
+
+    #[test]
+    fn explicit_precision_controls_decimal_places() {
+        assert_eq!(format_us(300, Some(0)), "300 µs");
+        assert_eq!(format_us(300, Some(3)), "0.300 ms");
+        assert_eq!(format_us(42, Some(2)), "0.04 ms");
+        assert_eq!(format_us(1_375, Some(3)), "1.375 ms");
+        assert_eq!(format_us(1_375, Some(0)), "1 ms");
+        assert_eq!(format_us(2_500_000, Some(1)), "2.5 s");
+        assert_eq!(
+            format_duration(Duration::from_micros(300), Some(1)),
+            "0.3 ms"
+        );
+        assert_eq!(format_ns(300, Some(0)), "300 ns");
+        assert_eq!(format_ns(300, Some(3)), "0.300 µs");
+    }
 }
@@ -1,6 +1,8 @@
 //! Common helper functions for tests and benchmarks.
-use input_linux_sys::{input_event, timeval, EV_KEY, EV_SYN};
+use input_linux_sys::{input_event, timeval, EV_KEY, EV_REL, EV_SYN};
 use intercept_bounce::{config::Config, logger::EventInfo};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -26,6 +28,19 @@ pub fn key_ev(ts_us: u64, code: u16, value: i32) -> input_event {
     }
 }
 
+/// Creates an EV_REL input_event (e.g. REL_WHEEL) with a specific microsecond timestamp.
+pub fn rel_ev(ts_us: u64, code: u16, value: i32) -> input_event {
+    input_event {
+        time: timeval {
+            tv_sec: (ts_us / 1_000_000) as i64,
+            tv_usec: (ts_us % 1_000_000) as i64,
+        },
+        type_: EV_REL as u16,
+        code,
+        value,
+    }
+}
+
 /// Creates a non-key input_event (e.g., EV_SYN) with a specific microsecond timestamp.
 pub fn non_key_ev(ts_us: u64) -> input_event {
     input_event {
@@ -53,6 +68,8 @@ pub fn passed_event_info(
         is_bounce: false,
         diff_us: None,
         last_passed_us,
+        paused: false,
+        filter_latency_ns: None,
     }
 }
 
@@ -69,6 +86,8 @@ pub fn bounced_event_info(
         is_bounce: true,
         diff_us: Some(diff_us),
         last_passed_us,
+        paused: false,
+        filter_latency_ns: None,
     }
 }
 
@@ -117,3 +136,53 @@ pub fn dummy_config_no_arc(debounce_time: Duration, near_miss_threshold: Duratio
         Vec::new(),
     )
 }
+
+// --- Temporary File Helper ---
+
+/// A path in the system temp directory, unique to the test process, that is
+/// removed (file or directory) when dropped.
+pub struct TempPath(PathBuf);
+
+/// Returns a fresh [`TempPath`] named `intercept-bounce-<pid>-<name>`, with any
+/// leftover from an earlier run removed.
+pub fn temp_path(name: &str) -> TempPath {
+    let path = std::env::temp_dir().join(format!("intercept-bounce-{}-{name}", std::process::id()));
+    let path = TempPath(path);
+    path.remove();
+    path
+}
+
+impl TempPath {
+    fn remove(&self) {
+        let _ = match self.0.symlink_metadata() {
+            Ok(meta) if meta.is_dir() => std::fs::remove_dir_all(&self.0),
+            _ => std::fs::remove_file(&self.0),
+        };
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
+impl Deref for TempPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<std::ffi::OsStr> for TempPath {
+    fn as_ref(&self) -> &std::ffi::OsStr {
+        self.0.as_os_str()
+    }
+}
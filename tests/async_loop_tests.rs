@@ -0,0 +1,125 @@
+#![cfg(feature = "tokio")]
+
+use assert_cmd::Command;
+use input_linux_sys::{input_event, EV_MSC};
+use intercept_bounce::async_loop::run_main_loop_async;
+use intercept_bounce::cli::try_parse_args;
+use intercept_bounce::config::Config;
+use intercept_bounce::event::event_as_bytes;
+use intercept_bounce::filter::BounceFilter;
+use std::io::Write;
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixStream;
+
+use test_helpers::*;
+
+fn events_to_bytes(events: &[input_event]) -> Vec<u8> {
+    events
+        .iter()
+        .flat_map(|ev| event_as_bytes(ev).to_vec())
+        .collect()
+}
+
+/// The MSC_SCAN the kernel sends just before a key event, with the same time.
+fn scan_ev(ts_us: u64, scancode: i32) -> input_event {
+    let mut ev = non_key_ev(ts_us);
+    ev.type_ = EV_MSC as u16;
+    ev.code = 4; // MSC_SCAN
+    ev.value = scancode;
+    ev
+}
+
+/// Runs `input` through the binary and through `run_main_loop_async`, both
+/// configured by `args`, and returns their outputs.
+fn run_both_loops(args: &[&str], input: &[input_event]) -> (Vec<u8>, Vec<u8>) {
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(args)
+        .env("RUST_LOG", "warn")
+        .write_stdin(events_to_bytes(input))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{output:?}");
+
+    let argv = std::iter::once("intercept-bounce").chain(args.iter().copied());
+    let cfg = Config::from(&try_parse_args(argv).unwrap());
+    let (reader, mut writer) = UnixStream::pair().unwrap();
+    writer.write_all(&events_to_bytes(input)).unwrap();
+    drop(writer);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()
+        .unwrap();
+    let mut filter = BounceFilter::new(0);
+    let mut async_output = Vec::new();
+    runtime
+        .block_on(run_main_loop_async(
+            reader,
+            &cfg,
+            &mut filter,
+            &mut async_output,
+        ))
+        .unwrap();
+
+    (output.stdout, async_output)
+}
+
+#[test]
+fn async_loop_writes_the_same_output_as_the_binary() {
+    let input = [
+        scan_ev(0, 0x1e),
+        key_ev(0, KEY_A, 1),
+        non_key_ev(0),
+        scan_ev(3_000, 0x1e),
+        key_ev(3_000, KEY_A, 1), // Bounce, and so is its scancode
+        non_key_ev(3_000),
+        non_key_ev(3_000),        // Exact duplicate
+        key_ev(20_000, KEY_A, 7), // Invalid value
+        non_key_ev(20_000),
+        scan_ev(50_000, 0x1e),
+        key_ev(50_000, KEY_A, 0),
+        non_key_ev(50_000),
+        scan_ev(60_000, 0x30), // Scancode held at EOF
+    ];
+
+    for args in [
+        &["--debounce-time", "10ms"][..],
+        &["--debounce-time", "10ms", "--strict-values"],
+        &["--debounce-time", "10ms", "--drop-orphan-scancodes"],
+        &["--debounce-time", "10ms", "--drop-exact-duplicates"],
+        &[
+            "--debounce-time",
+            "10ms",
+            "--key-events-only",
+            "--synthesize-syn",
+        ],
+    ] {
+        let (sync_output, async_output) = run_both_loops(args, &input);
+        assert!(!sync_output.is_empty(), "{args:?}");
+        assert_eq!(async_output, sync_output, "{args:?}");
+    }
+}
+
+#[test]
+fn async_reader_restores_the_input_flags() {
+    let (reader, writer) = UnixStream::pair().unwrap();
+    // A duplicate shares the open file, and with it the non-blocking mode.
+    let shared = reader.try_clone().unwrap();
+    drop(writer);
+    let flags = || unsafe { libc::fcntl(shared.as_raw_fd(), libc::F_GETFL) };
+    let before = flags();
+    assert_eq!(before & libc::O_NONBLOCK, 0);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_io()
+        .build()
+        .unwrap();
+    let cfg = Config::from(&try_parse_args(["intercept-bounce"]).unwrap());
+    let mut filter = BounceFilter::new(0);
+    let mut output = Vec::new();
+    runtime
+        .block_on(run_main_loop_async(reader, &cfg, &mut filter, &mut output))
+        .unwrap();
+
+    assert_eq!(flags(), before);
+}
@@ -1,7 +1,8 @@
 //! Unit tests for the BounceFilter logic.
 
-use input_linux_sys::input_event;
-use intercept_bounce::filter::BounceFilter;
+use input_linux_sys::{input_event, REL_HWHEEL, REL_WHEEL};
+use intercept_bounce::cli::DebounceState;
+use intercept_bounce::filter::{sweep, BounceFilter};
 use intercept_bounce::logger::EventInfo;
 use std::time::Duration;
 
@@ -276,6 +277,41 @@ fn window_zero_passes_all_key_events() {
     assert_eq!(results[3].last_passed_us, Some(2));
 }
 
+#[test]
+fn window_zero_updates_state_like_general_path() {
+    let mut filter = BounceFilter::new(0);
+    let e1 = key_ev(10, KEY_A, 1);
+    let e2 = key_ev(5, KEY_A, 1); // Time went backwards: still passes
+    let e3 = key_ev(5, KEY_B, 1);
+    let results = check_sequence(&mut filter, &[e1, e2, e3], Duration::ZERO);
+    assert!(results
+        .iter()
+        .all(|info| !info.is_bounce && info.diff_us.is_none()));
+    assert_eq!(results[1].last_passed_us, Some(10));
+    assert_eq!(results[2].last_passed_us, None);
+    assert_eq!(filter.last_passed(KEY_A, 1), Some(5));
+    assert_eq!(filter.last_passed(KEY_B, 1), Some(5));
+
+    // State recorded with a zero window is used once a window is set again.
+    let e4 = key_ev(2_000, KEY_A, 1);
+    let info = filter.check_event(&e4, DEBOUNCE_TIME, false);
+    assert!(info.is_bounce);
+    assert_eq!(info.diff_us, Some(1_995));
+}
+
+#[test]
+fn window_zero_still_applies_drop_rule() {
+    use intercept_bounce::filter::rule::DropRule;
+
+    let rule = DropRule::parse("diff_us < 1000").unwrap();
+    let mut filter = BounceFilter::new(0).with_drop_rule(Some(rule));
+    let e1 = key_ev(0, KEY_A, 1);
+    let e2 = key_ev(500, KEY_A, 1);
+    let results = check_sequence(&mut filter, &[e1, e2], Duration::ZERO);
+    assert!(!results[0].is_bounce);
+    assert!(results[1].is_bounce);
+}
+
 #[test]
 fn ignores_configured_keys() {
     let mut filter = BounceFilter::new(0);
@@ -315,3 +351,575 @@ fn initial_state_empty() {
     // Ensure runtime is None initially.
     assert_eq!(filter.get_runtime_us(), None);
 }
+
+// --- Press/Release Collapse Tests ---
+
+#[test]
+fn collapse_release_then_quick_press() {
+    use intercept_bounce::filter::Collapse;
+
+    let mut filter = BounceFilter::new(0);
+    let release = key_ev(100_000, KEY_A, 0);
+    let press = key_ev(103_000, KEY_A, 1); // 3ms after the release
+
+    assert_eq!(
+        filter.collapse_press_release(&release, DEBOUNCE_TIME),
+        Collapse::Held
+    );
+    assert!(filter.has_pending_releases());
+    match filter.collapse_press_release(&press, DEBOUNCE_TIME) {
+        Collapse::Collapsed { release: r, gap_us } => {
+            assert_eq!(r.code, KEY_A);
+            assert_eq!(r.value, 0);
+            assert_eq!(gap_us, 3_000);
+        }
+        other => panic!("Expected release/press pair to collapse, got {other:?}"),
+    }
+    assert!(!filter.has_pending_releases());
+}
+
+#[test]
+fn collapse_keeps_release_followed_by_slow_press() {
+    use intercept_bounce::filter::Collapse;
+
+    let mut filter = BounceFilter::new(0);
+    let release = key_ev(100_000, KEY_A, 0);
+    let press = key_ev(100_000 + DEBOUNCE_TIME.as_micros() as u64, KEY_A, 1);
+
+    assert_eq!(
+        filter.collapse_press_release(&release, DEBOUNCE_TIME),
+        Collapse::Held
+    );
+    // Window expired by the time the press arrives: the release is emitted.
+    let expired = filter.take_expired_releases(
+        Some(100_000 + DEBOUNCE_TIME.as_micros() as u64),
+        DEBOUNCE_TIME,
+    );
+    assert_eq!(expired.len(), 1);
+    assert_eq!(expired[0].code, KEY_A);
+    assert_eq!(
+        filter.collapse_press_release(&press, DEBOUNCE_TIME),
+        Collapse::Check
+    );
+}
+
+#[test]
+fn collapse_only_pairs_same_key() {
+    use intercept_bounce::filter::Collapse;
+
+    let mut filter = BounceFilter::new(0);
+    let release_a = key_ev(100_000, KEY_A, 0);
+    let press_b = key_ev(101_000, KEY_B, 1);
+
+    assert_eq!(
+        filter.collapse_press_release(&release_a, DEBOUNCE_TIME),
+        Collapse::Held
+    );
+    assert_eq!(
+        filter.collapse_press_release(&press_b, DEBOUNCE_TIME),
+        Collapse::Check
+    );
+    // Not expired yet relative to KEY_B's press.
+    assert!(filter
+        .take_expired_releases(Some(101_000), DEBOUNCE_TIME)
+        .is_empty());
+    // Flushing everything returns the held KEY_A release.
+    let flushed = filter.take_expired_releases(None, DEBOUNCE_TIME);
+    assert_eq!(flushed.len(), 1);
+    assert_eq!(flushed[0].code, KEY_A);
+}
+
+// --- Drop Rule Tests ---
+
+#[test]
+fn drop_rule_requires_repeated_bounces() {
+    use intercept_bounce::filter::rule::DropRule;
+
+    let rule = DropRule::parse("diff_us < debounce_us && prev_bounces >= 2").unwrap();
+    let mut filter = BounceFilter::new(0).with_drop_rule(Some(rule));
+    let events = [
+        key_ev(0, KEY_A, 1),       // First press: passes (no history)
+        key_ev(1_000, KEY_A, 1),   // In window, prev_bounces=0: passes
+        key_ev(2_000, KEY_A, 1),   // In window, prev_bounces=1: passes
+        key_ev(3_000, KEY_A, 1),   // In window, prev_bounces=2: dropped
+        key_ev(4_000, KEY_A, 1),   // In window, prev_bounces=3: dropped
+        key_ev(100_000, KEY_A, 1), // Outside window: passes and resets the streak
+        key_ev(101_000, KEY_A, 1), // In window, prev_bounces=0: passes
+    ];
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    let dropped: Vec<bool> = results.iter().map(|r| r.is_bounce).collect();
+    assert_eq!(dropped, [false, false, false, true, true, false, false]);
+}
+
+#[test]
+fn drop_rule_can_target_single_key_and_state() {
+    use intercept_bounce::filter::rule::DropRule;
+
+    // Only debounce releases of KEY_B, with a wider 20ms window.
+    let rule = DropRule::parse("key_code == 48 && value == 0 && diff_us < 20000").unwrap();
+    let mut filter = BounceFilter::new(0).with_drop_rule(Some(rule));
+    let events = [
+        key_ev(0, KEY_A, 0),
+        key_ev(5_000, KEY_A, 0), // KEY_A bounce, but the rule ignores KEY_A
+        key_ev(10_000, KEY_B, 0),
+        key_ev(25_000, KEY_B, 0), // 15ms later: dropped by the rule
+        key_ev(30_000, KEY_B, 1),
+        key_ev(31_000, KEY_B, 1), // Press bounce, but the rule only drops releases
+    ];
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    let dropped: Vec<bool> = results.iter().map(|r| r.is_bounce).collect();
+    assert_eq!(dropped, [false, false, false, true, false, false]);
+    assert_eq!(results[3].diff_us, Some(15_000));
+}
+
+// --- EV_REL Debounce Tests ---
+
+#[test]
+fn rel_events_pass_unless_enabled() {
+    let mut filter = BounceFilter::new(0);
+    let burst: Vec<_> = (0..5)
+        .map(|i| rel_ev(i * 1_000, REL_WHEEL as u16, 1))
+        .collect();
+    let results = check_sequence(&mut filter, &burst, DEBOUNCE_TIME);
+    assert!(results.iter().all(|info| !info.is_bounce));
+}
+
+#[test]
+fn rel_wheel_burst_is_thinned() {
+    let mut filter = BounceFilter::new(0).with_rel_debounce(true);
+    // Ticks every 3ms: with a 10ms window only every 4th one passes.
+    let burst: Vec<_> = (0..9)
+        .map(|i| rel_ev(i * 3_000, REL_WHEEL as u16, -1))
+        .collect();
+    let results = check_sequence(&mut filter, &burst, DEBOUNCE_TIME);
+    let passed: Vec<u64> = results
+        .iter()
+        .filter(|info| !info.is_bounce)
+        .map(|info| info.event_us)
+        .collect();
+    assert_eq!(passed, vec![0, 12_000, 24_000]);
+    assert_eq!(results[1].diff_us, Some(3_000));
+    assert_eq!(results[1].last_passed_us, Some(0));
+}
+
+#[test]
+fn rel_directions_and_axes_are_independent() {
+    let mut filter = BounceFilter::new(0).with_rel_debounce(true);
+    let e1 = rel_ev(0, REL_WHEEL as u16, 1);
+    let e2 = rel_ev(2_000, REL_WHEEL as u16, -1); // Opposite direction
+    let e3 = rel_ev(3_000, REL_HWHEEL as u16, 1); // Other axis
+    let e4 = rel_ev(4_000, REL_WHEEL as u16, 2); // Same direction: dropped
+    let e5 = rel_ev(5_000, REL_WHEEL as u16, 0); // No movement
+    let results = check_sequence(&mut filter, &[e1, e2, e3, e4, e5], DEBOUNCE_TIME);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![false, false, false, true, false]);
+}
+
+// --- Count Mode Tests ---
+
+#[test]
+fn count_mode_passes_first_n_within_window() {
+    let mut filter = BounceFilter::new(0).with_count_threshold(Some(2));
+    // Presses every 2ms, all within the 10ms window opened by the first one.
+    let presses: Vec<_> = (0..4).map(|i| key_ev(i * 2_000, KEY_A, 1)).collect();
+    let results = check_sequence(&mut filter, &presses, DEBOUNCE_TIME);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![false, false, true, true]);
+    assert_eq!(results[1].last_passed_us, Some(0));
+    // Drops report the time since the last passed event.
+    assert_eq!(results[2].diff_us, Some(2_000));
+    assert_eq!(results[3].last_passed_us, Some(2_000));
+}
+
+#[test]
+fn count_mode_window_resets_after_debounce_time() {
+    let mut filter = BounceFilter::new(0).with_count_threshold(Some(1));
+    let e1 = key_ev(0, KEY_A, 1); // Opens a window
+    let e2 = key_ev(5_000, KEY_A, 1); // Second in window: dropped
+    let e3 = key_ev(10_000, KEY_A, 1); // Window expired: new window, passes
+    let e4 = key_ev(12_000, KEY_A, 1); // Dropped
+    let e5 = key_ev(12_500, KEY_A, 0); // Release has its own count
+    let e6 = key_ev(13_000, KEY_B, 1); // Other key has its own count
+    let results = check_sequence(&mut filter, &[e1, e2, e3, e4, e5, e6], DEBOUNCE_TIME);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![false, true, false, true, false, false]);
+}
+
+#[test]
+fn count_mode_can_be_switched_off() {
+    let mut filter = BounceFilter::new(0).with_count_threshold(Some(3));
+    let e1 = key_ev(0, KEY_A, 1);
+    let e2 = key_ev(2_000, KEY_A, 1);
+    let results = check_sequence(&mut filter, &[e1, e2], DEBOUNCE_TIME);
+    assert!(!results[1].is_bounce);
+
+    filter.set_count_threshold(None);
+    let e3 = key_ev(4_000, KEY_A, 1); // 2ms after the last pass: a plain bounce again
+    assert!(filter.check_event(&e3, DEBOUNCE_TIME, false).is_bounce);
+}
+
+#[test]
+fn lead_in_passes_first_n_of_the_configured_key_only() {
+    let mut filter = BounceFilter::new(0).with_lead_in(&[(KEY_A, 2)]);
+    let events = [
+        key_ev(0, KEY_A, 1),
+        key_ev(2_000, KEY_A, 1),  // Second in the window: passes
+        key_ev(4_000, KEY_A, 1),  // Third in the window: dropped
+        key_ev(20_000, KEY_A, 1), // Window expired: passes
+        key_ev(30_000, KEY_B, 1),
+        key_ev(32_000, KEY_B, 1), // No lead-in: plain time-based bounce
+    ];
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![false, false, true, false, false, true]);
+    assert_eq!(results[2].diff_us, Some(2_000));
+
+    // The lead-in overrides a global count threshold for its key.
+    let mut filter = BounceFilter::new(0)
+        .with_count_threshold(Some(1))
+        .with_lead_in(&[(KEY_A, 2)]);
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![false, false, true, false, false, true]);
+
+    filter.set_lead_in(&[]);
+    let later = key_ev(21_000, KEY_A, 1); // Second in the count window opened at 20ms
+    assert!(filter.check_event(&later, DEBOUNCE_TIME, false).is_bounce);
+}
+
+#[test]
+fn burst_mode_collapses_a_long_ragged_burst_to_one_event() {
+    // Chatter every 4-9ms for 60ms: far longer than one 10ms window.
+    let offsets = [
+        0, 4_000, 13_000, 18_000, 26_000, 31_000, 40_000, 47_000, 55_000, 60_000,
+    ];
+    let burst: Vec<_> = offsets.iter().map(|&t| key_ev(t, KEY_A, 1)).collect();
+
+    let mut filter = BounceFilter::new(0).with_burst_mode(true);
+    let results = check_sequence(&mut filter, &burst, DEBOUNCE_TIME);
+    let passed: Vec<u64> = results
+        .iter()
+        .filter(|info| !info.is_bounce)
+        .map(|info| info.event_us)
+        .collect();
+    assert_eq!(passed, vec![0]);
+    // Drops report the time since the burst's passed event.
+    assert_eq!(results[9].diff_us, Some(60_000));
+
+    // The time window restarts at each passed event and lets parts of it through.
+    let mut time_filter = BounceFilter::new(0);
+    let results = check_sequence(&mut time_filter, &burst, DEBOUNCE_TIME);
+    assert!(results.iter().filter(|info| !info.is_bounce).count() > 1);
+
+    // After a quiet gap of the debounce time, the next burst passes its first event.
+    let next = [key_ev(70_000, KEY_A, 1), key_ev(72_000, KEY_A, 1)];
+    let results = check_sequence(&mut filter, &next, DEBOUNCE_TIME);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![false, true]);
+}
+
+#[test]
+fn burst_gap_overrides_the_debounce_time() {
+    let mut filter = BounceFilter::new(0)
+        .with_burst_mode(true)
+        .with_burst_gap(Some(Duration::from_millis(30)));
+    let events = [
+        key_ev(0, KEY_A, 1),
+        key_ev(20_000, KEY_A, 1), // 20ms of silence: still the same burst
+        key_ev(40_000, KEY_A, 0), // Other value: its own burst
+        key_ev(55_000, KEY_A, 1), // 35ms after the last press: a new burst
+    ];
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![false, true, false, false]);
+}
+
+#[test]
+fn debounce_states_press_passes_every_release() {
+    let mut filter = BounceFilter::new(0).with_debounce_states(&[DebounceState::Press]);
+    let events = [
+        key_ev(0, KEY_A, 1),
+        key_ev(2_000, KEY_A, 1), // Press bounce: dropped
+        key_ev(30_000, KEY_A, 0),
+        key_ev(31_000, KEY_A, 0), // Release 1ms later: passes unfiltered
+        key_ev(32_000, KEY_A, 0),
+    ];
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![false, true, false, false, false]);
+    assert_eq!(results[1].diff_us, Some(2_000));
+
+    // And the other way round: only releases are debounced.
+    filter.set_debounce_states(&[DebounceState::Release]);
+    let events = [
+        key_ev(100_000, KEY_B, 1),
+        key_ev(101_000, KEY_B, 1), // Press 1ms later: passes unfiltered
+        key_ev(150_000, KEY_B, 0),
+        key_ev(152_000, KEY_B, 0), // Release bounce: dropped
+    ];
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![false, false, false, true]);
+}
+
+#[test]
+fn apply_config_replaces_filter_options() {
+    let mut cfg = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+    cfg.debounce_states = vec![DebounceState::Press];
+    let mut filter = BounceFilter::new(0);
+    filter.apply_config(&cfg);
+    let events = [
+        key_ev(0, KEY_A, 1),
+        key_ev(30_000, KEY_A, 0),
+        key_ev(31_000, KEY_A, 0), // Release 1ms later: passes unfiltered
+    ];
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![false, false, false]);
+
+    // Back to the defaults, as on a reload.
+    cfg.debounce_states = vec![DebounceState::Press, DebounceState::Release];
+    filter.apply_config(&cfg);
+    let events = [key_ev(60_000, KEY_A, 0), key_ev(62_000, KEY_A, 0)];
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![false, true]);
+}
+
+// --- State Query Tests ---
+
+#[test]
+fn last_passed_reports_stored_timestamp() {
+    let mut filter = BounceFilter::new(0);
+    assert_eq!(filter.last_passed(KEY_A, 1), None);
+
+    let e1 = key_ev(1_000, KEY_A, 1); // Pass
+    let e2 = key_ev(3_000, KEY_A, 1); // Bounce: doesn't update the slot
+    let e3 = key_ev(20_000, KEY_A, 0); // Pass (release)
+    check_sequence(&mut filter, &[e1, e2, e3], DEBOUNCE_TIME);
+
+    assert_eq!(filter.last_passed(KEY_A, 1), Some(1_000));
+    assert_eq!(filter.last_passed(KEY_A, 0), Some(20_000));
+    assert_eq!(filter.last_passed(KEY_B, 1), None);
+    // Out of range
+    assert_eq!(filter.last_passed(u16::MAX, 1), None);
+    assert_eq!(filter.last_passed(KEY_A, -1), None);
+    assert_eq!(filter.last_passed(KEY_A, 3), None);
+}
+
+#[test]
+fn reset_key_lets_next_event_pass() {
+    let mut filter = BounceFilter::new(0);
+    let e1 = key_ev(0, KEY_A, 1);
+    let e2 = key_ev(20_000, KEY_A, 0);
+    check_sequence(&mut filter, &[e1, e2], DEBOUNCE_TIME);
+
+    filter.reset_key(KEY_A, 1);
+    assert_eq!(filter.last_passed(KEY_A, 1), None);
+    // Other slots are untouched.
+    assert_eq!(filter.last_passed(KEY_A, 0), Some(20_000));
+
+    // Would be a bounce (2ms after e1) without the reset.
+    let e3 = key_ev(2_000, KEY_A, 1);
+    let info = filter.check_event(&e3, DEBOUNCE_TIME, false);
+    assert!(!info.is_bounce);
+    assert_eq!(info.last_passed_us, None);
+    assert_eq!(filter.last_passed(KEY_A, 1), Some(2_000));
+
+    // Out-of-range slots are ignored.
+    filter.reset_key(u16::MAX, 1);
+    filter.reset_key(KEY_A, -1);
+
+    // The key's bounce streak, which drop rules see as `prev_bounces`, goes too.
+    use intercept_bounce::filter::rule::DropRule;
+    let rule = DropRule::parse("diff_us < debounce_us && prev_bounces >= 2").unwrap();
+    let mut filter = BounceFilter::new(0).with_drop_rule(Some(rule));
+    let events = [
+        key_ev(0, KEY_A, 0),     // Passes (no history)
+        key_ev(500, KEY_A, 1),   // Passes (no history)
+        key_ev(1_000, KEY_A, 1), // prev_bounces=0: passes
+        key_ev(2_000, KEY_A, 1), // prev_bounces=1: passes
+        key_ev(3_000, KEY_A, 1), // prev_bounces=2: dropped
+    ];
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    assert!(results[4].is_bounce);
+
+    filter.reset_key(KEY_A, 1);
+    // In the release's window, but with the streak gone it passes.
+    let release = key_ev(4_000, KEY_A, 0);
+    assert!(!filter.check_event(&release, DEBOUNCE_TIME, false).is_bounce);
+}
+
+// --- Exact Duplicate Tests ---
+
+#[test]
+fn exact_duplicates_are_dropped_even_without_debounce() {
+    let mut filter = BounceFilter::new(0).with_exact_duplicate_drop(true);
+    let press = key_ev(1_000, KEY_A, 1);
+    let syn = non_key_ev(1_000);
+    let same_time_other_key = key_ev(1_000, KEY_B, 1);
+    let events = [
+        press,
+        press, // Exact duplicate
+        press, // Still identical to the (dropped) event before it
+        syn,
+        syn, // Non-key events are checked too
+        same_time_other_key,
+        press, // Not identical to the event right before it
+    ];
+    let results = check_sequence(&mut filter, &events, Duration::ZERO);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![false, true, true, false, true, false, false]);
+    assert_eq!(results[1].diff_us, Some(0));
+    assert_eq!(results[1].last_passed_us, Some(1_000));
+}
+
+#[test]
+fn exact_duplicates_pass_when_disabled() {
+    let mut filter = BounceFilter::new(0);
+    let press = key_ev(1_000, KEY_A, 1);
+    let results = check_sequence(&mut filter, &[press, press], Duration::ZERO);
+    assert!(results.iter().all(|info| !info.is_bounce));
+}
+
+// --- Zero-Duration Collapse Tests ---
+
+#[test]
+fn zero_duration_collapse_drops_same_timestamp_release() {
+    let mut filter = BounceFilter::new(0).with_zero_duration_collapse(true);
+    let events = [
+        key_ev(1_000, KEY_A, 1),
+        key_ev(1_000, KEY_A, 0), // Same timestamp as the press: collapsed
+        key_ev(1_000, KEY_B, 0), // Other key, no press passed
+        key_ev(1_001, KEY_A, 0), // 1µs later: a real release
+    ];
+    let results = check_sequence(&mut filter, &events, Duration::ZERO);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![false, true, false, false]);
+    assert_eq!(results[1].diff_us, Some(0));
+}
+
+#[test]
+fn zero_duration_release_passes_when_disabled() {
+    let mut filter = BounceFilter::new(0);
+    let events = [key_ev(1_000, KEY_A, 1), key_ev(1_000, KEY_A, 0)];
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    assert!(results.iter().all(|info| !info.is_bounce));
+}
+
+// --- Startup Suppression Tests ---
+
+#[test]
+fn suppress_initial_drops_only_first_events_in_window() {
+    let window = Duration::from_millis(200);
+    let mut filter = BounceFilter::new(0).with_initial_suppression(Some(window));
+    let events = [
+        key_ev(0, KEY_A, 0),       // First KEY_A release, in window: dropped
+        key_ev(50_000, KEY_A, 1),  // First KEY_A press, in window: dropped
+        key_ev(100_000, KEY_A, 0), // Second KEY_A release: passes
+        key_ev(150_000, KEY_A, 1), // Second KEY_A press: passes
+        key_ev(300_000, KEY_B, 1), // First KEY_B press, after the window: passes
+        key_ev(310_000, KEY_A, 2), // Repeats are never suppressed
+    ];
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![true, true, false, false, false, false]);
+    // Suppressed events don't count as passed.
+    assert_eq!(results[2].last_passed_us, None);
+    assert_eq!(filter.last_passed(KEY_A, 1), Some(150_000));
+}
+
+#[test]
+fn suppress_initial_window_starts_at_the_start_time() {
+    let window = Duration::from_millis(200);
+    let mut filter = BounceFilter::new(0)
+        .with_initial_suppression(Some(window))
+        .with_start_time(1_000_000);
+    let events = [
+        key_ev(950_000, KEY_B, 1),   // Queued just before startup: dropped
+        key_ev(1_500_000, KEY_A, 1), // First real keystroke, after the window: passes
+        key_ev(1_550_000, KEY_C, 1), // Right after it, still long after startup: passes
+    ];
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    let bounces: Vec<bool> = results.iter().map(|info| info.is_bounce).collect();
+    assert_eq!(bounces, vec![true, false, false]);
+}
+
+#[test]
+fn suppress_initial_disabled_passes_first_events() {
+    let mut filter = BounceFilter::new(0);
+    let events = [key_ev(0, KEY_A, 0), key_ev(50_000, KEY_A, 1)];
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    assert!(results.iter().all(|info| !info.is_bounce));
+}
+
+// --- Debounce Time Sweep ---
+
+#[test]
+fn sweep_drops_grow_with_the_debounce_time() {
+    // Eight keystrokes 200ms apart; each press and release chatters once, after
+    // 2, 7, 12 or 18ms in turn.
+    let mut capture = Vec::new();
+    for i in 0..8u64 {
+        let start = i * 200_000;
+        let chatter_us = [2_000, 7_000, 12_000, 18_000][i as usize % 4];
+        let key = if i % 2 == 0 { KEY_A } else { KEY_B };
+        capture.push(key_ev(start, key, 1));
+        capture.push(key_ev(start + chatter_us, key, 1));
+        capture.push(key_ev(start + 80_000, key, 0));
+        capture.push(key_ev(start + 80_000 + chatter_us, key, 0));
+        capture.push(non_key_ev(start + 80_000 + chatter_us));
+    }
+    let cfg = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+    let windows = [25, 5, 15, 10, 20].map(Duration::from_millis);
+
+    let results = sweep::sweep(&capture, &cfg, &windows);
+
+    let swept: Vec<u64> = results.iter().map(|r| r.debounce_us).collect();
+    assert_eq!(swept, vec![5_000, 10_000, 15_000, 20_000, 25_000]);
+    let drops: Vec<u64> = results.iter().map(|r| r.key_events_dropped).collect();
+    assert!(drops.windows(2).all(|w| w[0] <= w[1]), "{drops:?}");
+    assert_eq!(drops, vec![4, 8, 12, 16, 16]);
+    assert!(results.iter().all(|r| r.key_events_processed == 32));
+    assert_eq!(results[4].drop_percentage, 50.0);
+}
+
+#[test]
+fn max_repeat_rate_caps_repeats_per_key_and_second() {
+    let mut filter = BounceFilter::new(0).with_max_repeat_rate(Some(50));
+
+    // A 2-second storm of 1000 repeats per second on KEY_A, with a few KEY_B
+    // repeats in between that have their own budget.
+    let mut events = vec![key_ev(0, KEY_A, 1)];
+    events.extend((1..=2_000).map(|i| key_ev(i * 1_000, KEY_A, 2)));
+    events.extend((0..10).map(|i| key_ev(500_500 + i * 10_000, KEY_B, 2)));
+    events.sort_by_key(|ev| (ev.time.tv_sec, ev.time.tv_usec));
+
+    let results = check_sequence(&mut filter, &events, DEBOUNCE_TIME);
+    let passed = |code: u16, from_us: u64, to_us: u64| {
+        results
+            .iter()
+            .filter(|info| {
+                info.event.code == code
+                    && info.event.value == 2
+                    && (from_us..to_us).contains(&info.event_us)
+                    && !info.is_bounce
+            })
+            .count()
+    };
+
+    assert_eq!(passed(KEY_A, 0, 1_001_000), 50);
+    assert_eq!(passed(KEY_A, 1_001_000, 2_001_000), 50);
+    assert_eq!(passed(KEY_B, 0, u64::MAX), 10);
+    // Presses are untouched, and dropped repeats carry no bounce timing.
+    assert!(!results[0].is_bounce);
+    assert!(results
+        .iter()
+        .filter(|info| info.is_bounce)
+        .all(|info| info.diff_us.is_none()));
+
+    // Without a limit, repeats still pass through untouched.
+    let mut unlimited = BounceFilter::new(0);
+    let results = check_sequence(&mut unlimited, &events, DEBOUNCE_TIME);
+    assert!(results.iter().all(|info| !info.is_bounce));
+}
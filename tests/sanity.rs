@@ -1,5 +1,5 @@
 use assert_cmd::Command;
-use input_linux_sys::input_event;
+use input_linux_sys::{input_event, EV_KEY, EV_MSC, EV_SYN, SYN_REPORT};
 use predicates::prelude::*;
 use serde_json::{json, Value};
 use std::io::Write;
@@ -470,6 +470,39 @@ fn stats_output_json() {
     assert_eq!(stats_json["overall_near_miss_histogram"]["count"], 0); // No near misses in this sequence
 }
 
+#[test]
+fn color_flag_controls_stats_escape_codes() {
+    let e1 = key_ev(0, KEY_A, 1);
+    let e2 = key_ev(3_000, KEY_A, 1); // Bounce
+    let input_bytes = events_to_bytes(&[e1, e2]);
+
+    let stats_stderr = |when: &str| {
+        let output = Command::cargo_bin("intercept-bounce")
+            .unwrap()
+            .arg("--debounce-time")
+            .arg("10ms")
+            .arg("--color")
+            .arg(when)
+            .env("RUST_LOG", "warn")
+            .write_stdin(input_bytes.clone())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    let plain = stats_stderr("never");
+    assert!(plain.contains("Percentage Dropped:  50.00%"), "{plain}");
+    assert!(!plain.contains('\x1b'), "Escape codes with --color never");
+
+    let colored = stats_stderr("always");
+    // 50% drop rate is red.
+    assert!(
+        colored.contains("Percentage Dropped:  \x1b[31m50.00%\x1b[0m"),
+        "{colored}"
+    );
+}
+
 #[test]
 fn log_bounces_flag() {
     let e1 = key_ev(0, KEY_A, 1); // Pass
@@ -523,6 +556,641 @@ fn log_all_events_flag() {
         .stderr(predicate::str::contains("EV_SYN").not());
 }
 
+#[test]
+fn collapse_press_release_drops_release_press_chatter() {
+    let e1 = key_ev(0, KEY_A, 1); // Real press
+    let e2 = key_ev(100_000, KEY_A, 0); // Spurious release while held
+    let e3 = key_ev(102_000, KEY_A, 1); // Re-press 2ms later: chatter pair
+    let e4 = key_ev(300_000, KEY_A, 0); // Real release
+    let input_bytes = events_to_bytes(&[e1, e2, e3, e4]);
+    // e4 is held until EOF, then emitted followed by a synthesized SYN_REPORT.
+    let expected = events_to_bytes(&[e1, e4, non_key_ev(300_000)]);
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--debounce-time")
+        .arg("10ms")
+        .arg("--collapse-press-release")
+        .arg("--stats-json")
+        .env("RUST_LOG", "warn")
+        .write_stdin(input_bytes);
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        output.stdout, expected,
+        "Release/press pair was not collapsed"
+    );
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let json_start = stderr.find('{').expect("No JSON stats in stderr");
+    let stats: Value = serde_json::Deserializer::from_str(&stderr[json_start..])
+        .into_iter::<Value>()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(stats["key_events_dropped"], 2);
+}
+
+#[test]
+fn collapse_press_release_keeps_slow_retap() {
+    let e1 = key_ev(0, KEY_A, 1);
+    let e2 = key_ev(100_000, KEY_A, 0);
+    let e3 = key_ev(150_000, KEY_A, 1); // 50ms after release: intentional re-tap
+    let e4 = key_ev(300_000, KEY_A, 0);
+    let input_bytes = events_to_bytes(&[e1, e2, e3, e4]);
+    // e2 goes out just ahead of e3, in its report; e4 is followed by its own at EOF.
+    let expected = events_to_bytes(&[e1, e2, e3, e4, non_key_ev(300_000)]);
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--debounce-time")
+        .arg("10ms")
+        .arg("--collapse-press-release")
+        .env("RUST_LOG", "warn")
+        .write_stdin(input_bytes);
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected);
+}
+
+#[test]
+fn suppress_initial_passes_a_first_keystroke_long_after_startup() {
+    // The timestamps are decades before startup, so nothing is in the window.
+    let events = [key_ev(0, KEY_A, 1), key_ev(50_000, KEY_B, 1)];
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.args(["--debounce-time", "10ms", "--suppress-initial"])
+        .env("RUST_LOG", "warn")
+        .write_stdin(events_to_bytes(&events));
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, events_to_bytes(&events));
+}
+
+#[test]
+fn collapse_press_release_never_reorders_keys() {
+    const KEY_LEFTSHIFT: u16 = 42;
+    let e1 = key_ev(0, KEY_LEFTSHIFT, 1);
+    let e2 = key_ev(100_000, KEY_LEFTSHIFT, 0); // Held
+    let e3 = key_ev(110_000, KEY_A, 1); // Another key within the window
+    let e4 = key_ev(200_000, KEY_A, 0);
+    let input_bytes = events_to_bytes(&[e1, e2, e3, e4]);
+    // The Shift release is written before the KEY_A press, not after it.
+    let expected = events_to_bytes(&[e1, e2, e3, e4, non_key_ev(200_000)]);
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--debounce-time")
+        .arg("30ms")
+        .arg("--collapse-press-release")
+        .env("RUST_LOG", "warn")
+        .write_stdin(input_bytes);
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, expected);
+}
+
+#[test]
+fn collapse_press_release_flush_does_not_double_syn_reports() {
+    const KEY_LEFTSHIFT: u16 = 42;
+    let events = [
+        key_ev(0, KEY_LEFTSHIFT, 1),
+        non_key_ev(0),
+        key_ev(10_000, KEY_A, 1),
+        non_key_ev(10_000),
+        // Both keys released in one report: the Shift release is held, then
+        // emitted ahead of the KEY_A release, which is held in turn.
+        key_ev(100_000, KEY_LEFTSHIFT, 0),
+        key_ev(100_000, KEY_A, 0),
+        non_key_ev(100_000),
+    ];
+    // The report's own SYN_REPORT closes the Shift release; only the KEY_A
+    // release, emitted at EOF, gets a synthesized one.
+    let expected = [
+        events[0],
+        events[1],
+        events[2],
+        events[3],
+        events[4],
+        events[6],
+        events[5],
+        non_key_ev(100_000),
+    ];
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.args(["--debounce-time", "10ms", "--collapse-press-release"])
+        .env("RUST_LOG", "warn")
+        .write_stdin(events_to_bytes(&events));
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, events_to_bytes(&expected));
+}
+
+#[test]
+fn collapse_press_release_emits_a_held_release_one_debounce_time_after_the_key_up() {
+    use std::io::Read;
+    use std::process::{Command as StdCommand, Stdio};
+    use std::time::{Duration, Instant};
+
+    const DEBOUNCE_MS: u64 = 400;
+    let mut child = StdCommand::new(assert_cmd::cargo::cargo_bin("intercept-bounce"))
+        .args(["--debounce-time", "400ms", "--collapse-press-release"])
+        .env("RUST_LOG", "warn")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to spawn intercept-bounce");
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = child.stdout.take().unwrap();
+    let release = key_ev(1_000_000, KEY_A, 0);
+
+    stdin
+        .write_all(&events_to_bytes(&[key_ev(0, KEY_A, 1), non_key_ev(0)]))
+        .unwrap();
+    let mut buf = vec![0u8; 2 * size_of::<input_event>()];
+    stdout.read_exact(&mut buf).unwrap();
+
+    let released_at = Instant::now();
+    stdin
+        .write_all(&events_to_bytes(&[release, non_key_ev(1_000_000)]))
+        .unwrap();
+    stdout
+        .read_exact(&mut buf[..size_of::<input_event>()])
+        .unwrap();
+    // A later report separator mustn't push the window back: the release is
+    // due when its own window ends, not a debounce time after the last read.
+    std::thread::sleep(Duration::from_millis(DEBOUNCE_MS * 3 / 4));
+    stdin
+        .write_all(&events_to_bytes(&[non_key_ev(1_300_000)]))
+        .unwrap();
+    stdout
+        .read_exact(&mut buf[..size_of::<input_event>()])
+        .unwrap();
+
+    stdout
+        .read_exact(&mut buf[..size_of::<input_event>()])
+        .unwrap();
+    let elapsed = released_at.elapsed();
+    assert_eq!(
+        &buf[..size_of::<input_event>()],
+        &events_to_bytes(&[release])[..]
+    );
+    assert!(
+        elapsed >= Duration::from_millis(DEBOUNCE_MS * 3 / 4),
+        "Released too early: {elapsed:?}"
+    );
+    assert!(
+        elapsed < Duration::from_millis(DEBOUNCE_MS * 7 / 5),
+        "Released too late: {elapsed:?}"
+    );
+
+    drop(stdin);
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn stats_count_recovered_passes() {
+    let e1 = key_ev(0, KEY_A, 1); // Pass
+    let e2 = key_ev(3_000, KEY_A, 1); // Drop (bounce)
+    let e3 = key_ev(30_000, KEY_A, 1); // Pass right after the drop: recovered
+    let e4 = key_ev(60_000, KEY_A, 1); // Pass after a pass: not recovered
+    let input_bytes = events_to_bytes(&[e1, e2, e3, e4]);
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--debounce-time")
+        .arg("10ms")
+        .arg("--stats-json")
+        .env("RUST_LOG", "warn")
+        .write_stdin(input_bytes);
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let json_start = stderr.find('{').expect("No JSON stats in stderr");
+    let stats: Value = serde_json::Deserializer::from_str(&stderr[json_start..])
+        .into_iter::<Value>()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(stats["recovered_passes"], 1);
+    let key_a = stats["per_key_stats"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|k| k["key_code"] == KEY_A)
+        .expect("KEY_A missing from per_key_stats");
+    assert_eq!(key_a["recovered_passes"], 1);
+}
+
+#[test]
+fn persist_state_accumulates_across_runs() {
+    let state_path = temp_path("state-test.json");
+
+    let e1 = key_ev(0, KEY_A, 1); // Pass
+    let e2 = key_ev(3_000, KEY_A, 1); // Drop
+    let e3 = key_ev(50_000, KEY_A, 0); // Pass
+    let input_bytes = events_to_bytes(&[e1, e2, e3]);
+
+    let run = || -> Value {
+        let output = Command::cargo_bin("intercept-bounce")
+            .unwrap()
+            .arg("--debounce-time")
+            .arg("10ms")
+            .arg("--stats-json")
+            .arg("--persist-state")
+            .arg(&state_path)
+            .env("RUST_LOG", "warn")
+            .write_stdin(input_bytes.clone())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let json_start = stderr.find('{').expect("No JSON stats in stderr");
+        serde_json::Deserializer::from_str(&stderr[json_start..])
+            .into_iter::<Value>()
+            .next()
+            .unwrap()
+            .unwrap()
+    };
+
+    let first = run();
+    assert_eq!(first["key_events_processed"], 3);
+    assert!(state_path.exists(), "State file was not written");
+
+    let second = run();
+    assert_eq!(second["key_events_processed"], 6);
+    assert_eq!(second["key_events_passed"], 4);
+    assert_eq!(second["key_events_dropped"], 2);
+    assert_eq!(second["overall_bounce_histogram"]["count"], 2);
+}
+
+#[test]
+fn stats_json_file_is_written_alongside_the_human_report() {
+    let json_path = temp_path("stats-json-file.json");
+
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--debounce-time", "10ms", "--stats-json-file"])
+        .arg(&json_path)
+        .env("RUST_LOG", "intercept_bounce=info")
+        .write_stdin(events_to_bytes(&[
+            key_ev(0, KEY_A, 1),
+            key_ev(3_000, KEY_A, 1), // Drop
+            key_ev(50_000, KEY_A, 0),
+        ]))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--- Overall Statistics"), "{stderr}");
+    assert!(!stderr.contains("\"key_events_processed\""), "{stderr}");
+
+    let report: Value =
+        serde_json::from_str(&std::fs::read_to_string(&json_path).unwrap()).unwrap();
+    assert_eq!(report["report_type"], "Cumulative");
+    assert_eq!(report["key_events_processed"], 3);
+    assert_eq!(report["key_events_dropped"], 1);
+}
+
+#[test]
+fn stats_json_file_write_errors_are_logged() {
+    // Opening /dev/full succeeds, but every write to it fails with ENOSPC.
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--stats-json-file", "/dev/full"])
+        .env("RUST_LOG", "intercept_bounce=info")
+        .write_stdin(events_to_bytes(&[key_ev(0, KEY_A, 1)]))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Failed to write JSON statistics"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("Wrote JSON statistics"), "{stderr}");
+}
+
+#[test]
+fn merge_sums_two_stats_reports() {
+    let report_a = temp_path("merge-a.json");
+    let report_b = temp_path("merge-b.json");
+    let merged_path = temp_path("merge-out.json");
+
+    let write_report = |path: &std::path::Path, events: &[input_event]| {
+        let output = Command::cargo_bin("intercept-bounce")
+            .unwrap()
+            .args(["--debounce-time", "10ms", "--analyze", "--stats-to-stdout"])
+            .arg("--stats-json")
+            .env("RUST_LOG", "warn")
+            .write_stdin(events_to_bytes(events))
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        std::fs::write(path, output.stdout).unwrap();
+    };
+    // A: KEY_A press passed, bounce dropped after 3ms, release passed.
+    write_report(
+        &report_a,
+        &[
+            key_ev(0, KEY_A, 1),
+            key_ev(3_000, KEY_A, 1),
+            key_ev(50_000, KEY_A, 0),
+        ],
+    );
+    // B: KEY_A press passed and bounced after 5ms; KEY_B press passed.
+    write_report(
+        &report_b,
+        &[
+            key_ev(0, KEY_A, 1),
+            key_ev(5_000, KEY_A, 1),
+            key_ev(80_000, KEY_B, 1),
+        ],
+    );
+
+    Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .arg("--merge")
+        .arg(&report_a)
+        .arg(&report_b)
+        .arg("-o")
+        .arg(&merged_path)
+        .env("RUST_LOG", "warn")
+        .assert()
+        .success();
+    let merged: Value =
+        serde_json::from_str(&std::fs::read_to_string(&merged_path).unwrap()).unwrap();
+
+    assert_eq!(merged["report_type"], "Merged");
+    assert_eq!(merged["debounce_time_us"], 10_000);
+    assert_eq!(merged["key_events_processed"], 6);
+    assert_eq!(merged["key_events_passed"], 4);
+    assert_eq!(merged["key_events_dropped"], 2);
+    assert_eq!(merged["overall_bounce_histogram"]["count"], 2);
+    assert_eq!(merged["overall_bounce_histogram"]["avg_us"], 4_000);
+    let per_key = merged["per_key_stats"].as_array().unwrap();
+    let key_a = per_key.iter().find(|k| k["key_code"] == KEY_A).unwrap();
+    assert_eq!(key_a["total_processed"], 5);
+    assert_eq!(key_a["stats"]["press"]["dropped_count"], 2);
+    let mut timings: Vec<u64> = key_a["stats"]["press"]["timings_us"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t.as_u64().unwrap())
+        .collect();
+    timings.sort_unstable();
+    assert_eq!(timings, [3_000, 5_000]);
+    assert_eq!(key_a["stats"]["press"]["min_us"], 3_000);
+    assert_eq!(key_a["stats"]["press"]["max_us"], 5_000);
+    assert!(per_key.iter().any(|k| k["key_code"] == KEY_B));
+}
+
+#[test]
+fn merge_rejects_files_that_cannot_be_merged() {
+    let merge = |a: &std::path::Path, b: &std::path::Path| {
+        Command::cargo_bin("intercept-bounce")
+            .unwrap()
+            .arg("--merge")
+            .arg(a)
+            .arg(b)
+            .env("RUST_LOG", "warn")
+            .output()
+            .unwrap()
+    };
+    let write_report = |path: &std::path::Path, args: &[&str]| {
+        let output = Command::cargo_bin("intercept-bounce")
+            .unwrap()
+            .args(["--analyze", "--stats-to-stdout", "--stats-json"])
+            .args(args)
+            .env("RUST_LOG", "warn")
+            .write_stdin(events_to_bytes(&[key_ev(0, KEY_A, 1)]))
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        std::fs::write(path, output.stdout).unwrap();
+    };
+    let report = temp_path("merge-report.json");
+    write_report(&report, &[]);
+
+    // JSON that isn't a stats report is not an all-zero report.
+    for (name, text) in [
+        ("empty", "{}"),
+        ("unrelated", r#"{"name": "x", "count": 3}"#),
+    ] {
+        let other = temp_path(&format!("merge-{name}.json"));
+        std::fs::write(&other, text).unwrap();
+        let output = merge(&report, &other);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(output.status.code(), Some(4), "{name}: {stderr}");
+        assert!(stderr.contains("Invalid stats report"), "{name}: {stderr}");
+        assert!(output.stdout.is_empty(), "{name}");
+    }
+
+    // Band counts of different near-miss bands don't add up.
+    let banded = temp_path("merge-banded.json");
+    write_report(&banded, &["--near-miss-threshold-time", "30ms,60ms,100ms"]);
+    let output = merge(&report, &banded);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(4), "{stderr}");
+    assert!(
+        stderr.contains("Cannot merge reports with different near-miss bands"),
+        "{stderr}"
+    );
+    assert!(merge(&banded, &banded).status.success());
+}
+
+#[test]
+fn debounce_rel_thins_scroll_wheel_burst() {
+    let wheel = input_linux_sys::REL_WHEEL as u16;
+    let mut input = Vec::new();
+    let mut expected = Vec::new();
+    for i in 0..6u64 {
+        let ts = i * 4_000; // A tick every 4ms, each in its own SYN frame
+        input.extend([rel_ev(ts, wheel, 1), non_key_ev(ts)]);
+        if ts % 12_000 == 0 {
+            expected.push(rel_ev(ts, wheel, 1));
+        }
+        expected.push(non_key_ev(ts));
+    }
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--debounce-time")
+        .arg("10ms")
+        .arg("--debounce-rel")
+        .env("RUST_LOG", "warn")
+        .write_stdin(events_to_bytes(&input));
+    cmd.assert().success().stdout(events_to_bytes(&expected));
+
+    // Without the flag, every tick passes.
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--debounce-time")
+        .arg("10ms")
+        .env("RUST_LOG", "warn")
+        .write_stdin(events_to_bytes(&input));
+    cmd.assert().success().stdout(events_to_bytes(&input));
+}
+
+#[test]
+fn gap_threshold_counts_stream_gaps() {
+    let events = [
+        key_ev(0, KEY_A, 1),
+        key_ev(50_000, KEY_A, 0),
+        key_ev(3_000_000, KEY_B, 1), // 2.95s of silence with no key held: idle
+        key_ev(8_000_000, KEY_B, 0), // 5s of silence while KEY_B is held: suspicious
+        key_ev(8_100_000, KEY_A, 1), // Short pause, not a gap
+    ];
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--gap-threshold")
+        .arg("1s")
+        .arg("--stats-json")
+        .env("RUST_LOG", "intercept_bounce=info")
+        .write_stdin(events_to_bytes(&events));
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Gap in event stream (keyboard idle)"));
+    assert!(stderr.contains("possible dropped events"));
+
+    let json_start = stderr.find("{\n").expect("No JSON stats in stderr");
+    let stats: Value = serde_json::Deserializer::from_str(&stderr[json_start..])
+        .into_iter::<Value>()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(stats["stream_gaps"], 2);
+    assert_eq!(stats["stream_gaps_while_held"], 1);
+}
+
+#[test]
+fn reports_average_and_peak_event_rate() {
+    // 20 events/s for five seconds (10s..15s), then a burst of 45 events within
+    // the sixth second: 145 events over 6 one-second windows.
+    let mut events: Vec<input_event> = (0..100u64)
+        .map(|i| key_ev(10_000_000 + i * 50_000, KEY_A, (i % 2) as i32))
+        .collect();
+    events.extend((0..45u64).map(|i| key_ev(15_000_000 + i * 10_000, KEY_B, (i % 2) as i32)));
+
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .arg("--stats-json")
+        .env("RUST_LOG", "warn")
+        .write_stdin(events_to_bytes(&events))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let json_start = stderr.find("{\n").expect("No JSON stats in stderr");
+    let stats: Value = serde_json::Deserializer::from_str(&stderr[json_start..])
+        .into_iter::<Value>()
+        .next()
+        .unwrap()
+        .unwrap();
+
+    let average = stats["avg_events_per_sec"].as_f64().unwrap();
+    assert!(
+        (24.0..24.5).contains(&average),
+        "avg_events_per_sec = {average}"
+    );
+    assert_eq!(stats["peak_events_per_sec"], 45);
+
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .env("RUST_LOG", "warn")
+        .write_stdin(events_to_bytes(&events))
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("Event Rate (all events): 24.2/s average, 45/s peak"));
+}
+
+#[test]
+fn log_all_events_reports_near_miss_margin() {
+    let e1 = key_ev(0, KEY_A, 1); // Pass
+    let e2 = key_ev(32_000, KEY_A, 1); // Pass, 32ms after e1: near-miss 7ms past the 25ms window
+    let input_bytes = events_to_bytes(&[e1, e2]);
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--debounce-time")
+        .arg("25ms")
+        .arg("--near-miss-threshold-time")
+        .arg("100ms")
+        .arg("--log-all-events")
+        .env("RUST_LOG", "intercept_bounce=info")
+        .write_stdin(input_bytes);
+
+    cmd.assert()
+        .success()
+        // Field formatting may include ANSI color codes around the `=`.
+        .stderr(predicate::str::is_match(r"near_miss_margin_us\S*=\S*7000\b").unwrap())
+        .stderr(predicate::str::contains("Diff since last passed"));
+}
+
+#[test]
+fn event_json_raw_hex_round_trips() {
+    let e1 = key_ev(0, KEY_A, 1); // Pass
+    let e2 = key_ev(3_000, KEY_A, 1); // Bounce
+    let input_bytes = events_to_bytes(&[e1, e2]);
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--debounce-time")
+        .arg("5ms")
+        .arg("--event-json-raw")
+        .env("RUST_LOG", "warn")
+        .write_stdin(input_bytes);
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    let records: Vec<Value> = stderr
+        .lines()
+        .filter(|line| line.starts_with('{') && line.contains("\"raw_hex\""))
+        .map(|line| serde_json::from_str(line).expect("event JSON line should parse"))
+        .collect();
+    assert_eq!(records.len(), 2, "Expected one JSON line per event");
+    assert_eq!(records[0]["status"], "PASS");
+    assert_eq!(records[1]["status"], "DROP");
+    assert_eq!(records[1]["key_name"], "KEY_A");
+
+    for (record, original) in records.iter().zip([e1, e2]) {
+        let hex = record["raw_hex"].as_str().unwrap();
+        assert_eq!(hex.len(), size_of::<input_event>() * 2);
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        // Safety: bytes holds exactly size_of::<input_event>() bytes of a POD struct.
+        let decoded: input_event =
+            unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const input_event) };
+        assert_eq!(events_to_bytes(&[decoded]), events_to_bytes(&[original]));
+        assert_eq!(decoded.code, original.code);
+        assert_eq!(decoded.value, original.value);
+        assert_eq!(decoded.time.tv_usec, original.time.tv_usec);
+    }
+}
+
+#[test]
+fn event_json_omits_raw_hex_by_default() {
+    let input_bytes = events_to_bytes(&[key_ev(0, KEY_A, 1)]);
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--event-json")
+        .env("RUST_LOG", "warn")
+        .write_stdin(input_bytes);
+
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("\"event_code\":30"))
+        .stderr(predicate::str::contains("raw_hex").not());
+}
+
 #[test]
 fn test_debounce_zero_passes_all() {
     let e1 = key_ev(0, KEY_A, 1);
@@ -770,3 +1438,2184 @@ fn stats_output_only_dropped() {
     // Check overall near-miss histogram is empty
     assert_eq!(stats_json["overall_near_miss_histogram"]["count"], 0);
 }
+
+#[test]
+fn poll_interval_drives_logger_wakeups() {
+    use std::io::{BufRead, BufReader};
+    use std::process::{Command as StdCommand, Stdio};
+
+    // With no input at all, periodic dumps can only happen when the logger
+    // wakes up from its receive timeout, i.e. once per poll interval. A busy
+    // machine can only delay the wakeups, so their spacing is a lower bound.
+    let mut child = StdCommand::new(assert_cmd::cargo::cargo_bin("intercept-bounce"))
+        .arg("--poll-interval")
+        .arg("250ms")
+        .arg("--log-interval")
+        .arg("1ms")
+        .arg("--periodic-compact")
+        .env("RUST_LOG", "warn,stats=info")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn intercept-bounce");
+
+    // The logger stamps each dump with its wall-clock time (HH:MM:SS.mmm).
+    let mut dump_times_ms = Vec::new();
+    for line in BufReader::new(child.stderr.take().unwrap()).lines() {
+        let line = line.unwrap();
+        let Some((_, rest)) = line.split_once("wallclock=") else {
+            continue;
+        };
+        let time = rest
+            .split_whitespace()
+            .nth(1)
+            .expect("No time in dump stamp");
+        let (hms, ms) = time.split_once('.').unwrap();
+        let ms: i64 = hms
+            .split(':')
+            .fold(0, |acc, part| acc * 60 + part.parse::<i64>().unwrap())
+            * 1000
+            + ms.parse::<i64>().unwrap();
+        dump_times_ms.push(ms);
+        if dump_times_ms.len() == 4 {
+            break;
+        }
+    }
+    drop(child.stdin.take());
+    assert!(child.wait().unwrap().success());
+
+    assert_eq!(dump_times_ms.len(), 4, "Expected four periodic dumps");
+    for pair in dump_times_ms.windows(2) {
+        let gap_ms = (pair[1] - pair[0]).rem_euclid(86_400_000);
+        // The default 100ms interval would dump about every 100ms.
+        assert!(
+            gap_ms >= 200,
+            "Dumps only {gap_ms}ms apart: {dump_times_ms:?}"
+        );
+    }
+}
+
+#[test]
+fn poll_interval_bounds_shutdown_on_a_signal_with_the_input_open() {
+    use std::io::{BufRead, BufReader, Read};
+    use std::process::{Command as StdCommand, Stdio};
+    use std::time::{Duration, Instant};
+
+    let mut child = StdCommand::new(assert_cmd::cargo::cargo_bin("intercept-bounce"))
+        .args(["--poll-interval", "10ms"])
+        .env("RUST_LOG", "intercept_bounce=info")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn intercept-bounce");
+    // Held open until the end of the test: only the signal can stop the run.
+    let stdin = child.stdin.take().unwrap();
+
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut stderr_str = String::new();
+    while !stderr_str.contains("Starting main event loop") {
+        let n = stderr.read_line(&mut stderr_str).unwrap();
+        assert!(n > 0, "stderr closed before the main loop started");
+    }
+    unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGTERM) };
+
+    // The read isn't interrupted by the signal; the main loop sees the
+    // shutdown at its next wakeup.
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        assert!(
+            started.elapsed() < Duration::from_secs(10),
+            "Still running after SIGTERM"
+        );
+        std::thread::sleep(Duration::from_millis(10));
+    };
+    stderr.read_to_string(&mut stderr_str).unwrap();
+    drop(stdin);
+
+    assert!(status.success(), "{stderr_str}");
+    assert!(
+        stderr_str.contains("Shutdown reason: SIGTERM"),
+        "{stderr_str}"
+    );
+}
+
+#[test]
+fn periodic_compact_replaces_full_periodic_blocks() {
+    use std::io::Read;
+    use std::process::{Command as StdCommand, Stdio};
+
+    let mut child = StdCommand::new(assert_cmd::cargo::cargo_bin("intercept-bounce"))
+        .arg("--poll-interval")
+        .arg("5ms")
+        .arg("--log-interval")
+        .arg("1ms")
+        .arg("--periodic-compact")
+        .env("RUST_LOG", "warn")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn intercept-bounce");
+
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    drop(child.stdin.take());
+    let mut stderr = String::new();
+    child
+        .stderr
+        .take()
+        .unwrap()
+        .read_to_string(&mut stderr)
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+
+    assert!(
+        stderr.contains("PERIODIC processed=0 dropped=0 drop_pct=0.00 top_key=none\n"),
+        "Missing compact periodic line: {stderr}"
+    );
+    assert!(
+        !stderr.contains("--- Overall Statistics (Periodic) ---"),
+        "Full periodic block was printed: {stderr}"
+    );
+    // The final report is still the full block.
+    assert!(stderr.contains("--- Overall Statistics (Cumulative) ---"));
+}
+
+#[test]
+fn periodic_json_reports_carry_increasing_event_intervals() {
+    use std::io::{Read, Write};
+    use std::process::{Command as StdCommand, Stdio};
+
+    let mut child = StdCommand::new(assert_cmd::cargo::cargo_bin("intercept-bounce"))
+        .arg("--poll-interval")
+        .arg("5ms")
+        .arg("--log-interval")
+        .arg("50ms")
+        .arg("--stats-json")
+        .env("RUST_LOG", "warn")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn intercept-bounce");
+
+    let mut stdin = child.stdin.take().unwrap();
+    for batch in [[0, 100_000], [1_000_000, 1_100_000]] {
+        let events: Vec<_> = batch
+            .iter()
+            .flat_map(|&us| [key_ev(us, KEY_A, 1), key_ev(us + 50_000, KEY_A, 0)])
+            .collect();
+        stdin.write_all(&events_to_bytes(&events)).unwrap();
+        stdin.flush().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(300));
+    }
+    drop(stdin);
+    let mut stderr = String::new();
+    child
+        .stderr
+        .take()
+        .unwrap()
+        .read_to_string(&mut stderr)
+        .unwrap();
+    assert!(child.wait().unwrap().success());
+
+    // Periodic reports with events in their interval, in order. Each report
+    // starts with a `{` at the beginning of a line.
+    let text = format!("\n{stderr}");
+    let intervals: Vec<(u64, u64)> = text
+        .match_indices("\n{")
+        .filter_map(|(i, _)| {
+            serde_json::Deserializer::from_str(&text[i + 1..])
+                .into_iter::<Value>()
+                .next()?
+                .ok()
+        })
+        .filter(|report| report["report_type"] == "Periodic")
+        .filter_map(|report| {
+            Some((
+                report["interval_start_us"].as_u64()?,
+                report["interval_end_us"].as_u64()?,
+            ))
+        })
+        .collect();
+
+    assert!(
+        intervals.len() >= 2,
+        "Expected at least two periodic reports with events: {stderr}"
+    );
+    // On a loaded machine a report can land mid-batch and split it across
+    // two intervals, but each batch's edges still follow its event timestamps.
+    assert_eq!(intervals[0].0, 0, "{intervals:?}");
+    assert!(
+        intervals.iter().any(|&(_, end)| end == 150_000),
+        "{intervals:?}"
+    );
+    assert!(
+        intervals.iter().any(|&(start, _)| start == 1_000_000),
+        "{intervals:?}"
+    );
+    assert_eq!(intervals[intervals.len() - 1].1, 1_150_000, "{intervals:?}");
+    for (start, end) in &intervals {
+        assert!(
+            start <= end,
+            "Interval ends before it starts: {intervals:?}"
+        );
+    }
+    for pair in intervals.windows(2) {
+        assert!(
+            pair[0].1 < pair[1].0,
+            "Periodic intervals overlap: {intervals:?}"
+        );
+    }
+}
+
+#[test]
+fn sigusr1_pauses_filtering() {
+    use std::io::{BufRead, BufReader, Read};
+    use std::process::{Command as StdCommand, Stdio};
+
+    let e1 = key_ev(0, KEY_A, 1);
+    let e2 = key_ev(3_000, KEY_A, 1); // Would be a bounce, but filtering is paused
+    let input_events = vec![e1, e2];
+    let expected_output_bytes = events_to_bytes(&input_events);
+
+    let mut child = StdCommand::new(assert_cmd::cargo::cargo_bin("intercept-bounce"))
+        .arg("--debounce-time")
+        .arg("5ms")
+        .arg("--stats-json")
+        .env("RUST_LOG", "intercept_bounce=info")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn intercept-bounce");
+
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut stderr_str = String::new();
+    let mut wait_for = |needle: &str, stderr_str: &mut String| loop {
+        let mut line = String::new();
+        let n = stderr.read_line(&mut line).expect("Failed to read stderr");
+        assert!(n > 0, "stderr closed before '{needle}' was logged");
+        stderr_str.push_str(&line);
+        if line.contains(needle) {
+            break;
+        }
+    };
+
+    // The signal handler is installed before the main loop starts.
+    wait_for("Starting main event loop", &mut stderr_str);
+    unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGUSR1) };
+    wait_for("Filtering paused", &mut stderr_str);
+
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(&expected_output_bytes).unwrap();
+    drop(stdin);
+
+    let mut stdout = Vec::new();
+    child
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_end(&mut stdout)
+        .unwrap();
+    stderr.read_to_string(&mut stderr_str).unwrap();
+    assert!(child.wait().unwrap().success());
+
+    assert_eq!(
+        stdout, expected_output_bytes,
+        "Bounce should pass while filtering is paused"
+    );
+
+    let json_start_index = stderr_str
+        .find("{\n")
+        .expect("No JSON block start found in stderr");
+    // Info-level log lines follow the JSON block, so only parse the first value.
+    let stats_json: Value = serde_json::Deserializer::from_str(&stderr_str[json_start_index..])
+        .into_iter::<Value>()
+        .next()
+        .expect("No JSON value found in stderr")
+        .unwrap_or_else(|e| panic!("Failed to parse JSON from stderr: {e}\nStderr:\n{stderr_str}"));
+    assert_eq!(stats_json["key_events_paused"], 2);
+    assert_eq!(stats_json["key_events_processed"], 0);
+    assert_eq!(stats_json["key_events_dropped"], 0);
+}
+
+#[test]
+fn exits_with_hint_when_stdin_is_tty() {
+    use std::os::unix::io::FromRawFd;
+    use std::process::Stdio;
+
+    let mut master: libc::c_int = -1;
+    let mut slave: libc::c_int = -1;
+    let res = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null(),
+            std::ptr::null(),
+        )
+    };
+    assert_eq!(
+        res,
+        0,
+        "openpty failed: {}",
+        std::io::Error::last_os_error()
+    );
+    // Safety: openpty returned two fresh descriptors that we now own.
+    let _master = unsafe { std::fs::File::from_raw_fd(master) };
+    let slave = unsafe { std::fs::File::from_raw_fd(slave) };
+
+    let output = std::process::Command::new(assert_cmd::cargo::cargo_bin("intercept-bounce"))
+        .env("RUST_LOG", "info")
+        .stdin(Stdio::from(slave))
+        .output()
+        .expect("Failed to execute command");
+
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Standard input is a terminal"),
+        "Missing TTY error in stderr: {stderr}"
+    );
+    assert!(
+        stderr.contains("Usage hint"),
+        "Missing usage hint: {stderr}"
+    );
+}
+
+#[test]
+fn sighup_reloads_config_file() {
+    use std::io::{BufRead, BufReader, Read};
+    use std::process::{Command as StdCommand, Stdio};
+
+    let config_path = temp_path("sighup.toml");
+    std::fs::write(&config_path, "debounce_time = \"5ms\"\n").unwrap();
+
+    let e1 = key_ev(0, KEY_A, 1);
+    let e2 = key_ev(20_000, KEY_A, 1); // Passes with 5ms, bounce with 50ms
+
+    let mut child = StdCommand::new(assert_cmd::cargo::cargo_bin("intercept-bounce"))
+        .arg("--config")
+        .arg(&config_path)
+        .env("RUST_LOG", "intercept_bounce=info")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn intercept-bounce");
+
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut stderr_str = String::new();
+    let mut wait_for = |needle: &str, stderr_str: &mut String| loop {
+        let mut line = String::new();
+        let n = stderr.read_line(&mut line).expect("Failed to read stderr");
+        assert!(n > 0, "stderr closed before '{needle}' was logged");
+        stderr_str.push_str(&line);
+        if line.contains(needle) {
+            break;
+        }
+    };
+
+    wait_for("Starting main event loop", &mut stderr_str);
+    std::fs::write(&config_path, "debounce_time = \"50ms\"\n").unwrap();
+    unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGHUP) };
+    wait_for("Reload requested", &mut stderr_str);
+
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(&events_to_bytes(&[e1, e2])).unwrap();
+    drop(stdin);
+
+    let mut stdout = Vec::new();
+    child
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_end(&mut stdout)
+        .unwrap();
+    stderr.read_to_string(&mut stderr_str).unwrap();
+    assert!(child.wait().unwrap().success());
+
+    assert!(
+        stderr_str.contains("Configuration reloaded"),
+        "Reload not logged:\n{stderr_str}"
+    );
+    assert_eq!(
+        stdout,
+        events_to_bytes(&[e1]),
+        "Reloaded 50ms debounce time was not applied"
+    );
+}
+
+#[test]
+fn reload_turning_off_collapse_press_release_emits_held_releases() {
+    use std::io::{BufRead, BufReader, Read};
+    use std::process::{Command as StdCommand, Stdio};
+
+    let config_path = temp_path("sighup-collapse.toml");
+    // A long debounce time, so the release is never emitted for being too old.
+    std::fs::write(
+        &config_path,
+        "debounce_time = \"10s\"\ncollapse_press_release = true\n",
+    )
+    .unwrap();
+
+    let release = key_ev(0, KEY_A, 0); // Held
+    let report = non_key_ev(100_000); // Read after the reload
+
+    let mut child = StdCommand::new(assert_cmd::cargo::cargo_bin("intercept-bounce"))
+        .arg("--config")
+        .arg(&config_path)
+        .env("RUST_LOG", "intercept_bounce=info")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn intercept-bounce");
+
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut stderr_str = String::new();
+    let mut wait_for = |needle: &str, stderr_str: &mut String| loop {
+        let mut line = String::new();
+        let n = stderr.read_line(&mut line).expect("Failed to read stderr");
+        assert!(n > 0, "stderr closed before '{needle}' was logged");
+        stderr_str.push_str(&line);
+        if line.contains(needle) {
+            break;
+        }
+    };
+
+    wait_for("Starting main event loop", &mut stderr_str);
+    let mut stdin = child.stdin.take().unwrap();
+    stdin.write_all(&events_to_bytes(&[release])).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(200));
+    std::fs::write(&config_path, "debounce_time = \"10s\"\n").unwrap();
+    unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGHUP) };
+    wait_for("Reload requested", &mut stderr_str);
+    stdin.write_all(&events_to_bytes(&[report])).unwrap();
+    drop(stdin);
+
+    let mut stdout = Vec::new();
+    child
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_end(&mut stdout)
+        .unwrap();
+    stderr.read_to_string(&mut stderr_str).unwrap();
+    assert!(child.wait().unwrap().success(), "{stderr_str}");
+
+    // The held release (with its own SYN_REPORT) comes out at the reload,
+    // ahead of the next event, not at the end of input.
+    assert_eq!(
+        stdout,
+        events_to_bytes(&[release, non_key_ev(0), report]),
+        "{stderr_str}"
+    );
+}
+
+#[test]
+fn analyze_writes_stats_to_stdout() {
+    let events = [
+        key_ev(0, KEY_A, 1),
+        key_ev(3_000, KEY_A, 1), // Bounce
+        key_ev(50_000, KEY_A, 0),
+    ];
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--analyze")
+        .arg("--stats-to-stdout")
+        .arg("--stats-json")
+        .env("RUST_LOG", "off")
+        .write_stdin(events_to_bytes(&events));
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert!(
+        output.stderr.is_empty(),
+        "Expected empty stderr, got: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    // No events on stdout, only the report.
+    let stats: Value = serde_json::from_slice(&output.stdout).expect("stdout is not stats JSON");
+    assert_eq!(stats["key_events_processed"], 3);
+    assert_eq!(stats["key_events_passed"], 2);
+    assert_eq!(stats["key_events_dropped"], 1);
+}
+
+#[cfg(not(feature = "msgpack"))]
+#[test]
+fn stats_format_msgpack_is_a_usage_error_without_the_msgpack_feature() {
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--stats-format", "msgpack"])
+        .write_stdin(events_to_bytes(&[key_ev(0, KEY_A, 1)]))
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("'msgpack' needs a build with the `msgpack` feature"),
+        "{stderr}"
+    );
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn environment_variables_configure_timings_unless_flags_are_given() {
+    let report = |args: &[&str]| -> Value {
+        let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+        cmd.args(["--analyze", "--stats-to-stdout", "--stats-json"])
+            .args(args)
+            .env("INTERCEPT_BOUNCE_DEBOUNCE_TIME", "7ms")
+            .env("INTERCEPT_BOUNCE_NEAR_MISS", "40ms")
+            .env("INTERCEPT_BOUNCE_LOG_INTERVAL", "30s")
+            .env("RUST_LOG", "off")
+            .write_stdin(events_to_bytes(&[key_ev(0, KEY_A, 1)]));
+        let output = cmd.output().unwrap();
+        assert!(output.status.success());
+        serde_json::from_slice(&output.stdout).expect("stdout is not stats JSON")
+    };
+
+    let from_env = report(&[]);
+    assert_eq!(from_env["debounce_time_us"], 7_000);
+    assert_eq!(from_env["near_miss_threshold_us"], 40_000);
+    assert_eq!(from_env["log_interval_us"], 30_000_000);
+
+    let from_flags = report(&[
+        "--debounce-time",
+        "12ms",
+        "--near-miss-threshold-time",
+        "80ms",
+    ]);
+    assert_eq!(from_flags["debounce_time_us"], 12_000);
+    assert_eq!(from_flags["near_miss_threshold_us"], 80_000);
+    assert_eq!(from_flags["log_interval_us"], 30_000_000);
+}
+
+#[test]
+fn measure_latency_reports_filter_timings() {
+    let events = [
+        key_ev(0, KEY_A, 1),
+        key_ev(3_000, KEY_A, 1), // Bounce
+        non_key_ev(3_000),
+        key_ev(50_000, KEY_A, 0),
+    ];
+    let report = |args: &[&str]| -> Value {
+        let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+        cmd.args(["--analyze", "--stats-to-stdout", "--stats-json"])
+            .args(args)
+            .env("RUST_LOG", "off")
+            .write_stdin(events_to_bytes(&events));
+        let output = cmd.output().unwrap();
+        assert!(output.status.success());
+        serde_json::from_slice(&output.stdout).expect("stdout is not stats JSON")
+    };
+
+    let stats = report(&["--measure-latency"]);
+    let latency = |field: &str| {
+        stats[field]
+            .as_u64()
+            .unwrap_or_else(|| panic!("{field} missing"))
+    };
+    assert!(latency("filter_latency_min_ns") <= latency("filter_latency_avg_ns"));
+    assert!(latency("filter_latency_avg_ns") <= latency("filter_latency_max_ns"));
+    assert!(latency("filter_latency_max_ns") > 0);
+
+    let stats = report(&[]);
+    assert!(stats.get("filter_latency_avg_ns").is_none());
+}
+
+#[cfg(feature = "otel")]
+#[test]
+fn otel_required_fails_on_unreachable_endpoint() {
+    // Nothing listens on port 1, so the connection is refused right away.
+    let run = |required: bool| {
+        let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+        cmd.args(["--otel-endpoint", "http://127.0.0.1:1"])
+            .env("RUST_LOG", "warn")
+            .write_stdin(events_to_bytes(&[key_ev(0, KEY_A, 1)]));
+        if required {
+            cmd.arg("--otel-required");
+        }
+        cmd.output().unwrap()
+    };
+
+    let output = run(true);
+    assert_eq!(output.status.code(), Some(7));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Failed to initialize OpenTelemetry"),
+        "{stderr}"
+    );
+    assert!(output.stdout.is_empty());
+
+    // Without the flag the events are still filtered.
+    let output = run(false);
+    assert!(output.status.success());
+    assert_eq!(output.stdout, events_to_bytes(&[key_ev(0, KEY_A, 1)]));
+}
+
+#[cfg(not(feature = "otel"))]
+#[test]
+fn otel_endpoint_is_rejected_without_the_otel_feature() {
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--otel-endpoint", "http://127.0.0.1:4317"])
+        .write_stdin(events_to_bytes(&[key_ev(0, KEY_A, 1)]))
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(4));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("--otel-endpoint needs a build with the `otel` feature"),
+        "{stderr}"
+    );
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn stats_to_stdout_requires_analyze() {
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--stats-to-stdout").write_stdin(Vec::new());
+    cmd.assert().failure();
+}
+
+#[test]
+fn sample_seed_keeps_timing_samples_reproducible() {
+    // One press followed by 1000 bounces with distinct timings (4 µs .. 4 ms):
+    // more than the samples retain, but few enough to fit the logger queue, so
+    // no event info is dropped under load and both runs see the same stream.
+    let mut events = vec![key_ev(0, KEY_A, 1)];
+    events.extend((1..=1000u64).map(|i| key_ev(i * 4, KEY_A, 1)));
+    let input = events_to_bytes(&events);
+
+    let sampled_timings = |seed: &str| -> Vec<Value> {
+        let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+        cmd.arg("--sample-seed")
+            .arg(seed)
+            .arg("--stats-json")
+            .write_stdin(input.clone());
+        let output = cmd.output().unwrap();
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let json_start = stderr.find("{\n").expect("No JSON stats in stderr");
+        let stats: Value = serde_json::Deserializer::from_str(&stderr[json_start..])
+            .into_iter::<Value>()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(stats["sample_seed"], seed.parse::<u64>().unwrap());
+        stats["per_key_stats"][0]["stats"]["press"]["timings_us"]
+            .as_array()
+            .expect("No sampled press timings")
+            .clone()
+    };
+
+    // The newest 512 bounce timings, oldest first.
+    let newest: Vec<Value> = (489..=1000u64).map(|i| json!(i * 4)).collect();
+    let first = sampled_timings("42");
+    assert_eq!(first, newest);
+    assert_eq!(first, sampled_timings("42"));
+}
+
+#[test]
+fn replays_without_a_sample_seed_give_identical_json() {
+    // More bounces than the samples retain, as above, but no `--sample-seed`.
+    let mut events = vec![key_ev(0, KEY_A, 1)];
+    events.extend((1..=1000u64).map(|i| key_ev(i * 4, KEY_A, 1)));
+    let input = events_to_bytes(&events);
+
+    let replay = || -> Value {
+        let output = Command::cargo_bin("intercept-bounce")
+            .unwrap()
+            .arg("--stats-json")
+            .write_stdin(input.clone())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let json_start = stderr.find("{\n").expect("No JSON stats in stderr");
+        serde_json::Deserializer::from_str(&stderr[json_start..])
+            .into_iter::<Value>()
+            .next()
+            .unwrap()
+            .unwrap()
+    };
+
+    let first = replay();
+    assert_eq!(first["sample_seed"], 0);
+    assert!(first["recommended_debounce_us"].is_u64(), "{first}");
+    assert_eq!(first, replay());
+}
+
+#[test]
+fn timeline_csv_has_a_row_per_key_event() {
+    let timeline_path = temp_path("timeline-test.csv");
+
+    let events = [
+        key_ev(0, KEY_A, 1),       // Pass
+        non_key_ev(0),             // SYN, not in the timeline
+        key_ev(3_000, KEY_A, 1),   // Drop
+        key_ev(50_000, KEY_A, 0),  // Pass
+        key_ev(120_000, KEY_A, 1), // Near miss (120ms after the last press)
+        key_ev(900_000, KEY_B, 1), // Pass
+        non_key_ev(900_000),       // SYN
+    ];
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--debounce-time")
+        .arg("10ms")
+        .arg("--near-miss-threshold-time")
+        .arg("200ms")
+        .arg("--timeline-csv")
+        .arg(&timeline_path)
+        .arg("--stats-json")
+        .write_stdin(events_to_bytes(&events));
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let json_start = stderr.find("{\n").expect("No JSON stats in stderr");
+    let stats: Value = serde_json::Deserializer::from_str(&stderr[json_start..])
+        .into_iter::<Value>()
+        .next()
+        .unwrap()
+        .unwrap();
+
+    let timeline = std::fs::read_to_string(&timeline_path).unwrap();
+    let mut lines = timeline.lines();
+    assert_eq!(
+        lines.next(),
+        Some("event_us,key_code,key_name,value,decision,diff_us")
+    );
+    let rows: Vec<&str> = lines.collect();
+    assert_eq!(stats["key_events_processed"], rows.len());
+    assert_eq!(rows[1], "3000,30,KEY_A,1,drop,3000");
+    assert_eq!(rows[3], "120000,30,KEY_A,1,near_miss,120000");
+    assert_eq!(rows[4], "900000,48,KEY_B,1,pass,");
+}
+
+#[test]
+fn export_trailer_counts_and_checksums_the_records() {
+    let timeline_path = temp_path("trailer.csv");
+    let drops_path = temp_path("trailer.jsonl");
+
+    let events = [
+        key_ev(0, KEY_A, 1),      // Pass
+        key_ev(3_000, KEY_A, 1),  // Drop
+        key_ev(50_000, KEY_A, 0), // Pass
+        key_ev(54_000, KEY_A, 0), // Drop
+        key_ev(90_000, KEY_B, 1), // Pass
+        non_key_ev(90_000),
+    ];
+    Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--debounce-time", "10ms", "--export-trailer"])
+        .arg("--timeline-csv")
+        .arg(&timeline_path)
+        .arg("--dropped-events-file")
+        .arg(&drops_path)
+        .env("RUST_LOG", "warn")
+        .write_stdin(events_to_bytes(&events))
+        .assert()
+        .success();
+    let timeline = std::fs::read_to_string(&timeline_path).unwrap();
+    let drops = std::fs::read_to_string(&drops_path).unwrap();
+
+    // Checksum of the record lines, newlines included.
+    let checksum = |records: &[&str]| {
+        let bytes: String = records.iter().map(|line| format!("{line}\n")).collect();
+        format!(
+            "{:016x}",
+            intercept_bounce::util::fnv1a64(intercept_bounce::util::FNV1A_OFFSET, bytes.as_bytes())
+        )
+    };
+
+    let lines: Vec<&str> = timeline.lines().collect();
+    let (trailer, rows) = lines[1..].split_last().unwrap();
+    assert_eq!(rows.len(), 5);
+    assert_eq!(
+        *trailer,
+        format!("# records={} fnv1a64={}", rows.len(), checksum(rows))
+    );
+
+    let lines: Vec<&str> = drops.lines().collect();
+    let (trailer, records) = lines.split_last().unwrap();
+    assert_eq!(records.len(), 2);
+    let trailer: Value = serde_json::from_str(trailer).unwrap();
+    assert_eq!(trailer["trailer"], true);
+    assert_eq!(trailer["records"], records.len());
+    assert_eq!(trailer["fnv1a64"], checksum(records));
+}
+
+#[test]
+fn timeline_csv_error_does_not_stop_filtering() {
+    let events = [key_ev(0, KEY_A, 1), key_ev(50_000, KEY_A, 0)];
+    let input_bytes = events_to_bytes(&events);
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--timeline-csv")
+        .arg("/nonexistent-dir/timeline.csv")
+        .write_stdin(input_bytes.clone());
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, input_bytes);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Cannot create event timeline"));
+}
+
+#[test]
+fn dropped_events_file_records_exactly_the_drops() {
+    let drops_path = temp_path("drops-test.jsonl");
+
+    let events = [
+        key_ev(0, KEY_A, 1),       // Pass
+        non_key_ev(0),             // SYN
+        key_ev(3_000, KEY_A, 1),   // Drop
+        key_ev(50_000, KEY_A, 0),  // Pass
+        key_ev(54_000, KEY_A, 0),  // Drop
+        key_ev(120_000, KEY_A, 1), // Pass
+        key_ev(900_000, KEY_B, 1), // Pass
+        key_ev(901_500, KEY_B, 1), // Drop
+        non_key_ev(901_500),       // SYN
+    ];
+
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--debounce-time", "10ms", "--dropped-events-file"])
+        .arg(&drops_path)
+        .write_stdin(events_to_bytes(&events))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let contents = std::fs::read_to_string(&drops_path).unwrap();
+    let records: Vec<Value> = contents
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        records,
+        vec![
+            json!({"event_us": 3000, "event_type": 1, "event_type_name": "EV_KEY", "code": 30,
+                   "key_name": "KEY_A", "value": 1, "value_name": "Press", "diff_us": 3000,
+                   "debounce_us": 10000}),
+            json!({"event_us": 54000, "event_type": 1, "event_type_name": "EV_KEY", "code": 30,
+                   "key_name": "KEY_A", "value": 0, "value_name": "Release", "diff_us": 4000,
+                   "debounce_us": 10000}),
+            json!({"event_us": 901500, "event_type": 1, "event_type_name": "EV_KEY", "code": 48,
+                   "key_name": "KEY_B", "value": 1, "value_name": "Press", "diff_us": 1500,
+                   "debounce_us": 10000}),
+        ]
+    );
+}
+
+#[test]
+fn dropped_events_file_error_does_not_stop_filtering() {
+    let events = [key_ev(0, KEY_A, 1), key_ev(3_000, KEY_A, 1)];
+
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--dropped-events-file", "/nonexistent-dir/drops.jsonl"])
+        .write_stdin(events_to_bytes(&events))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout, events_to_bytes(&events[..1]));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Cannot create dropped events file"));
+}
+
+#[test]
+fn shutdown_timeout_bounds_wait_for_stuck_logger() {
+    use std::os::unix::ffi::OsStrExt;
+
+    // Opening a FIFO for writing blocks until a reader shows up, so pointing the
+    // timeline at one with no reader wedges the logger thread at startup.
+    let fifo_path = temp_path("stuck-logger.fifo");
+    let c_path = std::ffi::CString::new(fifo_path.as_os_str().as_bytes()).unwrap();
+    assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+    let state_path = temp_path("stuck-logger-state.json");
+
+    let events = [key_ev(0, KEY_A, 1), key_ev(50_000, KEY_A, 0)];
+    let input_bytes = events_to_bytes(&events);
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--timeline-csv")
+        .arg(&fifo_path)
+        .arg("--persist-state")
+        .arg(&state_path)
+        .arg("--shutdown-timeout")
+        .arg("200ms")
+        .timeout(std::time::Duration::from_secs(30))
+        .write_stdin(input_bytes.clone());
+
+    let start = std::time::Instant::now();
+    let output = cmd.output().unwrap();
+    let elapsed = start.elapsed();
+
+    assert!(
+        elapsed < std::time::Duration::from_secs(5),
+        "Shutdown took {elapsed:?}"
+    );
+    // A wedged logger doesn't turn a clean run into a failure.
+    assert!(output.status.success());
+    // Filtering itself doesn't depend on the logger.
+    assert_eq!(output.stdout, input_bytes);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Logger thread did not finish in time"),
+        "Missing logger timeout warning: {stderr}"
+    );
+    // No all-zero placeholder report, printed or persisted.
+    assert!(!stderr.contains("Overall Statistics"), "{stderr}");
+    assert!(
+        !state_path.exists(),
+        "Placeholder statistics were persisted"
+    );
+}
+
+#[test]
+fn default_shutdown_timeout_waits_for_a_briefly_slow_logger() {
+    use std::io::Read;
+    use std::os::unix::ffi::OsStrExt;
+
+    // As above, the logger is stuck opening the timeline FIFO, but a reader
+    // shows up after 300ms, well within the default bound.
+    let fifo_path = temp_path("slow-logger.fifo");
+    let c_path = std::ffi::CString::new(fifo_path.as_os_str().as_bytes()).unwrap();
+    assert_eq!(unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) }, 0);
+    let state_path = temp_path("slow-logger-state.json");
+
+    let reader = {
+        let fifo_path = fifo_path.to_path_buf();
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(300));
+            let mut timeline = String::new();
+            std::fs::File::open(&fifo_path)
+                .unwrap()
+                .read_to_string(&mut timeline)
+                .unwrap();
+            timeline
+        })
+    };
+
+    let events = [key_ev(0, KEY_A, 1), key_ev(50_000, KEY_A, 0)];
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .arg("--timeline-csv")
+        .arg(&fifo_path)
+        .arg("--persist-state")
+        .arg(&state_path)
+        .timeout(std::time::Duration::from_secs(30))
+        .write_stdin(events_to_bytes(&events))
+        .output()
+        .unwrap();
+    let timeline = reader.join().unwrap();
+    let state = std::fs::read_to_string(&state_path);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(!stderr.contains("did not finish in time"), "{stderr}");
+    assert!(stderr.contains("Overall Statistics"), "{stderr}");
+    let state: Value = serde_json::from_str(&state.expect("No persisted state")).unwrap();
+    assert_eq!(state["key_events_processed"], 2);
+    assert_eq!(timeline.lines().count(), 3, "{timeline}");
+}
+
+#[test]
+fn output_totals_match_passed_events_on_clean_run() {
+    let events = [
+        key_ev(0, KEY_A, 1),
+        non_key_ev(0),
+        key_ev(3_000, KEY_A, 1), // Bounce
+        non_key_ev(3_000),
+        key_ev(50_000, KEY_A, 0),
+        non_key_ev(50_000),
+    ];
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.env("RUST_LOG", "info")
+        .arg("--debounce-time")
+        .arg("10ms")
+        .write_stdin(events_to_bytes(&events));
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let event_size = std::mem::size_of::<input_event>();
+    let written = output.stdout.len() / event_size;
+    assert_eq!(written, events.len() - 1);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let expected = format!(
+        "events_passed={written} events_synthesized=0 events_written={written} bytes_written={}",
+        written * event_size
+    );
+    assert!(
+        stderr.contains(&expected),
+        "Missing output totals ({expected}): {stderr}"
+    );
+    assert!(!stderr.contains("never reached stdout"));
+}
+
+#[test]
+fn synthesized_syn_reports_are_not_counted_as_passed() {
+    // The release is held back and emitted at EOF with a SYN_REPORT of its own.
+    let events = [key_ev(0, KEY_A, 1), key_ev(50_000, KEY_A, 0)];
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.env("RUST_LOG", "info")
+        .args(["--debounce-time", "10ms", "--collapse-press-release"])
+        .write_stdin(events_to_bytes(&events));
+
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(output.stdout.len(), 3 * std::mem::size_of::<input_event>());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("events_passed=2 events_synthesized=1 events_written=3"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("never reached stdout"), "{stderr}");
+}
+
+#[test]
+fn input_path_is_read_instead_of_stdin() {
+    let input_path = temp_path("input-test.bin");
+    let events = [
+        key_ev(0, KEY_A, 1),
+        key_ev(3_000, KEY_A, 1), // Bounce
+        key_ev(50_000, KEY_A, 0),
+    ];
+    std::fs::write(&input_path, events_to_bytes(&events)).unwrap();
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--debounce-time")
+        .arg("10ms")
+        .arg("--input")
+        .arg(&input_path)
+        .write_stdin(events_to_bytes(&[key_ev(0, KEY_B, 1)])); // Must be ignored
+    let output = cmd.output().unwrap();
+    std::fs::remove_file(&input_path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(output.stdout, events_to_bytes(&[events[0], events[2]]));
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--input").arg(&input_path).write_stdin(Vec::new());
+    let output = cmd.output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Cannot open input"));
+}
+
+#[test]
+fn tolerate_partial_ends_cleanly_on_truncated_input() {
+    let input_path = temp_path("truncated-test.bin");
+    let events = [key_ev(0, KEY_A, 1), key_ev(50_000, KEY_A, 0)];
+    let mut capture = events_to_bytes(&[events[0], events[1], key_ev(90_000, KEY_B, 1)]);
+    capture.truncate(capture.len() - 10); // Cut the last event short
+    std::fs::write(&input_path, &capture).unwrap();
+
+    // Strict by default: the truncated event is a read error.
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .arg("--input")
+        .arg(&input_path)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("EOF reached mid-event"));
+
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .arg("--input")
+        .arg(&input_path)
+        .arg("--tolerate-partial")
+        .env("RUST_LOG", "intercept_bounce=info")
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(output.stdout, events_to_bytes(&events));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Discarding partial event at end of input"),
+        "{stderr}"
+    );
+    let size = size_of::<input_event>();
+    assert!(
+        stderr.contains(&format!("after {} of {size} bytes", size - 10)),
+        "{stderr}"
+    );
+    assert!(stderr.contains("partial_events_discarded=1"), "{stderr}");
+}
+
+#[test]
+fn grab_fails_cleanly_on_non_evdev_input() {
+    let input_path = temp_path("grab-test.bin");
+    std::fs::write(&input_path, events_to_bytes(&[key_ev(0, KEY_A, 1)])).unwrap();
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--input").arg(&input_path).arg("--grab");
+    let output = cmd.output().unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(
+        output.stdout.is_empty(),
+        "Nothing may be filtered without the grab"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Cannot grab"));
+
+    // Grabbing only makes sense for a device opened with --input.
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--grab").write_stdin(Vec::new());
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--input"));
+}
+
+#[test]
+fn expect_events_fails_on_empty_input() {
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--expect-events").write_stdin(Vec::new());
+    cmd.assert()
+        .code(5)
+        .stderr(predicate::str::contains("No key events were received"))
+        .stderr(predicate::str::contains("Key Events Processed: 0"));
+
+    // Any key event satisfies it.
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--expect-events")
+        .write_stdin(events_to_bytes(&[key_ev(0, KEY_A, 1)]));
+    cmd.assert().success();
+}
+
+/// Runs the filter with stdout on a non-blocking pipe that is never read, so once
+/// the pipe buffer fills, every further write fails with EAGAIN. Returns the exit
+/// code, what reached the pipe and stderr.
+fn run_with_full_stdout(input: Vec<u8>, write_error: &str) -> (Option<i32>, Vec<u8>, String) {
+    use std::io::Read;
+    use std::os::unix::io::FromRawFd;
+    use std::process::Stdio;
+
+    let mut fds = [0 as libc::c_int; 2];
+    let res = unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_NONBLOCK | libc::O_CLOEXEC) };
+    assert_eq!(res, 0, "pipe2 failed: {}", std::io::Error::last_os_error());
+    // Safety: pipe2 returned two fresh descriptors that we now own.
+    let mut read_end = unsafe { std::fs::File::from_raw_fd(fds[0]) };
+    let write_end = unsafe { std::fs::File::from_raw_fd(fds[1]) };
+
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("intercept-bounce"))
+        .args(["--debounce-time", "5ms", "--write-error", write_error])
+        .env("RUST_LOG", "intercept_bounce=info")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::from(write_end))
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn intercept-bounce");
+    let mut stdin = child.stdin.take().unwrap();
+    let writer = std::thread::spawn(move || {
+        // The child may stop reading early (abort), so a broken pipe is expected.
+        let _ = stdin.write_all(&input);
+    });
+    let output = child.wait_with_output().expect("Failed to wait for child");
+    writer.join().unwrap();
+
+    let mut stdout = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        match read_end.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => stdout.extend_from_slice(&buf[..n]),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+            Err(e) => panic!("Failed to read stdout pipe: {e}"),
+        }
+    }
+    (
+        output.status.code(),
+        stdout,
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+#[test]
+fn write_error_skip_keeps_running_through_failed_writes() {
+    // More passing events than a pipe buffer (64 KiB) holds.
+    let events: Vec<input_event> = (0..4000u64)
+        .map(|i| key_ev(i * 100_000, KEY_A, (i % 2) as i32))
+        .collect();
+    let input = events_to_bytes(&events);
+
+    let (code, stdout, stderr) = run_with_full_stdout(input.clone(), "abort");
+    assert_eq!(code, Some(1), "abort should end the run: {stderr}");
+    assert!(stderr.contains("Stdout write error"), "{stderr}");
+
+    let (code, stdout_skip, stderr) = run_with_full_stdout(input.clone(), "skip");
+    assert_eq!(code, Some(0), "skip should keep running: {stderr}");
+    assert!(
+        stderr.contains("event lost (--write-error skip)"),
+        "{stderr}"
+    );
+    // Every event was still processed; those that fit went out intact.
+    assert!(stderr.contains("events_passed=4000"), "{stderr}");
+    assert!(!stdout_skip.is_empty() && stdout_skip.len() < input.len());
+    assert_eq!(stdout_skip, input[..stdout_skip.len()]);
+    assert_eq!(stdout_skip.len(), stdout.len());
+}
+
+#[test]
+fn write_error_skip_aborts_on_a_torn_event() {
+    use std::os::unix::process::CommandExt;
+
+    // A file size limit that isn't a whole number of events: the fifth event is
+    // cut off after 4 bytes, then the write fails with EFBIG.
+    let limit = 4 * size_of::<input_event>() as u64 + 4;
+    let in_path = temp_path("torn-write-in.bin");
+    let out_path = temp_path("torn-write-out.bin");
+    let events: Vec<input_event> = (0..10u64)
+        .map(|i| key_ev(i * 100_000, KEY_A, ((i + 1) % 2) as i32))
+        .collect();
+    std::fs::write(&in_path, events_to_bytes(&events)).unwrap();
+
+    let mut cmd = std::process::Command::new(assert_cmd::cargo::cargo_bin("intercept-bounce"));
+    cmd.args(["--write-error", "skip"])
+        .stdin(std::fs::File::open(&in_path).unwrap())
+        .stdout(std::fs::File::create(&out_path).unwrap());
+    // Safety: only async-signal-safe calls between fork and exec.
+    unsafe {
+        cmd.pre_exec(move || {
+            let rlim = libc::rlimit {
+                rlim_cur: limit,
+                rlim_max: limit,
+            };
+            if libc::setrlimit(libc::RLIMIT_FSIZE, &rlim) != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            // Get EFBIG instead of being killed by SIGXFSZ.
+            libc::signal(libc::SIGXFSZ, libc::SIG_IGN);
+            Ok(())
+        });
+    }
+    let output = cmd.output().unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    assert!(
+        stderr.contains("Stdout write failed partway through an event"),
+        "{stderr}"
+    );
+    assert!(
+        !stderr.contains("event lost (--write-error skip)"),
+        "{stderr}"
+    );
+    assert_eq!(std::fs::metadata(&out_path).unwrap().len(), limit);
+}
+
+/// Builds one raw `input_event` record field by field, independent of the struct layout.
+fn raw_event_record(sec: i64, usec: i64, type_: u16, code: u16, value: i32) -> Vec<u8> {
+    let mut record = Vec::with_capacity(size_of::<input_event>());
+    record.extend_from_slice(&(sec as libc::time_t).to_ne_bytes());
+    record.extend_from_slice(&(usec as libc::suseconds_t).to_ne_bytes());
+    record.extend_from_slice(&type_.to_ne_bytes());
+    record.extend_from_slice(&code.to_ne_bytes());
+    record.extend_from_slice(&value.to_ne_bytes());
+    assert_eq!(record.len(), size_of::<input_event>());
+    record
+}
+
+#[test]
+fn passed_events_are_written_byte_identical() {
+    // Unusual but valid field contents: large seconds, odd microseconds,
+    // scan codes, out-of-range key values and arbitrary misc payloads.
+    let press = raw_event_record(0x1234_5678, 999_999, EV_KEY as u16, KEY_A, 1);
+    let bounce = raw_event_record(0x1234_5679, 1, EV_KEY as u16, KEY_A, 1); // 2us later
+    let release = raw_event_record(0x1234_567a, 654_321, EV_KEY as u16, KEY_A, 0);
+    let scan = raw_event_record(0x1234_567a, 654_321, EV_MSC as u16, 4, 0x0007_0004);
+    let odd_value = raw_event_record(0x1234_567b, 7, EV_KEY as u16, KEY_B, -0x5a5a_5a5a);
+    let syn = raw_event_record(0x1234_567b, 7, EV_SYN as u16, SYN_REPORT as u16, 0);
+
+    let input: Vec<u8> = [&press[..], &bounce, &release, &scan, &odd_value, &syn].concat();
+    let expected: Vec<u8> = [&press[..], &release, &scan, &odd_value, &syn].concat();
+
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--debounce-time", "25ms"])
+        .env("RUST_LOG", "warn")
+        .write_stdin(input)
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        output.stdout, expected,
+        "Passed events must reach stdout byte for byte"
+    );
+}
+
+#[test]
+fn sweep_prints_a_drop_table_per_debounce_time() {
+    let events = [
+        key_ev(0, KEY_A, 1),
+        key_ev(4_000, KEY_A, 1), // Dropped from 5ms up
+        key_ev(80_000, KEY_A, 0),
+        key_ev(92_000, KEY_A, 0), // Dropped from 15ms up
+    ];
+
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--sweep", "15ms,5ms,10ms"])
+        .env("RUST_LOG", "warn")
+        .write_stdin(events_to_bytes(&events))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let rows: Vec<&str> = stdout.lines().collect();
+    assert_eq!(rows[0], "--- Debounce Time Sweep (4 key events) ---");
+    assert_eq!(
+        rows[2].split_whitespace().collect::<Vec<_>>(),
+        ["5ms", "1", "25.00%"]
+    );
+    assert_eq!(
+        rows[3].split_whitespace().collect::<Vec<_>>(),
+        ["10ms", "1", "25.00%"]
+    );
+    assert_eq!(
+        rows[4].split_whitespace().collect::<Vec<_>>(),
+        ["15ms", "2", "50.00%"]
+    );
+
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--sweep", "5ms,15ms", "--stats-json"])
+        .env("RUST_LOG", "warn")
+        .write_stdin(events_to_bytes(&events))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["debounce_us"], 5_000);
+    assert_eq!(json[0]["key_events_dropped"], 1);
+    assert_eq!(json[1]["debounce_us"], 15_000);
+    assert_eq!(json[1]["key_events_dropped"], 2);
+}
+
+#[test]
+fn drop_exact_duplicates_collapses_identical_events() {
+    let press = key_ev(1_000, KEY_A, 1);
+    let syn = non_key_ev(1_000);
+    let release = key_ev(90_000, KEY_A, 0);
+    let input = [press, press, syn, release, non_key_ev(90_000)];
+    let expected = [press, syn, release, non_key_ev(90_000)];
+
+    let mut cmd = Command::cargo_bin("intercept-bounce").unwrap();
+    cmd.arg("--debounce-time")
+        .arg("0ms")
+        .arg("--drop-exact-duplicates")
+        .env("RUST_LOG", "warn")
+        .write_stdin(events_to_bytes(&input));
+    cmd.assert().success().stdout(events_to_bytes(&expected));
+}
+
+#[test]
+fn passthrough_types_drops_unlisted_event_types() {
+    const REL_WHEEL: u16 = 8;
+    let key = key_ev(0, KEY_A, 1);
+    let wheel = rel_ev(1_000, REL_WHEEL, 1);
+    let syn = non_key_ev(1_000);
+    let key_release = key_ev(50_000, KEY_A, 0);
+    let input_events = vec![key, syn, wheel, syn, key_release, syn];
+    let expected_events = vec![key, syn, syn, key_release, syn];
+
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--passthrough-types", "EV_KEY,EV_SYN"])
+        .env("RUST_LOG", "info")
+        .write_stdin(events_to_bytes(&input_events))
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        output.stdout,
+        events_to_bytes(&expected_events),
+        "EV_REL event was not dropped from the output"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("events_type_filtered=1"),
+        "Missing filtered count in output totals: {stderr}"
+    );
+    // Nothing was lost that the filter passed.
+    assert!(!stderr.contains("Passed events that never reached stdout"));
+}
+
+#[test]
+fn key_events_only_writes_just_key_events_and_synthesized_syns() {
+    const REL_WHEEL: u16 = 8;
+    let press = key_ev(0, KEY_A, 1);
+    let bounce = key_ev(3_000, KEY_A, 1);
+    let wheel = rel_ev(10_000, REL_WHEEL, 1);
+    let release = key_ev(50_000, KEY_A, 0);
+    let input_events = vec![
+        press,
+        non_key_ev(0),
+        bounce,
+        non_key_ev(3_000),
+        wheel,
+        non_key_ev(10_000),
+        release,
+        non_key_ev(50_000),
+    ];
+    let run = |extra_args: &[&str]| {
+        let output = Command::cargo_bin("intercept-bounce")
+            .unwrap()
+            .arg("--key-events-only")
+            .args(extra_args)
+            .env("RUST_LOG", "warn")
+            .write_stdin(events_to_bytes(&input_events))
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success(), "{output:?}");
+        output.stdout
+    };
+
+    assert_eq!(run(&[]), events_to_bytes(&[press, release]));
+    assert_eq!(
+        run(&["--synthesize-syn"]),
+        events_to_bytes(&[press, non_key_ev(0), release, non_key_ev(50_000)])
+    );
+}
+
+#[test]
+fn warn_near_miss_logs_near_miss_passes() {
+    // The second press passes 40ms after the first: outside the 10ms debounce
+    // time, inside the default 100ms near-miss threshold.
+    let input_events = vec![
+        key_ev(0, KEY_A, 1),
+        key_ev(20_000, KEY_A, 0),
+        key_ev(40_000, KEY_A, 1),
+        key_ev(500_000, KEY_A, 0),
+    ];
+    let run = |extra_args: &[&str]| {
+        let output = Command::cargo_bin("intercept-bounce")
+            .unwrap()
+            .args(["--debounce-time", "10ms"])
+            .args(extra_args)
+            .env("RUST_LOG", "warn")
+            .write_stdin(events_to_bytes(&input_events))
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    let stderr = run(&["--warn-near-miss"]);
+    assert_eq!(stderr.matches("[NEAR-MISS]").count(), 1, "{stderr}");
+    assert!(
+        stderr.contains("WARN") && stderr.contains("Key [KEY_A] (30, Press) passed 40.0 ms"),
+        "Missing near-miss warning: {stderr}"
+    );
+
+    let stderr = run(&[]);
+    assert!(!stderr.contains("[NEAR-MISS]"), "{stderr}");
+}
+
+#[test]
+fn key_aliases_rename_keys_in_the_human_report() {
+    let aliases_path = temp_path("aliases-test.toml");
+    std::fs::write(&aliases_path, "KEY_A = \"Alpha\"\n48 = \"Bravo\"\n").unwrap();
+
+    let events = [
+        key_ev(0, KEY_A, 1),
+        key_ev(3_000, KEY_A, 1), // Bounce
+        key_ev(50_000, KEY_A, 0),
+        key_ev(100_000, KEY_C, 1),
+    ];
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .arg("--key-aliases")
+        .arg(&aliases_path)
+        .args(["--debounce-time", "5ms", "--log-bounces"])
+        .env("RUST_LOG", "info")
+        .write_stdin(events_to_bytes(&events))
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("\nKey [Alpha] (30):"), "{stderr}");
+    assert!(stderr.contains("1. Alpha (30): 1 dropped"), "{stderr}");
+    // The --log-bounces line uses the alias too.
+    assert!(
+        stderr
+            .lines()
+            .any(|line| line.contains("[DROP]") && line.contains("Key [Alpha] (30)")),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("KEY_A"), "{stderr}");
+    // Keys without an alias keep their canonical name.
+    assert!(stderr.contains("Key [KEY_C] (46):"), "{stderr}");
+}
+
+#[test]
+fn unknown_key_in_aliases_file_is_a_config_error() {
+    let aliases_path = temp_path("bad-aliases-test.toml");
+    std::fs::write(&aliases_path, "KEY_NOPE = \"Nope\"\n").unwrap();
+
+    Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .arg("--key-aliases")
+        .arg(&aliases_path)
+        .write_stdin(Vec::new())
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("Unknown key 'KEY_NOPE'"));
+}
+
+/// Runs the filter with a 10ms debounce time and returns the final JSON report.
+fn final_json_stats(events: &[input_event]) -> Value {
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--debounce-time", "10ms", "--stats-json"])
+        .env("RUST_LOG", "warn")
+        .write_stdin(events_to_bytes(events))
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let json_start = stderr.find('{').expect("No JSON stats in stderr");
+    serde_json::Deserializer::from_str(&stderr[json_start..])
+        .into_iter::<Value>()
+        .next()
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn detects_double_character_chatter() {
+    // One keystroke that chattered: the spurious release and re-press are 2ms
+    // apart. The re-press is dropped, so nothing was typed twice.
+    let caught = final_json_stats(&[
+        key_ev(0, KEY_A, 1),
+        key_ev(3_000, KEY_A, 0),
+        key_ev(5_000, KEY_A, 1),
+        key_ev(80_000, KEY_A, 0),
+    ]);
+    assert_eq!(caught["double_characters"], 1);
+    assert_eq!(caught["double_characters_passed"], 0);
+    assert_eq!(caught["per_key_stats"][0]["double_characters"], 1);
+
+    // The same signature mid-hold, with the re-press outside the debounce time
+    // of the first press: it passes and the user sees "aa".
+    let typed_twice = final_json_stats(&[
+        key_ev(0, KEY_A, 1),
+        key_ev(40_000, KEY_A, 0),
+        key_ev(45_000, KEY_A, 1),
+        key_ev(100_000, KEY_A, 0),
+    ]);
+    assert_eq!(typed_twice["double_characters"], 1);
+    assert_eq!(typed_twice["double_characters_passed"], 1);
+}
+
+#[test]
+fn deliberate_double_tap_is_not_a_double_character() {
+    // A real double tap: 120ms between release and re-press.
+    let stats = final_json_stats(&[
+        key_ev(0, KEY_A, 1),
+        key_ev(80_000, KEY_A, 0),
+        key_ev(200_000, KEY_A, 1),
+        key_ev(280_000, KEY_A, 0),
+    ]);
+    assert_eq!(stats["double_characters"], 0);
+    assert_eq!(stats["double_characters_passed"], 0);
+}
+
+#[test]
+fn no_stats_filters_without_reporting() {
+    let input_events = vec![
+        key_ev(0, KEY_A, 1),
+        key_ev(3_000, KEY_A, 1), // Bounce
+        key_ev(50_000, KEY_A, 0),
+    ];
+    let expected_events = vec![key_ev(0, KEY_A, 1), key_ev(50_000, KEY_A, 0)];
+
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--debounce-time", "10ms", "--no-stats"])
+        .write_stdin(events_to_bytes(&input_events))
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(output.stdout, events_to_bytes(&expected_events));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("Overall Statistics"), "{stderr}");
+
+    // Statistics options make no sense without statistics.
+    Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--no-stats", "--stats-json"])
+        .write_stdin(Vec::new())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn strict_values_drops_invalid_key_values() {
+    let input_events = vec![
+        key_ev(0, KEY_A, 1),
+        key_ev(100_000, KEY_A, 5), // Not a valid key value
+        key_ev(200_000, KEY_A, 0),
+    ];
+
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--debounce-time", "10ms", "--strict-values"])
+        .env("RUST_LOG", "info")
+        .write_stdin(events_to_bytes(&input_events))
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        output.stdout,
+        events_to_bytes(&[input_events[0], input_events[2]])
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Dropping key event with invalid value"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("invalid_values_dropped=1"), "{stderr}");
+
+    // Without --strict-values the event passes and counts as processed, but
+    // fits none of the per-key press/release/repeat lines.
+    let stats = final_json_stats(&input_events);
+    assert_eq!(stats["key_events_processed"], 3);
+    assert_eq!(stats["key_events_passed"], 2);
+    let key_a = &stats["per_key_stats"][0]["stats"];
+    assert_eq!(key_a["press"]["total_processed"], 1);
+    assert_eq!(key_a["release"]["total_processed"], 1);
+    assert_eq!(key_a["repeat"]["total_processed"], 0);
+}
+
+#[test]
+fn self_test_reports_success_without_input() {
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .arg("--self-test")
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("PASS KEY_A Press at 5ms is dropped"),
+        "{stdout}"
+    );
+    assert!(!stdout.contains("FAIL"), "{stdout}");
+    assert!(stdout.contains("Self-test passed"), "{stdout}");
+}
+
+/// Runs the filter with its stdout closed by the reader before any event is
+/// written, so the first write fails with a broken pipe. Returns stderr.
+fn run_into_broken_pipe(extra_args: &[&str]) -> String {
+    use std::io::{Read, Write};
+    use std::process::{Command as StdCommand, Stdio};
+
+    let mut child = StdCommand::new(assert_cmd::cargo::cargo_bin("intercept-bounce"))
+        .args(extra_args)
+        .env("RUST_LOG", "info")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn intercept-bounce");
+    drop(child.stdout.take());
+
+    let events: Vec<_> = (0..100)
+        .map(|i| key_ev(i * 100_000, KEY_A, (i % 2) as i32))
+        .collect();
+    // The child may exit before all input is written.
+    let _ = child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(&events_to_bytes(&events));
+    let mut stderr = String::new();
+    child
+        .stderr
+        .take()
+        .unwrap()
+        .read_to_string(&mut stderr)
+        .unwrap();
+    assert!(child.wait().unwrap().success(), "{stderr}");
+    assert!(stderr.contains("Stdout pipe broken"), "{stderr}");
+    stderr
+}
+
+#[test]
+fn no_stats_on_broken_pipe_skips_final_statistics() {
+    let stderr = run_into_broken_pipe(&[]);
+    assert!(
+        stderr.contains("Overall Statistics (Cumulative)"),
+        "{stderr}"
+    );
+
+    let stderr = run_into_broken_pipe(&["--no-stats-on-broken-pipe"]);
+    assert!(!stderr.contains("Overall Statistics"), "{stderr}");
+    assert!(stderr.contains("skipping final statistics"), "{stderr}");
+}
+
+#[test]
+fn final_report_carries_the_shutdown_reason() {
+    use std::io::{BufRead, BufReader, Read};
+    use std::process::{Command as StdCommand, Stdio};
+
+    // End of input.
+    let stats = final_json_stats(&[key_ev(0, KEY_A, 1), key_ev(50_000, KEY_A, 0)]);
+    assert_eq!(stats["shutdown_reason"], "EOF");
+
+    // A terminating signal while the input is still open.
+    let mut child = StdCommand::new(assert_cmd::cargo::cargo_bin("intercept-bounce"))
+        .env("RUST_LOG", "intercept_bounce=info")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn intercept-bounce");
+    let mut stderr = BufReader::new(child.stderr.take().unwrap());
+    let mut stderr_str = String::new();
+    // The signal handler is installed before the main loop starts.
+    while !stderr_str.contains("Starting main event loop") {
+        let n = stderr.read_line(&mut stderr_str).unwrap();
+        assert!(n > 0, "stderr closed early: {stderr_str}");
+    }
+    unsafe { libc::kill(child.id() as libc::pid_t, libc::SIGINT) };
+    while !stderr_str.contains("Initiating shutdown") {
+        let n = stderr.read_line(&mut stderr_str).unwrap();
+        assert!(n > 0, "stderr closed early: {stderr_str}");
+    }
+    // The blocking read only returns once the input closes.
+    drop(child.stdin.take());
+    stderr.read_to_string(&mut stderr_str).unwrap();
+    assert!(child.wait().unwrap().success(), "{stderr_str}");
+
+    assert!(
+        stderr_str.contains("Shutdown reason: SIGINT"),
+        "{stderr_str}"
+    );
+}
+
+/// Runs the filter with a test hook (the `test-hooks` feature) that makes the
+/// logger thread panic on the first KEY_B event, followed by KEY_A presses and
+/// releases once it has.
+#[cfg(feature = "test-hooks")]
+fn run_with_logger_panic(extra_args: &[&str]) -> Output {
+    use std::process::{Command as StdCommand, Stdio};
+
+    let mut child = StdCommand::new(assert_cmd::cargo::cargo_bin("intercept-bounce"))
+        .args(extra_args)
+        .env("RUST_LOG", "intercept_bounce=info")
+        .env("INTERCEPT_BOUNCE_TEST_PANIC_ON_KEY", KEY_B.to_string())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn intercept-bounce");
+    let mut stdin = child.stdin.take().unwrap();
+    stdin
+        .write_all(&events_to_bytes(&[key_ev(0, KEY_B, 1)]))
+        .unwrap();
+    // Let the logger thread pick up the event and die before more arrive.
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    let events: Vec<_> = (0..4)
+        .map(|i| key_ev(1_000_000 + i * 100_000, KEY_A, ((i + 1) % 2) as i32))
+        .collect();
+    // Without a restart, the child stops at the first event after the panic.
+    let _ = stdin.write_all(&events_to_bytes(&events));
+    drop(stdin);
+    child.wait_with_output().unwrap()
+}
+
+#[cfg(feature = "test-hooks")]
+#[test]
+fn logger_panic_is_fatal_unless_the_logger_is_restarted() {
+    let output = run_with_logger_panic(&[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    assert!(stderr.contains("Logger thread error"), "{stderr}");
+
+    let output = run_with_logger_panic(&["--restart-logger"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(stderr.contains("Restarted the logger thread"), "{stderr}");
+    // Every event was still filtered and written.
+    assert_eq!(output.stdout.len(), 5 * size_of::<input_event>());
+    // The restarted logger missed the event that found the old one gone.
+    assert!(stderr.contains("Key Events Processed: 3"), "{stderr}");
+
+    // Accepted on its own, where a panic is already fatal.
+    let output = run_with_logger_panic(&["--fail-on-logger-panic"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    assert!(stderr.contains("Logger thread error"), "{stderr}");
+
+    let output = run_with_logger_panic(&["--restart-logger", "--fail-on-logger-panic"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(1), "{stderr}");
+    assert_eq!(output.stdout.len(), 5 * size_of::<input_event>());
+    assert!(stderr.contains("panicked and was restarted"), "{stderr}");
+}
+
+#[test]
+fn microsecond_debounce_time_is_applied_exactly() {
+    let input_events = [
+        key_ev(0, KEY_A, 1),
+        key_ev(749, KEY_A, 1), // 749µs after the press: dropped
+        key_ev(100_000, KEY_A, 0),
+        key_ev(100_750, KEY_A, 0), // Exactly 750µs after the release: passed
+    ];
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .args(["--debounce-time", "750us", "--stats-json"])
+        .env("RUST_LOG", "warn")
+        .write_stdin(events_to_bytes(&input_events))
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        output.stdout,
+        events_to_bytes(&[input_events[0], input_events[2], input_events[3]])
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let json_start = stderr.find('{').expect("No JSON stats in stderr");
+    let stats: Value = serde_json::Deserializer::from_str(&stderr[json_start..])
+        .into_iter::<Value>()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(stats["debounce_time_us"], 750);
+    assert_eq!(stats["key_events_dropped"], 1);
+}
+
+#[test]
+fn drop_orphan_scancodes_drops_the_scancode_of_a_dropped_key() {
+    let scan = |ts_us: u64| {
+        let mut ev = non_key_ev(ts_us);
+        ev.type_ = EV_MSC as u16;
+        ev.code = 4; // MSC_SCAN
+        ev.value = 0x0007_0004;
+        ev
+    };
+    let input_events = [
+        scan(0),
+        key_ev(0, KEY_A, 1),
+        non_key_ev(0),
+        scan(5_000),
+        key_ev(5_000, KEY_A, 1), // Bounce
+        non_key_ev(5_000),
+    ];
+    let run = |extra_args: &[&str]| {
+        let output = Command::cargo_bin("intercept-bounce")
+            .unwrap()
+            .args(["--debounce-time", "10ms"])
+            .args(extra_args)
+            .env("RUST_LOG", "warn")
+            .write_stdin(events_to_bytes(&input_events))
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success(), "{output:?}");
+        output.stdout
+    };
+
+    // The scancode of the bounce is passed through on its own by default...
+    assert_eq!(
+        run(&[]),
+        events_to_bytes(&[
+            input_events[0],
+            input_events[1],
+            input_events[2],
+            input_events[3],
+            input_events[5],
+        ])
+    );
+    // ...and dropped together with it under --drop-orphan-scancodes.
+    assert_eq!(
+        run(&["--drop-orphan-scancodes"]),
+        events_to_bytes(&[
+            input_events[0],
+            input_events[1],
+            input_events[2],
+            input_events[5],
+        ])
+    );
+}
+
+#[test]
+fn warns_about_zero_or_identical_timestamps() {
+    let run = |events: &[input_event]| {
+        let output = Command::cargo_bin("intercept-bounce")
+            .unwrap()
+            .env("RUST_LOG", "warn")
+            .write_stdin(events_to_bytes(events))
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    };
+    let warning = "Most key events have zero or identical timestamps";
+
+    let zero: Vec<_> = (0..20).map(|i| key_ev(0, KEY_A, i % 2)).collect();
+    let stderr = run(&zero);
+    assert!(stderr.contains(warning), "{stderr}");
+    assert_eq!(stderr.matches(warning).count(), 1, "{stderr}");
+
+    // A short run with one frozen timestamp is judged at exit.
+    let frozen: Vec<_> = (0..4).map(|i| key_ev(5_000_000, KEY_A, i % 2)).collect();
+    let stderr = run(&frozen);
+    assert!(stderr.contains(warning), "{stderr}");
+
+    let normal: Vec<_> = (0..20)
+        .map(|i| key_ev(i * 100_000, KEY_A, (i % 2) as i32))
+        .collect();
+    let stderr = run(&normal);
+    assert!(!stderr.contains(warning), "{stderr}");
+}
+
+#[test]
+fn stats_stream_fd_pushes_length_prefixed_frames() {
+    use std::fs::File;
+    use std::io::Read;
+    use std::os::fd::FromRawFd;
+    use std::os::unix::process::CommandExt;
+    use std::process::{Command as StdCommand, Stdio};
+
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+    let [read_fd, write_fd] = fds;
+    let mut command = StdCommand::new(assert_cmd::cargo::cargo_bin("intercept-bounce"));
+    command
+        .args(["--debounce-time", "10ms", "--stats-stream-interval", "20ms"])
+        .arg("--stats-stream-fd")
+        .arg(write_fd.to_string())
+        .env("RUST_LOG", "warn")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Only the child gets the write end, so the pipe ends when the child exits.
+    unsafe {
+        command.pre_exec(move || {
+            if libc::fcntl(write_fd, libc::F_SETFD, 0) < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let mut child = command.spawn().expect("Failed to spawn intercept-bounce");
+    unsafe { libc::close(write_fd) };
+    let mut frames = unsafe { File::from_raw_fd(read_fd) };
+
+    let read_frame = |frames: &mut File| -> Option<Value> {
+        let mut len = [0u8; 4];
+        frames.read_exact(&mut len).ok()?;
+        let mut body = vec![0u8; u32::from_be_bytes(len) as usize];
+        frames.read_exact(&mut body).unwrap();
+        Some(serde_json::from_slice(&body).expect("Frame is not JSON"))
+    };
+
+    let mut stdin = child.stdin.take().unwrap();
+    stdin
+        .write_all(&events_to_bytes(&[
+            key_ev(0, KEY_A, 1),
+            key_ev(3_000, KEY_A, 1), // Bounce
+        ]))
+        .unwrap();
+    // Two periodic frames arrive while the input is still open.
+    let first = read_frame(&mut frames).expect("No first frame");
+    let second = read_frame(&mut frames).expect("No second frame");
+    assert_eq!(first["seq"], 0);
+    assert_eq!(second["seq"], 1);
+
+    drop(stdin);
+    let mut last = second;
+    while let Some(frame) = read_frame(&mut frames) {
+        last = frame;
+    }
+    assert!(child.wait().unwrap().success());
+    assert_eq!(last["key_events_processed"], 2);
+    assert_eq!(last["key_events_dropped"], 1);
+    assert_eq!(last["keys"][0]["key_name"], "KEY_A");
+    assert_eq!(last["keys"][0]["total_dropped"], 1);
+}
+
+#[test]
+fn tap_mirrors_only_the_tapped_keys_events() {
+    use std::fs::File;
+    use std::io::Read;
+    use std::os::fd::FromRawFd;
+    use std::os::unix::process::CommandExt;
+    use std::process::{Command as StdCommand, Stdio};
+
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe2(fds.as_mut_ptr(), libc::O_CLOEXEC) }, 0);
+    let [read_fd, write_fd] = fds;
+    let mut command = StdCommand::new(assert_cmd::cargo::cargo_bin("intercept-bounce"));
+    command
+        .args([
+            "--debounce-time",
+            "10ms",
+            "--tap",
+            "KEY_A",
+            "--tap",
+            "KEY_C",
+        ])
+        .arg("--tap-fd")
+        .arg(write_fd.to_string())
+        .env("RUST_LOG", "warn")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    // Only the child gets the write end, so the pipe ends when the child exits.
+    unsafe {
+        command.pre_exec(move || {
+            if libc::fcntl(write_fd, libc::F_SETFD, 0) < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+    let mut child = command.spawn().expect("Failed to spawn intercept-bounce");
+    unsafe { libc::close(write_fd) };
+    let mut tap = unsafe { File::from_raw_fd(read_fd) };
+
+    let events = [
+        key_ev(0, KEY_A, 1),
+        key_ev(3_000, KEY_A, 1), // Bounce
+        key_ev(20_000, KEY_B, 1),
+        non_key_ev(20_000),
+        key_ev(40_000, KEY_C, 1),
+        key_ev(60_000, KEY_B, 0),
+    ];
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(&events_to_bytes(&events))
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let mut tapped = Vec::new();
+    tap.read_to_end(&mut tapped).unwrap();
+
+    // The tap sees KEY_A's bounce too; stdout is filtered as usual.
+    assert_eq!(tapped, events_to_bytes(&[events[0], events[1], events[4]]));
+    assert_eq!(
+        output.stdout,
+        events_to_bytes(&[events[0], events[2], events[3], events[4], events[5]])
+    );
+}
+
+#[test]
+fn max_runtime_stops_the_filter_with_the_input_still_open() {
+    use std::io::Read;
+    use std::process::{Command as StdCommand, Stdio};
+    use std::time::{Duration, Instant};
+
+    let started = Instant::now();
+    let mut child = StdCommand::new(assert_cmd::cargo::cargo_bin("intercept-bounce"))
+        .args(["--max-runtime", "300ms", "--debounce-time", "10ms"])
+        .env("RUST_LOG", "warn")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to spawn intercept-bounce");
+    // Held open until the end of the test: only the runtime limit can stop the run.
+    let mut stdin = child.stdin.take().unwrap();
+    let input_events = [key_ev(0, KEY_A, 1), key_ev(3_000, KEY_A, 1)];
+    stdin.write_all(&events_to_bytes(&input_events)).unwrap();
+
+    let mut stderr = String::new();
+    child
+        .stderr
+        .take()
+        .unwrap()
+        .read_to_string(&mut stderr)
+        .unwrap();
+    let mut stdout = Vec::new();
+    child
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_end(&mut stdout)
+        .unwrap();
+    assert!(child.wait().unwrap().success(), "{stderr}");
+    assert!(started.elapsed() >= Duration::from_millis(300));
+    drop(stdin);
+
+    assert_eq!(stdout, events_to_bytes(&input_events[..1]));
+    assert!(
+        stderr.contains("--- Overall Statistics"),
+        "No final statistics: {stderr}"
+    );
+    assert!(stderr.contains("Shutdown reason: max runtime"), "{stderr}");
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn no_priority_env_skips_the_niceness_attempt() {
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .env("RUST_LOG", "debug")
+        .env("INTERCEPT_BOUNCE_NO_PRIORITY", "1")
+        .write_stdin(events_to_bytes(&[key_ev(0, KEY_A, 1)]))
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("INTERCEPT_BOUNCE_NO_PRIORITY=1"),
+        "{stderr}"
+    );
+    assert!(
+        !stderr.contains("Unable to set process niceness"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("Process priority set"), "{stderr}");
+}
+
+#[cfg(feature = "test-hooks")]
+#[test]
+fn heavy_logger_backpressure_reports_the_lost_percentage() {
+    // Far more events than the logger channel holds, with a logger that sleeps
+    // before each message (a `test-hooks` hook), so most of them never reach it.
+    let events: Vec<_> = (0..4_000)
+        .map(|i| key_ev(i * 100_000, KEY_A, ((i + 1) % 2) as i32))
+        .collect();
+    let output = Command::cargo_bin("intercept-bounce")
+        .unwrap()
+        .env("RUST_LOG", "intercept_bounce=info")
+        .env("INTERCEPT_BOUNCE_TEST_LOGGER_DELAY_US", "200")
+        .write_stdin(events_to_bytes(&events))
+        .output()
+        .expect("Failed to execute command");
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(stderr.contains("STATISTICS ARE UNRELIABLE: "), "{stderr}");
+    assert!(
+        stderr.contains("% of events never reached the logger"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("events_processed=4000"), "{stderr}");
+}
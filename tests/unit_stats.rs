@@ -1,8 +1,11 @@
 //! Unit tests for the StatsCollector logic.
 
+use intercept_bounce::cli::StatsGranularity;
 use intercept_bounce::config::Config;
+use intercept_bounce::filter::keynames::pseudonym_codes;
 use intercept_bounce::filter::stats::{
-    StatsCollector, TimingHistogram, HISTOGRAM_BUCKET_BOUNDARIES_MS, NUM_HISTOGRAM_BUCKETS,
+    StatsCollector, TimingHistogram, WindowRatioHistogram, HISTOGRAM_BUCKET_BOUNDARIES_MS,
+    MAX_BOUNCE_TIMING_SAMPLES, NUM_HISTOGRAM_BUCKETS,
 };
 use intercept_bounce::logger::EventInfo;
 use serde_json::{json, Value};
@@ -223,6 +226,245 @@ fn stats_near_miss_custom_threshold() {
     ); // Diff between ev2 and ev1, and ev3 and ev2
 }
 
+#[test]
+fn stats_near_miss_bands_assign_each_diff_to_innermost_band() {
+    let mut stats = StatsCollector::with_capacity();
+    let mut config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(120));
+    config.near_miss_bands = vec![
+        Duration::from_millis(30),
+        Duration::from_millis(60),
+        Duration::from_millis(120),
+    ];
+
+    // 20ms -> band 0, 30ms (boundary) -> band 0, 45ms -> band 1,
+    // 100ms -> band 2, 150ms -> outside every band.
+    let diffs = [20_000, 30_000, 45_000, 100_000, 150_000];
+    let mut ts = 0;
+    stats
+        .record_event_info_with_config(&passed_event_info(key_ev(ts, KEY_A, 1), ts, None), &config);
+    for diff in diffs {
+        let last = ts;
+        ts += diff;
+        stats.record_event_info_with_config(
+            &passed_event_info(key_ev(ts, KEY_A, 1), ts, Some(last)),
+            &config,
+        );
+    }
+
+    let near_misses_stats = &stats.per_key_near_miss_stats[KEY_A as usize * 3 + 1];
+    assert_eq!(near_misses_stats.summary.count(), 4);
+    assert_eq!(near_misses_stats.band_counts, vec![2, 1, 1]);
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_stats_human_readable(&config, "Test", &mut writer)
+        .unwrap();
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    assert!(
+        output.contains("Bands: <=30ms: 2, <=60ms: 1, <=120ms: 1"),
+        "{output}"
+    );
+    assert!(
+        output.contains("Near misses by band: <=30ms: 2, <=60ms: 1, <=120ms: 1"),
+        "{output}"
+    );
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .print_stats_json(&config, None, "Test", &mut writer)
+        .unwrap();
+    let json_value: Value = serde_json::from_slice(&writer.into_inner()).unwrap();
+    assert_eq!(
+        json_value["near_miss_bands_us"],
+        json!([30_000, 60_000, 120_000])
+    );
+    assert_eq!(
+        json_value["per_key_near_miss_stats"][0]["band_counts"],
+        json!([2, 1, 1])
+    );
+}
+
+#[test]
+fn human_report_shows_recovered_passes_only_if_any() {
+    let mut stats = StatsCollector::with_capacity();
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+    let info = passed_event_info(key_ev(0, KEY_A, 1), 0, None);
+    stats.record_event_info_with_config(&info, &config);
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_stats_human_readable(&config, "Test", &mut writer)
+        .unwrap();
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    assert!(!output.contains("Recovered Passes"), "{output}");
+
+    stats.record_recovered_pass(&info);
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_stats_human_readable(&config, "Test", &mut writer)
+        .unwrap();
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    assert!(output.contains("Recovered Passes:    1"), "{output}");
+}
+
+#[test]
+fn stats_count_distinct_chattering_keys() {
+    let mut stats = StatsCollector::with_capacity();
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+
+    // KEY_A chatters once; KEY_B and KEY_C are clean.
+    let events = [
+        passed_event_info(key_ev(0, KEY_A, 1), 0, None),
+        bounced_event_info(key_ev(2_000, KEY_A, 1), 2_000, 2_000, Some(0)),
+        passed_event_info(key_ev(50_000, KEY_A, 0), 50_000, None),
+        passed_event_info(key_ev(100_000, KEY_B, 1), 100_000, None),
+        passed_event_info(key_ev(150_000, KEY_B, 0), 150_000, None),
+        passed_event_info(key_ev(200_000, KEY_C, 1), 200_000, None),
+    ];
+    for info in &events {
+        stats.record_event_info_with_config(info, &config);
+    }
+
+    assert_eq!(stats.chattering_keys(), (1, 3));
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_stats_human_readable(&config, "Test", &mut writer)
+        .unwrap();
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    assert!(
+        output.contains("Keys Chattering:     1 of 3 keys used"),
+        "{output}"
+    );
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .print_stats_json(&config, None, "Test", &mut writer)
+        .unwrap();
+    let json_value: Value = serde_json::from_slice(&writer.into_inner()).unwrap();
+    assert_eq!(json_value["keys_chattering"], 1);
+    assert_eq!(json_value["keys_used"], 3);
+}
+
+#[test]
+fn stats_key_inspection_reports_only_the_inspected_key() {
+    let mut stats = StatsCollector::with_capacity();
+    let mut config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+    config.inspect_key = Some(KEY_A);
+
+    let events = [
+        passed_event_info(key_ev(0, KEY_A, 1), 0, None),
+        bounced_event_info(key_ev(2_000, KEY_A, 1), 2_000, 2_000, Some(0)),
+        passed_event_info(key_ev(50_000, KEY_A, 0), 50_000, None),
+        passed_event_info(key_ev(100_000, KEY_B, 1), 100_000, None),
+        bounced_event_info(key_ev(101_000, KEY_B, 1), 101_000, 1_000, Some(100_000)),
+        passed_event_info(key_ev(150_000, KEY_B, 0), 150_000, None),
+    ];
+    for info in &events {
+        stats.record_event_info_with_config(info, &config);
+    }
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_key_inspection(&config, KEY_A, "Test", &mut writer)
+        .unwrap();
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    assert!(
+        output.contains("--- Key Inspection: [KEY_A] (30) (Test) ---"),
+        "{output}"
+    );
+    assert!(output.contains("Total Processed: 3, Passed: 2, Dropped: 1"));
+    assert!(output.contains("Press   (1): Processed: 2, Passed: 1, Dropped: 1"));
+    assert!(output.contains("--- Press Bounce Timing Histogram ---"));
+    assert!(output.contains("--- Release Near-Miss Timing Histogram"));
+    assert!(
+        output.contains("Bounce Percentiles: p50 2.0 ms"),
+        "{output}"
+    );
+    assert!(
+        output.contains("First Event: 0 µs, Last Event: 50.0 ms"),
+        "{output}"
+    );
+    assert!(output.contains("Recommended Debounce Time:"));
+    assert!(!output.contains("KEY_B"));
+    assert!(!output.contains("Overall Statistics"));
+}
+
+#[test]
+fn stats_json_report_returns_write_errors() {
+    /// Accepts the first `room` bytes, then fails like a full disk.
+    struct FullAfter {
+        room: usize,
+    }
+    impl std::io::Write for FullAfter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.room == 0 {
+                return Err(std::io::Error::from(std::io::ErrorKind::StorageFull));
+            }
+            let n = buf.len().min(self.room);
+            self.room -= n;
+            Ok(n)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let mut stats = StatsCollector::with_capacity();
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+    stats.record_event_info_with_config(&passed_event_info(key_ev(0, KEY_A, 1), 0, None), &config);
+
+    for room in [0, 100] {
+        let err = stats
+            .print_stats_json(&config, None, "Cumulative", FullAfter { room })
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::StorageFull, "{room}");
+        let err = stats
+            .export_stats_json(&config, None, "Cumulative", FullAfter { room })
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::StorageFull, "{room}");
+    }
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn stats_msgpack_report_round_trips_to_the_json_report() {
+    let mut stats = StatsCollector::with_capacity();
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+    let events = [
+        passed_event_info(key_ev(0, KEY_A, 1), 0, None),
+        bounced_event_info(key_ev(2_000, KEY_A, 1), 2_000, 2_000, Some(0)),
+        passed_event_info(key_ev(50_000, KEY_A, 0), 50_000, None),
+        passed_event_info(key_ev(80_000, KEY_A, 1), 80_000, Some(0)),
+        passed_event_info(key_ev(100_000, KEY_B, 1), 100_000, None),
+    ];
+    for info in &events {
+        stats.record_event_info_with_config(info, &config);
+    }
+
+    let mut json_writer = Cursor::new(Vec::new());
+    stats
+        .print_stats_json(&config, Some(1_500_000), "Cumulative", &mut json_writer)
+        .unwrap();
+    let from_json: Value = serde_json::from_slice(&json_writer.into_inner()).unwrap();
+
+    let mut msgpack_writer = Cursor::new(Vec::new());
+    stats
+        .print_stats_msgpack(&config, Some(1_500_000), "Cumulative", &mut msgpack_writer)
+        .unwrap();
+    let frame = msgpack_writer.into_inner();
+    let (len, body) = frame.split_at(4);
+    assert_eq!(
+        u32::from_be_bytes(len.try_into().unwrap()) as usize,
+        body.len()
+    );
+    let from_msgpack: Value = rmp_serde::from_slice(body).unwrap();
+
+    assert_eq!(from_msgpack["key_events_dropped"], 1);
+    assert_eq!(from_msgpack["per_key_stats"][0]["key_name"], "KEY_A");
+    assert_eq!(from_msgpack, from_json);
+}
+
 #[test]
 fn stats_ignores_non_key_events() {
     let mut stats = StatsCollector::with_capacity();
@@ -234,6 +476,8 @@ fn stats_ignores_non_key_events() {
         is_bounce: false, // Non-key events are never bounces
         diff_us: None,
         last_passed_us: None,
+        paused: false,
+        filter_latency_ns: None,
     };
 
     let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
@@ -287,7 +531,9 @@ fn stats_json_output_structure() {
 
     let mut buf = Vec::new();
     let runtime_us = ev3_ts + 1000; // Example runtime
-    stats.print_stats_json(&config, Some(runtime_us), "Cumulative", &mut buf);
+    stats
+        .print_stats_json(&config, Some(runtime_us), "Cumulative", &mut buf)
+        .unwrap();
     let s = String::from_utf8(buf).unwrap();
     println!("JSON Output:\n{s}"); // Print for debugging
 
@@ -575,10 +821,12 @@ fn stats_human_output_formatting() {
     assert!(
         output_string.contains("--- Passed Event Near-Miss Statistics (Passed within 50ms) ---")
     );
-    assert!(output_string
-        .contains("Key [KEY_A] (30, 1): 1 (Near-Miss Time: 20.0 ms / 20.0 ms / 20.0 ms)")); // ev_a3 diff 20000 us
-    assert!(output_string
-        .contains("Key [KEY_B] (48, 1): 1 (Near-Miss Time: 50.0 ms / 50.0 ms / 50.0 ms)"));
+    assert!(output_string.contains(
+        "Key [KEY_A] (30, 1): 1 (Near-Miss Time: 20.0 ms / 20.0 ms / 20.0 ms, Stddev: 0 µs)"
+    )); // ev_a3 diff 20000 us
+    assert!(output_string.contains(
+        "Key [KEY_B] (48, 1): 1 (Near-Miss Time: 50.0 ms / 50.0 ms / 50.0 ms, Stddev: 0 µs)"
+    ));
     // ev_b2 diff 49999 us (rounded to 50.0 ms)
 }
 
@@ -905,7 +1153,9 @@ fn stats_drop_rate_edge_cases() {
 
     // Check JSON output for edge cases
     let mut buf = Vec::new();
-    stats.print_stats_json(&config, None, "Cumulative", &mut buf);
+    stats
+        .print_stats_json(&config, None, "Cumulative", &mut buf)
+        .unwrap();
     let s = String::from_utf8(buf).unwrap();
     let json_value: Value = serde_json::from_str(&s).expect("Failed to parse JSON output");
 
@@ -956,3 +1206,999 @@ fn stats_drop_rate_edge_cases() {
         "KEY_D should not be in JSON stats because it had no activity"
     );
 }
+
+#[test]
+fn stats_recommended_debounce_exceeds_max_bounce() {
+    let mut stats = StatsCollector::with_capacity();
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+
+    // KEY_A: bounces at 3.0ms (press) and 4.5ms (release).
+    let ev_a1 = key_ev(0, KEY_A, 1);
+    let ev_a2 = key_ev(3_000, KEY_A, 1);
+    let ev_a3 = key_ev(50_000, KEY_A, 0);
+    let ev_a4 = key_ev(54_500, KEY_A, 0);
+    stats.record_event_info_with_config(&passed_event_info(ev_a1, 0, None), &config);
+    stats.record_event_info_with_config(&bounced_event_info(ev_a2, 3_000, 3_000, Some(0)), &config);
+    stats.record_event_info_with_config(&passed_event_info(ev_a3, 50_000, None), &config);
+    stats.record_event_info_with_config(
+        &bounced_event_info(ev_a4, 54_500, 4_500, Some(50_000)),
+        &config,
+    );
+
+    // KEY_B: one bounce at 7.2ms.
+    let ev_b1 = key_ev(100_000, KEY_B, 1);
+    let ev_b2 = key_ev(107_200, KEY_B, 1);
+    stats.record_event_info_with_config(&passed_event_info(ev_b1, 100_000, None), &config);
+    stats.record_event_info_with_config(
+        &bounced_event_info(ev_b2, 107_200, 7_200, Some(100_000)),
+        &config,
+    );
+
+    // KEY_C: passes only, so no recommendation.
+    let ev_c1 = key_ev(200_000, KEY_C, 1);
+    stats.record_event_info_with_config(&passed_event_info(ev_c1, 200_000, None), &config);
+
+    let key_a_rec = stats.per_key_stats[KEY_A as usize]
+        .recommended_debounce_us()
+        .expect("KEY_A should have a recommendation");
+    let key_b_rec = stats.per_key_stats[KEY_B as usize]
+        .recommended_debounce_us()
+        .expect("KEY_B should have a recommendation");
+    assert!(key_a_rec > 4_500, "recommendation must exceed max bounce");
+    assert!(key_b_rec > 7_200, "recommendation must exceed max bounce");
+    assert_eq!(key_a_rec % 1_000, 0, "recommendation rounds to whole ms");
+    assert_eq!(
+        stats.per_key_stats[KEY_C as usize].recommended_debounce_us(),
+        None
+    );
+    assert_eq!(stats.recommended_debounce_us(), Some(key_b_rec));
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_stats_human_readable(&config, "Cumulative", &mut writer)
+        .expect("Formatting failed");
+    let output_string = String::from_utf8(writer.into_inner()).expect("Output not UTF-8");
+    assert!(output_string.contains("--- Recommended Debounce Time ---"));
+    assert!(output_string.contains("Overall: --debounce-time 9ms"));
+
+    let mut buf = Vec::new();
+    stats
+        .print_stats_json(&config, None, "Cumulative", &mut buf)
+        .unwrap();
+    let json_value: Value = serde_json::from_slice(&buf).expect("Failed to parse JSON output");
+    assert_eq!(json_value["recommended_debounce_us"], key_b_rec);
+    let key_a_json = json_value["per_key_stats"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["key_code"] == KEY_A)
+        .expect("KEY_A not found");
+    assert_eq!(key_a_json["recommended_debounce_us"], key_a_rec);
+    let key_c_json = json_value["per_key_stats"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["key_code"] == KEY_C)
+        .expect("KEY_C not found");
+    assert!(key_c_json["recommended_debounce_us"].is_null());
+}
+
+#[test]
+fn stats_counts_press_release_protocol_violations() {
+    let mut stats = StatsCollector::with_capacity();
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+
+    // press, press, release, release: the second of each pair is a violation.
+    for (i, value) in [1, 1, 0, 0].into_iter().enumerate() {
+        let ts = i as u64 * 200_000;
+        let ev = key_ev(ts, KEY_A, value);
+        stats.record_event_info_with_config(&passed_event_info(ev, ts, None), &config);
+    }
+    // A healthy key alongside it.
+    for (i, value) in [1, 0, 1, 0].into_iter().enumerate() {
+        let ts = i as u64 * 200_000 + 50_000;
+        let ev = key_ev(ts, KEY_B, value);
+        stats.record_event_info_with_config(&passed_event_info(ev, ts, None), &config);
+    }
+
+    assert_eq!(stats.per_key_stats[KEY_A as usize].protocol_violations, 2);
+    assert_eq!(stats.per_key_stats[KEY_B as usize].protocol_violations, 0);
+    assert_eq!(stats.protocol_violations(), 2);
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_stats_human_readable(&config, "Cumulative", &mut writer)
+        .expect("Formatting failed");
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    assert!(output.contains("--- Key State Violations ---"));
+    assert!(output.contains("Key [KEY_A] (30): 2"));
+    assert!(!output.contains("Key [KEY_B] (48): 0"));
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .print_stats_json(&config, None, "Cumulative", &mut writer)
+        .unwrap();
+    let json: Value = serde_json::from_slice(&writer.into_inner()).unwrap();
+    assert_eq!(json["protocol_violations"], 2);
+}
+
+#[test]
+fn timing_histogram_cumulative_is_monotonic_and_reaches_100() {
+    let mut hist = TimingHistogram::default();
+    for us in [500, 1_500, 1_800, 3_000, 9_000, 200_000] {
+        hist.record(us);
+    }
+
+    let cumulative = hist.cumulative_percentages();
+    assert_eq!(cumulative.len(), NUM_HISTOGRAM_BUCKETS);
+    for pair in cumulative.windows(2) {
+        assert!(pair[0] <= pair[1], "cumulative must be non-decreasing");
+    }
+    assert!((cumulative[0] - 100.0 / 6.0).abs() < 1e-9); // <1ms
+    assert!((cumulative[1] - 50.0).abs() < 1e-9); // <2ms
+    assert!((cumulative[NUM_HISTOGRAM_BUCKETS - 1] - 100.0).abs() < 1e-9);
+
+    assert!(TimingHistogram::default()
+        .cumulative_percentages()
+        .iter()
+        .all(|&pct| pct == 0.0));
+}
+
+#[test]
+fn stats_histogram_cumulative_output() {
+    let mut stats = StatsCollector::with_capacity();
+    let mut config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+    config.histogram_cumulative = true;
+
+    let ev1 = key_ev(0, KEY_A, 1);
+    let ev2 = key_ev(3_000, KEY_A, 1);
+    let ev3 = key_ev(5_000, KEY_A, 1);
+    stats.record_event_info_with_config(&passed_event_info(ev1, 0, None), &config);
+    stats.record_event_info_with_config(&bounced_event_info(ev2, 3_000, 3_000, Some(0)), &config);
+    stats.record_event_info_with_config(&bounced_event_info(ev3, 5_000, 5_000, Some(0)), &config);
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_stats_human_readable(&config, "Cumulative", &mut writer)
+        .expect("Formatting failed");
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    assert!(
+        output.contains("cum  50.0%"),
+        "Missing cumulative column: {output}"
+    );
+    assert!(output.contains("cum 100.0%"));
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .print_stats_json(&config, None, "Cumulative", &mut writer)
+        .unwrap();
+    let json: Value = serde_json::from_slice(&writer.into_inner()).unwrap();
+    let cumulative: Vec<f64> = json["overall_bounce_histogram"]["cumulative"]
+        .as_array()
+        .expect("cumulative array missing")
+        .iter()
+        .map(|v| v.as_f64().unwrap())
+        .collect();
+    assert_eq!(cumulative.len(), NUM_HISTOGRAM_BUCKETS);
+    assert!(cumulative.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(*cumulative.last().unwrap(), 100.0);
+
+    // Without the flag, the cumulative array is omitted.
+    config.histogram_cumulative = false;
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .print_stats_json(&config, None, "Cumulative", &mut writer)
+        .unwrap();
+    let json: Value = serde_json::from_slice(&writer.into_inner()).unwrap();
+    assert!(json["overall_bounce_histogram"].get("cumulative").is_none());
+}
+
+#[test]
+fn stats_merge_sums_counts_and_caps_samples() {
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+    let record_bounces = |stats: &mut StatsCollector, count: u64, diff: u64| {
+        for i in 0..count {
+            let ts = i * 100_000;
+            let ev = key_ev(ts, KEY_A, 1);
+            stats.record_event_info_with_config(&bounced_event_info(ev, ts, diff, None), &config);
+        }
+    };
+
+    let mut older = StatsCollector::with_capacity();
+    record_bounces(&mut older, 400, 2_000);
+    let mut newer = StatsCollector::with_capacity();
+    record_bounces(&mut newer, 300, 5_000);
+
+    // Round-trip the older stats the way --persist-state stores them.
+    let json = serde_json::to_string(&older).unwrap();
+    let mut merged: StatsCollector = serde_json::from_str(&json).unwrap();
+    merged.merge(&newer);
+
+    assert_eq!(merged.key_events_processed, 700);
+    assert_eq!(merged.key_events_dropped, 700);
+    let press = &merged.per_key_stats[KEY_A as usize].press;
+    assert_eq!(press.dropped_count, 700);
+    assert_eq!(press.bounce_histogram.count, 700);
+    assert_eq!(press.bounce_summary.min_us(), Some(2_000));
+    assert_eq!(press.bounce_summary.max_us(), Some(5_000));
+    // Samples stay capped, with the newest run's timings retained.
+    assert_eq!(press.bounce_samples.len(), MAX_BOUNCE_TIMING_SAMPLES);
+    assert_eq!(press.bounce_samples.to_vec().last(), Some(&5_000));
+}
+
+#[test]
+fn stats_warn_about_likely_dead_switches() {
+    let mut stats = StatsCollector::with_capacity();
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+
+    // KEY_A: 19 of 20 events dropped (95%). KEY_B: 2 of 20 dropped.
+    for i in 0..20u64 {
+        let ts = i * 100_000;
+        let ev_a = key_ev(ts, KEY_A, 1);
+        let info_a = if i == 0 {
+            passed_event_info(ev_a, ts, None)
+        } else {
+            bounced_event_info(ev_a, ts, 1_000, Some(0))
+        };
+        stats.record_event_info_with_config(&info_a, &config);
+
+        let ev_b = key_ev(ts + 50_000, KEY_B, (i % 2) as i32);
+        let info_b = if i < 2 {
+            bounced_event_info(ev_b, ts + 50_000, 1_000, None)
+        } else {
+            passed_event_info(ev_b, ts + 50_000, None)
+        };
+        stats.record_event_info_with_config(&info_b, &config);
+    }
+
+    assert!(stats.per_key_stats[KEY_A as usize].is_likely_dead_switch(90.0));
+    assert!(!stats.per_key_stats[KEY_B as usize].is_likely_dead_switch(90.0));
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_stats_human_readable(&config, "Cumulative", &mut writer)
+        .expect("Formatting failed");
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    assert!(output.contains("--- Possible Dead Switches ---"));
+    assert!(output.contains("WARNING: Key [KEY_A] (30) dropped 95.00%"));
+    assert!(!output.contains("WARNING: Key [KEY_B]"));
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .print_stats_json(&config, None, "Cumulative", &mut writer)
+        .unwrap();
+    let json: Value = serde_json::from_slice(&writer.into_inner()).unwrap();
+    let flag = |code: u16| {
+        json["per_key_stats"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|k| k["key_code"] == code)
+            .unwrap()["likely_dead_switch"]
+            .clone()
+    };
+    assert_eq!(flag(KEY_A), true);
+    assert_eq!(flag(KEY_B), false);
+}
+
+#[test]
+fn timing_histogram_stddev() {
+    let mut identical = TimingHistogram::default();
+    for _ in 0..10 {
+        identical.record(5_000);
+    }
+    assert_eq!(identical.stddev_us(), 0);
+
+    let mut spread = TimingHistogram::default();
+    for timing in [2_000, 4_000, 4_000, 4_000, 5_000, 5_000, 7_000, 9_000] {
+        spread.record(timing);
+    }
+    assert_eq!(spread.average_us(), 5_000);
+    assert_eq!(spread.stddev_us(), 2_000); // Textbook population stddev of 2,4,4,4,5,5,7,9
+
+    assert_eq!(TimingHistogram::default().stddev_us(), 0);
+}
+
+#[test]
+fn stats_report_sample_counts_and_stddev() {
+    let mut stats = StatsCollector::with_capacity();
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+    for (i, diff) in [2_000u64, 4_000].into_iter().enumerate() {
+        let ts = i as u64 * 100_000;
+        let ev = key_ev(ts, KEY_A, 1);
+        stats.record_event_info_with_config(&bounced_event_info(ev, ts, diff, None), &config);
+    }
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_stats_human_readable(&config, "Cumulative", &mut writer)
+        .expect("Formatting failed");
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    assert!(
+        output.contains("(Bounce Time: 2.0 ms / 3.0 ms / 4.0 ms, Stddev: 1.0 ms, n=2)"),
+        "{output}"
+    );
+    assert!(output.contains("Total: 2, Avg: 3.0 ms, Stddev: 1.0 ms"));
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .print_stats_json(&config, None, "Cumulative", &mut writer)
+        .unwrap();
+    let json: Value = serde_json::from_slice(&writer.into_inner()).unwrap();
+    assert_eq!(json["overall_bounce_histogram"]["stddev_us"], 1_000);
+    assert_eq!(
+        json["per_key_stats"][0]["stats"]["press"]["bounce_histogram"]["stddev_us"],
+        1_000
+    );
+}
+
+#[test]
+fn stats_repeat_line_is_marked_passthrough_and_can_be_hidden() {
+    let mut stats = StatsCollector::with_capacity();
+    let mut config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+
+    let press = key_ev(0, KEY_A, 1);
+    stats.record_event_info_with_config(&passed_event_info(press, 0, None), &config);
+    let bounce = key_ev(2_000, KEY_A, 1);
+    stats
+        .record_event_info_with_config(&bounced_event_info(bounce, 2_000, 2_000, Some(0)), &config);
+    let repeat = key_ev(500_000, KEY_A, 2);
+    stats.record_event_info_with_config(&passed_event_info(repeat, 500_000, None), &config);
+
+    let render = |stats: &mut StatsCollector, config: &Config| {
+        let mut writer = Cursor::new(Vec::new());
+        stats
+            .format_stats_human_readable(config, "Cumulative", &mut writer)
+            .expect("Formatting failed");
+        String::from_utf8(writer.into_inner()).expect("Output not UTF-8")
+    };
+
+    let shown = render(&mut stats, &config);
+    assert!(shown.contains(
+        "  Repeat  (2): Processed: 1, Passed: 1, Dropped: 0 (0.00%) (passthrough, never debounced)"
+    ));
+
+    config.hide_repeat_stats = true;
+    let hidden = render(&mut stats, &config);
+    assert!(!hidden.contains("Repeat  (2)"));
+    assert!(hidden.contains("Press   (1): Processed: 2, Passed: 1, Dropped: 1"));
+}
+
+#[test]
+fn stats_key_granularity_hides_per_state_lines() {
+    let mut stats = StatsCollector::with_capacity();
+    let mut config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+
+    let press = key_ev(0, KEY_A, 1);
+    stats.record_event_info_with_config(&passed_event_info(press, 0, None), &config);
+    let bounce = key_ev(2_000, KEY_A, 1);
+    stats
+        .record_event_info_with_config(&bounced_event_info(bounce, 2_000, 2_000, Some(0)), &config);
+    let release = key_ev(50_000, KEY_A, 0);
+    stats.record_event_info_with_config(&passed_event_info(release, 50_000, None), &config);
+
+    config.stats_granularity = StatsGranularity::Key;
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_stats_human_readable(&config, "Cumulative", &mut writer)
+        .expect("Formatting failed");
+    let output = String::from_utf8(writer.into_inner()).expect("Output not UTF-8");
+
+    assert!(output.contains("Key [KEY_A] (30):"));
+    assert!(output.contains("Total Processed: 3, Passed: 2, Dropped: 1 (33.33%)"));
+    assert!(!output.contains("State (Value)"));
+    assert!(!output.contains("Press   (1)"));
+    assert!(!output.contains("Release (0)"));
+}
+
+#[test]
+fn stats_json_flags_sampled_timings() {
+    let mut stats = StatsCollector::with_capacity();
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+
+    let bounces = MAX_BOUNCE_TIMING_SAMPLES as u64 + 88;
+    for i in 0..bounces {
+        let ts = i * 100_000;
+        let ev = key_ev(ts, KEY_A, 1);
+        stats.record_event_info_with_config(&bounced_event_info(ev, ts, 1_000, None), &config);
+    }
+    let ev = key_ev(0, KEY_B, 1);
+    stats.record_event_info_with_config(&bounced_event_info(ev, 0, 2_000, None), &config);
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .print_stats_json(&config, None, "Cumulative", &mut writer)
+        .unwrap();
+    let json: Value = serde_json::from_slice(&writer.into_inner()).expect("Invalid JSON");
+    let press_stats = |key_code: u16| {
+        json["per_key_stats"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|entry| entry["key_code"] == key_code)
+            .expect("Key missing from JSON")["stats"]["press"]
+            .clone()
+    };
+
+    let key_a = press_stats(KEY_A);
+    assert_eq!(
+        key_a["timings_us"].as_array().unwrap().len(),
+        MAX_BOUNCE_TIMING_SAMPLES
+    );
+    assert_eq!(key_a["timings_sampled"], true);
+    assert_eq!(key_a["timings_total_count"], bounces);
+
+    let key_b = press_stats(KEY_B);
+    assert_eq!(key_b["timings_us"], json!([2_000]));
+    assert_eq!(key_b["timings_sampled"], false);
+    assert_eq!(key_b["timings_total_count"], 1);
+}
+
+#[test]
+fn per_key_first_and_last_event_bound_the_key_events() {
+    let mut stats = StatsCollector::with_capacity();
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+
+    stats.record_event_info_with_config(
+        &passed_event_info(key_ev(10_000, KEY_A, 1), 10_000, None),
+        &config,
+    );
+    stats.record_event_info_with_config(
+        &passed_event_info(key_ev(20_000, KEY_B, 1), 20_000, None),
+        &config,
+    );
+    stats.record_event_info_with_config(
+        &bounced_event_info(key_ev(12_000, KEY_A, 1), 12_000, 2_000, Some(10_000)),
+        &config,
+    );
+    stats.record_event_info_with_config(
+        &passed_event_info(key_ev(90_000, KEY_A, 0), 90_000, None),
+        &config,
+    );
+
+    let key_a = &stats.per_key_stats[usize::from(KEY_A)];
+    assert_eq!(key_a.first_event_us, Some(10_000));
+    assert_eq!(key_a.last_event_us, Some(90_000));
+    assert_eq!(stats.per_key_stats[usize::from(KEY_C)].first_event_us, None);
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .print_stats_json(&config, None, "Test", &mut writer)
+        .unwrap();
+    let report: Value = serde_json::from_slice(&writer.into_inner()).unwrap();
+    let per_key = report["per_key_stats"].as_array().unwrap();
+    let entry = |name: &str| {
+        per_key
+            .iter()
+            .find(|key| key["key_name"] == name)
+            .unwrap_or_else(|| panic!("No entry for {name}"))
+    };
+    assert_eq!(entry("KEY_A")["first_event_us"], 10_000);
+    assert_eq!(entry("KEY_A")["last_event_us"], 90_000);
+    assert_eq!(entry("KEY_B")["first_event_us"], 20_000);
+    assert_eq!(entry("KEY_B")["last_event_us"], 20_000);
+}
+
+#[test]
+fn anonymized_reports_hide_key_names_and_keep_counts() {
+    let mut stats = StatsCollector::with_capacity();
+    let mut config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+    config.anonymize = true;
+    config.key_pseudonyms = pseudonym_codes(7);
+
+    stats.record_event_info_with_config(&passed_event_info(key_ev(0, KEY_A, 1), 0, None), &config);
+    stats.record_event_info_with_config(
+        &bounced_event_info(key_ev(1_000, KEY_A, 1), 1_000, 1_000, Some(0)),
+        &config,
+    );
+    stats.record_event_info_with_config(
+        &passed_event_info(key_ev(50_000, KEY_B, 1), 50_000, None),
+        &config,
+    );
+
+    let (mut anonymized, anon_config) = stats.anonymized(&config);
+    let mut json_writer = Cursor::new(Vec::new());
+    anonymized
+        .print_stats_json(&anon_config, None, "Test", &mut json_writer)
+        .unwrap();
+    let json_output = String::from_utf8(json_writer.into_inner()).unwrap();
+    let mut human_writer = Cursor::new(Vec::new());
+    anonymized
+        .format_stats_human_readable(&anon_config, "Test", &mut human_writer)
+        .expect("Formatting failed");
+    let human_output = String::from_utf8(human_writer.into_inner()).unwrap();
+    for output in [&json_output, &human_output] {
+        assert!(!output.contains("KEY_A"), "{output}");
+        assert!(!output.contains("KEY_B"), "{output}");
+    }
+
+    let report: Value = serde_json::from_str(&json_output).unwrap();
+    assert_eq!(report["key_events_processed"], 3);
+    assert_eq!(report["key_events_dropped"], 1);
+    let pseudonym_a = config.key_pseudonyms[usize::from(KEY_A)];
+    let per_key = report["per_key_stats"].as_array().unwrap();
+    assert_eq!(per_key.len(), 2);
+    let key_a = per_key
+        .iter()
+        .find(|key| key["key_code"] == pseudonym_a)
+        .expect("No entry under KEY_A's pseudonym");
+    assert_eq!(key_a["key_name"], format!("key_{pseudonym_a:03x}"));
+    assert_eq!(key_a["total_processed"], 2);
+    assert_eq!(key_a["total_dropped"], 1);
+    assert!(human_output.contains(&format!("Key [key_{pseudonym_a:03x}] ({pseudonym_a})")));
+
+    // Every report of the run uses the same mapping.
+    let (mut again, _) = stats.anonymized(&config);
+    let mut again_writer = Cursor::new(Vec::new());
+    again
+        .print_stats_json(&anon_config, None, "Test", &mut again_writer)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(again_writer.into_inner()).unwrap(),
+        json_output
+    );
+}
+
+#[test]
+fn compact_summary_is_a_single_line_naming_the_top_key() {
+    let mut stats = StatsCollector::with_capacity();
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+
+    // KEY_A: 1 pass, 2 drops. KEY_B: 1 pass, 1 drop.
+    stats.record_event_info_with_config(&passed_event_info(key_ev(0, KEY_A, 1), 0, None), &config);
+    for t in [1_000, 2_000] {
+        stats.record_event_info_with_config(
+            &bounced_event_info(key_ev(t, KEY_A, 1), t, t, Some(0)),
+            &config,
+        );
+    }
+    stats.record_event_info_with_config(
+        &passed_event_info(key_ev(50_000, KEY_B, 1), 50_000, None),
+        &config,
+    );
+    stats.record_event_info_with_config(
+        &bounced_event_info(key_ev(51_000, KEY_B, 1), 51_000, 1_000, Some(50_000)),
+        &config,
+    );
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_compact_summary("PERIODIC", &mut writer)
+        .expect("Formatting failed");
+    let output = String::from_utf8(writer.into_inner()).expect("Output not UTF-8");
+    assert_eq!(
+        output,
+        "PERIODIC processed=5 dropped=3 drop_pct=60.00 top_key=KEY_A(66.7%)\n"
+    );
+
+    let mut writer = Cursor::new(Vec::new());
+    StatsCollector::with_capacity()
+        .format_compact_summary("PERIODIC", &mut writer)
+        .expect("Formatting failed");
+    let output = String::from_utf8(writer.into_inner()).expect("Output not UTF-8");
+    assert_eq!(
+        output,
+        "PERIODIC processed=0 dropped=0 drop_pct=0.00 top_key=none\n"
+    );
+}
+
+#[test]
+fn histogram_legend_is_shared_by_both_histograms() {
+    let mut stats = StatsCollector::with_capacity();
+    let mut config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+    config.histogram_legend = true;
+
+    // Four bounces at 3ms (bucket 2) and two near misses at 10ms (bucket 4).
+    stats.record_event_info_with_config(&passed_event_info(key_ev(0, KEY_A, 1), 0, None), &config);
+    for i in 1..=4 {
+        let t = i * 20_000;
+        stats.record_event_info_with_config(
+            &bounced_event_info(key_ev(t, KEY_A, 1), t, 3_000, Some(t - 3_000)),
+            &config,
+        );
+    }
+    for t in [200_000, 210_000, 220_000] {
+        let last = (t != 200_000).then(|| t - 10_000);
+        stats.record_event_info_with_config(
+            &passed_event_info(key_ev(t, KEY_B, 1), t, last),
+            &config,
+        );
+    }
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_stats_human_readable(&config, "Cumulative", &mut writer)
+        .expect("Formatting failed");
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+
+    assert_eq!(output.matches("Histogram Buckets").count(), 1, "{output}");
+    assert_eq!(output.matches("[2] 2-4ms").count(), 1);
+    assert_eq!(output.matches("[8] >= 128ms").count(), 1);
+    // Rows refer to the legend instead of repeating the ranges.
+    assert!(!output.contains("2-4ms     :"));
+
+    let bounce_start = output.find("Overall Bounce Timing Histogram").unwrap();
+    let near_miss_start = output.find("Overall Near-Miss Timing Histogram").unwrap();
+    let bounce = &output[bounce_start..near_miss_start];
+    let near_miss = &output[near_miss_start..];
+    let bar = |n: usize| format!("[{}]", "#".repeat(n));
+
+    // Both histograms are drawn against the fullest bucket of either (4 bounces).
+    assert!(bounce.contains(&format!("  [2]       : 4     (100.0%) {}", bar(50))));
+    assert!(near_miss.contains(&format!("  [4]       : 2     (100.0%) {}", bar(25))));
+    assert!(near_miss.contains(&format!("  [2]       : 0     (  0.0%) {}", bar(0))));
+}
+
+#[test]
+fn top_keys_ranks_keys_by_drop_count() {
+    let mut stats = StatsCollector::with_capacity();
+    let mut config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+
+    // KEY_A: 1 drop, KEY_B: 3 drops, KEY_C: 2 drops, KEY_D: none.
+    for (offset, key, drops) in [
+        (0, KEY_A, 1),
+        (1_000_000, KEY_B, 3),
+        (2_000_000, KEY_C, 2),
+        (3_000_000, KEY_D, 0),
+    ] {
+        stats.record_event_info_with_config(
+            &passed_event_info(key_ev(offset, key, 1), offset, None),
+            &config,
+        );
+        for i in 1..=drops {
+            let t = offset + i * 1_000;
+            stats.record_event_info_with_config(
+                &bounced_event_info(key_ev(t, key, 1), t, i * 1_000, Some(offset)),
+                &config,
+            );
+        }
+    }
+
+    assert_eq!(stats.top_keys(5), vec![KEY_B, KEY_C, KEY_A]);
+    assert_eq!(stats.top_keys(2), vec![KEY_B, KEY_C]);
+
+    config.top_keys = 2;
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_stats_human_readable(&config, "Cumulative", &mut writer)
+        .expect("Formatting failed");
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    let top = output
+        .find("--- Top 2 Chattiest Keys (by drops) ---")
+        .expect("Missing top keys section");
+    let first = output.find("  1. KEY_B (48): 3 dropped (75.00%)").unwrap();
+    let second = output.find("  2. KEY_C (46): 2 dropped (66.67%)").unwrap();
+    // The summary comes before the histograms and the per-key breakdown.
+    let histograms = output.find("Overall Bounce Timing Histogram").unwrap();
+    assert!(top < first && first < second && second < histograms);
+    assert!(!output.contains("3. KEY_A"));
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .print_stats_json(&config, None, "Cumulative", &mut writer)
+        .unwrap();
+    let json: Value = serde_json::from_slice(&writer.into_inner()).unwrap();
+    let top_keys = json["top_keys"].as_array().unwrap();
+    assert_eq!(top_keys.len(), 2);
+    assert_eq!(top_keys[0]["key_name"], "KEY_B");
+    assert_eq!(top_keys[0]["total_dropped"], 3);
+    assert_eq!(top_keys[1]["key_code"], KEY_C);
+}
+
+#[test]
+fn focus_key_prints_per_key_histogram_only_for_that_key() {
+    let mut stats = StatsCollector::with_capacity();
+    let mut config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+
+    for (offset, key) in [(0, KEY_A), (1_000_000, KEY_B)] {
+        stats.record_event_info_with_config(
+            &passed_event_info(key_ev(offset, key, 1), offset, None),
+            &config,
+        );
+        let t = offset + 3_000;
+        stats.record_event_info_with_config(
+            &bounced_event_info(key_ev(t, key, 1), t, 3_000, Some(offset)),
+            &config,
+        );
+    }
+
+    let render = |stats: &mut StatsCollector, config: &Config| {
+        let mut writer = Cursor::new(Vec::new());
+        stats
+            .format_stats_human_readable(config, "Cumulative", &mut writer)
+            .expect("Formatting failed");
+        String::from_utf8(writer.into_inner()).unwrap()
+    };
+
+    assert!(!render(&mut stats, &config).contains("Bounce Histogram:"));
+
+    config.focus_keys = vec![KEY_A];
+    let output = render(&mut stats, &config);
+    assert_eq!(
+        output.matches("Press Bounce Histogram:").count(),
+        1,
+        "{output}"
+    );
+    assert!(!output.contains("Release Bounce Histogram:"));
+    let key_a = output.find("Key [KEY_A] (30):").unwrap();
+    let key_b = output.find("Key [KEY_B] (48):").unwrap();
+    let block = output.find("  Press Bounce Histogram:").unwrap();
+    assert!(
+        key_a < block && block < key_b,
+        "Histogram not under KEY_A: {output}"
+    );
+    assert!(output[block..key_b].contains("    2-4ms     : 1"));
+
+    config.focus_keys.clear();
+    config.per_key_histograms = true;
+    let output = render(&mut stats, &config);
+    assert_eq!(output.matches("Press Bounce Histogram:").count(), 2);
+}
+
+#[test]
+fn interval_histogram_counts_every_pass_after_the_first() {
+    let mut stats = StatsCollector::with_capacity();
+    let mut config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+    config.interval_histogram = true;
+
+    // KEY_A: 4 presses and 4 releases 250ms apart, plus one dropped press bounce.
+    // KEY_B: 3 presses only.
+    let mut last_passed: [Option<u64>; 2] = [None, None];
+    for i in 0..8u64 {
+        let ts = i * 250_000;
+        let value = (i % 2 == 0) as i32;
+        let ev = key_ev(ts, KEY_A, value);
+        stats.record_event_info_with_config(
+            &passed_event_info(ev, ts, last_passed[value as usize]),
+            &config,
+        );
+        last_passed[value as usize] = Some(ts);
+    }
+    stats.record_event_info_with_config(
+        &bounced_event_info(key_ev(1_500_003, KEY_A, 1), 1_500_003, 3, Some(1_500_000)),
+        &config,
+    );
+    let mut last_b = None;
+    for ts in [0, 400_000, 1_000_000] {
+        stats.record_event_info_with_config(
+            &passed_event_info(key_ev(ts, KEY_B, 1), ts, last_b),
+            &config,
+        );
+        last_b = Some(ts);
+    }
+
+    let key_a = &stats.per_key_stats[KEY_A as usize];
+    let key_b = &stats.per_key_stats[KEY_B as usize];
+    for kv_stats in [&key_a.press, &key_a.release, &key_b.press] {
+        assert_eq!(
+            kv_stats.pass_interval_histogram.count,
+            kv_stats.passed_count - 1
+        );
+    }
+    assert_eq!(key_a.press.pass_interval_histogram.average_us(), 500_000);
+    assert_eq!(key_b.press.pass_interval_histogram.average_us(), 500_000);
+    assert_eq!(key_b.release.pass_interval_histogram.count, 0);
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_stats_human_readable(&config, "Test", &mut writer)
+        .unwrap();
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    assert!(
+        output.contains("--- Overall Pass Interval Histogram"),
+        "{output}"
+    );
+    assert!(
+        output.contains("  Key [KEY_A] (30, 1): 3 (Avg: 500.0 ms"),
+        "{output}"
+    );
+    assert!(
+        output.contains("  Key [KEY_B] (48, 1): 2 (Avg: 500.0 ms"),
+        "{output}"
+    );
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .print_stats_json(&config, None, "Test", &mut writer)
+        .unwrap();
+    let json: Value = serde_json::from_slice(&writer.into_inner()).unwrap();
+    assert_eq!(json["overall_interval_histogram"]["count"], 8);
+    assert_eq!(
+        json["per_key_stats"][0]["stats"]["press"]["pass_interval_histogram"]["count"],
+        3
+    );
+}
+
+#[test]
+fn histogram_bars_scale_with_configured_width_and_char() {
+    let mut stats = StatsCollector::with_capacity();
+    let mut config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+
+    // Two bounces at 3ms (bucket 2) and one at 10ms (bucket 4).
+    stats.record_event_info_with_config(&passed_event_info(key_ev(0, KEY_A, 1), 0, None), &config);
+    for (t, diff) in [(20_000, 3_000), (40_000, 3_000), (60_000, 10_000)] {
+        stats.record_event_info_with_config(
+            &bounced_event_info(key_ev(t, KEY_A, 1), t, diff, Some(t - diff)),
+            &config,
+        );
+    }
+
+    let mut histogram = |config: &Config| {
+        let mut writer = Cursor::new(Vec::new());
+        stats
+            .format_stats_human_readable(config, "Cumulative", &mut writer)
+            .expect("Formatting failed");
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+        let start = output.find("Overall Bounce Timing Histogram").unwrap();
+        output[start..].to_string()
+    };
+
+    assert!(histogram(&config).contains(&format!("[{}]", "#".repeat(50))));
+
+    config.histogram_width = 20;
+    config.histogram_char = '█';
+    let narrow = histogram(&config);
+    assert!(
+        narrow.contains(&format!("( 66.7%) [{}]", "█".repeat(20))),
+        "{narrow}"
+    );
+    assert!(
+        narrow.contains(&format!("( 33.3%) [{}]", "█".repeat(10))),
+        "{narrow}"
+    );
+    assert!(!narrow.contains('#'), "{narrow}");
+
+    // Auto-detection fits the bar to the terminal, within sane bounds.
+    assert_eq!(StatsCollector::histogram_width_for_columns(200, false), 50);
+    assert_eq!(StatsCollector::histogram_width_for_columns(61, false), 30);
+    assert_eq!(StatsCollector::histogram_width_for_columns(61, true), 19);
+    assert_eq!(StatsCollector::histogram_width_for_columns(20, false), 10);
+}
+
+#[test]
+fn histogram_buckets_carry_exact_microsecond_bounds() {
+    let mut stats = StatsCollector::with_capacity();
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+    stats.record_event_info_with_config(&passed_event_info(key_ev(0, KEY_A, 1), 0, None), &config);
+    stats.record_event_info_with_config(
+        &bounced_event_info(key_ev(3_000, KEY_A, 1), 3_000, 3_000, Some(0)),
+        &config,
+    );
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .print_stats_json(&config, None, "Test", &mut writer)
+        .unwrap();
+    let json: Value = serde_json::from_slice(&writer.into_inner()).unwrap();
+    let buckets = json["overall_bounce_histogram"]["buckets"]
+        .as_array()
+        .unwrap();
+    assert_eq!(buckets.len(), NUM_HISTOGRAM_BUCKETS);
+
+    for (i, bucket) in buckets.iter().enumerate() {
+        let min_ms = bucket["min_ms"].as_u64().unwrap();
+        assert_eq!(bucket["min_us"], min_ms * 1_000, "bucket {i}");
+        match bucket["max_ms"].as_u64() {
+            Some(max_ms) => {
+                assert_eq!(bucket["max_us"], max_ms * 1_000, "bucket {i}");
+                // Buckets are contiguous: each ends where the next one starts.
+                assert_eq!(bucket["max_us"], buckets[i + 1]["min_us"]);
+            }
+            None => {
+                assert_eq!(i, NUM_HISTOGRAM_BUCKETS - 1);
+                assert!(bucket["max_us"].is_null());
+            }
+        }
+    }
+    // The 3ms bounce lands in the 2-4ms bucket.
+    assert_eq!(buckets[2]["min_us"], 2_000);
+    assert_eq!(buckets[2]["max_us"], 4_000);
+    assert_eq!(buckets[2]["count"], 1);
+}
+
+#[test]
+fn heatmap_intensities_are_normalized_to_the_hottest_key() {
+    let mut stats = StatsCollector::with_capacity();
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+
+    // (key, passes, drops): KEY_A has the most drops but a low rate (4²/24), KEY_B
+    // fewer drops at a high rate (3²/4), KEY_C a single drop, KEY_D none.
+    for (offset, key, passes, drops) in [
+        (0, KEY_A, 20, 4),
+        (10_000_000, KEY_B, 1, 3),
+        (20_000_000, KEY_C, 4, 1),
+        (30_000_000, KEY_D, 5, 0),
+    ] {
+        for pass in 0..passes {
+            let t = offset + pass * 100_000;
+            stats.record_event_info_with_config(
+                &passed_event_info(key_ev(t, key, 1), t, None),
+                &config,
+            );
+        }
+        let last_pass = offset + (passes - 1) * 100_000;
+        for i in 1..=drops {
+            let t = last_pass + i * 1_000;
+            stats.record_event_info_with_config(
+                &bounced_event_info(key_ev(t, key, 1), t, i * 1_000, Some(last_pass)),
+                &config,
+            );
+        }
+    }
+
+    let heatmap = stats.heatmap();
+    assert_eq!(heatmap.len(), 4);
+    assert!(heatmap
+        .iter()
+        .all(|key| (0.0..=1.0).contains(&key.intensity)));
+    let hottest = heatmap
+        .iter()
+        .max_by(|a, b| a.intensity.total_cmp(&b.intensity))
+        .unwrap();
+    assert_eq!(hottest.key_code, KEY_B);
+    assert_eq!(hottest.intensity, 1.0);
+    let intensity = |code| {
+        heatmap
+            .iter()
+            .find(|k| k.key_code == code)
+            .unwrap()
+            .intensity
+    };
+    assert!((intensity(KEY_A) - (16.0 / 24.0) / (9.0 / 4.0)).abs() < 1e-9);
+    assert_eq!(intensity(KEY_D), 0.0);
+
+    let mut writer = Cursor::new(Vec::new());
+    stats.write_heatmap_json(&mut writer).unwrap();
+    let json: Value = serde_json::from_slice(&writer.into_inner()).unwrap();
+    let keys = json["keys"].as_array().unwrap();
+    // Ordered by key code: KEY_A (30), KEY_D (32), KEY_C (46), KEY_B (48).
+    assert_eq!(keys.len(), 4);
+    assert_eq!(keys[3]["key_name"], "KEY_B");
+    assert_eq!(keys[3]["intensity"], 1.0);
+}
+
+#[test]
+fn window_ratio_histogram_buckets_decisions_by_fraction_of_debounce_time() {
+    let mut stats = StatsCollector::with_capacity();
+    let config = dummy_config_no_arc(DEBOUNCE_TIME, Duration::from_millis(100));
+    let debounce_us = DEBOUNCE_TIME.as_micros() as u64;
+
+    stats.record_event_info_with_config(&passed_event_info(key_ev(0, KEY_A, 1), 0, None), &config);
+    // Bounce at half the debounce time: 0.5-0.6 bucket.
+    let half = debounce_us / 2;
+    stats.record_event_info_with_config(
+        &bounced_event_info(key_ev(half, KEY_A, 1), half, half, Some(0)),
+        &config,
+    );
+    // Pass just past the window: 1.0-1.1 bucket.
+    stats.record_event_info_with_config(
+        &passed_event_info(key_ev(debounce_us, KEY_A, 1), debounce_us, Some(0)),
+        &config,
+    );
+    // Pass at 5x the window is ordinary typing and isn't counted.
+    let late = 6 * debounce_us;
+    stats.record_event_info_with_config(
+        &passed_event_info(key_ev(late, KEY_A, 1), late, Some(debounce_us)),
+        &config,
+    );
+
+    let histogram = &stats.window_ratio_histogram;
+    assert_eq!(histogram.buckets[5], 1);
+    assert_eq!(histogram.buckets[10], 1);
+    assert_eq!(histogram.total(), 2);
+    assert_eq!(WindowRatioHistogram::bucket_start(5), 0.5);
+
+    let mut writer = Cursor::new(Vec::new());
+    stats
+        .format_stats_human_readable(&config, "Cumulative", &mut writer)
+        .unwrap();
+    let output = String::from_utf8(writer.into_inner()).unwrap();
+    assert!(output.contains("--- Decisions Relative to Debounce Time"));
+    assert!(output.contains("0.5-0.6   : 1"));
+    assert!(output.contains("1.0-1.1   : 1"));
+
+    let mut merged = StatsCollector::with_capacity();
+    merged.merge(&stats);
+    merged.merge(&stats);
+    assert_eq!(merged.window_ratio_histogram.buckets[5], 2);
+}
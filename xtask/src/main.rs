@@ -148,6 +148,40 @@ If the time difference is greater than or equal to the debounce time, or if the
 .PP
 Key repeat events (value=2) are always passed through without debouncing, as they represent intentional key holds.
 .PP
+.B Startup Artifacts (\-\-suppress\-initial)
+.PP
+Some pipelines inject a spurious press or release while the virtual device is being set up. With \fB\-\-suppress\-initial\fR, the first event of each key and state (press or release) is dropped if it arrives within \fB\-\-initial\-window\fR (default 200ms) of the first event received. Later events, and first events after the window, are unaffected.
+.PP
+.B Exact Duplicates (\-\-drop\-exact\-duplicates)
+.PP
+A malfunctioning driver may deliver the same \fIinput_event\fR twice in a row, timestamp included. With \fB\-\-drop\-exact\-duplicates\fR, any event (of any type) identical in every field to the one right before it is dropped, independent of the debounce time. Dropped key events are counted as bounces with a bounce time of 0.
+.PP
+.B Zero-Duration Keypresses (\-\-collapse\-zero\-duration)
+.PP
+A hard bounce can produce a press and a release with identical timestamps, which pass since presses and releases are tracked separately. With \fB\-\-collapse\-zero\-duration\fR, a release stamped with the same time as the last passed press of its key is dropped (counted as a bounce with a bounce time of 0), and the key stays down until its next release. A device that reports a tap shorter than its polling interval as press and release in a single report produces the same pattern, so such taps lose their release as well.
+.PP
+.B Scancodes (\-\-drop\-orphan\-scancodes)
+.PP
+Keyboards report an \fBMSC_SCAN\fR event with the same timestamp right before each key event. Only the key event is debounced, so by default the scancode of a dropped key still reaches the consumer on its own. With \fB\-\-drop\-orphan\-scancodes\fR, each \fBMSC_SCAN\fR is held back until the next event arrives and is dropped together with the key event that follows it if that one is dropped.
+.PP
+.B Key Events Only (\-\-key\-events\-only, \-\-synthesize\-syn)
+.PP
+\fB\-\-key\-events\-only\fR writes nothing but key events to standard output, the same as \fB\-\-passthrough\-types EV_KEY\fR. This includes the \fBSYN_REPORT\fR events that end each report, which most evdev consumers (including \fBuinput\fR) need before they act on a report. \fB\-\-synthesize\-syn\fR writes a \fBSYN_REPORT\fR with the key event's timestamp after every key event written, so each key event becomes a report of its own; several keys changing in one original report are split into separate reports.
+.PP
+.B Count Mode (\-\-mode count)
+.PP
+With \fB\-\-mode count\fR, a window of \fB\-\-debounce\-time\fR opens at the first press (or release) of a key after the previous window expired. The first \fB\-\-count\-threshold\fR \fIN\fR identical events in the window are passed and later ones are dropped. With \fIN\fR=2, a fast intentional double-tap gets through while longer bursts of chatter are cut. Drop rules are not used in this mode.
+.PP
+.B Burst Mode (\-\-mode burst)
+.PP
+With \fB\-\-mode burst\fR, every event of the same key and value that follows the previous one, passed or dropped, by less than \fB\-\-burst\-gap\fR (default: the debounce time) belongs to the same burst, and only the burst's first event passes. Unlike \fBtime\fR mode, whose window restarts at each \fIpassed\fR event and so lets a burst longer than the window through once per window, a burst is only over once the key has been quiet for the gap. Events repeated faster than the gap on purpose are collapsed as well. Drop rules are not used in this mode.
+.PP
+\fB\-\-lead\-in\fR \fIKEY\fR=\fIN\fR applies the same counting to a single key in either mode, e.g. a macro key that is double-tapped on purpose. Its window is the global \fB\-\-debounce\-time\fR, and the lead-in takes precedence over \fB\-\-count\-threshold\fR and \fB\-\-drop\-rule\fR for that key.
+.PP
+.B Debounced Key States (\-\-debounce\-states)
+.PP
+By default both presses and releases are debounced. \fB\-\-debounce\-states press\fR debounces only presses and passes every release unfiltered, for switches that chatter only as they close; \fB\-\-debounce\-states release\fR does the opposite. Unfiltered events are handled like those of keys excluded with \fB\-\-ignore\-key\fR and count as passed in the statistics.
+.PP
 .B Choosing \-\-debounce\-time
 .IP \(bu 4
 Start with the default (\fB25ms\fR) or a common value like \fB15ms\fR.
@@ -161,6 +195,40 @@ To diagnose chatter on specific keys, run with \fB\-\-log\-bounces\fR and a low
 Typical values range from 5ms to 30ms. Mechanical switches might require slightly higher values than membrane switches.
 .IP \(bu 4
 Setting \fB\-\-debounce\-time 0ms\fR effectively disables filtering, passing all events through. This is useful with \fB\-\-log\-all\-events\fR or \fB\-\-log\-bounces\fR to observe raw event timings and diagnose chatter.
+.PP
+.B Custom Drop Rules (\-\-drop\-rule)
+.PP
+\fB\-\-drop\-rule\fR \fIEXPR\fR replaces the \fIdiff < debounce time\fR test with an expression evaluated for every debounce candidate, i.e. a key press or release with a previous passed event of the same key and state. The event is dropped if the expression is non-zero. The expression is parsed at startup; invalid rules are rejected with an error. Available variables:
+.IP "\fBdiff_us\fR" 4
+Time since the last passed event with the same key code and value.
+.IP "\fBdebounce_us\fR" 4
+The configured \fB\-\-debounce\-time\fR in microseconds.
+.IP "\fBkey_code\fR, \fBvalue\fR" 4
+The event's key code and value (0=release, 1=press).
+.IP "\fBprev_bounces\fR" 4
+Number of consecutive preceding events of this key that arrived within the debounce window, whether or not they were dropped.
+.PP
+Operators follow C precedence: \fB! \- * / % + \- < <= > >= == != && ||\fR, with parentheses for grouping. All values are 64-bit integers; comparisons yield 0 or 1 and division by zero yields 0. Example: \fB\-\-drop\-rule "diff_us < debounce_us && prev_bounces >= 2"\fR only starts dropping once a key has bounced twice in a row.
+.PP
+.B Release/Press Chatter (\-\-collapse\-press\-release)
+.PP
+Some switches chatter as a spurious \fIrelease\fR immediately followed by a re-press while the key is held. Because press and release are tracked independently, the default algorithm passes both. With \fB\-\-collapse\-press\-release\fR, key releases are held back for up to the debounce time. If a press of the same key arrives within that window, both events are dropped as chatter; otherwise the release is emitted once the window expires: just ahead of the next event read, which the input's own \fBSYN_REPORT\fR follows, or followed by a synthesized \fBSYN_REPORT\fR if no input arrives in time.
+.IP \(bu 4
+Releases of debounced keys are delayed by up to \fB\-\-debounce\-time\fR.
+.IP \(bu 4
+An intentional re-tap faster than the debounce time is swallowed: the release and the second press disappear, so the two taps merge into one long press. Keep the debounce time low when using this mode.
+.PP
+.B Scroll Wheel Chatter (\-\-debounce\-rel)
+.PP
+Relative axis events (\fBEV_REL\fR, e.g. \fBREL_WHEEL\fR) are passed unconditionally by default. A worn scroll encoder can emit bursts of spurious ticks. With \fB\-\-debounce\-rel\fR, an \fBEV_REL\fR event is dropped if a non-zero delta in the \fIsame direction\fR on the \fIsame axis\fR passed less than \fB\-\-debounce\-time\fR ago. There is no press/release for relative events, so the window is tracked per axis code and sign instead of per key state.
+.IP \(bu 4
+Zero deltas, other axes and movement in the opposite direction are never dropped.
+.IP \(bu 4
+Fast intentional scrolling is thinned too: with a 25ms window, at most 40 ticks per second per direction get through. Use a short \fB\-\-debounce\-time\fR, or leave this mode off, if that is too slow.
+.IP \(bu 4
+High-resolution wheel axes (\fBREL_WHEEL_HI_RES\fR) are tracked as separate axes. Their events share the timestamp of the matching low-resolution tick, so both are thinned alike.
+.IP \(bu 4
+\fB\-\-debounce\-key\fR, \fB\-\-ignore\-key\fR and \fB\-\-drop\-rule\fR apply to key events only. Dropped relative events do not appear in the key statistics.
 "#;
 
 const MAN_NEAR_MISS: &str = r#"
@@ -185,6 +253,8 @@ Near-miss statistics are primarily useful for fine-tuning the debounce time or i
 The \fB\-\-near\-miss\-threshold\-time\fR should generally be set higher than the \fB\-\-debounce\-time\fR (e.g., 100ms threshold vs 20ms debounce) to capture relevant events without being overly noisy.
 .IP \(bu 4
 Setting the threshold to the same value as the debounce time (or 0ms) effectively disables near-miss tracking.
+.IP \(bu 4
+A comma-separated list such as \fB30ms,60ms,120ms\fR splits near misses into bands. The largest value is the overall threshold, and each near miss is also counted in the innermost band that contains it.
 "#;
 
 const MAN_EXAMPLES: &str = r#"
@@ -222,13 +292,21 @@ sudo sh \-c 'intercept \-g ... | {bin_name} \-\-debounce\-time 5ms | uinput \-d
 .PP
 Run with a low debounce time (5ms) and examine the statistics printed on exit (Ctrl+C). Look at the `Bounce Time (Min/Avg/Max)` and the `Bounce Timing Histogram` for problematic keys to determine an appropriate debounce time.
 .PP
+.B Comparing Debounce Times on a Capture:
+.IP
+.nf
+{bin_name} \-\-sweep 5ms,10ms,15ms,20ms,25ms < capture.bin
+.fi
+.PP
+Replay a recorded capture once per debounce time and print a table of drop counts and drop rates per setting to standard output. Pick the setting just past the point where drops level off.
+.PP
 .B Periodic Stats Dump (every 5 minutes):
 .IP
 .nf
 sudo sh \-c 'intercept \-g ... | {bin_name} \-\-log\-interval 5m | uinput \-d ...'
 .fi
 .PP
-Run with default filtering and print detailed statistics to standard error every 5 minutes, in addition to the final report on exit.
+Run with default filtering and print detailed statistics to standard error every 5 minutes, in addition to the final report on exit. Add \fB\-\-periodic\-compact\fR to print each dump as a single \fBPERIODIC processed=.. dropped=.. drop_pct=.. top_key=..\fR line instead.
 .PP
 .B JSON Statistics Output:
 .IP
@@ -291,18 +369,24 @@ const MAN_STATISTICS: &str = r#"
 .PP
 .B Metrics Reported (Human-Readable):
 .IP "\fBOverall Statistics\fR" 4
-Includes total key events processed, passed, dropped, and the overall drop percentage.
+Includes total key events processed, passed, dropped, and the overall drop percentage, plus the average and peak rate of input events of any type per second (counted over 1-second windows of event timestamps). With \fB\-\-measure\-latency\fR, a \fBFilter Latency\fR line gives the min, average and max time the main thread spent on the filter decision per event.
+.IP "\fBTop Chattiest Keys\fR" 4
+Up to \fB\-\-top\-keys\fR \fIN\fR keys (default 5, 0 omits the section) ranked by drop count, with their drop rates. Printed right after the overall counts and mirrored in the JSON \fBtop_keys\fR array.
 .IP "\fBOverall Bounce Timing Histogram\fR" 4
 Shows the distribution of time differences (in milliseconds) between dropped events and the previous passed event of the same key/state across all keys. Helps visualize the typical bounce duration.
 .IP "\fBOverall Near-Miss Timing Histogram\fR" 4
 Shows the distribution of time differences (in milliseconds) between passed events that qualified as near-misses and the previous passed event of the same key/state. Helps visualize timing consistency just outside the debounce window.
+.IP "\fBCumulative Histograms (\-\-histogram\-cumulative)\fR" 4
+Each histogram bucket also shows the cumulative percentage of timings below its upper boundary, and JSON histograms gain a \fBcumulative\fR array (one percentage per bucket, reaching 100 at the last bucket). The first bucket reaching 99% gives a debounce time that catches 99% of the observed bounces.
+.IP "\fBShared Histogram Legend (\-\-histogram\-legend)\fR" 4
+The bucket ranges are printed once, before the overall histograms, and their rows refer to buckets by index. Bounce and near-miss bars are drawn on one scale (the fullest bucket of either), so the two distributions can be compared side by side.
 .IP "\fBDropped Event Statistics Per Key\fR" 4
 For each key code that had events processed:
 .RS 4
 .IP \(bu 4
 Summary line: Total processed, passed, dropped, and drop percentage for that key.
 .IP \(bu 4
-Detailed stats for \fBPress\fR (value=1), \fBRelease\fR (value=0), and \fBRepeat\fR (value=2) states:
+Detailed stats for \fBPress\fR (value=1), \fBRelease\fR (value=0), and \fBRepeat\fR (value=2) states. Repeats are passed through without debouncing, so the Repeat line is marked \fI(passthrough, never debounced)\fR and never shows drops; \fB\-\-hide\-repeat\-stats\fR leaves it out. With \fB\-\-stats\-granularity key\fR, none of these per-state lines are shown:
 .RS 4
 .IP \(bu 4
 \fBProcessed\fR: Number of events seen for this key/state.
@@ -313,17 +397,41 @@ Detailed stats for \fBPress\fR (value=1), \fBRelease\fR (value=0), and \fBRepeat
 .IP \(bu 4
 \fBDrop Rate\fR: Percentage of processed events dropped for this key/state.
 .IP \(bu 4
-\fBBounce Time (Min/Avg/Max)\fR: The time difference (µs) between a dropped event and the previous \fIpassed\fR event of the same key and state. (Only shown if drops occurred).
+\fBBounce Time (Min/Avg/Max)\fR: The time difference (µs) between a dropped event and the previous \fIpassed\fR event of the same key and state, followed by the standard deviation and the number of samples (\fBn\fR). Averages over few samples deserve little trust. (Only shown if drops occurred).
 .RE
+.IP \(bu 4
+With \fB\-\-per\-key\-histograms\fR, or \fB\-\-focus\-key\fR \fIKEY\fR for selected keys only, the key's Press and Release bounce histograms, in the same format as the overall ones.
 .RE
+.IP "\fBKey Inspection (\-\-inspect \fIKEY\fB)\fR" 4
+Replaces the whole human-readable report with one key's data: its per-state lines, press and release bounce and near-miss histograms, p50/p90/p99 of its bounce timings, first and last event timestamps and its recommended debounce time. Nothing about other keys or the overall totals is printed. Can't be combined with \fB\-\-stats\-json\fR.
+.IP "\fBPass Intervals\fR" 4
+With \fB\-\-interval\-histogram\fR: an overall histogram of the time between consecutive passed presses (and releases) of the same key, and per key and state the number of such intervals with their average and standard deviation. Auto-repeats are not included.
+.IP "\fBDecisions Relative to Debounce Time\fR" 4
+Bounces and passes by the time since the previous event of the same key and state divided by the debounce time, in 0.1-wide buckets. Bounces fall below 1.0, passes at or above it; passes at 2.0 or more are left out as ordinary typing. Many passes just above 1.0 or bounces just below it mean the window sits right at the edge of real keystrokes. Only printed if anything was recorded.
 .IP "\fBPassed Event Near-Miss Statistics\fR" 4
 For each key code/state combination with near-misses:
 .RS 4
 .IP \(bu 4
 \fBCount\fR: Number of passed events that qualified as near-misses.
 .IP \(bu 4
-\fBNear-Miss Time (Min/Avg/Max)\fR: The time difference (µs) between a passed near-miss event and the previous \fIpassed\fR event of the same key and state.
+\fBNear-Miss Time (Min/Avg/Max)\fR: The time difference (µs) between a passed near-miss event and the previous \fIpassed\fR event of the same key and state, followed by the standard deviation.
+.IP \(bu 4
+\fBBands\fR: With several near-miss thresholds, the number of near misses in each band, innermost first. A \fBNear misses by band\fR line totals them over all keys.
 .RE
+.IP "\fBStream Gaps\fR" 4
+With \fB\-\-gap\-threshold\fR, the number of silences of at least that length between consecutive input events, and how many of them happened while a key was held. Held keys normally produce a steady stream of repeats, so a gap during a hold points at events lost before they reached \fB{bin_name}\fR (e.g. a USB hiccup) rather than an idle keyboard. Each gap is also logged at info level.
+.IP "\fBRecovered Passes\fR" 4
+Passed key events whose previous event for the same key code and state was dropped, i.e. the real keystroke getting through after chatter was rejected. With \fB\-\-log\-all\-events\fR these lines are tagged \fI(Recovered)\fR. Only printed if any occurred.
+.IP "\fBKeys Chattering\fR" 4
+How many distinct keys had at least one drop, out of the keys used at all. A single chattering key points at one failing switch; many at a board-wide problem (or a debounce time that is too long).
+.IP "\fBLikely Double-Character Events\fR" 4
+Press, release, press, release sequences of one key whose re-press followed the release within the debounce time: one keystroke that chattered into two characters. The count in parentheses is how many still reached the output because the re-press passed. Only printed if any occurred.
+.IP "\fBKey State Violations\fR" 4
+Keys whose \fIpassed\fR events broke the press/release alternation: a press while the key was already pressed, or a release while it was already released. Unlike bounce timings, this points at devices that lose or duplicate edges. Only printed if any violations occurred.
+.IP "\fBPossible Dead Switches\fR" 4
+A \fBWARNING\fR for each key whose drop rate reached \fB\-\-dead\-switch\-threshold\fR (default 90%) over at least 20 events. A key dropping nearly everything is more likely a switch failing open/closed or a misconfiguration than chatter, and no debounce time will fix it. Only printed if a key qualifies.
+.IP "\fBRecommended Debounce Time\fR" 4
+For each key with drops, a suggested \fB\-\-debounce\-time\fR computed from the p99 of its sampled bounce timings plus a 1ms margin, rounded up to a whole millisecond. The overall suggestion is the largest per-key value, so it covers every chattering key. This automates the manual "read the histogram, pick a value slightly above it" workflow described under DEBOUNCING.
 .PP
 .B Interpretation:
 .IP \(bu 4
@@ -341,12 +449,42 @@ Provides a machine-readable format containing all the same information, plus raw
 .RS 4
 .IP "\fBreport_type\fR": "Cumulative" or "Periodic".
 .IP "\fBruntime_us\fR": Total runtime in microseconds (cumulative only).
+.IP "\fBinterval_start_us\fR", "\fBinterval_end_us\fR": Event timestamps of the first and last event in the interval (periodic only, omitted when the interval had no events).
 .IP "\fBdebounce_time_us\fR", "\fBnear_miss_threshold_us\fR", "\fBlog_interval_us\fR": Configuration values used (microseconds).
 .IP "\fBkey_events_processed\fR", "\fBkey_events_passed\fR", "\fBkey_events_dropped\fR": Overall counts.
-.IP "\fBoverall_bounce_histogram\fR", "\fBoverall_near_miss_histogram\fR": Detailed histogram objects (buckets, count, avg_us).
-.IP "\fBper_key_stats\fR": Array of objects, each containing `key_code`, `key_name`, overall counts/percentage, and detailed `stats` (press/release/repeat) including counts, rates, sampled `timings_us`, summary fields, and a `bounce_histogram` object.
-.IP "\fBper_key_near_miss_stats\fR": Array of objects, each containing `key_code`, `key_value`, `key_name`, `value_name`, `count`, sampled `timings_us`, summary fields, and a `near_miss_histogram` object.
+.IP "\fBavg_events_per_sec\fR", "\fBpeak_events_per_sec\fR": Average and peak input events (any type) per 1-second window; the average is omitted before the first event.
+.IP "\fBfilter_latency_min_ns\fR", "\fBfilter_latency_avg_ns\fR", "\fBfilter_latency_max_ns\fR": Time the filter decision (lock and check) took per event, in nanoseconds. Only present with \fB\-\-measure\-latency\fR.
+.IP "\fBstream_gaps\fR", "\fBstream_gaps_while_held\fR": Event stream gaps of at least \fB\-\-gap\-threshold\fR, total and while a key was held (0 if disabled).
+.IP "\fBrecovered_passes\fR": Passed events that immediately followed a drop of the same key and state. Each \fBper_key_stats\fR entry also carries its own \fBrecovered_passes\fR count.
+.IP "\fBkeys_chattering\fR", "\fBkeys_used\fR": Distinct keys with at least one drop, and keys with any processed event.
+.IP "\fBdouble_characters\fR", "\fBdouble_characters_passed\fR": Likely double-character sequences, and those whose re-press was passed. Each \fBper_key_stats\fR entry also carries its own \fBdouble_characters\fR count.
+.IP "\fBrepeats_dropped\fR": Key repeats dropped, i.e. over \fB\-\-max\-repeat\-rate\fR (or exact duplicates).
+.IP "\fBshutdown_reason\fR": Why the run ended (cumulative only), e.g. "EOF", "SIGINT", "SIGTERM", "broken pipe" or "max runtime" (\fB\-\-max\-runtime\fR ran out). The human-readable report ends with a matching \fBShutdown reason:\fR line.
+.IP "\fBprotocol_violations\fR": Total press/release state violations. Each \fBper_key_stats\fR entry also carries its own \fBprotocol_violations\fR count.
+.IP "\fBrecommended_debounce_us\fR": Overall debounce suggestion in microseconds (omitted if nothing bounced). Each \fBper_key_stats\fR entry also carries its own \fBrecommended_debounce_us\fR.
+.IP "\fBoverall_bounce_histogram\fR", "\fBoverall_near_miss_histogram\fR": Detailed histogram objects (buckets, count, avg_us, stddev_us). Each bucket gives its range as min_ms/max_ms and as exact min_us/max_us; the upper bound is null for the last bucket.
+.IP "\fBwindow_ratio_histogram\fR": Array of 20 decision counts, bucket \fIi\fR covering ratios (time since previous event / debounce time) from \fIi\fR/10 up to (\fIi\fR+1)/10.
+.IP "\fBper_key_stats\fR": Array of objects, each containing `key_code`, `key_name`, overall counts/percentage, `likely_dead_switch`, `first_event_us`/`last_event_us` (timestamps of the key's first and latest event), and detailed `stats` (press/release/repeat) including counts, rates, sampled `timings_us` (with `timings_sampled` and `timings_total_count`), summary fields, and a `bounce_histogram` object.
+.IP "\fBper_key_near_miss_stats\fR": Array of objects, each containing `key_code`, `key_value`, `key_name`, `value_name`, `count`, sampled `timings_us` (with `timings_sampled` and `timings_total_count`), summary fields, and a `near_miss_histogram` object.
 .RE
+.PP
+With \fB\-\-stats\-format msgpack\fR (implies \fB\-\-stats\-json\fR), each report is instead written as a MessagePack map with the same fields, preceded by its length as a 4-byte big-endian integer. Requires the \fBmsgpack\fR build feature, which is off by default.
+.PP
+.B Anonymized Reports (\-\-anonymize):
+.IP
+With \fB\-\-anonymize\fR, the statistics reports (human-readable and JSON) show every key under a pseudonym such as \fBkey_1a3\fR, with a matching pseudonymous code, so they can be shared without revealing what was typed. Counts and timings are unchanged. The mapping is drawn at random at startup and kept for all reports of the run. Log lines and the other per-event outputs are not anonymized.
+.PP
+.B Live Stats Stream (\-\-stats\-stream\-fd):
+.IP
+With \fB\-\-stats\-stream\-fd\fR \fIFD\fR, the cumulative statistics are pushed to the already open descriptor \fIFD\fR every \fB\-\-stats\-stream\-interval\fR (default 1s) and once more at exit, for a live display that needn't parse stderr. Each frame is a compact JSON object with \fBseq\fR, \fBkey_events_processed\fR, \fBkey_events_passed\fR, \fBkey_events_dropped\fR, \fBdrop_percentage\fR and \fBkeys\fR (the \fB\-\-heatmap\-json\fR entries), preceded by its length as a 4-byte big-endian integer. A descriptor that can't be used or written is given up with a warning.
+.PP
+.B Persisted Statistics (\-\-persist\-state):
+.IP
+With \fB\-\-persist\-state\fR \fIFILE\fR, counts, histograms and (capped) timing samples from earlier runs are loaded at startup and merged with the current run. The final cumulative report covers all runs, and the merged totals are written back to \fIFILE\fR (JSON, replaced atomically) on exit. Periodic reports and the runtime cover only the current run. A missing file starts a fresh history; an unreadable one makes \fB{bin_name}\fR exit with status 4.
+.PP
+.B Merged Reports (\-\-merge):
+.IP
+\fB\-\-merge\fR \fIA\fR \fIB\fR reads two reports saved with \fB\-\-stats\-json\fR, rebuilds their counts, histograms and timing samples, adds them up as \fB\-\-persist\-state\fR would and writes the combined report (report type "Merged") as JSON to standard output, or to \fB\-o\fR \fIPATH\fR. Totals, percentages, percentiles and recommendations are recomputed; the settings shown are the first report's, and the runtime is the sum of both (if both have one). Event rate and filter latency are not carried over. No events are read. An unreadable or malformed report, a JSON file that isn't a stats report, or two reports with different near-miss bands exit with status 4.
 "#;
 
 const MAN_LOGGING: &str = r#"
@@ -357,6 +495,10 @@ const MAN_LOGGING: &str = r#"
 Log details of every incoming event ([PASS] or [DROP]). Useful for seeing the full event stream and filter decisions. Note: `EV_SYN` and `EV_MSC` events are skipped for cleaner output by default in this mode.
 .IP "\fB\-\-log\-bounces\fR" 4
 Log details of *only dropped* (bounced) key events. Less verbose than `--log-all-events`, focusing on problematic events. Ignored if `--log-all-events` is active.
+.IP "\fB\-\-event\-json\fR" 4
+Write one compact JSON object per event (including `EV_SYN` and `EV_MSC`) with the decoded fields and the filter decision. Independent of the other log flags.
+.IP "\fB\-\-event\-json\-raw\fR" 4
+Like \fB\-\-event\-json\fR, but also include a `raw_hex` field containing the raw `input_event` bytes as read from standard input. Useful for bug reports about unusual devices.
 .IP "\fB\-\-verbose\fR" 4
 Enable verbose logging (DEBUG level). Includes internal state information, thread startup/shutdown messages, etc. Sets the default log filter to `intercept_bounce=debug` if `RUST_LOG` is not set.
 .PP
@@ -424,6 +566,26 @@ Print the final cumulative statistics to standard error (unless `--stats-json` i
 Exit cleanly (typically with status 0).
 .PP
 This ensures that valuable statistics are not lost even if the filter is terminated externally via these signals.
+.PP
+.B Pausing Filtering (SIGUSR1)
+.PP
+Sending \fBSIGUSR1\fR toggles filtering on and off without restarting the pipeline. While paused, every event is passed through unfiltered and the debounce state is \fIfrozen\fR (not updated), so resuming picks up from the timestamps recorded before the pause. Key events passed while paused are counted separately (`Key Events Passed While Paused`, or `key_events_paused` in JSON) and are excluded from the processed/passed/dropped totals. This is useful for comparing the filtered and raw feel of a keyboard live:
+.IP
+.nf
+pkill \-USR1 \-x {bin_name}
+.fi
+.PP
+.B Reloading the Configuration File (SIGHUP)
+.PP
+When started with \fB\-\-config\fR \fIFILE\fR, sending \fBSIGHUP\fR re-reads the file and applies the new settings before the next event is processed. Debounce and near-miss times, log interval, logging flags, per-key lists and drop rules take effect immediately; the filter's timing state is kept. \fB\-\-ring\-buffer\-size\fR, \fB\-\-otel\-endpoint\fR, \fB\-\-otel\-flush\-interval\fR and the log level (\fB\-\-verbose\fR/\fBRUST_LOG\fR) require a restart; a warning is logged if they changed. If the file can't be read or parsed, the current settings are kept. Without \fB\-\-config\fR, \fBSIGHUP\fR is ignored.
+.PP
+The file is TOML with long option names as keys; flags given on the command line take precedence:
+.IP
+.nf
+debounce_time = "15ms"
+log_bounces = true
+debounce_key = ["KEY_ENTER", "KEY_SPACE"]
+.fi
 "#;
 
 const MAN_EXIT_STATUS: &str = r#"
@@ -433,16 +595,24 @@ Success. The program completed normally or was terminated cleanly by a handled s
 Runtime Error. An unexpected error occurred during execution, such as:
 .RS 4
 .IP \(bu 4
-Error reading from standard input or writing to standard output.
+Error reading from standard input (or the \fB\-\-input\fR path) or writing to standard output, or the \fB\-\-input\fR path could not be opened or grabbed (\fB\-\-grab\fR, e.g. the device is already grabbed by another program).
 .IP \(bu 4
-Error creating or communicating with the logger thread.
+Error creating or communicating with the logger thread (e.g. it panicked, unless \fB\-\-restart\-logger\fR recovered it without \fB\-\-fail\-on\-logger\-panic\fR). A logger that doesn't finish within \fB\-\-shutdown\-timeout\fR only gets a warning: the run exits without final statistics, with the status it would have had otherwise.
 .IP \(bu 4
 Internal logic errors (panics).
-.IP \(bu 4
-Errors initializing OpenTelemetry (if configured).
 .RE
 .IP 2 4
-Device Listing Error. An error occurred when using the \fB\-\-list\-devices\fR option, likely due to insufficient permissions to access \fI/dev/input/event*\fR devices or other I/O errors during the scan.
+Device Listing Error. An error occurred when using the \fB\-\-list\-devices\fR or \fB\-\-auto\-device\fR option, likely due to insufficient permissions to access \fI/dev/input/event*\fR devices or other I/O errors during the scan, or \fB\-\-auto\-device\fR found no keyboard.
+.IP 3 4
+Standard input is a terminal. \fB{bin_name}\fR expects a stream of binary \fIinput_event\fR structs (e.g. from \fBintercept \-g\fR) and exits with a usage hint instead of waiting on an interactive terminal.
+.IP 4 4
+Configuration Error. The \fB\-\-persist\-state\fR file exists but could not be read or parsed, the \fB\-\-key\-aliases\fR file could not be read or names an unknown key, a \fB\-\-merge\fR report could not be read or parsed or has other near-miss bands than the other one, or \fB\-\-otel\-endpoint\fR was given to a build without the \fIotel\fR feature. Nothing is processed, and the file is left untouched.
+.IP 5 4
+No Events. \fB\-\-expect\-events\fR was given, but the run ended without processing a single key event (e.g. the \fBintercept\fR stage exited immediately). Final statistics are still printed.
+.IP 6 4
+Self-Test Failure. \fB\-\-self\-test\fR found at least one check whose result didn't match the expectation; the FAIL lines on standard output name them.
+.IP 7 4
+OpenTelemetry Unavailable. \fB\-\-otel\-required\fR was given and the OTLP exporter could not be initialized or the \fB\-\-otel\-endpoint\fR did not accept a connection at startup. Without \fB\-\-otel\-required\fR, such failures are logged as a warning and the run continues without telemetry.
 "#;
 
 const MAN_ENVIRONMENT: &str = r#"
@@ -450,8 +620,17 @@ const MAN_ENVIRONMENT: &str = r#"
 .B RUST_LOG
 Controls the logging verbosity and filtering, overriding defaults set by \fB\-\-verbose\fR. See the LOGGING section for details and examples. Uses the \fBtracing_subscriber::EnvFilter\fR format.
 .TP
+.B INTERCEPT_BOUNCE_DEBOUNCE_TIME\fR, \fBINTERCEPT_BOUNCE_NEAR_MISS\fR, \fBINTERCEPT_BOUNCE_LOG_INTERVAL
+Default values for \fB\-\-debounce\-time\fR, \fB\-\-near\-miss\-threshold\-time\fR and \fB\-\-log\-interval\fR, in the same formats. Precedence is command-line flag, then config file, then environment variable, then built-in default.
+.TP
+.B INTERCEPT_BOUNCE_NO_PRIORITY
+Set to \fB1\fR to skip raising the process priority (niceness \-20) at startup, and the warning logged when that isn't permitted, e.g. in unprivileged containers or CI.
+.TP
 .B RUST_BACKTRACE
 Set to \fB1\fR or \fBfull\fR to enable backtraces on panic, which can be helpful for debugging crashes.
+.TP
+.B NO_COLOR
+If set, \fB\-\-color auto\fR (the default) disables colored log lines and statistics even on a terminal.
 "#;
 
 const MAN_PERFORMANCE: &str = r#"
@@ -525,6 +704,9 @@ If running interactively with logging enabled, log messages (stderr) might mix w
 .B "Logger channel full, dropping log messages" Warning:
 This means the logger thread cannot keep up with the rate of events from the main thread, likely due to heavy logging (`--log-all-events`, `RUST_LOG=trace`), a slow OTLP endpoint, or high system load. Log messages and stats updates may be lost, but input filtering latency is prioritized. Reduce logging verbosity or disable OTLP if problematic.
 .TP
+.B "Most key events have zero or identical timestamps" Warning:
+The event timestamps don't advance (e.g. a replay tool writing zero timevals), so all events look simultaneous: debouncing and the timing statistics are meaningless. Fix the event source. The first 16 key events are checked, or all of them in a shorter run.
+.TP
 .B Errors Reading/Writing Events:
 Check device permissions, physical keyboard connection, and system logs (`dmesg`) for hardware errors related to the input device. Ensure `intercept` and `uinput` are functioning correctly.
 .TP
@@ -564,7 +746,7 @@ Updates statistics stored in a `StatsCollector` struct. Simple counts use `u64`,
 .IP \(bu 4
 Performs logging to standard error based on the configured logging flags (`--log-all-events`, `--log-bounces`) and log level (`RUST_LOG`, `--verbose`). Logging uses the `tracing` framework.
 .IP \(bu 4
-If OpenTelemetry is enabled (`--otel-endpoint`), increments relevant OTLP counters (e.g., near-misses).
+If OpenTelemetry is enabled (`--otel-endpoint`), records relevant OTLP metrics (e.g., the near-miss histogram, tagged with the key and state).
 .RE
 .IP 4. 4
 Periodically checks if the configured `--log-interval` has elapsed. If so, prints the current interval statistics (human-readable or JSON) and resets the interval stats collector.